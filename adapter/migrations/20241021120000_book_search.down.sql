@@ -0,0 +1,13 @@
+DROP INDEX IF EXISTS books_isbn_prefix_idx;
+
+DROP INDEX IF EXISTS books_description_trgm_idx;
+
+DROP INDEX IF EXISTS books_author_trgm_idx;
+
+DROP INDEX IF EXISTS books_title_trgm_idx;
+
+DROP INDEX IF EXISTS books_search_vector_idx;
+
+ALTER TABLE books DROP COLUMN IF EXISTS search_vector;
+
+DROP EXTENSION IF EXISTS pg_trgm;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS reservations_book_id_reserved_at_idx;
+
+DROP TABLE IF EXISTS reservations;
@@ -0,0 +1,9 @@
+ALTER TABLE returned_checkouts DROP COLUMN IF EXISTS renewal_count;
+
+ALTER TABLE returned_checkouts DROP COLUMN IF EXISTS due_at;
+
+DROP INDEX IF EXISTS checkouts_due_at_idx;
+
+ALTER TABLE checkouts DROP COLUMN IF EXISTS renewal_count;
+
+ALTER TABLE checkouts DROP COLUMN IF EXISTS due_at;
@@ -0,0 +1,24 @@
+ALTER TABLE checkouts ADD COLUMN due_at TIMESTAMP(3) WITH TIME ZONE;
+
+-- 既存の貸出の返却期限は、アプリケーションの CHECKOUT_LOAN_PERIOD_DAYS と同じ日数で埋める。
+-- 既定値の 14 日以外で運用している場合は、マイグレーションの前に
+-- ALTER DATABASE ... SET app.loan_period_days = '<日数>' で同じ値を設定しておく。
+UPDATE checkouts
+SET due_at = checked_out_at
+    + make_interval(days => COALESCE(NULLIF(current_setting('app.loan_period_days', true), '')::INTEGER, 14));
+
+ALTER TABLE checkouts ALTER COLUMN due_at SET NOT NULL;
+
+ALTER TABLE checkouts ADD COLUMN renewal_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS checkouts_due_at_idx ON checkouts (due_at);
+
+ALTER TABLE returned_checkouts ADD COLUMN due_at TIMESTAMP(3) WITH TIME ZONE;
+
+UPDATE returned_checkouts
+SET due_at = checked_out_at
+    + make_interval(days => COALESCE(NULLIF(current_setting('app.loan_period_days', true), '')::INTEGER, 14));
+
+ALTER TABLE returned_checkouts ALTER COLUMN due_at SET NOT NULL;
+
+ALTER TABLE returned_checkouts ADD COLUMN renewal_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS notification_preferences_updated_at_trigger ON notification_preferences;
+
+DROP TABLE IF EXISTS notification_preferences;
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id UUID NOT NULL,
+    kind VARCHAR(64) NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+    PRIMARY KEY (user_id, kind),
+    FOREIGN KEY (user_id) REFERENCES users (user_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE TRIGGER notification_preferences_updated_at_trigger BEFORE
+UPDATE ON notification_preferences FOR EACH ROW
+EXECUTE PROCEDURE set_updated_at ();
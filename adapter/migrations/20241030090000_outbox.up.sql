@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS outbox (
+    outbox_id BIGSERIAL PRIMARY KEY,
+    aggregate_type VARCHAR(64) NOT NULL,
+    aggregate_id UUID NOT NULL,
+    event_type VARCHAR(64) NOT NULL,
+    payload JSONB NOT NULL,
+    occurred_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    published_at TIMESTAMP(3) WITH TIME ZONE
+);
+
+-- 未配信のイベントを記録順に取り出すためのインデックス
+CREATE INDEX IF NOT EXISTS outbox_unpublished_idx ON outbox (outbox_id)
+WHERE published_at IS NULL;
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS audit_log (
+    audit_log_id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+    -- ユーザーが削除された後も操作の記録を残すため、外部キーは張らない
+    actor_id UUID NOT NULL,
+    action VARCHAR(64) NOT NULL,
+    target_id UUID NOT NULL,
+    before JSONB,
+    after JSONB,
+    request_id VARCHAR(255),
+    occurred_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+CREATE INDEX IF NOT EXISTS audit_log_occurred_at_idx ON audit_log (occurred_at DESC);
+CREATE INDEX IF NOT EXISTS audit_log_actor_id_idx ON audit_log (actor_id, occurred_at DESC);
+CREATE INDEX IF NOT EXISTS audit_log_target_id_idx ON audit_log (target_id, occurred_at DESC);
@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS user_identities (
+    user_identity_id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+    user_id UUID NOT NULL,
+    -- ID トークンの `iss` と `sub` の組で外部のアカウントを一意に識別する
+    issuer VARCHAR(255) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    email VARCHAR(255),
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+    UNIQUE (issuer, subject),
+    FOREIGN KEY (user_id) REFERENCES users (user_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS user_identities_user_id_idx ON user_identities (user_id);
+
+CREATE TRIGGER user_identities_updated_at_trigger BEFORE
+UPDATE ON user_identities FOR EACH ROW
+EXECUTE PROCEDURE set_updated_at ();
+
+-- 認可リクエストからコールバックまでの間、PKCE の code_verifier と nonce を保持する
+CREATE TABLE IF NOT EXISTS oidc_authorization_requests (
+    state VARCHAR(255) PRIMARY KEY,
+    code_verifier VARCHAR(255) NOT NULL,
+    nonce VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS two_factor_challenges;
+DROP TABLE IF EXISTS two_factor_recovery_codes;
+DROP TRIGGER IF EXISTS user_two_factor_updated_at_trigger ON user_two_factor;
+DROP TABLE IF EXISTS user_two_factor;
+ALTER TABLE roles DROP COLUMN IF EXISTS two_factor_required;
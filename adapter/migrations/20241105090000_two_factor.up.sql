@@ -0,0 +1,39 @@
+-- ロールごとに 2 段階認証を必須とするか
+ALTER TABLE roles ADD COLUMN IF NOT EXISTS two_factor_required BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- 認証アプリとの照合に用いるため、秘密鍵は Base32 のまま保持する
+CREATE TABLE IF NOT EXISTS user_two_factor (
+    user_id UUID PRIMARY KEY,
+    secret VARCHAR(64) NOT NULL,
+    -- 登録後、最初のコードを確認するまでは NULL
+    enabled_at TIMESTAMP(3) WITH TIME ZONE,
+    -- 同じコードの再利用を防ぐため、最後に受け付けたタイムステップを記録する
+    last_used_step BIGINT,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+    FOREIGN KEY (user_id) REFERENCES users (user_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE TRIGGER user_two_factor_updated_at_trigger BEFORE
+UPDATE ON user_two_factor FOR EACH ROW
+EXECUTE PROCEDURE set_updated_at ();
+
+-- リカバリーコードは使い捨てで、使用したものは削除する
+CREATE TABLE IF NOT EXISTS two_factor_recovery_codes (
+    user_id UUID NOT NULL,
+    code_hash VARCHAR(64) NOT NULL,
+
+    PRIMARY KEY (user_id, code_hash),
+    FOREIGN KEY (user_id) REFERENCES users (user_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+-- パスワードの確認後、2 段階目のコードを確認するまでの間のチャレンジ
+CREATE TABLE IF NOT EXISTS two_factor_challenges (
+    token_hash VARCHAR(64) PRIMARY KEY,
+    user_id UUID NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+
+    FOREIGN KEY (user_id) REFERENCES users (user_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS role_permissions;
+DROP INDEX IF EXISTS roles_name_idx;
@@ -0,0 +1,24 @@
+-- ロール名で割り当てるため、重複を許さない
+CREATE UNIQUE INDEX IF NOT EXISTS roles_name_idx ON roles (name);
+
+CREATE TABLE IF NOT EXISTS role_permissions (
+    role_id UUID NOT NULL,
+    permission VARCHAR(64) NOT NULL,
+
+    PRIMARY KEY (role_id, permission),
+    FOREIGN KEY (role_id) REFERENCES roles (role_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+-- 既存の Admin ロールには、従来どおりすべての操作を許可する
+INSERT INTO role_permissions (role_id, permission)
+SELECT role_id, permission
+FROM roles
+CROSS JOIN UNNEST(ARRAY[
+    'book:write:any',
+    'checkout:return:any',
+    'user:manage',
+    'role:manage',
+    'audit:read'
+]) AS permission
+WHERE name = 'Admin'
+ON CONFLICT DO NOTHING;
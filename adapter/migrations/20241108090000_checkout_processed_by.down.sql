@@ -0,0 +1,7 @@
+DELETE FROM role_permissions WHERE permission = 'checkout:create:any';
+
+ALTER TABLE returned_checkouts DROP COLUMN IF EXISTS returned_by;
+
+ALTER TABLE returned_checkouts DROP COLUMN IF EXISTS processed_by;
+
+ALTER TABLE checkouts DROP COLUMN IF EXISTS processed_by;
@@ -0,0 +1,23 @@
+-- 貸出・返却の手続きをした利用者。本人が手続きした場合は借り手と同じになる
+ALTER TABLE checkouts ADD COLUMN processed_by UUID;
+
+UPDATE checkouts SET processed_by = user_id;
+
+ALTER TABLE checkouts ALTER COLUMN processed_by SET NOT NULL;
+
+ALTER TABLE returned_checkouts ADD COLUMN processed_by UUID;
+
+ALTER TABLE returned_checkouts ADD COLUMN returned_by UUID;
+
+UPDATE returned_checkouts SET processed_by = user_id, returned_by = user_id;
+
+ALTER TABLE returned_checkouts ALTER COLUMN processed_by SET NOT NULL;
+
+ALTER TABLE returned_checkouts ALTER COLUMN returned_by SET NOT NULL;
+
+-- 既存の Admin ロールには、他のユーザーへの貸出も許可する
+INSERT INTO role_permissions (role_id, permission)
+SELECT role_id, 'checkout:create:any'
+FROM roles
+WHERE name = 'Admin'
+ON CONFLICT DO NOTHING;
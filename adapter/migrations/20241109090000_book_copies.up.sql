@@ -0,0 +1,50 @@
+-- books は書誌情報として扱い、実際に貸し出す 1 冊ごとの情報は複本として管理する
+CREATE TABLE IF NOT EXISTS book_copies (
+    copy_id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+    book_id UUID NOT NULL,
+    barcode VARCHAR(64) NOT NULL UNIQUE,
+    shelf_location VARCHAR(255) NOT NULL DEFAULT '',
+    condition VARCHAR(16) NOT NULL DEFAULT 'good',
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+    FOREIGN KEY (book_id) REFERENCES books (book_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS book_copies_book_id_idx ON book_copies (book_id);
+
+CREATE TRIGGER book_copies_updated_at_trigger BEFORE
+UPDATE ON book_copies FOR EACH ROW
+EXECUTE PROCEDURE set_updated_at ();
+
+-- 既存の蔵書は、それぞれ 1 冊の複本を持つものとして移行する
+INSERT INTO book_copies (book_id, barcode)
+SELECT book_id, replace(book_id::text, '-', '')
+FROM books;
+
+-- 貸出は蔵書ではなく複本を対象にし、同じ蔵書の複本を同時に貸し出せるようにする
+ALTER TABLE checkouts ADD COLUMN copy_id UUID;
+
+UPDATE checkouts AS c
+SET copy_id = bc.copy_id
+FROM book_copies AS bc
+WHERE bc.book_id = c.book_id;
+
+ALTER TABLE checkouts ALTER COLUMN copy_id SET NOT NULL;
+
+ALTER TABLE checkouts DROP CONSTRAINT IF EXISTS checkouts_book_id_key;
+
+ALTER TABLE checkouts ADD CONSTRAINT checkouts_copy_id_key UNIQUE (copy_id);
+
+ALTER TABLE checkouts
+ADD CONSTRAINT checkouts_copy_id_fkey FOREIGN KEY (copy_id) REFERENCES book_copies (copy_id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS checkouts_book_id_idx ON checkouts (book_id);
+
+-- 返却済みの貸出は履歴として残すため、複本が削除されても参照を保つ
+ALTER TABLE returned_checkouts ADD COLUMN copy_id UUID;
+
+UPDATE returned_checkouts AS rc
+SET copy_id = bc.copy_id
+FROM book_copies AS bc
+WHERE bc.book_id = rc.book_id;
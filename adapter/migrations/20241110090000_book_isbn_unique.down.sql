@@ -0,0 +1,2 @@
+-- 正規化した ISBN は元の表記に戻せないため、一意制約のみを取り除く
+DROP INDEX IF EXISTS books_user_id_isbn_key;
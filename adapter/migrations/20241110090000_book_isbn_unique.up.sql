@@ -0,0 +1,128 @@
+-- ISBN の形をした値からハイフンと空白を取り除く。"N/A" のような ISBN でない値はそのまま残す
+UPDATE books
+SET isbn = upper(regexp_replace(isbn, '[-[:space:]]', '', 'g'))
+WHERE upper(regexp_replace(isbn, '[-[:space:]]', '', 'g')) ~ '^([0-9]{9}[0-9X]|[0-9]{13})$';
+
+-- ISBN-10 を 978 から始まる ISBN-13 に変換する
+UPDATE books b
+SET isbn = t.body || (
+    (
+        10 - (
+            SELECT sum(substr(t.body, i, 1)::int * CASE WHEN i % 2 = 1 THEN 1 ELSE 3 END)
+            FROM generate_series(1, 12) AS i
+        ) % 10
+    ) % 10
+)::text
+FROM (
+    SELECT book_id, '978' || left(isbn, 9) AS body
+    FROM books
+    WHERE isbn ~ '^[0-9]{9}[0-9X]$'
+) t
+WHERE b.book_id = t.book_id;
+
+-- チェックディジットの正しい ISBN-13 を持つ蔵書。統合の対象はこれらに限る
+CREATE TEMPORARY TABLE valid_isbn_books AS
+SELECT book_id
+FROM books
+WHERE CASE
+    WHEN isbn ~ '^[0-9]{13}$' THEN (
+        SELECT sum(substr(isbn, i, 1)::int * CASE WHEN i % 2 = 1 THEN 1 ELSE 3 END)
+        FROM generate_series(1, 13) AS i
+    ) % 10 = 0
+    ELSE false
+END;
+
+-- チェックディジットの誤った ISBN-13 が重複している場合は、どれを残すべきか判断できないため
+-- 削除せずに移行を中止し、手作業で解消すべき蔵書を報告する
+DO $$
+DECLARE
+    report TEXT;
+BEGIN
+    SELECT string_agg(
+        format('user_id=%s isbn=%s book_ids=[%s]', user_id, isbn, book_ids),
+        E'\n'
+    )
+    INTO report
+    FROM (
+        SELECT user_id, isbn, string_agg(book_id::text, ', ' ORDER BY created_at, book_id) AS book_ids
+        FROM books
+        WHERE isbn ~ '^[0-9]{13}$'
+        AND book_id NOT IN (SELECT book_id FROM valid_isbn_books)
+        GROUP BY user_id, isbn
+        HAVING count(*) > 1
+    ) t;
+
+    IF report IS NOT NULL THEN
+        RAISE EXCEPTION E'Duplicate books with invalid ISBN-13 must be resolved manually:\n%', report;
+    END IF;
+END $$;
+
+-- 正規化によって同じ所有者の同じ ISBN になった蔵書は、最も古く登録されたものに統合する
+CREATE TEMPORARY TABLE isbn_duplicates AS
+SELECT book_id, survivor_id
+FROM (
+    SELECT
+        book_id,
+        first_value(book_id) OVER (
+            PARTITION BY user_id, isbn
+            ORDER BY created_at, book_id
+        ) AS survivor_id
+    FROM books
+    WHERE book_id IN (SELECT book_id FROM valid_isbn_books)
+) t
+WHERE book_id <> survivor_id;
+
+UPDATE book_copies AS bc
+SET book_id = d.survivor_id
+FROM isbn_duplicates AS d
+WHERE bc.book_id = d.book_id;
+
+UPDATE checkouts AS c
+SET book_id = d.survivor_id
+FROM isbn_duplicates AS d
+WHERE c.book_id = d.book_id;
+
+UPDATE returned_checkouts AS rc
+SET book_id = d.survivor_id
+FROM isbn_duplicates AS d
+WHERE rc.book_id = d.book_id;
+
+-- 予約は利用者ごとに 1 件までのため、統合先で重なる予約は最も早いものだけを残す
+DELETE FROM reservations AS r
+USING isbn_duplicates AS d
+WHERE r.book_id = d.book_id
+AND EXISTS (
+    SELECT 1
+    FROM reservations AS r2
+    LEFT JOIN isbn_duplicates AS d2 ON d2.book_id = r2.book_id
+    WHERE r2.user_id = r.user_id
+    AND COALESCE(d2.survivor_id, r2.book_id) = d.survivor_id
+    AND (r2.reserved_at, r2.reservation_id) < (r.reserved_at, r.reservation_id)
+);
+
+-- 統合先の予約のほうが遅い場合は、統合先の予約を取り除いて統合元の予約を移す
+DELETE FROM reservations AS r
+WHERE r.book_id IN (SELECT survivor_id FROM isbn_duplicates)
+AND EXISTS (
+    SELECT 1
+    FROM reservations AS r2
+    JOIN isbn_duplicates AS d2 ON d2.book_id = r2.book_id
+    WHERE r2.user_id = r.user_id
+    AND d2.survivor_id = r.book_id
+);
+
+UPDATE reservations AS r
+SET book_id = d.survivor_id
+FROM isbn_duplicates AS d
+WHERE r.book_id = d.book_id;
+
+DELETE FROM books
+WHERE book_id IN (SELECT book_id FROM isbn_duplicates);
+
+DROP TABLE isbn_duplicates;
+DROP TABLE valid_isbn_books;
+
+-- 同じ所有者が同じ ISBN の蔵書を重複して登録できないようにする。
+-- 以前に登録された ISBN でない値は対象外とし、重複したまま残す
+CREATE UNIQUE INDEX books_user_id_isbn_key ON books (user_id, isbn)
+WHERE isbn ~ '^[0-9]{13}$';
@@ -0,0 +1,4 @@
+-- 表紙画像の形式と登録日時。画像そのものは BlobStore に保存する
+ALTER TABLE books ADD COLUMN cover_content_type VARCHAR(64);
+
+ALTER TABLE books ADD COLUMN cover_updated_at TIMESTAMP(3) WITH TIME ZONE;
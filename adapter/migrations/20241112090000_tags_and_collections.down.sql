@@ -0,0 +1,11 @@
+DROP TABLE IF EXISTS collection_books;
+
+DROP TRIGGER IF EXISTS collections_updated_at_trigger ON collections;
+
+DROP TABLE IF EXISTS collections;
+
+DROP TABLE IF EXISTS book_tags;
+
+DROP TRIGGER IF EXISTS tags_updated_at_trigger ON tags;
+
+DROP TABLE IF EXISTS tags;
@@ -0,0 +1,55 @@
+-- タグは全ユーザーで共有する語彙として扱い、大文字・小文字の違いだけの重複は許さない
+CREATE TABLE IF NOT EXISTS tags (
+    tag_id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+    name VARCHAR(64) NOT NULL,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS tags_name_key ON tags (lower(name));
+
+CREATE TRIGGER tags_updated_at_trigger BEFORE
+UPDATE ON tags FOR EACH ROW
+EXECUTE PROCEDURE set_updated_at ();
+
+CREATE TABLE IF NOT EXISTS book_tags (
+    book_id UUID NOT NULL,
+    tag_id UUID NOT NULL,
+
+    PRIMARY KEY (book_id, tag_id),
+    FOREIGN KEY (book_id) REFERENCES books (book_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags (tag_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS book_tags_tag_id_idx ON book_tags (tag_id);
+
+-- コレクションは作成したユーザーが所有し、共有した場合は他のユーザーも参照・蔵書の出し入れができる
+CREATE TABLE IF NOT EXISTS collections (
+    collection_id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+    user_id UUID NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    shared BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+    FOREIGN KEY (user_id) REFERENCES users (user_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS collections_user_id_idx ON collections (user_id);
+
+CREATE TRIGGER collections_updated_at_trigger BEFORE
+UPDATE ON collections FOR EACH ROW
+EXECUTE PROCEDURE set_updated_at ();
+
+CREATE TABLE IF NOT EXISTS collection_books (
+    collection_id UUID NOT NULL,
+    book_id UUID NOT NULL,
+    added_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+    PRIMARY KEY (collection_id, book_id),
+    FOREIGN KEY (collection_id) REFERENCES collections (collection_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (book_id) REFERENCES books (book_id) ON UPDATE CASCADE ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS collection_books_book_id_idx ON collection_books (book_id);
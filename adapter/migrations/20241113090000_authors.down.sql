@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS book_authors;
+
+DROP TRIGGER IF EXISTS authors_updated_at_trigger ON authors;
+
+DROP TABLE IF EXISTS authors;
@@ -0,0 +1,58 @@
+-- 著者は蔵書をまたいで同じ人物として扱い、大文字・小文字の違いだけの重複は許さない
+CREATE TABLE IF NOT EXISTS authors (
+    author_id UUID PRIMARY KEY DEFAULT gen_random_uuid (),
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS authors_name_key ON authors (lower(name));
+
+CREATE TRIGGER authors_updated_at_trigger BEFORE
+UPDATE ON authors FOR EACH ROW
+EXECUTE PROCEDURE set_updated_at ();
+
+-- 蔵書ごとの著者の並び順と役割（著者・編者・訳者など）を持つ
+CREATE TABLE IF NOT EXISTS book_authors (
+    book_id UUID NOT NULL,
+    author_id UUID NOT NULL,
+    position INTEGER NOT NULL,
+    role VARCHAR(32) NOT NULL DEFAULT 'author',
+
+    PRIMARY KEY (book_id, position),
+    UNIQUE (book_id, author_id, role),
+    FOREIGN KEY (book_id) REFERENCES books (book_id) ON UPDATE CASCADE ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES authors (author_id) ON UPDATE CASCADE ON DELETE RESTRICT
+);
+
+CREATE INDEX IF NOT EXISTS book_authors_author_id_idx ON book_authors (author_id);
+
+-- 既存の蔵書の著者を区切り文字で分け、人物ごとに登録する。
+-- books.author は表示と全文検索のために残す。
+-- データベースの文字コードによってはマルチバイト文字をバイト単位で比べるため、区切り文字は文字クラスではなく選択で書く。
+CREATE TEMPORARY TABLE split_book_authors AS
+SELECT b.book_id, trim(n.name) AS name, n.ord
+FROM books AS b
+CROSS JOIN LATERAL regexp_split_to_table(b.author, '(,|;|、|，|；)') WITH ORDINALITY AS n (name, ord)
+WHERE trim(n.name) <> '';
+
+INSERT INTO authors (name)
+SELECT DISTINCT ON (lower(name)) name
+FROM split_book_authors
+ORDER BY lower(name), name
+ON CONFLICT DO NOTHING;
+
+INSERT INTO book_authors (book_id, author_id, position, role)
+SELECT
+    book_id,
+    author_id,
+    row_number() OVER (PARTITION BY book_id ORDER BY ord)::INTEGER,
+    'author'
+FROM (
+    SELECT DISTINCT ON (s.book_id, a.author_id) s.book_id, a.author_id, s.ord
+    FROM split_book_authors AS s
+    INNER JOIN authors AS a ON lower(a.name) = lower(s.name)
+    ORDER BY s.book_id, a.author_id, s.ord
+) AS distinct_authors;
+
+DROP TABLE split_book_authors;
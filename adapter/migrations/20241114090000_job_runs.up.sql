@@ -0,0 +1,5 @@
+-- 定期実行ジョブの最終実行日時。再起動やレプリカの追加で、間隔を待たずに再実行されないようにする
+CREATE TABLE IF NOT EXISTS job_runs (
+    name VARCHAR(64) PRIMARY KEY,
+    last_run_at TIMESTAMP(3) WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,7 @@
+-- 表紙画像はアップロードごとに異なるキーで保存し、現在の画像のキーを蔵書に持たせる
+ALTER TABLE books ADD COLUMN cover_key VARCHAR(255);
+
+-- 既存の表紙画像は、蔵書 ID から決まる従来のキーに保存されている
+UPDATE books
+SET cover_key = 'books/' || book_id || '/cover'
+WHERE cover_content_type IS NOT NULL;
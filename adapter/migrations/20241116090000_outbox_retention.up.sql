@@ -0,0 +1,3 @@
+-- 保持期間を過ぎた配信済みのイベントを削除するためのインデックス
+CREATE INDEX IF NOT EXISTS outbox_published_at_idx ON outbox (published_at)
+WHERE published_at IS NOT NULL;
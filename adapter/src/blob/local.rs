@@ -0,0 +1,97 @@
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use kernel::repository::blob::BlobStore;
+use shared::{
+    config::LocalBlobStoreConfig,
+    error::{AppError, AppResult},
+};
+
+/// 指定のディレクトリ以下に、キーをそのままパスとしてファイルを保存する。
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(config: &LocalBlobStoreConfig) -> Self {
+        Self {
+            root: PathBuf::from(&config.root),
+        }
+    }
+
+    /// 保存先のディレクトリの外を指すキーは受け付けない。
+    fn path_of(&self, key: &str) -> AppResult<PathBuf> {
+        let relative = Path::new(key);
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(AppError::BlobStoreError(format!("Invalid blob key: {key}")));
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, _content_type: &str, data: Vec<u8>) -> AppResult<()> {
+        let path = self.path_of(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| AppError::BlobStoreError(e.to_string()))?;
+        }
+        tokio::fs::write(&path, data)
+            .await
+            .map_err(|e| AppError::BlobStoreError(e.to_string()))
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path_of(key)?).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(AppError::BlobStoreError(e.to_string())),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        match tokio::fs::remove_file(self.path_of(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::BlobStoreError(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_put_get_delete_on_local_filesystem() -> anyhow::Result<()> {
+        let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let store = LocalBlobStore::new(&LocalBlobStoreConfig {
+            root: root.to_string_lossy().into_owned(),
+        });
+
+        store
+            .put("books/1/cover", "image/png", b"png".to_vec())
+            .await?;
+        assert_eq!(store.get("books/1/cover").await?, Some(b"png".to_vec()));
+
+        store.delete("books/1/cover").await?;
+        assert_eq!(store.get("books/1/cover").await?, None);
+        // 既に削除したキーを指定してもエラーにならない
+        store.delete("books/1/cover").await?;
+
+        assert!(store.get("../outside").await.is_err());
+
+        tokio::fs::remove_dir_all(root).await?;
+
+        Ok(())
+    }
+}
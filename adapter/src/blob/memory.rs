@@ -0,0 +1,38 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use async_trait::async_trait;
+use kernel::repository::blob::BlobStore;
+use shared::error::AppResult;
+
+/// データをメモリ上に保持するだけの保存先。テストで保存内容を検証するために使う。
+#[derive(Default)]
+pub struct InMemoryBlobStore {
+    blobs: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl InMemoryBlobStore {
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.blobs.lock().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+}
+
+#[async_trait]
+impl BlobStore for InMemoryBlobStore {
+    async fn put(&self, key: &str, _content_type: &str, data: Vec<u8>) -> AppResult<()> {
+        self.blobs.lock().unwrap().insert(key.to_string(), data);
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>> {
+        Ok(self.blobs.lock().unwrap().get(key).cloned())
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        self.blobs.lock().unwrap().remove(key);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use kernel::repository::blob::BlobStore;
+use shared::{config::BlobStoreConfig, error::AppResult};
+
+pub mod local;
+pub mod memory;
+pub mod s3;
+
+/// 設定に応じたファイルの保存先を生成する。
+pub fn build_blob_store(config: &BlobStoreConfig) -> AppResult<Arc<dyn BlobStore>> {
+    let store: Arc<dyn BlobStore> = match config {
+        BlobStoreConfig::Local(cfg) => Arc::new(local::LocalBlobStore::new(cfg)),
+        BlobStoreConfig::S3(cfg) => Arc::new(s3::S3BlobStore::new(cfg)?),
+    };
+
+    Ok(store)
+}
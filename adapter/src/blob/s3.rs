@@ -0,0 +1,298 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use kernel::repository::blob::BlobStore;
+use reqwest::{Method, StatusCode, Url};
+use sha2::{Digest, Sha256};
+use shared::{
+    config::S3Config,
+    error::{AppError, AppResult},
+};
+
+/// S3 互換のオブジェクトストレージに保存する。リクエストには署名バージョン 4 で署名する。
+pub struct S3BlobStore {
+    client: reqwest::Client,
+    endpoint: Url,
+    region: String,
+    bucket: String,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+impl S3BlobStore {
+    /// 表紙画像の読み書きはリクエストの処理の中で行うため、ストレージが応答しなくても API を待たせ続けないよう上限を設ける。
+    pub fn new(config: &S3Config) -> AppResult<Self> {
+        let endpoint = Url::parse(&config.endpoint)
+            .map_err(|e| AppError::BlobStoreError(format!("{}: {}", config.endpoint, e)))?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .connect_timeout(Duration::from_secs(config.connect_timeout))
+            .build()
+            .map_err(|e| AppError::BlobStoreError(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            region: config.region.clone(),
+            bucket: config.bucket.clone(),
+            access_key_id: config.access_key_id.clone(),
+            secret_access_key: config.secret_access_key.clone(),
+        })
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        content_type: Option<&str>,
+        body: Vec<u8>,
+    ) -> AppResult<reqwest::Response> {
+        let path = format!("/{}/{}", self.bucket, encode_key(key));
+        let mut url = self.endpoint.clone();
+        url.set_path(&path);
+
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let payload_hash = format!("{:x}", Sha256::digest(&body));
+        let now = Utc::now();
+        let authorization = self.authorization(&method, &path, &host, &payload_hash, now);
+
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header("x-amz-date", amz_date(now))
+            .header("authorization", authorization);
+        if let Some(content_type) = content_type {
+            request = request.header("content-type", content_type);
+        }
+
+        request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| AppError::BlobStoreError(e.to_string()))
+    }
+
+    fn authorization(
+        &self,
+        method: &Method,
+        path: &str,
+        host: &str,
+        payload_hash: &str,
+        now: DateTime<Utc>,
+    ) -> String {
+        const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+        let amz_date = amz_date(now);
+        let date = now.format("%Y%m%d").to_string();
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method, path, host, payload_hash, amz_date, SIGNED_HEADERS, payload_hash
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+            amz_date,
+            scope,
+            Sha256::digest(canonical_request.as_bytes())
+        );
+
+        let signing_key = [self.region.as_str(), "s3", "aws4_request"].iter().fold(
+            hmac_sha256(
+                format!("AWS4{}", self.secret_access_key).as_bytes(),
+                date.as_bytes(),
+            ),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+        let signature = hmac_sha256(&signing_key, string_to_sign.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key_id, scope, SIGNED_HEADERS, signature
+        )
+    }
+}
+
+fn amz_date(now: DateTime<Utc>) -> String {
+    now.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC can take key of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// キーを URI エンコードする。区切りの `/` はそのまま残す。
+fn encode_key(key: &str) -> String {
+    key.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, content_type: &str, data: Vec<u8>) -> AppResult<()> {
+        self.send(Method::PUT, key, Some(content_type), data)
+            .await?
+            .error_for_status()
+            .map_err(|e| AppError::BlobStoreError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>> {
+        let res = self.send(Method::GET, key, None, Vec::new()).await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let data = res
+            .error_for_status()
+            .map_err(|e| AppError::BlobStoreError(e.to_string()))?
+            .bytes()
+            .await
+            .map_err(|e| AppError::BlobStoreError(e.to_string()))?;
+
+        Ok(Some(data.to_vec()))
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        // S3 は存在しないキーを削除しても 204 を返すが、互換サービスによっては 404 を返す
+        let res = self.send(Method::DELETE, key, None, Vec::new()).await?;
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        res.error_for_status()
+            .map_err(|e| AppError::BlobStoreError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{
+        body::Bytes,
+        extract::{Path, State},
+        http::{HeaderMap, StatusCode as AxumStatusCode},
+        routing::put,
+        Router,
+    };
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    type Objects = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+    /// 署名付きのリクエストだけを受け付ける、S3 の代わりの簡易なサーバー。
+    fn stand_in(objects: Objects) -> Router {
+        fn is_signed(headers: &HeaderMap) -> bool {
+            headers
+                .get("authorization")
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| {
+                    v.starts_with("AWS4-HMAC-SHA256 Credential=minio/")
+                        && v.contains("/us-east-1/s3/aws4_request")
+                })
+                && headers.contains_key("x-amz-date")
+                && headers.contains_key("x-amz-content-sha256")
+        }
+
+        Router::new()
+            .route(
+                "/:bucket/*key",
+                put(
+                    |State(objects): State<Objects>,
+                     Path((bucket, key)): Path<(String, String)>,
+                     headers: HeaderMap,
+                     body: Bytes| async move {
+                        if !is_signed(&headers) {
+                            return AxumStatusCode::FORBIDDEN;
+                        }
+                        objects
+                            .lock()
+                            .unwrap()
+                            .insert(format!("{bucket}/{key}"), body.to_vec());
+                        AxumStatusCode::OK
+                    },
+                )
+                .get(
+                    |State(objects): State<Objects>,
+                     Path((bucket, key)): Path<(String, String)>,
+                     headers: HeaderMap| async move {
+                        if !is_signed(&headers) {
+                            return Err(AxumStatusCode::FORBIDDEN);
+                        }
+                        objects
+                            .lock()
+                            .unwrap()
+                            .get(&format!("{bucket}/{key}"))
+                            .cloned()
+                            .ok_or(AxumStatusCode::NOT_FOUND)
+                    },
+                )
+                .delete(
+                    |State(objects): State<Objects>,
+                     Path((bucket, key)): Path<(String, String)>,
+                     headers: HeaderMap| async move {
+                        if !is_signed(&headers) {
+                            return AxumStatusCode::FORBIDDEN;
+                        }
+                        objects.lock().unwrap().remove(&format!("{bucket}/{key}"));
+                        AxumStatusCode::NO_CONTENT
+                    },
+                ),
+            )
+            .with_state(objects)
+    }
+
+    #[tokio::test]
+    async fn test_put_get_delete_on_s3_compatible_storage() -> anyhow::Result<()> {
+        let objects = Objects::default();
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let app = stand_in(objects.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let store = S3BlobStore::new(&S3Config {
+            endpoint: format!("http://{}", addr),
+            region: "us-east-1".into(),
+            bucket: "covers".into(),
+            access_key_id: "minio".into(),
+            secret_access_key: "minio-secret".into(),
+            timeout: 5,
+            connect_timeout: 5,
+        })?;
+
+        store
+            .put("books/1/cover", "image/png", b"png".to_vec())
+            .await?;
+        assert!(objects.lock().unwrap().contains_key("covers/books/1/cover"));
+        assert_eq!(store.get("books/1/cover").await?, Some(b"png".to_vec()));
+
+        store.delete("books/1/cover").await?;
+        assert_eq!(store.get("books/1/cover").await?, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{model::catalog::CatalogEntry, repository::catalog::CatalogLookup};
+use shared::error::AppResult;
+
+use crate::{
+    database::model::catalog::{CatalogCacheKey, CatalogCacheValue},
+    redis::RedisClient,
+};
+
+/// 問い合わせ結果を Redis にキャッシュする。
+/// 見つからなかった結果もキャッシュし、同じ ISBN で外部サービスに繰り返し問い合わせないようにする。
+#[derive(new)]
+pub struct CachedCatalogLookup {
+    inner: Arc<dyn CatalogLookup>,
+    kv: Arc<RedisClient>,
+    ttl: u64,
+}
+
+#[async_trait]
+impl CatalogLookup for CachedCatalogLookup {
+    async fn find_by_isbn(&self, isbn: &str) -> AppResult<Option<CatalogEntry>> {
+        let key = CatalogCacheKey::new(isbn);
+
+        // キャッシュを読み書きできなくても、問い合わせ自体は続ける
+        match self.kv.get(&key).await {
+            Ok(Some(cached)) => return Ok(cached.into_inner()),
+            Ok(None) => {}
+            Err(e) => tracing::warn!(
+                error.message = %e,
+                "Failed to read catalog cache"
+            ),
+        }
+
+        let entry = self.inner.find_by_isbn(isbn).await?;
+
+        if let Err(e) = self
+            .kv
+            .set_with_ex(&key, &CatalogCacheValue::new(entry.clone()), self.ttl)
+            .await
+        {
+            tracing::warn!(
+                error.message = %e,
+                "Failed to write catalog cache"
+            );
+        }
+
+        Ok(entry)
+    }
+}
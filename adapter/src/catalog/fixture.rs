@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use kernel::{
+    model::{book::isbn::normalize_isbn, catalog::CatalogEntry},
+    repository::catalog::CatalogLookup,
+};
+use shared::{
+    config::CatalogFixtureConfig,
+    error::{AppError, AppResult},
+};
+
+/// あらかじめ用意した書誌情報から検索する。外部サービスに接続できない開発環境やテストで使う。
+pub struct FixtureCatalogLookup {
+    entries: HashMap<String, CatalogEntry>,
+}
+
+impl FixtureCatalogLookup {
+    pub fn new(entries: Vec<CatalogEntry>) -> Self {
+        let entries = entries
+            .into_iter()
+            .map(|mut entry| {
+                if let Some(isbn) = normalize_isbn(&entry.isbn) {
+                    entry.isbn = isbn;
+                }
+                (entry.isbn.clone(), entry)
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    /// `CatalogEntry` の配列を JSON として記述したファイルから読み込む。
+    pub fn from_file(config: &CatalogFixtureConfig) -> AppResult<Self> {
+        let content = std::fs::read_to_string(&config.path)
+            .map_err(|e| AppError::CatalogLookupError(format!("{}: {}", config.path, e)))?;
+        let entries: Vec<CatalogEntry> = serde_json::from_str(&content)
+            .map_err(|e| AppError::CatalogLookupError(format!("{}: {}", config.path, e)))?;
+
+        Ok(Self::new(entries))
+    }
+}
+
+#[async_trait]
+impl CatalogLookup for FixtureCatalogLookup {
+    async fn find_by_isbn(&self, isbn: &str) -> AppResult<Option<CatalogEntry>> {
+        Ok(self.entries.get(isbn).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_find_by_isbn_from_fixture() -> anyhow::Result<()> {
+        let lookup = FixtureCatalogLookup::new(vec![CatalogEntry {
+            isbn: "978-4-06-536957-9".into(),
+            title: "RustによるWebアプリケーション開発".into(),
+            author: "豊田優貴他".into(),
+            description: "".into(),
+        }]);
+
+        // 用意した書誌情報の ISBN は ISBN-13 に揃えてから照合する
+        let entry = lookup.find_by_isbn("9784065369579").await?.unwrap();
+        assert_eq!(entry.isbn, "9784065369579");
+        assert!(lookup.find_by_isbn("9784798061702").await?.is_none());
+
+        Ok(())
+    }
+}
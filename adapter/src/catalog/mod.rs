@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use kernel::repository::catalog::CatalogLookup;
+use shared::{
+    config::{CatalogConfig, CatalogProviderConfig},
+    error::AppResult,
+};
+
+use crate::redis::RedisClient;
+
+pub mod cache;
+pub mod fixture;
+pub mod open_library;
+
+/// 設定に応じた書誌情報の問い合わせ先を生成し、Redis によるキャッシュを挟む。
+pub fn build_catalog_lookup(
+    config: &CatalogConfig,
+    kv: Arc<RedisClient>,
+) -> AppResult<Arc<dyn CatalogLookup>> {
+    let provider: Arc<dyn CatalogLookup> = match &config.provider {
+        CatalogProviderConfig::OpenLibrary(cfg) => {
+            Arc::new(open_library::OpenLibraryCatalogLookup::new(cfg)?)
+        }
+        CatalogProviderConfig::Fixture(cfg) => {
+            Arc::new(fixture::FixtureCatalogLookup::from_file(cfg)?)
+        }
+    };
+
+    Ok(Arc::new(cache::CachedCatalogLookup::new(
+        provider,
+        kv,
+        config.cache_ttl,
+    )))
+}
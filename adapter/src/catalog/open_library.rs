@@ -0,0 +1,193 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_trait::async_trait;
+use kernel::{model::catalog::CatalogEntry, repository::catalog::CatalogLookup};
+use serde::Deserialize;
+use shared::{
+    config::OpenLibraryConfig,
+    error::{AppError, AppResult},
+};
+
+/// OpenLibrary 互換の Books API（`/api/books?jscmd=data`）に問い合わせる。
+pub struct OpenLibraryCatalogLookup {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl OpenLibraryCatalogLookup {
+    /// 問い合わせ先が応答しなくても蔵書の登録を待たせ続けないよう、待ち時間に上限を設ける。
+    pub fn new(config: &OpenLibraryConfig) -> AppResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .connect_timeout(Duration::from_secs(config.connect_timeout))
+            .build()
+            .map_err(|e| AppError::CatalogLookupError(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+fn map_request_error(e: reqwest::Error) -> AppError {
+    if e.is_timeout() {
+        AppError::CatalogLookupError(format!("Catalog did not respond in time: {e}"))
+    } else {
+        AppError::CatalogLookupError(e.to_string())
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenLibraryBook {
+    title: String,
+    #[serde(default)]
+    authors: Vec<OpenLibraryAuthor>,
+    #[serde(default)]
+    notes: Option<OpenLibraryText>,
+    #[serde(default)]
+    excerpts: Vec<OpenLibraryExcerpt>,
+}
+
+#[derive(Deserialize)]
+struct OpenLibraryAuthor {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct OpenLibraryExcerpt {
+    text: String,
+}
+
+/// 文字列のまま返される場合と、`{ "type": ..., "value": ... }` の形で返される場合がある。
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OpenLibraryText {
+    Plain(String),
+    Typed { value: String },
+}
+
+impl OpenLibraryBook {
+    fn into_entry(self, isbn: &str) -> CatalogEntry {
+        let description = match self.notes {
+            Some(OpenLibraryText::Plain(text) | OpenLibraryText::Typed { value: text }) => text,
+            None => self
+                .excerpts
+                .into_iter()
+                .next()
+                .map(|excerpt| excerpt.text)
+                .unwrap_or_default(),
+        };
+
+        CatalogEntry {
+            isbn: isbn.to_string(),
+            title: self.title,
+            author: self
+                .authors
+                .into_iter()
+                .map(|author| author.name)
+                .collect::<Vec<_>>()
+                .join(", "),
+            description,
+        }
+    }
+}
+
+#[async_trait]
+impl CatalogLookup for OpenLibraryCatalogLookup {
+    async fn find_by_isbn(&self, isbn: &str) -> AppResult<Option<CatalogEntry>> {
+        let bibkey = format!("ISBN:{isbn}");
+        let mut books: HashMap<String, OpenLibraryBook> = self
+            .client
+            .get(format!("{}/api/books", self.base_url))
+            .query(&[
+                ("bibkeys", bibkey.as_str()),
+                ("format", "json"),
+                ("jscmd", "data"),
+            ])
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(map_request_error)?
+            .json()
+            .await
+            .map_err(map_request_error)?;
+
+        Ok(books.remove(&bibkey).map(|book| book.into_entry(isbn)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{extract::Query, routing::get, Json, Router};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_find_by_isbn_from_open_library() -> anyhow::Result<()> {
+        let app = Router::new().route(
+            "/api/books",
+            get(|Query(query): Query<HashMap<String, String>>| async move {
+                let body = match query.get("bibkeys").map(String::as_str) {
+                    Some("ISBN:9784065369579") => serde_json::json!({
+                        "ISBN:9784065369579": {
+                            "title": "RustによるWebアプリケーション開発",
+                            "authors": [{ "name": "豊田優貴" }, { "name": "松本健太郎" }],
+                            "notes": { "type": "/type/text", "value": "設計からリリース・運用まで" }
+                        }
+                    }),
+                    _ => serde_json::json!({}),
+                };
+                Json(body)
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let lookup = OpenLibraryCatalogLookup::new(&OpenLibraryConfig {
+            base_url: format!("http://{}/", addr),
+            timeout: 5,
+            connect_timeout: 5,
+        })?;
+
+        let entry = lookup.find_by_isbn("9784065369579").await?.unwrap();
+        assert_eq!(entry.isbn, "9784065369579");
+        assert_eq!(entry.title, "RustによるWebアプリケーション開発");
+        assert_eq!(entry.author, "豊田優貴, 松本健太郎");
+        assert_eq!(entry.description, "設計からリリース・運用まで");
+
+        assert!(lookup.find_by_isbn("9784798061702").await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_by_isbn_times_out() -> anyhow::Result<()> {
+        // 応答を返さないまま待たせる問い合わせ先
+        let app = Router::new().route(
+            "/api/books",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Json(serde_json::json!({}))
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let lookup = OpenLibraryCatalogLookup::new(&OpenLibraryConfig {
+            base_url: format!("http://{}/", addr),
+            timeout: 1,
+            connect_timeout: 1,
+        })?;
+
+        let started = std::time::Instant::now();
+        let res = lookup.find_by_isbn("9784065369579").await;
+        assert!(matches!(res, Err(AppError::CatalogLookupError(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use shared::{
     config::DatabaseConfig,
     error::{AppError, AppResult},
@@ -32,6 +33,98 @@ impl ConnectionPool {
     }
 }
 
+/// トランザクション分離レベルを SERIALIZABLE に設定することで結果の整合性を担保する。
+/// 貸出や予約のように、読み取った状態をもとに書き込みを行うトランザクションで呼び出す。
+/// @see https://www.postgresql.jp/document/16/html/transaction-iso.html
+pub async fn set_transaction_serializable(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+) -> AppResult<()> {
+    sqlx::query!("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
+/// 指定したキーでトランザクションレベルのアドバイザリロックの取得を試みる。
+/// ロックはトランザクションの終了時に自動的に解放される。
+pub async fn try_advisory_xact_lock(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    key: &str,
+) -> AppResult<bool> {
+    sqlx::query_scalar!(
+        r#"SELECT pg_try_advisory_xact_lock(hashtext($1)) AS "locked!""#,
+        key
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
+/// ジョブの最終実行日時を取得する。一度も実行されていない場合は `None` を返す。
+pub async fn find_job_last_run(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    name: &str,
+) -> AppResult<Option<DateTime<Utc>>> {
+    sqlx::query_scalar!(
+        r#"
+            SELECT last_run_at FROM job_runs
+            WHERE name = $1;
+        "#,
+        name
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
+/// ジョブの最終実行日時を記録する。
+pub async fn record_job_run(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    name: &str,
+    run_at: DateTime<Utc>,
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            INSERT INTO job_runs (name, last_run_at)
+            VALUES ($1, $2)
+            ON CONFLICT (name) DO UPDATE SET last_run_at = EXCLUDED.last_run_at;
+        "#,
+        name,
+        run_at
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
 pub fn connect_database_with(cfg: &DatabaseConfig) -> ConnectionPool {
     ConnectionPool(PgPool::connect_lazy_with(make_pg_connect_options(cfg)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test]
+    async fn test_advisory_lock_is_exclusive(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+
+        let mut tx1 = db.begin().await?;
+        let mut tx2 = db.begin().await?;
+        assert!(try_advisory_xact_lock(&mut tx1, "job").await?);
+        assert!(!try_advisory_xact_lock(&mut tx2, "job").await?);
+        // キーが異なればロックは競合しない
+        assert!(try_advisory_xact_lock(&mut tx2, "another-job").await?);
+
+        // トランザクションが終了するとロックは解放される
+        tx1.commit().await?;
+        let mut tx3 = db.begin().await?;
+        assert!(try_advisory_xact_lock(&mut tx3, "job").await?);
+
+        Ok(())
+    }
+}
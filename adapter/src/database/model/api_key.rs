@@ -0,0 +1,103 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    api_key::{ApiKey, ApiKeyPrincipal, ApiKeyScope},
+    id::{ApiKeyId, RoleId, UserId},
+    role::Role,
+    user::User,
+};
+use shared::error::{AppError, AppResult};
+
+use super::role::RoleRow;
+
+pub struct ApiKeyRow {
+    pub api_key_id: ApiKeyId,
+    pub user_id: UserId,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<ApiKeyRow> for ApiKey {
+    type Error = AppError;
+
+    fn try_from(value: ApiKeyRow) -> Result<Self, Self::Error> {
+        let ApiKeyRow {
+            api_key_id,
+            user_id,
+            name,
+            key_prefix,
+            scopes,
+            expires_at,
+            last_used_at,
+            created_at,
+        } = value;
+
+        Ok(ApiKey {
+            id: api_key_id,
+            user_id,
+            name,
+            prefix: key_prefix,
+            scopes: parse_scopes(&scopes)?,
+            expires_at,
+            last_used_at,
+            created_at,
+        })
+    }
+}
+
+pub struct ApiKeyPrincipalRow {
+    pub api_key_id: ApiKeyId,
+    pub scopes: Vec<String>,
+    pub user_id: UserId,
+    pub name: String,
+    pub email: String,
+    pub role_id: RoleId,
+    pub role_name: String,
+    pub role_permissions: Vec<String>,
+}
+
+impl TryFrom<ApiKeyPrincipalRow> for ApiKeyPrincipal {
+    type Error = AppError;
+
+    fn try_from(value: ApiKeyPrincipalRow) -> Result<Self, Self::Error> {
+        let ApiKeyPrincipalRow {
+            api_key_id,
+            scopes,
+            user_id,
+            name,
+            email,
+            role_id,
+            role_name,
+            role_permissions,
+        } = value;
+
+        Ok(ApiKeyPrincipal {
+            api_key_id,
+            user: User {
+                id: user_id,
+                name,
+                email,
+                role: Role::try_from(RoleRow {
+                    role_id,
+                    name: role_name,
+                    permissions: role_permissions,
+                })?,
+            },
+            scopes: parse_scopes(&scopes)?,
+        })
+    }
+}
+
+fn parse_scopes(scopes: &[String]) -> AppResult<Vec<ApiKeyScope>> {
+    scopes
+        .iter()
+        .map(|scope| {
+            ApiKeyScope::from_str(scope).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+        })
+        .collect()
+}
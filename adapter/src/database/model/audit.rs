@@ -0,0 +1,51 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    audit::{AuditAction, AuditLog},
+    id::{AuditLogId, UserId},
+};
+use shared::error::AppError;
+use sqlx::types::Uuid;
+
+pub struct PaginatedAuditLogRow {
+    pub total: i64,
+    pub audit_log_id: AuditLogId,
+    pub actor_id: UserId,
+    pub action: String,
+    pub target_id: Uuid,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl TryFrom<PaginatedAuditLogRow> for AuditLog {
+    type Error = AppError;
+
+    fn try_from(value: PaginatedAuditLogRow) -> Result<Self, Self::Error> {
+        let PaginatedAuditLogRow {
+            audit_log_id,
+            actor_id,
+            action,
+            target_id,
+            before,
+            after,
+            request_id,
+            occurred_at,
+            ..
+        } = value;
+
+        Ok(AuditLog {
+            id: audit_log_id,
+            actor_id,
+            action: AuditAction::from_str(action.as_str())
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            target_id,
+            before,
+            after,
+            request_id,
+            occurred_at,
+        })
+    }
+}
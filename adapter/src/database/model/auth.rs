@@ -1,11 +1,12 @@
-use std::str::FromStr;
+use std::{collections::HashMap, fmt, net::IpAddr, str::FromStr};
 
 use crate::redis::model::{RedisKey, RedisValue};
+use chrono::{DateTime, Utc};
 use kernel::model::{
-    auth::{event::CreateToken, AccessToken},
-    id::UserId,
+    auth::{AccessToken, LoginAttempt, RefreshToken, Session},
+    id::{SessionId, UserId},
 };
-use shared::error::AppError;
+use shared::error::{AppError, AppResult};
 
 pub struct UserItem {
     pub user_id: UserId,
@@ -15,11 +16,10 @@ pub struct UserItem {
 pub struct AuthorizationKey(String);
 pub struct AuthorizedUserId(UserId);
 
-pub fn from(event: CreateToken) -> (AuthorizationKey, AuthorizedUserId) {
-    (
-        AuthorizationKey(event.access_token),
-        AuthorizedUserId(event.user_id),
-    )
+impl AuthorizedUserId {
+    pub fn new(user_id: UserId) -> Self {
+        Self(user_id)
+    }
 }
 
 impl From<AuthorizationKey> for AccessToken {
@@ -69,3 +69,333 @@ impl AuthorizedUserId {
         self.0
     }
 }
+
+/// ログインごとに採番され、ローテーションで発行されたトークンをひとまとめにする ID。
+/// トークンファミリーはそのままセッションに対応する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenFamilyId(SessionId);
+
+impl TokenFamilyId {
+    pub fn into_inner(self) -> SessionId {
+        self.0
+    }
+}
+
+impl From<SessionId> for TokenFamilyId {
+    fn from(session_id: SessionId) -> Self {
+        Self(session_id)
+    }
+}
+
+impl RedisValue for TokenFamilyId {
+    fn inner(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl TryFrom<String> for TokenFamilyId {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Ok(Self(SessionId::from_str(&s)?))
+    }
+}
+
+/// アクセストークンから、それが属するトークンファミリーを引くためのキー
+pub struct AccessTokenFamilyKey(String);
+
+impl From<&AccessToken> for AccessTokenFamilyKey {
+    fn from(token: &AccessToken) -> Self {
+        Self(token.0.clone())
+    }
+}
+
+impl RedisKey for AccessTokenFamilyKey {
+    type Value = TokenFamilyId;
+
+    fn inner(&self) -> String {
+        format!("access_token_family:{}", self.0)
+    }
+}
+
+/// 未使用のリフレッシュトークンのキー
+pub struct RefreshTokenKey(String);
+
+impl From<&RefreshToken> for RefreshTokenKey {
+    fn from(token: &RefreshToken) -> Self {
+        Self(token.0.clone())
+    }
+}
+
+impl RedisKey for RefreshTokenKey {
+    type Value = RefreshTokenSession;
+
+    fn inner(&self) -> String {
+        format!("refresh_token:{}", self.0)
+    }
+}
+
+/// ローテーション済みのリフレッシュトークンのキー。再利用の検知に用いる。
+pub struct UsedRefreshTokenKey(String);
+
+impl From<&RefreshToken> for UsedRefreshTokenKey {
+    fn from(token: &RefreshToken) -> Self {
+        Self(token.0.clone())
+    }
+}
+
+impl RedisKey for UsedRefreshTokenKey {
+    type Value = TokenFamilyId;
+
+    fn inner(&self) -> String {
+        format!("used_refresh_token:{}", self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RefreshTokenSession {
+    pub user_id: UserId,
+    pub family_id: TokenFamilyId,
+}
+
+impl RedisValue for RefreshTokenSession {
+    fn inner(&self) -> String {
+        format!("{}:{}", self.user_id, self.family_id.inner())
+    }
+}
+
+impl TryFrom<String> for RefreshTokenSession {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let (user_id, family_id) = s.split_once(':').ok_or_else(|| {
+            AppError::ConversionEntityError(format!("Invalid refresh token session: {}", s))
+        })?;
+
+        Ok(Self {
+            user_id: UserId::from_str(user_id)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            family_id: TokenFamilyId::try_from(family_id.to_string())?,
+        })
+    }
+}
+
+/// トークンファミリーのキー。値には現在有効なアクセストークンを保持する。
+/// このキーが存在しないファミリーは失効済みとして扱う。
+pub struct TokenFamilyKey(TokenFamilyId);
+
+impl From<TokenFamilyId> for TokenFamilyKey {
+    fn from(family_id: TokenFamilyId) -> Self {
+        Self(family_id)
+    }
+}
+
+impl RedisKey for TokenFamilyKey {
+    type Value = CurrentAccessToken;
+
+    fn inner(&self) -> String {
+        format!("token_family:{}", self.0.inner())
+    }
+}
+
+pub struct CurrentAccessToken(String);
+
+impl From<&AccessToken> for CurrentAccessToken {
+    fn from(token: &AccessToken) -> Self {
+        Self(token.0.clone())
+    }
+}
+
+impl From<CurrentAccessToken> for AccessToken {
+    fn from(value: CurrentAccessToken) -> Self {
+        Self(value.0)
+    }
+}
+
+impl RedisValue for CurrentAccessToken {
+    fn inner(&self) -> String {
+        self.0.clone()
+    }
+}
+
+impl TryFrom<String> for CurrentAccessToken {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Ok(Self(s))
+    }
+}
+
+/// ユーザーごとのセッション ID の集合を保持するキー
+pub fn user_sessions_key(user_id: UserId) -> String {
+    format!("user_sessions:{}", user_id)
+}
+
+/// セッションのメタデータを保持するハッシュのキー
+pub fn session_key(session_id: SessionId) -> String {
+    format!("session:{}", session_id)
+}
+
+const USER_ID_FIELD: &str = "user_id";
+const USER_AGENT_FIELD: &str = "user_agent";
+const CREATED_AT_FIELD: &str = "created_at";
+pub const LAST_SEEN_AT_FIELD: &str = "last_seen_at";
+
+pub fn session_fields(session: &Session) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        (USER_ID_FIELD, session.user_id.to_string()),
+        (CREATED_AT_FIELD, session.created_at.to_rfc3339()),
+        (LAST_SEEN_AT_FIELD, session.last_seen_at.to_rfc3339()),
+    ];
+    if let Some(user_agent) = &session.user_agent {
+        fields.push((USER_AGENT_FIELD, user_agent.clone()));
+    }
+    fields
+}
+
+/// `HGETALL` の結果からセッションを復元する。キーが存在しない場合は `None` を返す。
+pub fn session_from_fields(
+    session_id: SessionId,
+    mut fields: HashMap<String, String>,
+) -> AppResult<Option<Session>> {
+    if fields.is_empty() {
+        return Ok(None);
+    }
+
+    let mut take = |name: &str| {
+        fields.remove(name).ok_or_else(|| {
+            AppError::ConversionEntityError(format!("Missing session field: {}", name))
+        })
+    };
+    let user_id = UserId::from_str(&take(USER_ID_FIELD)?)?;
+    let created_at = parse_datetime(&take(CREATED_AT_FIELD)?)?;
+    let last_seen_at = parse_datetime(&take(LAST_SEEN_AT_FIELD)?)?;
+
+    Ok(Some(Session {
+        id: session_id,
+        user_id,
+        user_agent: fields.remove(USER_AGENT_FIELD),
+        created_at,
+        last_seen_at,
+    }))
+}
+
+/// ログインの失敗を数える単位
+#[derive(Debug, PartialEq, Eq)]
+pub enum LoginAttemptScope {
+    Email(String),
+    Ip(IpAddr),
+}
+
+impl LoginAttemptScope {
+    /// メールアドレスは大文字・小文字や前後の空白の違いで制限をすり抜けられないよう正規化する
+    pub fn email(email: &str) -> Self {
+        Self::Email(email.trim().to_lowercase())
+    }
+
+    pub fn of(attempt: &LoginAttempt) -> Vec<Self> {
+        let mut scopes = vec![Self::email(&attempt.email)];
+        scopes.extend(attempt.client_ip.map(Self::Ip));
+        scopes
+    }
+}
+
+impl fmt::Display for LoginAttemptScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Email(email) => write!(f, "email:{}", email),
+            Self::Ip(ip) => write!(f, "ip:{}", ip),
+        }
+    }
+}
+
+/// 最後の失敗から一定時間内の、連続したログインの失敗回数を保持するキー
+pub fn login_failures_key(scope: &LoginAttemptScope) -> String {
+    format!("login_failures:{}", scope)
+}
+
+/// 存在する間はログインを受け付けないキー。失敗のたびに有効期限を延ばして設定する。
+pub fn login_backoff_key(scope: &LoginAttemptScope) -> String {
+    format!("login_backoff:{}", scope)
+}
+
+/// 存在する間はアカウントをロックするキー。ロックはメールアドレスに対してのみ行う。
+pub fn account_lock_key(scope: &LoginAttemptScope) -> String {
+    format!("account_lock:{}", scope)
+}
+
+fn parse_datetime(s: &str) -> AppResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_token_session_round_trip() -> anyhow::Result<()> {
+        let session = RefreshTokenSession {
+            user_id: UserId::new(),
+            family_id: SessionId::new().into(),
+        };
+
+        let restored = RefreshTokenSession::try_from(session.inner())?;
+        assert_eq!(restored, session);
+
+        assert!(RefreshTokenSession::try_from("invalid".to_string()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn session_fields_round_trip() -> anyhow::Result<()> {
+        let now = Utc::now();
+        let session = Session {
+            id: SessionId::new(),
+            user_id: UserId::new(),
+            user_agent: Some("curl/8.0".to_string()),
+            created_at: now,
+            last_seen_at: now,
+        };
+        let fields = session_fields(&session)
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+
+        let restored = session_from_fields(session.id, fields)?.expect("session should exist");
+        assert_eq!(restored.user_id, session.user_id);
+        assert_eq!(restored.user_agent, session.user_agent);
+        assert_eq!(restored.last_seen_at, session.last_seen_at);
+
+        assert!(session_from_fields(session.id, HashMap::new())?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn login_attempt_scopes_normalize_email() {
+        let attempt = LoginAttempt::new(
+            " Alice@Example.com ".to_string(),
+            Some("192.0.2.1".parse().unwrap()),
+        );
+
+        let scopes = LoginAttemptScope::of(&attempt);
+        assert_eq!(
+            scopes,
+            vec![
+                LoginAttemptScope::Email("alice@example.com".to_string()),
+                LoginAttemptScope::Ip("192.0.2.1".parse().unwrap()),
+            ]
+        );
+        assert_eq!(
+            login_failures_key(&scopes[0]),
+            "login_failures:email:alice@example.com"
+        );
+        assert_eq!(login_backoff_key(&scopes[1]), "login_backoff:ip:192.0.2.1");
+
+        let attempt = LoginAttempt::new("alice@example.com".to_string(), None);
+        assert_eq!(LoginAttemptScope::of(&attempt).len(), 1);
+    }
+}
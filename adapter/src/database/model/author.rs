@@ -0,0 +1,50 @@
+use std::str::FromStr;
+
+use kernel::model::{
+    author::{Author, AuthorRole, BookAuthor},
+    id::{AuthorId, BookId},
+};
+use shared::error::AppError;
+
+pub struct AuthorRow {
+    pub author_id: AuthorId,
+    pub name: String,
+}
+
+impl From<AuthorRow> for Author {
+    fn from(value: AuthorRow) -> Self {
+        let AuthorRow { author_id, name } = value;
+
+        Self {
+            id: author_id,
+            name,
+        }
+    }
+}
+
+pub struct BookAuthorRow {
+    pub book_id: BookId,
+    pub author_id: AuthorId,
+    pub name: String,
+    pub role: String,
+}
+
+impl TryFrom<BookAuthorRow> for BookAuthor {
+    type Error = AppError;
+
+    fn try_from(value: BookAuthorRow) -> Result<Self, Self::Error> {
+        let BookAuthorRow {
+            book_id: _,
+            author_id,
+            name,
+            role,
+        } = value;
+
+        Ok(Self {
+            id: author_id,
+            name,
+            role: AuthorRole::from_str(&role)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+        })
+    }
+}
@@ -1,9 +1,14 @@
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
 use kernel::model::{
-    book::{Book, Checkout},
-    id::{BookId, CheckoutId, UserId},
+    author::BookAuthor,
+    book::{Book, BookCopy, BookCover, BookListOptions, Checkout, CopyCondition, OwnerFacet},
+    id::{AuthorId, BookCopyId, BookId, CheckoutId, CollectionId, TagId, UserId},
+    tag::Tag,
     user::{BookOwner, CheckoutUser},
 };
+use shared::error::AppError;
 
 pub struct BookRow {
     pub book_id: BookId,
@@ -13,10 +18,18 @@ pub struct BookRow {
     pub description: String,
     pub owned_by: UserId,
     pub owner_name: String,
+    pub cover_content_type: Option<String>,
+    pub cover_updated_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
 }
 
 impl BookRow {
-    pub fn into_book(self, checkout: Option<Checkout>) -> Book {
+    pub fn into_book(
+        self,
+        authors: Vec<BookAuthor>,
+        copies: Vec<BookCopy>,
+        tags: Vec<Tag>,
+    ) -> Book {
         let BookRow {
             book_id,
             title,
@@ -25,19 +38,31 @@ impl BookRow {
             description,
             owned_by,
             owner_name,
+            cover_content_type,
+            cover_updated_at,
+            created_at,
         } = self;
 
         Book {
             id: book_id,
             title,
             author,
+            authors,
             isbn,
             description,
             owner: BookOwner {
                 id: owned_by,
                 name: owner_name,
             },
-            checkout,
+            copies,
+            tags,
+            cover: cover_content_type
+                .zip(cover_updated_at)
+                .map(|(content_type, updated_at)| BookCover {
+                    content_type,
+                    updated_at,
+                }),
+            created_at,
         }
     }
 }
@@ -47,31 +72,146 @@ pub struct PaginatedBookRow {
     pub id: BookId,
 }
 
-pub struct BookCheckoutRow {
-    pub checkout_id: CheckoutId,
-    pub book_id: BookId,
+/// 蔵書検索の絞り込み条件を SQL のパラメータとして渡せる形に変換したもの。
+pub struct BookSearchFilter {
+    pub query: Option<String>,
+    pub query_pattern: Option<String>,
+    pub owner_id: Option<UserId>,
+    pub checkout_status: Option<String>,
+    pub isbn_pattern: Option<String>,
+    pub tag_id: Option<TagId>,
+    pub collection_id: Option<CollectionId>,
+    pub author_id: Option<AuthorId>,
+    pub after_created_at: Option<DateTime<Utc>>,
+    pub after_book_id: Option<BookId>,
+}
+
+impl From<&BookListOptions> for BookSearchFilter {
+    fn from(value: &BookListOptions) -> Self {
+        let BookListOptions {
+            query,
+            owner_id,
+            checkout_status,
+            isbn_prefix,
+            tag_id,
+            collection_id,
+            author_id,
+            after,
+            ..
+        } = value;
+
+        Self {
+            query: query.clone(),
+            query_pattern: query.as_deref().map(|q| format!("%{}%", escape_like(q))),
+            owner_id: *owner_id,
+            checkout_status: checkout_status.map(|s| s.as_ref().to_string()),
+            // ISBN はハイフンを除いて保存しているため、前方一致の条件からも取り除く
+            isbn_pattern: isbn_prefix
+                .as_deref()
+                .map(|p| format!("{}%", escape_like(&p.replace('-', "")))),
+            tag_id: *tag_id,
+            collection_id: *collection_id,
+            author_id: *author_id,
+            after_created_at: after.map(|a| a.created_at),
+            after_book_id: after.map(|a| a.book_id),
+        }
+    }
+}
+
+/// LIKE 演算子で特別な意味を持つ文字をエスケープする。
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+pub struct BookStatusFacetRow {
+    pub available: i64,
+    pub checked_out: i64,
+}
+
+pub struct BookOwnerFacetRow {
     pub user_id: UserId,
-    pub user_name: String,
-    pub checked_out_at: DateTime<Utc>,
+    pub owner_name: String,
+    pub count: i64,
 }
 
-impl From<BookCheckoutRow> for Checkout {
-    fn from(value: BookCheckoutRow) -> Self {
-        let BookCheckoutRow {
-            checkout_id,
-            book_id: _,
+impl From<BookOwnerFacetRow> for OwnerFacet {
+    fn from(value: BookOwnerFacetRow) -> Self {
+        let BookOwnerFacetRow {
             user_id,
-            user_name,
-            checked_out_at,
+            owner_name,
+            count,
         } = value;
 
         Self {
-            checkout_id,
-            checked_out_by: CheckoutUser {
+            owner: BookOwner {
                 id: user_id,
-                name: user_name,
+                name: owner_name,
             },
-            checked_out_at,
+            count,
         }
     }
 }
+
+pub struct BookCopyRow {
+    pub copy_id: BookCopyId,
+    pub book_id: BookId,
+    pub barcode: String,
+    pub shelf_location: String,
+    pub condition: String,
+    pub checkout_id: Option<CheckoutId>,
+    pub user_id: Option<UserId>,
+    pub user_name: Option<String>,
+    pub checked_out_at: Option<DateTime<Utc>>,
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<BookCopyRow> for BookCopy {
+    type Error = AppError;
+
+    fn try_from(value: BookCopyRow) -> Result<Self, Self::Error> {
+        let BookCopyRow {
+            copy_id,
+            book_id: _,
+            barcode,
+            shelf_location,
+            condition,
+            checkout_id,
+            user_id,
+            user_name,
+            checked_out_at,
+            due_at,
+        } = value;
+
+        // 貸出中の複本のみ、貸出情報の各列が揃って入る
+        let checkout = match (checkout_id, user_id, user_name, checked_out_at, due_at) {
+            (
+                Some(checkout_id),
+                Some(user_id),
+                Some(user_name),
+                Some(checked_out_at),
+                Some(due_at),
+            ) => Some(Checkout {
+                checkout_id,
+                checked_out_by: CheckoutUser {
+                    id: user_id,
+                    name: user_name,
+                },
+                checked_out_at,
+                due_at,
+            }),
+            _ => None,
+        };
+
+        Ok(Self {
+            id: copy_id,
+            barcode,
+            shelf_location,
+            condition: CopyCondition::from_str(&condition)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            checkout,
+        })
+    }
+}
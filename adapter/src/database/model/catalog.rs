@@ -0,0 +1,50 @@
+use kernel::model::catalog::CatalogEntry;
+use shared::error::AppError;
+
+use crate::redis::model::{RedisKey, RedisValue};
+
+/// ISBN ごとの書誌情報の問い合わせ結果を保存するキー。
+pub struct CatalogCacheKey(String);
+
+/// 書誌情報の問い合わせ結果。見つからなかったことも `None` として保存する。
+pub struct CatalogCacheValue(Option<CatalogEntry>);
+
+impl CatalogCacheKey {
+    pub fn new(isbn: &str) -> Self {
+        Self(isbn.to_string())
+    }
+}
+
+impl RedisKey for CatalogCacheKey {
+    type Value = CatalogCacheValue;
+
+    fn inner(&self) -> String {
+        format!("catalog:isbn:{}", self.0)
+    }
+}
+
+impl CatalogCacheValue {
+    pub fn new(entry: Option<CatalogEntry>) -> Self {
+        Self(entry)
+    }
+
+    pub fn into_inner(self) -> Option<CatalogEntry> {
+        self.0
+    }
+}
+
+impl RedisValue for CatalogCacheValue {
+    fn inner(&self) -> String {
+        serde_json::to_string(&self.0).unwrap_or_else(|_| "null".to_string())
+    }
+}
+
+impl TryFrom<String> for CatalogCacheValue {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        serde_json::from_str(&s)
+            .map(Self)
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
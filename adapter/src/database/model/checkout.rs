@@ -1,11 +1,12 @@
 use chrono::{DateTime, Utc};
 use kernel::model::{
     checkout::{Checkout, CheckoutBook},
-    id::{BookId, CheckoutId, UserId},
+    id::{BookCopyId, BookId, CheckoutId, UserId},
 };
 
-pub struct CheckoutStateRow {
-    pub book_id: BookId,
+/// 蔵書の複本ごとの貸出状況。貸出中でなければ `checkout_id` と `user_id` は `None` になる
+pub struct CopyStateRow {
+    pub copy_id: BookCopyId,
     pub checkout_id: Option<CheckoutId>,
     pub user_id: Option<UserId>,
 }
@@ -13,8 +14,12 @@ pub struct CheckoutStateRow {
 pub struct CheckoutRow {
     pub checkout_id: CheckoutId,
     pub book_id: BookId,
+    pub copy_id: BookCopyId,
     pub user_id: UserId,
+    pub processed_by: UserId,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i32,
     pub title: String,
     pub author: String,
     pub isbn: String,
@@ -25,8 +30,12 @@ impl From<CheckoutRow> for Checkout {
         let CheckoutRow {
             checkout_id,
             book_id,
+            copy_id,
             user_id,
+            processed_by,
             checked_out_at,
+            due_at,
+            renewal_count,
             title,
             author,
             isbn,
@@ -35,10 +44,15 @@ impl From<CheckoutRow> for Checkout {
         Self {
             id: checkout_id,
             checked_out_by: user_id,
+            processed_by,
             checked_out_at,
+            due_at,
+            renewal_count,
             returned_at: None, // 未返却なので、返却日時データは入らない
+            returned_by: None,
             book: CheckoutBook {
                 book_id,
+                copy_id: Some(copy_id),
                 title,
                 author,
                 isbn,
@@ -50,9 +64,14 @@ impl From<CheckoutRow> for Checkout {
 pub struct ReturnedCheckoutRow {
     pub checkout_id: CheckoutId,
     pub book_id: BookId,
+    pub copy_id: Option<BookCopyId>,
     pub user_id: UserId,
+    pub processed_by: UserId,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i32,
     pub returned_at: DateTime<Utc>,
+    pub returned_by: UserId,
     pub title: String,
     pub author: String,
     pub isbn: String,
@@ -63,9 +82,14 @@ impl From<ReturnedCheckoutRow> for Checkout {
         let ReturnedCheckoutRow {
             checkout_id,
             book_id,
+            copy_id,
             user_id,
+            processed_by,
             checked_out_at,
+            due_at,
+            renewal_count,
             returned_at,
+            returned_by,
             title,
             author,
             isbn,
@@ -75,9 +99,14 @@ impl From<ReturnedCheckoutRow> for Checkout {
             id: checkout_id,
             checked_out_at,
             checked_out_by: user_id,
+            processed_by,
+            due_at,
+            renewal_count,
             returned_at: Some(returned_at), // 返却済みなので、必ず日時データが入る
+            returned_by: Some(returned_by),
             book: CheckoutBook {
                 book_id,
+                copy_id,
                 title,
                 author,
                 isbn,
@@ -85,3 +114,10 @@ impl From<ReturnedCheckoutRow> for Checkout {
         }
     }
 }
+
+pub struct CheckoutRenewalRow {
+    pub user_id: UserId,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i32,
+    pub has_reservations: bool,
+}
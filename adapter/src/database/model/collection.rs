@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    collection::{Collection, CollectionOwner},
+    id::{CollectionId, UserId},
+};
+
+pub struct CollectionRow {
+    pub collection_id: CollectionId,
+    pub name: String,
+    pub description: String,
+    pub shared: bool,
+    pub owned_by: UserId,
+    pub owner_name: String,
+    pub book_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<CollectionRow> for Collection {
+    fn from(value: CollectionRow) -> Self {
+        let CollectionRow {
+            collection_id,
+            name,
+            description,
+            shared,
+            owned_by,
+            owner_name,
+            book_count,
+            created_at,
+            updated_at,
+        } = value;
+
+        Self {
+            id: collection_id,
+            name,
+            description,
+            owner: CollectionOwner {
+                id: owned_by,
+                name: owner_name,
+            },
+            shared,
+            book_count,
+            created_at,
+            updated_at,
+        }
+    }
+}
@@ -1,4 +1,14 @@
+pub mod api_key;
+pub mod audit;
 pub mod auth;
+pub mod author;
 pub mod book;
+pub mod catalog;
 pub mod checkout;
+pub mod collection;
+pub mod notification;
+pub mod outbox;
+pub mod reservation;
+pub mod role;
+pub mod tag;
 pub mod user;
@@ -0,0 +1,29 @@
+use std::str::FromStr;
+
+use kernel::model::notification::{NotificationKind, NotificationPreference};
+use shared::error::AppError;
+
+pub struct NotificationPreferenceRow {
+    pub kind: String,
+    pub enabled: bool,
+}
+
+impl TryFrom<NotificationPreferenceRow> for NotificationPreference {
+    type Error = AppError;
+
+    fn try_from(value: NotificationPreferenceRow) -> Result<Self, Self::Error> {
+        let NotificationPreferenceRow { kind, enabled } = value;
+
+        Ok(NotificationPreference {
+            kind: NotificationKind::from_str(kind.as_str())
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            enabled,
+        })
+    }
+}
+
+pub struct NotificationRecipientRow {
+    pub name: String,
+    pub email: String,
+    pub enabled: Option<bool>,
+}
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use kernel::model::outbox::OutboxEvent;
+use sqlx::types::Uuid;
+
+pub struct OutboxEventRow {
+    pub outbox_id: i64,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub event_type: String,
+    pub payload: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl From<OutboxEventRow> for OutboxEvent {
+    fn from(value: OutboxEventRow) -> Self {
+        let OutboxEventRow {
+            outbox_id,
+            aggregate_type,
+            aggregate_id,
+            event_type,
+            payload,
+            occurred_at,
+        } = value;
+
+        Self {
+            id: outbox_id,
+            aggregate_type,
+            aggregate_id,
+            event_type,
+            payload,
+            occurred_at,
+        }
+    }
+}
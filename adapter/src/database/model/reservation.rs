@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    id::{BookId, ReservationId, UserId},
+    reservation::Reservation,
+};
+
+pub struct ReservationRow {
+    pub reservation_id: ReservationId,
+    pub book_id: BookId,
+    pub user_id: UserId,
+    pub reserved_at: DateTime<Utc>,
+    pub position: i64,
+    pub pickup_deadline: Option<DateTime<Utc>>,
+}
+
+impl From<ReservationRow> for Reservation {
+    fn from(value: ReservationRow) -> Self {
+        let ReservationRow {
+            reservation_id,
+            book_id,
+            user_id,
+            reserved_at,
+            position,
+            pickup_deadline,
+        } = value;
+
+        Self {
+            id: reservation_id,
+            book_id,
+            reserved_by: user_id,
+            reserved_at,
+            position,
+            pickup_deadline,
+        }
+    }
+}
+
+pub struct ReservationHoldRow {
+    pub reservation_id: ReservationId,
+    pub user_id: UserId,
+    pub pickup_deadline: DateTime<Utc>,
+}
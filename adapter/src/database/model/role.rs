@@ -0,0 +1,43 @@
+use std::str::FromStr;
+
+use kernel::model::{
+    id::RoleId,
+    role::{Permission, Role},
+};
+use shared::error::{AppError, AppResult};
+
+pub struct RoleRow {
+    pub role_id: RoleId,
+    pub name: String,
+    pub permissions: Vec<String>,
+}
+
+impl TryFrom<RoleRow> for Role {
+    type Error = AppError;
+
+    fn try_from(value: RoleRow) -> Result<Self, Self::Error> {
+        let RoleRow {
+            role_id,
+            name,
+            permissions,
+        } = value;
+
+        Ok(Role {
+            id: role_id,
+            name,
+            permissions: parse_permissions(&permissions)?,
+        })
+    }
+}
+
+pub fn parse_permissions(permissions: &[String]) -> AppResult<Vec<Permission>> {
+    let mut permissions = permissions
+        .iter()
+        .map(|permission| {
+            Permission::from_str(permission)
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+    permissions.sort();
+    Ok(permissions)
+}
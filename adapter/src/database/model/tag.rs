@@ -0,0 +1,23 @@
+use kernel::model::{
+    id::{BookId, TagId},
+    tag::Tag,
+};
+
+pub struct TagRow {
+    pub tag_id: TagId,
+    pub name: String,
+}
+
+impl From<TagRow> for Tag {
+    fn from(value: TagRow) -> Self {
+        let TagRow { tag_id, name } = value;
+
+        Self { id: tag_id, name }
+    }
+}
+
+pub struct BookTagRow {
+    pub book_id: BookId,
+    pub tag_id: TagId,
+    pub name: String,
+}
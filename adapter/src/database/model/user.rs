@@ -1,14 +1,20 @@
-use std::str::FromStr;
-
 use chrono::{DateTime, Utc};
-use kernel::model::{id::UserId, role::Role, user::User};
+use kernel::model::{
+    id::{RoleId, UserId},
+    role::Role,
+    user::User,
+};
 use shared::error::AppError;
 
+use super::role::RoleRow;
+
 pub struct UserRow {
     pub user_id: UserId,
     pub name: String,
     pub email: String,
+    pub role_id: RoleId,
     pub role_name: String,
+    pub role_permissions: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -21,7 +27,9 @@ impl TryFrom<UserRow> for User {
             user_id,
             name,
             email,
+            role_id,
             role_name,
+            role_permissions,
             ..
         } = value;
 
@@ -29,8 +37,11 @@ impl TryFrom<UserRow> for User {
             id: user_id,
             name,
             email,
-            role: Role::from_str(role_name.as_str())
-                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            role: Role::try_from(RoleRow {
+                role_id,
+                name: role_name,
+                permissions: role_permissions,
+            })?,
         })
     }
 }
@@ -0,0 +1,26 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use kernel::{model::outbox::OutboxEvent, repository::outbox::EventPublisher};
+use shared::error::AppResult;
+
+/// 配信したイベントをメモリ上に保持するだけの配信手段。テストで配信内容を検証するために使う。
+#[derive(Default)]
+pub struct InMemoryEventPublisher {
+    published: Mutex<Vec<OutboxEvent>>,
+}
+
+impl InMemoryEventPublisher {
+    pub fn published(&self) -> Vec<OutboxEvent> {
+        self.published.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl EventPublisher for InMemoryEventPublisher {
+    async fn publish(&self, event: &OutboxEvent) -> AppResult<()> {
+        self.published.lock().unwrap().push(event.clone());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{model::outbox::OutboxEvent, repository::outbox::EventPublisher};
+use shared::error::AppResult;
+
+use crate::redis::RedisClient;
+
+/// イベントを配信する Redis Streams のキー。下流のサービスはこのストリームを購読する。
+pub const LIBRARY_EVENT_STREAM: &str = "library:events";
+
+/// アウトボックスのイベントを Redis Streams に追加する配信手段。
+#[derive(new)]
+pub struct RedisStreamEventPublisher {
+    kv: Arc<RedisClient>,
+}
+
+#[async_trait]
+impl EventPublisher for RedisStreamEventPublisher {
+    async fn publish(&self, event: &OutboxEvent) -> AppResult<()> {
+        let id = event.id.to_string();
+        let aggregate_id = event.aggregate_id.to_string();
+        let occurred_at = event.occurred_at.to_rfc3339();
+
+        self.kv
+            .xadd(
+                LIBRARY_EVENT_STREAM,
+                &[
+                    ("id", id.as_str()),
+                    ("aggregate_type", event.aggregate_type.as_str()),
+                    ("aggregate_id", aggregate_id.as_str()),
+                    ("event_type", event.event_type.as_str()),
+                    ("payload", event.payload.as_str()),
+                    ("occurred_at", occurred_at.as_str()),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
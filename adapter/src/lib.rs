@@ -1,3 +1,10 @@
+pub mod blob;
+pub mod catalog;
 pub mod database;
+pub mod event;
+pub mod notification;
+pub mod oidc;
 pub mod redis;
 pub mod repository;
+pub mod token;
+pub mod totp;
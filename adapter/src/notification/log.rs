@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use kernel::{model::notification::Notification, repository::notification::NotificationSender};
+use shared::error::AppResult;
+
+/// 通知をログに出力するだけの送信手段。開発環境での利用を想定している。
+pub struct LogNotificationSender;
+
+#[async_trait]
+impl NotificationSender for LogNotificationSender {
+    async fn send(&self, notification: &Notification) -> AppResult<()> {
+        tracing::info!(
+            user_id = %notification.user_id,
+            kind = notification.kind.as_ref(),
+            subject = %notification.subject,
+            "Notification"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use kernel::{model::notification::Notification, repository::notification::NotificationSender};
+use shared::error::AppResult;
+
+/// 送信した通知をメモリ上に保持するだけの送信手段。テストで送信内容を検証するために使う。
+#[derive(Default)]
+pub struct InMemoryNotificationSender {
+    sent: Mutex<Vec<Notification>>,
+}
+
+impl InMemoryNotificationSender {
+    pub fn sent(&self) -> Vec<Notification> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl NotificationSender for InMemoryNotificationSender {
+    async fn send(&self, notification: &Notification) -> AppResult<()> {
+        self.sent.lock().unwrap().push(notification.clone());
+
+        Ok(())
+    }
+}
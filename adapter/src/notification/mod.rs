@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use kernel::repository::notification::NotificationSender;
+use shared::{config::NotificationConfig, error::AppResult};
+
+pub mod log;
+pub mod memory;
+pub mod smtp;
+pub mod webhook;
+
+/// 設定に応じた通知の送信手段を生成する。
+pub fn build_notification_sender(
+    config: &NotificationConfig,
+) -> AppResult<Arc<dyn NotificationSender>> {
+    let sender: Arc<dyn NotificationSender> = match config {
+        NotificationConfig::Log => Arc::new(log::LogNotificationSender),
+        NotificationConfig::Smtp(cfg) => Arc::new(smtp::SmtpNotificationSender::new(cfg)?),
+        NotificationConfig::Webhook(cfg) => Arc::new(webhook::WebhookNotificationSender::new(cfg)?),
+    };
+
+    Ok(sender)
+}
@@ -0,0 +1,212 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use kernel::{model::notification::Notification, repository::notification::NotificationSender};
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+use shared::{
+    config::SmtpConfig,
+    error::{AppError, AppResult},
+};
+
+pub struct SmtpNotificationSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    timeout: Duration,
+}
+
+impl SmtpNotificationSender {
+    pub fn new(config: &SmtpConfig) -> AppResult<Self> {
+        let builder = if config.starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                .map_err(|e| AppError::NotificationError(e.to_string()))?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+        };
+        let builder = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username.clone(), password.clone()))
+            }
+            _ => builder,
+        };
+
+        let from = config
+            .from
+            .parse()
+            .map_err(|e: lettre::address::AddressError| {
+                AppError::NotificationError(e.to_string())
+            })?;
+
+        let timeout = Duration::from_secs(config.timeout);
+        let transport = builder.port(config.port).timeout(Some(timeout)).build();
+
+        Ok(Self {
+            transport,
+            from,
+            timeout,
+        })
+    }
+}
+
+#[async_trait]
+impl NotificationSender for SmtpNotificationSender {
+    async fn send(&self, notification: &Notification) -> AppResult<()> {
+        let to = Mailbox::new(
+            Some(notification.user_name.clone()),
+            notification
+                .email
+                .parse()
+                .map_err(|e: lettre::address::AddressError| {
+                    AppError::NotificationError(e.to_string())
+                })?,
+        );
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&notification.subject)
+            .body(notification.body.clone())
+            .map_err(|e| AppError::NotificationError(e.to_string()))?;
+
+        // 通知は API の処理の中で送るため、応答しないサーバーに長く待たされないようにする。
+        // トランスポートのタイムアウトは接続の確立にしか効かないため、送信全体にも上限を設ける
+        tokio::time::timeout(self.timeout, self.transport.send(message))
+            .await
+            .map_err(|_| AppError::NotificationError("SMTP server timed out".into()))?
+            .map_err(|e| AppError::NotificationError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kernel::model::{id::UserId, notification::NotificationKind};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// 1 通のメールを受信して、DATA コマンドで送られた内容を返すだけの SMTP サーバー。
+    async fn receive_one_mail(listener: TcpListener) -> anyhow::Result<String> {
+        let (stream, _) = listener.accept().await?;
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+
+        writer.write_all(b"220 localhost ESMTP mock\r\n").await?;
+
+        let mut data = String::new();
+        let mut in_data = false;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 {
+                break;
+            }
+
+            if in_data {
+                if line == ".\r\n" {
+                    in_data = false;
+                    writer.write_all(b"250 OK\r\n").await?;
+                } else {
+                    data.push_str(&line);
+                }
+                continue;
+            }
+
+            let command = line.to_ascii_uppercase();
+            if command.starts_with("EHLO") {
+                writer.write_all(b"250 localhost\r\n").await?;
+            } else if command.starts_with("DATA") {
+                in_data = true;
+                writer
+                    .write_all(b"354 End data with <CR><LF>.<CR><LF>\r\n")
+                    .await?;
+            } else if command.starts_with("QUIT") {
+                writer.write_all(b"221 Bye\r\n").await?;
+                break;
+            } else {
+                writer.write_all(b"250 OK\r\n").await?;
+            }
+        }
+
+        Ok(data)
+    }
+
+    #[tokio::test]
+    async fn test_send_mail_to_smtp_server() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        let server = tokio::spawn(receive_one_mail(listener));
+
+        let sender = SmtpNotificationSender::new(&SmtpConfig {
+            host: "127.0.0.1".into(),
+            port,
+            username: None,
+            password: None,
+            starttls: false,
+            from: "library@example.com".into(),
+            timeout: 5,
+        })?;
+        sender
+            .send(&Notification {
+                user_id: UserId::new(),
+                email: "eleazar.fig@example.com".into(),
+                user_name: "Eleazar Fig".into(),
+                kind: NotificationKind::CheckedOut,
+                subject: "Book checked out".into(),
+                body: "You have checked out a book.".into(),
+            })
+            .await?;
+        drop(sender);
+
+        let data = server.await??;
+        assert!(data.contains("To: \"Eleazar Fig\" <eleazar.fig@example.com>"));
+        assert!(data.contains("Subject: Book checked out"));
+        assert!(data.contains("You have checked out a book."));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_mail_times_out() -> anyhow::Result<()> {
+        // 接続を受け付けたまま、挨拶を返さないサーバー
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let port = listener.local_addr()?.port();
+        tokio::spawn(async move {
+            let (_stream, _) = listener.accept().await?;
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            anyhow::Ok(())
+        });
+
+        let sender = SmtpNotificationSender::new(&SmtpConfig {
+            host: "127.0.0.1".into(),
+            port,
+            username: None,
+            password: None,
+            starttls: false,
+            from: "library@example.com".into(),
+            timeout: 1,
+        })?;
+
+        let started = std::time::Instant::now();
+        let res = sender
+            .send(&Notification {
+                user_id: UserId::new(),
+                email: "eleazar.fig@example.com".into(),
+                user_name: "Eleazar Fig".into(),
+                kind: NotificationKind::Returned,
+                subject: "Book returned".into(),
+                body: "Thank you for returning the book.".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::NotificationError(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        Ok(())
+    }
+}
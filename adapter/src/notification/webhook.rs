@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use kernel::{
+    model::{id::UserId, notification::Notification},
+    repository::notification::NotificationSender,
+};
+use serde::Serialize;
+use shared::{
+    config::WebhookConfig,
+    error::{AppError, AppResult},
+};
+
+/// 通知内容を JSON として指定の URL に POST する送信手段。
+pub struct WebhookNotificationSender {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotificationSender {
+    /// 通知はリクエストの処理の中で送るため、送信先が応答しなくても API を待たせ続けないよう上限を設ける。
+    pub fn new(config: &WebhookConfig) -> AppResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .connect_timeout(Duration::from_secs(config.connect_timeout))
+            .build()
+            .map_err(|e| AppError::NotificationError(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            url: config.url.clone(),
+        })
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookPayload<'a> {
+    kind: &'a str,
+    user_id: UserId,
+    email: &'a str,
+    subject: &'a str,
+    body: &'a str,
+}
+
+impl<'a> From<&'a Notification> for WebhookPayload<'a> {
+    fn from(value: &'a Notification) -> Self {
+        Self {
+            kind: value.kind.as_ref(),
+            user_id: value.user_id,
+            email: &value.email,
+            subject: &value.subject,
+            body: &value.body,
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSender for WebhookNotificationSender {
+    async fn send(&self, notification: &Notification) -> AppResult<()> {
+        self.client
+            .post(&self.url)
+            .json(&WebhookPayload::from(notification))
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| AppError::NotificationError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{routing::post, Json, Router};
+    use kernel::model::notification::NotificationKind;
+    use tokio::{net::TcpListener, sync::mpsc};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_post_notification_to_webhook() -> anyhow::Result<()> {
+        let (tx, mut rx) = mpsc::channel::<String>(1);
+        let app = Router::new().route(
+            "/hook",
+            post(move |body: String| async move {
+                tx.send(body).await.unwrap();
+                Json(())
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let sender = WebhookNotificationSender::new(&WebhookConfig {
+            url: format!("http://{}/hook", addr),
+            timeout: 5,
+            connect_timeout: 5,
+        })?;
+        sender
+            .send(&Notification {
+                user_id: UserId::new(),
+                email: "eleazar.fig@example.com".into(),
+                user_name: "Eleazar Fig".into(),
+                kind: NotificationKind::Returned,
+                subject: "Book returned".into(),
+                body: "Thank you for returning the book.".into(),
+            })
+            .await?;
+
+        let body = rx.recv().await.unwrap();
+        assert!(body.contains(r#""kind":"returned""#));
+        assert!(body.contains(r#""subject":"Book returned""#));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_post_notification_times_out() -> anyhow::Result<()> {
+        // 応答を返さないまま待たせる送信先
+        let app = Router::new().route(
+            "/hook",
+            post(|| async {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Json(())
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let sender = WebhookNotificationSender::new(&WebhookConfig {
+            url: format!("http://{}/hook", addr),
+            timeout: 1,
+            connect_timeout: 1,
+        })?;
+
+        let started = std::time::Instant::now();
+        let res = sender
+            .send(&Notification {
+                user_id: UserId::new(),
+                email: "eleazar.fig@example.com".into(),
+                user_name: "Eleazar Fig".into(),
+                kind: NotificationKind::Returned,
+                subject: "Book returned".into(),
+                body: "Thank you for returning the book.".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::NotificationError(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use shared::{
+    config::OidcConfig,
+    error::{AppError, AppResult},
+};
+use tokio::sync::OnceCell;
+
+#[derive(Deserialize)]
+struct ProviderMetadata {
+    authorization_endpoint: String,
+    token_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl Audience {
+    fn contains(&self, client_id: &str) -> bool {
+        match self {
+            Self::Single(aud) => aud == client_id,
+            Self::Multiple(auds) => auds.iter().any(|aud| aud == client_id),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    aud: Audience,
+    exp: i64,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+}
+
+/// ID トークンから取り出した、外部アカウントの情報
+#[derive(Debug)]
+pub struct OidcIdentity {
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
+
+/// 認可コードフローで IdP とやりとりするクライアント。
+/// IdP のエンドポイントは初回利用時にディスカバリーで取得し、以後は使い回す。
+pub struct OidcClient {
+    config: OidcConfig,
+    http: Client,
+    metadata: OnceCell<ProviderMetadata>,
+}
+
+impl OidcClient {
+    /// ログインの処理の中で IdP に問い合わせるため、IdP が応答しなくても API を待たせ続けないよう上限を設ける。
+    pub fn new(config: &OidcConfig) -> AppResult<Self> {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .connect_timeout(Duration::from_secs(config.connect_timeout))
+            .build()
+            .map_err(|e| AppError::IdentityProviderError(e.to_string()))?;
+
+        Ok(Self {
+            config: config.clone(),
+            http,
+            metadata: OnceCell::new(),
+        })
+    }
+
+    pub fn auto_provision(&self) -> bool {
+        self.config.auto_provision
+    }
+
+    async fn metadata(&self) -> AppResult<&ProviderMetadata> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer_url.trim_end_matches('/')
+                );
+                self.http
+                    .get(url)
+                    .send()
+                    .await
+                    .and_then(|res| res.error_for_status())
+                    .map_err(|e| AppError::IdentityProviderError(e.to_string()))?
+                    .json::<ProviderMetadata>()
+                    .await
+                    .map_err(|e| AppError::IdentityProviderError(e.to_string()))
+            })
+            .await
+    }
+
+    pub async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_verifier: &str,
+    ) -> AppResult<String> {
+        let metadata = self.metadata().await?;
+        let mut url = Url::parse(&metadata.authorization_endpoint)
+            .map_err(|e| AppError::IdentityProviderError(e.to_string()))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.config.client_id)
+            .append_pair("redirect_uri", &self.config.redirect_uri)
+            .append_pair("scope", "openid email profile")
+            .append_pair("state", state)
+            .append_pair("nonce", nonce)
+            .append_pair("code_challenge", &code_challenge(code_verifier))
+            .append_pair("code_challenge_method", "S256");
+
+        Ok(url.to_string())
+    }
+
+    /// 認可コードを ID トークンと交換し、発行者・対象者・有効期限・nonce を検証する。
+    /// ID トークンはトークンエンドポイントから TLS で直接受け取るため、署名の検証は省略する
+    /// （OpenID Connect Core 1.0 3.1.3.7 を参照）。
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> AppResult<OidcIdentity> {
+        let metadata = self.metadata().await?;
+        let res = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", &self.config.redirect_uri),
+                ("client_id", &self.config.client_id),
+                ("client_secret", &self.config.client_secret),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await
+            .map_err(|e| AppError::IdentityProviderError(e.to_string()))?;
+
+        // 認可コードの誤りや期限切れは、利用者側の問題として認証エラーにする
+        if res.status().is_client_error() {
+            return Err(AppError::UnauthenticatedError);
+        }
+        let token = res
+            .error_for_status()
+            .map_err(|e| AppError::IdentityProviderError(e.to_string()))?
+            .json::<TokenResponse>()
+            .await
+            .map_err(|e| AppError::IdentityProviderError(e.to_string()))?;
+
+        let claims = decode_id_token(&token.id_token)?;
+        if claims.iss.trim_end_matches('/') != self.config.issuer_url.trim_end_matches('/')
+            || !claims.aud.contains(&self.config.client_id)
+            || claims.exp <= Utc::now().timestamp()
+            || claims.nonce.as_deref() != Some(nonce)
+        {
+            return Err(AppError::UnauthenticatedError);
+        }
+
+        Ok(OidcIdentity {
+            issuer: claims.iss,
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            name: claims.name,
+        })
+    }
+}
+
+fn code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+fn decode_id_token(id_token: &str) -> AppResult<IdTokenClaims> {
+    let payload = id_token
+        .split('.')
+        .nth(1)
+        .ok_or(AppError::UnauthenticatedError)?;
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| AppError::UnauthenticatedError)?;
+
+    serde_json::from_slice(&payload).map_err(|_| AppError::UnauthenticatedError)
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    //! テスト用の OIDC プロバイダー。ディスカバリー、認可コードの払い出し、トークンエンドポイントを備える。
+
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{
+        extract::{Form, State},
+        routing::{get, post},
+        Json, Router,
+    };
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// 認可リクエストで受け取った challenge と nonce、ログインさせる利用者の情報
+    struct Grant {
+        challenge: String,
+        nonce: String,
+        user: serde_json::Value,
+    }
+
+    /// 認可コードごとに払い出した `Grant` を持つ
+    #[derive(Clone, Default)]
+    pub struct MockProvider {
+        pub issuer: String,
+        grants: Arc<Mutex<HashMap<String, Grant>>>,
+    }
+
+    impl MockProvider {
+        pub async fn start() -> anyhow::Result<Self> {
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            let provider = Self {
+                issuer: format!("http://{}", listener.local_addr()?),
+                ..Default::default()
+            };
+
+            let app = Router::new()
+                .route("/.well-known/openid-configuration", get(discovery))
+                .route("/token", post(token))
+                .with_state(provider.clone());
+            tokio::spawn(async move { axum::serve(listener, app).await });
+
+            Ok(provider)
+        }
+
+        pub fn config(&self, auto_provision: bool) -> OidcConfig {
+            OidcConfig {
+                issuer_url: self.issuer.clone(),
+                client_id: "library".into(),
+                client_secret: "secret".into(),
+                redirect_uri: "http://localhost:8080/auth/oidc/callback".into(),
+                auto_provision,
+                timeout: 5,
+                connect_timeout: 5,
+            }
+        }
+
+        /// 利用者が IdP でログインを終えたものとして、認可 URL に対する認可コードを払い出す
+        pub fn grant(&self, authorization_url: &str, user: serde_json::Value) -> (String, String) {
+            let url = Url::parse(authorization_url).unwrap();
+            let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+            let code = uuid::Uuid::new_v4().simple().to_string();
+            self.grants.lock().unwrap().insert(
+                code.clone(),
+                Grant {
+                    challenge: params["code_challenge"].clone(),
+                    nonce: params["nonce"].clone(),
+                    user,
+                },
+            );
+            (code, params["state"].clone())
+        }
+    }
+
+    async fn discovery(State(provider): State<MockProvider>) -> Json<serde_json::Value> {
+        Json(json!({
+            "issuer": provider.issuer,
+            "authorization_endpoint": format!("{}/authorize", provider.issuer),
+            "token_endpoint": format!("{}/token", provider.issuer),
+        }))
+    }
+
+    async fn token(
+        State(provider): State<MockProvider>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> Result<Json<serde_json::Value>, axum::http::StatusCode> {
+        let Grant {
+            challenge,
+            nonce,
+            mut user,
+        } = provider
+            .grants
+            .lock()
+            .unwrap()
+            .remove(&form["code"])
+            .ok_or(axum::http::StatusCode::BAD_REQUEST)?;
+        if code_challenge(&form["code_verifier"]) != challenge {
+            return Err(axum::http::StatusCode::BAD_REQUEST);
+        }
+
+        let claims = user.as_object_mut().unwrap();
+        claims.insert("iss".into(), json!(provider.issuer));
+        claims.insert("aud".into(), json!(form["client_id"]));
+        claims.insert("exp".into(), json!(Utc::now().timestamp() + 60));
+        claims.insert("nonce".into(), json!(nonce));
+        let id_token = format!(
+            "{}.{}.",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#),
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&user).unwrap())
+        );
+
+        Ok(Json(json!({
+            "access_token": "mock",
+            "token_type": "Bearer",
+            "id_token": id_token,
+        })))
+    }
+}
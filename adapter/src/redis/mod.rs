@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use model::{RedisKey, RedisValue};
 use redis::{AsyncCommands, Client};
 use shared::{config::RedisConfig, error::AppResult};
@@ -36,10 +38,183 @@ impl RedisClient {
         result.map(T::Value::try_from).transpose()
     }
 
+    /// 値を取得すると同時にキーを削除する。並行して呼ばれても値を得られるのは 1 つの呼び出しのみとなる。
+    pub async fn get_and_delete<T: RedisKey>(&self, key: &T) -> AppResult<Option<T::Value>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let result: Option<String> = conn.get_del(key.inner()).await?;
+
+        result.map(T::Value::try_from).transpose()
+    }
+
     pub async fn delete<T: RedisKey>(&self, key: &T) -> AppResult<()> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
         () = conn.del(key.inner()).await?;
 
         Ok(())
     }
+
+    /// ストリームにエントリを追加し、採番されたエントリ ID を返す。
+    pub async fn xadd(&self, stream: &str, fields: &[(&str, &str)]) -> AppResult<String> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let id: String = conn.xadd(stream, "*", fields).await?;
+
+        Ok(id)
+    }
+
+    /// ハッシュのフィールドをまとめて設定し、キーの有効期限を更新する。
+    pub async fn hset_multiple_with_ex(
+        &self,
+        key: &str,
+        fields: &[(&str, &str)],
+        ttl: u64,
+    ) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        () = redis::pipe()
+            .atomic()
+            .hset_multiple(key, fields)
+            .ignore()
+            .expire(key, ttl as i64)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn hgetall(&self, key: &str) -> AppResult<HashMap<String, String>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let result: HashMap<String, String> = conn.hgetall(key).await?;
+
+        Ok(result)
+    }
+
+    /// セットにメンバーを追加し、キーの有効期限を更新する。
+    pub async fn sadd_with_ex(&self, key: &str, member: &str, ttl: u64) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        () = redis::pipe()
+            .atomic()
+            .sadd(key, member)
+            .ignore()
+            .expire(key, ttl as i64)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn srem(&self, key: &str, member: &str) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        () = conn.srem(key, member).await?;
+
+        Ok(())
+    }
+
+    pub async fn smembers(&self, key: &str) -> AppResult<Vec<String>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let result: Vec<String> = conn.smembers(key).await?;
+
+        Ok(result)
+    }
+
+    pub async fn set_ex(&self, key: &str, value: &str, ttl: u64) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        () = conn.set_ex(key, value, ttl).await?;
+
+        Ok(())
+    }
+
+    /// 値を 1 増やし、キーの有効期限を更新する。増やした後の値を返す。
+    pub async fn incr_with_ex(&self, key: &str, ttl: u64) -> AppResult<u64> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let (count,): (u64,) = redis::pipe()
+            .atomic()
+            .incr(key, 1)
+            .expire(key, ttl as i64)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// 値を 1 減らす。有効期限が切れてキーが既に存在しない場合は何もしない。
+    pub async fn decr_if_exists(&self, key: &str) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        // 存在しないキーを DECR すると、有効期限のないキーが作られてしまうため、確認と減算をまとめて行う
+        () = redis::Script::new(
+            r"
+                if redis.call('EXISTS', KEYS[1]) == 1 then
+                    redis.call('DECR', KEYS[1])
+                end
+            ",
+        )
+        .key(key)
+        .invoke_async(&mut conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 数値として保持している値を返す。キーが存在しない場合は 0 となる。
+    pub async fn get_count(&self, key: &str) -> AppResult<u64> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let count: Option<u64> = conn.get(key).await?;
+
+        Ok(count.unwrap_or(0))
+    }
+
+    /// キーが存在しない場合に限り、有効期限付きで値を設定する。設定できた場合は `true` を返す。
+    pub async fn set_nx_ex(&self, key: &str, value: &str, ttl: u64) -> AppResult<bool> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let result: Option<String> = conn
+            .set_options(
+                key,
+                value,
+                redis::SetOptions::default()
+                    .conditional_set(redis::ExistenceCheck::NX)
+                    .with_expiration(redis::SetExpiry::EX(ttl)),
+            )
+            .await?;
+
+        Ok(result.is_some())
+    }
+
+    /// キーの残りの有効期間（秒）を返す。キーが存在しないか、有効期限が設定されていない場合は `None` となる。
+    pub async fn ttl(&self, key: &str) -> AppResult<Option<u64>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let ttl: i64 = conn.ttl(key).await?;
+
+        Ok(u64::try_from(ttl).ok())
+    }
+
+    pub async fn delete_key(&self, key: &str) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        () = conn.del(key).await?;
+
+        Ok(())
+    }
+
+    pub async fn zadd(&self, key: &str, member: &str, score: i64) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        () = conn.zadd(key, member, score).await?;
+
+        Ok(())
+    }
+
+    /// スコアが `min` 以上のメンバーを取得する。
+    pub async fn zrangebyscore_from(&self, key: &str, min: i64) -> AppResult<Vec<String>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let result: Vec<String> = conn.zrangebyscore(key, min, "+inf").await?;
+
+        Ok(result)
+    }
+
+    /// スコアが `max` 未満のメンバーを削除する。
+    pub async fn zremrangebyscore_before(&self, key: &str, max: i64) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        () = conn.zrembyscore(key, "-inf", format!("({}", max)).await?;
+
+        Ok(())
+    }
 }
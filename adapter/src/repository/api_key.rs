@@ -0,0 +1,269 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use derive_new::new;
+use kernel::{
+    model::{
+        api_key::{
+            event::{CreateApiKey, RevokeApiKey},
+            ApiKey, ApiKeyPrincipal, IssuedApiKey,
+        },
+        id::UserId,
+    },
+    repository::api_key::ApiKeyRepository,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+use shared::error::{AppError, AppResult};
+
+use crate::database::{
+    model::api_key::{ApiKeyPrincipalRow, ApiKeyRow},
+    ConnectionPool,
+};
+
+/// シークレットの先頭に付ける目印。ログなどに紛れ込んだ際に見つけやすくする。
+const SECRET_PREFIX: &str = "rbm_";
+const SECRET_LENGTH: usize = 40;
+/// 一覧でキーを見分けるために保存する、シークレットの先頭の文字数
+const DISPLAY_PREFIX_LENGTH: usize = 12;
+
+#[derive(new)]
+pub struct ApiKeyRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl ApiKeyRepository for ApiKeyRepositoryImpl {
+    async fn create(&self, event: CreateApiKey) -> AppResult<IssuedApiKey> {
+        if event
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+        {
+            return Err(AppError::UnprocessableEntity(
+                "The expiry of an API key must be in the future".to_string(),
+            ));
+        }
+
+        let secret = generate_secret();
+        let scopes: Vec<String> = event
+            .scopes
+            .iter()
+            .map(|scope| scope.as_ref().to_string())
+            .collect();
+
+        let row = sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+                INSERT INTO api_keys (user_id, name, key_hash, key_prefix, scopes, expires_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING
+                    api_key_id,
+                    user_id,
+                    name,
+                    key_prefix,
+                    scopes,
+                    expires_at,
+                    last_used_at,
+                    created_at;
+            "#,
+            event.user_id as _,
+            event.name,
+            hash_secret(&secret),
+            &secret[..DISPLAY_PREFIX_LENGTH],
+            &scopes,
+            event.expires_at
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(IssuedApiKey {
+            api_key: row.try_into()?,
+            secret,
+        })
+    }
+
+    async fn find_all(&self, user_id: UserId) -> AppResult<Vec<ApiKey>> {
+        sqlx::query_as!(
+            ApiKeyRow,
+            r#"
+                SELECT
+                    api_key_id,
+                    user_id,
+                    name,
+                    key_prefix,
+                    scopes,
+                    expires_at,
+                    last_used_at,
+                    created_at
+                FROM api_keys
+                WHERE user_id = $1
+                ORDER BY created_at DESC;
+            "#,
+            user_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(ApiKey::try_from)
+        .collect()
+    }
+
+    async fn revoke(&self, event: RevokeApiKey) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM api_keys
+                WHERE api_key_id = $1 AND user_id = $2;
+            "#,
+            event.api_key_id as _,
+            event.user_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound(
+                "Specified API key not found".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn authenticate(&self, secret: &str) -> AppResult<Option<ApiKeyPrincipal>> {
+        sqlx::query_as!(
+            ApiKeyPrincipalRow,
+            r#"
+                UPDATE api_keys AS k
+                SET last_used_at = CURRENT_TIMESTAMP(3)
+                FROM users AS u
+                INNER JOIN roles AS r USING(role_id)
+                WHERE k.key_hash = $1
+                    AND k.user_id = u.user_id
+                    AND (k.expires_at IS NULL OR k.expires_at > CURRENT_TIMESTAMP(3))
+                RETURNING
+                    k.api_key_id,
+                    k.scopes,
+                    u.user_id,
+                    u.name,
+                    u.email,
+                    r.role_id,
+                    r.name AS role_name,
+                    ARRAY(
+                        SELECT permission FROM role_permissions AS p
+                        WHERE p.role_id = r.role_id
+                    ) AS "role_permissions!";
+            "#,
+            hash_secret(secret)
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .map(ApiKeyPrincipal::try_from)
+        .transpose()
+    }
+}
+
+fn generate_secret() -> String {
+    let random: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(SECRET_LENGTH)
+        .map(char::from)
+        .collect();
+    format!("{}{}", SECRET_PREFIX, random)
+}
+
+fn hash_secret(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use chrono::Duration;
+    use kernel::model::api_key::ApiKeyScope;
+
+    use super::*;
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_api_key_lifecycle(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let repo = ApiKeyRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+
+        let issued = repo
+            .create(CreateApiKey::new(
+                user_id,
+                "backup script".into(),
+                vec![ApiKeyScope::ReadBooks],
+                None,
+            ))
+            .await?;
+        assert!(issued.secret.starts_with(SECRET_PREFIX));
+        assert!(issued.secret.starts_with(&issued.api_key.prefix));
+        assert!(issued.api_key.last_used_at.is_none());
+
+        // シークレットはハッシュ値のみを保存する
+        let stored: Vec<String> = sqlx::query_scalar!("SELECT key_hash FROM api_keys")
+            .fetch_all(&pool)
+            .await?;
+        assert_eq!(stored, vec![hash_secret(&issued.secret)]);
+
+        let principal = repo
+            .authenticate(&issued.secret)
+            .await?
+            .expect("API key should be valid");
+        assert_eq!(principal.user.id, user_id);
+        assert!(principal.has_scope(ApiKeyScope::ReadBooks));
+        assert!(!principal.has_scope(ApiKeyScope::ManageCheckouts));
+        assert!(repo.authenticate("rbm_unknown").await?.is_none());
+
+        let keys = repo.find_all(user_id).await?;
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0].last_used_at.is_some());
+
+        // 他のユーザーのキーは失効させられない
+        let res = repo
+            .revoke(RevokeApiKey::new(UserId::new(), issued.api_key.id))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.revoke(RevokeApiKey::new(user_id, issued.api_key.id))
+            .await?;
+        assert!(repo.authenticate(&issued.secret).await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_expired_api_key(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let repo = ApiKeyRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+
+        let res = repo
+            .create(CreateApiKey::new(
+                user_id,
+                "expired".into(),
+                vec![ApiKeyScope::Admin],
+                Some(Utc::now() - Duration::minutes(1)),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        let issued = repo
+            .create(CreateApiKey::new(
+                user_id,
+                "short-lived".into(),
+                vec![ApiKeyScope::Admin],
+                Some(Utc::now() + Duration::minutes(1)),
+            ))
+            .await?;
+        sqlx::query!("UPDATE api_keys SET expires_at = CURRENT_TIMESTAMP(3) - INTERVAL '1 second'")
+            .execute(&pool)
+            .await?;
+        assert!(repo.authenticate(&issued.secret).await?.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        audit::{event::CreateAuditLog, AuditLog, AuditLogListOptions},
+        list::PaginatedList,
+    },
+    repository::audit::AuditRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::database::{model::audit::PaginatedAuditLogRow, ConnectionPool};
+
+#[derive(new)]
+pub struct AuditRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl AuditRepository for AuditRepositoryImpl {
+    async fn record(&self, event: CreateAuditLog) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+                INSERT INTO audit_log (actor_id, action, target_id, before, after, request_id)
+                VALUES ($1, $2, $3, $4, $5, $6);
+            "#,
+            event.actor_id as _,
+            event.action.as_ref(),
+            event.target_id,
+            event.before,
+            event.after,
+            event.request_id,
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(())
+    }
+
+    async fn find_all(&self, options: AuditLogListOptions) -> AppResult<PaginatedList<AuditLog>> {
+        let AuditLogListOptions {
+            actor_id,
+            target_id,
+            from,
+            to,
+            limit,
+            offset,
+        } = options;
+
+        let rows = sqlx::query_as!(
+            PaginatedAuditLogRow,
+            r#"
+                SELECT
+                    COUNT(*) OVER() AS "total!",
+                    audit_log_id,
+                    actor_id,
+                    action,
+                    target_id,
+                    before,
+                    after,
+                    request_id,
+                    occurred_at
+                FROM audit_log
+                WHERE ($3::uuid IS NULL OR actor_id = $3)
+                AND ($4::uuid IS NULL OR target_id = $4)
+                AND ($5::timestamptz IS NULL OR occurred_at >= $5)
+                AND ($6::timestamptz IS NULL OR occurred_at < $6)
+                ORDER BY occurred_at DESC
+                LIMIT $1
+                OFFSET $2;
+            "#,
+            limit,
+            offset,
+            actor_id as _,
+            target_id,
+            from,
+            to,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let total = rows.first().map(|r| r.total).unwrap_or_default();
+        let items = rows
+            .into_iter()
+            .map(AuditLog::try_from)
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use chrono::{Duration, Utc};
+    use kernel::model::{audit::AuditAction, id::UserId};
+
+    use super::*;
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_find_audit_logs_with_filters(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = AuditRepositoryImpl::new(ConnectionPool::new(pool));
+        let admin_id = UserId::from_str("2bbd820c-7a88-450c-b056-19dcbadd527d")?;
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        repo.record(CreateAuditLog::new(
+            admin_id,
+            AuditAction::UserRoleChanged,
+            user_id.raw(),
+            Some(serde_json::json!({ "role": "user" })),
+            Some(serde_json::json!({ "role": "admin" })),
+            Some("req-1".into()),
+        ))
+        .await?;
+        repo.record(CreateAuditLog::new(
+            user_id,
+            AuditAction::UserPasswordChanged,
+            user_id.raw(),
+            None,
+            None,
+            None,
+        ))
+        .await?;
+
+        let all = repo
+            .find_all(AuditLogListOptions {
+                limit: 20,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(all.total, 2);
+
+        let by_actor = repo
+            .find_all(AuditLogListOptions {
+                actor_id: Some(admin_id),
+                limit: 20,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(by_actor.total, 1);
+        let log = &by_actor.items[0];
+        assert_eq!(log.action, AuditAction::UserRoleChanged);
+        assert_eq!(log.target_id, user_id.raw());
+        assert_eq!(log.after, Some(serde_json::json!({ "role": "admin" })));
+        assert_eq!(log.request_id.as_deref(), Some("req-1"));
+
+        let by_target = repo
+            .find_all(AuditLogListOptions {
+                target_id: Some(user_id.raw()),
+                limit: 20,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(by_target.total, 2);
+
+        // 期間外のログは含まれない
+        let in_future = repo
+            .find_all(AuditLogListOptions {
+                from: Some(Utc::now() + Duration::hours(1)),
+                limit: 20,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(in_future.total, 0);
+        assert!(in_future.items.is_empty());
+
+        Ok(())
+    }
+}
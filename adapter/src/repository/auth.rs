@@ -1,19 +1,29 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::Utc;
 use derive_new::new;
 use kernel::{
     model::{
-        auth::{event::CreateToken, AccessToken},
-        id::UserId,
+        auth::{
+            event::{CreateToken, RevokeSession},
+            AccessToken, AuthToken, RefreshToken, Session,
+        },
+        id::{SessionId, UserId},
+        user::User,
     },
-    repository::auth::AuthRepository,
+    repository::auth::{AccessTokenStore, AuthRepository},
 };
 use shared::error::{AppError, AppResult};
 
 use crate::{
     database::{
-        model::auth::{from, AuthorizationKey, AuthorizedUserId, UserItem},
+        model::auth::{
+            session_fields, session_from_fields, session_key, user_sessions_key, RefreshTokenKey,
+            RefreshTokenSession, TokenFamilyId, TokenFamilyKey, UsedRefreshTokenKey, UserItem,
+            LAST_SEEN_AT_FIELD,
+        },
+        model::user::UserRow,
         ConnectionPool,
     },
     redis::RedisClient,
@@ -23,31 +33,147 @@ use crate::{
 pub struct AuthRepositoryImpl {
     db: ConnectionPool,
     kv: Arc<RedisClient>,
+    access: Arc<dyn AccessTokenStore>,
     ttl: u64,
+    refresh_ttl: u64,
 }
 
 #[async_trait]
 impl AuthRepository for AuthRepositoryImpl {
-    async fn fetch_user_id_from_token(
-        &self,
-        access_token: &AccessToken,
-    ) -> AppResult<Option<UserId>> {
-        let key: AuthorizationKey = access_token.into();
-        self.kv
-            .get(&key)
-            .await
-            .map(|x| x.map(AuthorizedUserId::into_inner))
+    async fn fetch_user_from_token(&self, access_token: &AccessToken) -> AppResult<Option<User>> {
+        let Some(claims) = self.access.verify(access_token).await? else {
+            return Ok(None);
+        };
+
+        match claims.user {
+            Some(user) => Ok(Some(user)),
+            None => self.find_user(claims.user_id).await,
+        }
+    }
+
+    async fn create_token(&self, event: CreateToken) -> AppResult<AuthToken> {
+        let now = Utc::now();
+        let session = Session {
+            id: SessionId::new(),
+            user_id: event.user_id,
+            user_agent: event.user_agent.clone(),
+            created_at: now,
+            last_seen_at: now,
+        };
+        self.save_session(&session).await?;
+
+        self.issue_token(event.user_id, session.id.into()).await
     }
 
-    async fn create_token(&self, event: CreateToken) -> AppResult<AccessToken> {
-        let (key, value) = from(event);
-        self.kv.set_with_ex(&key, &value, self.ttl).await?;
-        Ok(key.into())
+    async fn refresh_token(&self, refresh_token: RefreshToken) -> AppResult<AuthToken> {
+        // GETDEL で取り出すことで、同じリフレッシュトークンでの並行したローテーションを 1 回に限る
+        let Some(session) = self
+            .kv
+            .get_and_delete(&RefreshTokenKey::from(&refresh_token))
+            .await?
+        else {
+            // ローテーション済みのトークンが再利用された場合は、漏洩とみなしてファミリーごと失効させる
+            if let Some(family_id) = self
+                .kv
+                .get(&UsedRefreshTokenKey::from(&refresh_token))
+                .await?
+            {
+                tracing::warn!(
+                    family_id = ?family_id,
+                    "refresh token reuse detected; revoking token family"
+                );
+                self.revoke_family(family_id).await?;
+            }
+            return Err(AppError::UnauthorizedError);
+        };
+
+        let RefreshTokenSession { user_id, family_id } = session;
+
+        // ファミリーが既に失効している場合はローテーションを行わない
+        let family_key = TokenFamilyKey::from(family_id);
+        let Some(current) = self.kv.get(&family_key).await? else {
+            return Err(AppError::UnauthorizedError);
+        };
+
+        self.kv
+            .set_with_ex(
+                &UsedRefreshTokenKey::from(&refresh_token),
+                &family_id,
+                self.refresh_ttl,
+            )
+            .await?;
+        self.access.revoke(&current.into()).await?;
+
+        let now = Utc::now().to_rfc3339();
+        self.kv
+            .hset_multiple_with_ex(
+                &session_key(family_id.into_inner()),
+                &[(LAST_SEEN_AT_FIELD, &now)],
+                self.refresh_ttl,
+            )
+            .await?;
+        self.kv
+            .sadd_with_ex(
+                &user_sessions_key(user_id),
+                &family_id.into_inner().to_string(),
+                self.refresh_ttl,
+            )
+            .await?;
+
+        self.issue_token(user_id, family_id).await
     }
 
     async fn delete_token(&self, access_token: AccessToken) -> AppResult<()> {
-        let key: AuthorizationKey = access_token.into();
-        self.kv.delete(&key).await
+        if let Some(session_id) = self
+            .access
+            .verify(&access_token)
+            .await?
+            .and_then(|claims| claims.session_id)
+        {
+            self.revoke_family(session_id.into()).await?;
+        }
+        self.access.revoke(&access_token).await
+    }
+
+    async fn find_sessions(&self, user_id: UserId) -> AppResult<Vec<Session>> {
+        let index_key = user_sessions_key(user_id);
+        let mut sessions = Vec::new();
+        for member in self.kv.smembers(&index_key).await? {
+            let session_id: SessionId = member.parse()?;
+            match session_from_fields(session_id, self.kv.hgetall(&session_key(session_id)).await?)?
+            {
+                Some(session) => sessions.push(session),
+                // 有効期限切れで消えたセッションは索引からも取り除く
+                None => self.kv.srem(&index_key, &member).await?,
+            }
+        }
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.last_seen_at));
+
+        Ok(sessions)
+    }
+
+    async fn revoke_session(&self, event: RevokeSession) -> AppResult<()> {
+        let session = session_from_fields(
+            event.session_id,
+            self.kv.hgetall(&session_key(event.session_id)).await?,
+        )?;
+        match session {
+            Some(session) if session.user_id == event.user_id => {
+                self.revoke_family(event.session_id.into()).await
+            }
+            _ => Err(AppError::EntityNotFound(
+                "Specified session not found".to_string(),
+            )),
+        }
+    }
+
+    async fn revoke_all_sessions(&self, user_id: UserId) -> AppResult<()> {
+        let index_key = user_sessions_key(user_id);
+        for member in self.kv.smembers(&index_key).await? {
+            let session_id: SessionId = member.parse()?;
+            self.revoke_family(session_id.into()).await?;
+        }
+        self.kv.delete_key(&index_key).await
     }
 
     async fn verify_user(&self, email: &str, password: &str) -> AppResult<UserId> {
@@ -59,9 +185,11 @@ impl AuthRepository for AuthRepositoryImpl {
             "#,
             email
         )
-        .fetch_one(self.db.inner_ref())
+        .fetch_optional(self.db.inner_ref())
         .await
-        .map_err(AppError::SpecificOperationError)?;
+        .map_err(AppError::SpecificOperationError)?
+        // 登録されていないメールアドレスも、パスワードの誤りと区別せずに扱う
+        .ok_or(AppError::UnauthenticatedError)?;
 
         let valid = bcrypt::verify(password, &user_item.password_hash)?;
 
@@ -72,3 +200,99 @@ impl AuthRepository for AuthRepositoryImpl {
         Ok(user_item.user_id)
     }
 }
+
+impl AuthRepositoryImpl {
+    /// 指定したファミリーに属するアクセストークンとリフレッシュトークンを発行し、保存する。
+    async fn issue_token(&self, user_id: UserId, family_id: TokenFamilyId) -> AppResult<AuthToken> {
+        let user = self
+            .find_user(user_id)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFound("Specified user not found".to_string()))?;
+        let access_token = self.access.issue(&user, family_id.into_inner()).await?;
+        let refresh_token = RefreshToken(uuid::Uuid::new_v4().simple().to_string());
+
+        self.kv
+            .set_with_ex(
+                &RefreshTokenKey::from(&refresh_token),
+                &RefreshTokenSession { user_id, family_id },
+                self.refresh_ttl,
+            )
+            .await?;
+        self.kv
+            .set_with_ex(
+                &TokenFamilyKey::from(family_id),
+                &(&access_token).into(),
+                self.refresh_ttl,
+            )
+            .await?;
+
+        Ok(AuthToken {
+            user_id,
+            access_token,
+            refresh_token,
+            expires_in: self.ttl,
+        })
+    }
+
+    async fn find_user(&self, user_id: UserId) -> AppResult<Option<User>> {
+        let row = sqlx::query_as!(
+            UserRow,
+            r#"
+                SELECT
+                    u.user_id,
+                    u.name,
+                    u.email,
+                    r.role_id,
+                    r.name AS role_name,
+                    ARRAY(
+                        SELECT permission FROM role_permissions AS p
+                        WHERE p.role_id = r.role_id
+                    ) AS "role_permissions!",
+                    u.created_at,
+                    u.updated_at
+                FROM users AS u
+                INNER JOIN roles AS r USING(role_id)
+                WHERE u.user_id = $1;
+            "#,
+            user_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        row.map(User::try_from).transpose()
+    }
+
+    async fn save_session(&self, session: &Session) -> AppResult<()> {
+        let fields = session_fields(session);
+        let fields: Vec<(&str, &str)> = fields.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        self.kv
+            .hset_multiple_with_ex(&session_key(session.id), &fields, self.refresh_ttl)
+            .await?;
+        self.kv
+            .sadd_with_ex(
+                &user_sessions_key(session.user_id),
+                &session.id.to_string(),
+                self.refresh_ttl,
+            )
+            .await
+    }
+
+    /// ファミリーを失効させ、現在有効なアクセストークンとセッションの情報も削除する。
+    /// 未使用のリフレッシュトークンはファミリーが存在しないため以後ローテーションできなくなる。
+    async fn revoke_family(&self, family_id: TokenFamilyId) -> AppResult<()> {
+        let family_key = TokenFamilyKey::from(family_id);
+        if let Some(current) = self.kv.get_and_delete(&family_key).await? {
+            self.access.revoke(&current.into()).await?;
+        }
+
+        let session_id = family_id.into_inner();
+        let key = session_key(session_id);
+        if let Some(session) = session_from_fields(session_id, self.kv.hgetall(&key).await?)? {
+            self.kv
+                .srem(&user_sessions_key(session.user_id), &session_id.to_string())
+                .await?;
+        }
+        self.kv.delete_key(&key).await
+    }
+}
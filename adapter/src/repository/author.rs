@@ -0,0 +1,309 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        author::{
+            event::{AuthorRef, BookAuthorEntry},
+            Author, BookAuthor,
+        },
+        id::{AuthorId, BookId},
+    },
+    repository::author::AuthorRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::database::{model::author::AuthorRow, ConnectionPool};
+
+/// 著者の名前と、蔵書の表示用の著者に保存できる最大の文字数
+const MAX_AUTHOR_LENGTH: usize = 255;
+
+#[derive(new)]
+pub struct AuthorRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl AuthorRepository for AuthorRepositoryImpl {
+    async fn find_by_id(&self, author_id: AuthorId) -> AppResult<Option<Author>> {
+        let row = sqlx::query_as!(
+            AuthorRow,
+            r#"
+                SELECT author_id, name
+                FROM authors
+                WHERE author_id = $1;
+            "#,
+            author_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(row.map(Author::from))
+    }
+}
+
+/// 蔵書の登録・更新で指定された人物を、登録済みの著者に置き換える。
+/// 名前で指定された人物は、大文字・小文字の違いを除いて同じ名前の著者がいなければ新たに登録する。
+pub(crate) async fn resolve_book_authors(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    entries: Vec<BookAuthorEntry>,
+) -> AppResult<Vec<BookAuthor>> {
+    let mut authors: Vec<BookAuthor> = Vec::with_capacity(entries.len());
+
+    for BookAuthorEntry { author, role } in entries {
+        let row = match author {
+            AuthorRef::Id(author_id) => sqlx::query_as!(
+                AuthorRow,
+                r#"
+                    SELECT author_id, name
+                    FROM authors
+                    WHERE author_id = $1;
+                "#,
+                author_id as _
+            )
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?
+            .ok_or_else(|| {
+                AppError::UnprocessableEntity(format!("Author not found: author_id={author_id}"))
+            })?,
+            AuthorRef::Name(name) => {
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(AppError::UnprocessableEntity(
+                        "Author name must not be blank".into(),
+                    ));
+                }
+                if name.chars().count() > MAX_AUTHOR_LENGTH {
+                    return Err(AppError::UnprocessableEntity(format!(
+                        "Author name must be at most {MAX_AUTHOR_LENGTH} characters"
+                    )));
+                }
+
+                sqlx::query_as!(
+                    AuthorRow,
+                    r#"
+                        INSERT INTO authors (name)
+                        VALUES ($1)
+                        ON CONFLICT (lower(name)) DO UPDATE SET name = authors.name
+                        RETURNING author_id, name;
+                    "#,
+                    name
+                )
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(AppError::SpecificOperationError)?
+            }
+        };
+
+        // 同じ人物を同じ役割で重ねて指定した場合は、最初の位置だけを残す
+        if authors
+            .iter()
+            .any(|a| a.id == row.author_id && a.role == role)
+        {
+            continue;
+        }
+        authors.push(BookAuthor {
+            id: row.author_id,
+            name: row.name,
+            role,
+        });
+    }
+
+    Ok(authors)
+}
+
+/// 蔵書の著者を、指定した並び順で登録し直す。
+pub(crate) async fn replace_book_authors(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    book_id: BookId,
+    authors: &[BookAuthor],
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            DELETE FROM book_authors
+            WHERE book_id = $1;
+        "#,
+        book_id as _
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    let author_ids = authors.iter().map(|a| a.id).collect::<Vec<_>>();
+    let roles = authors
+        .iter()
+        .map(|a| a.role.as_ref().to_string())
+        .collect::<Vec<_>>();
+
+    sqlx::query!(
+        r#"
+            INSERT INTO book_authors (book_id, author_id, position, role)
+            SELECT $1, a.author_id, a.position::INTEGER, a.role
+            FROM UNNEST($2::uuid[], $3::text[]) WITH ORDINALITY AS a (author_id, role, position);
+        "#,
+        book_id as _,
+        &author_ids as _,
+        &roles
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
+/// 表示や検索に使う、1 つの文字列にまとめた著者。
+/// 保存できる長さを超える場合は、表示用の著者を明示するよう求める。
+pub(crate) fn display_author(authors: &[BookAuthor]) -> AppResult<String> {
+    let author = authors
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if author.chars().count() > MAX_AUTHOR_LENGTH {
+        return Err(AppError::UnprocessableEntity(format!(
+            "Joined author names exceed {MAX_AUTHOR_LENGTH} characters; specify author explicitly"
+        )));
+    }
+
+    Ok(author)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, sync::Arc};
+
+    use kernel::{
+        model::{
+            author::AuthorRole,
+            book::{
+                event::{CreateBook, UpdateBook},
+                BookListOptions,
+            },
+            id::UserId,
+        },
+        repository::book::BookRepository,
+    };
+
+    use super::*;
+    use crate::{blob::memory::InMemoryBlobStore, repository::book::BookRepositoryImpl};
+
+    #[sqlx::test(fixtures("common", "user", "book"))]
+    async fn test_book_authors(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let owner = UserId::from_str("2bbd820c-7a88-450c-b056-19dcbadd527d")?;
+        let repo = AuthorRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let books = BookRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            Arc::new(InMemoryBlobStore::default()),
+        );
+        let entry = |name: &str, role| BookAuthorEntry::new(AuthorRef::Name(name.into()), role);
+
+        // 表示用の著者を省略すると、人物の名前を並べたものになる
+        let book_id = books
+            .create(
+                CreateBook {
+                    title: "プログラミング言語Rust".into(),
+                    author: "".into(),
+                    authors: vec![
+                        entry("Steve Klabnik", AuthorRole::Author),
+                        entry("Carol Nichols", AuthorRole::Author),
+                        entry("尾崎亮太", AuthorRole::Translator),
+                        // 大文字・小文字だけが異なる名前は同じ人物として扱う
+                        entry("steve klabnik", AuthorRole::Author),
+                    ],
+                    isbn: "9784048930703".into(),
+                    description: "".into(),
+                },
+                owner,
+            )
+            .await?;
+        let book = books.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.author, "Steve Klabnik, Carol Nichols, 尾崎亮太");
+        assert_eq!(
+            book.authors
+                .iter()
+                .map(|a| (a.name.as_str(), a.role))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Steve Klabnik", AuthorRole::Author),
+                ("Carol Nichols", AuthorRole::Author),
+                ("尾崎亮太", AuthorRole::Translator),
+            ]
+        );
+
+        let translator = book.authors[2].clone();
+        assert_eq!(
+            repo.find_by_id(translator.id).await?,
+            Some(Author {
+                id: translator.id,
+                name: translator.name.clone(),
+            })
+        );
+        let translated = books
+            .find_all(BookListOptions {
+                limit: 10,
+                author_id: Some(translator.id),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(translated.total, 1);
+        assert_eq!(translated.items[0].id, book_id);
+
+        // 存在しない著者の ID は指定できない
+        let update = |authors| UpdateBook {
+            book_id,
+            title: book.title.clone(),
+            author: "".into(),
+            authors,
+            isbn: book.isbn.clone(),
+            description: book.description.clone(),
+            requested_user: owner,
+            any_owner: false,
+        };
+        let res = books
+            .update(update(vec![BookAuthorEntry::new(
+                AuthorRef::Id(AuthorId::new()),
+                AuthorRole::Author,
+            )]))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 更新すると、人物の並びはまるごと置き換わる
+        books
+            .update(update(vec![
+                BookAuthorEntry::new(AuthorRef::Id(translator.id), AuthorRole::Editor),
+                entry("Carol Nichols", AuthorRole::Author),
+            ]))
+            .await?;
+        let book = books.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.author, "尾崎亮太, Carol Nichols");
+        assert_eq!(book.authors[0].role, AuthorRole::Editor);
+        assert_eq!(book.authors.len(), 2);
+
+        // 空白だけの名前の著者は登録しない
+        let res = books
+            .update(update(vec![entry("  ", AuthorRole::Author)]))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // まとめた著者が保存できる長さを超える場合は、表示用の著者の指定を求める
+        let long_names = (0..30)
+            .map(|i| entry(&format!("Contributor Number {i:02}"), AuthorRole::Author))
+            .collect::<Vec<_>>();
+        let res = books.update(update(long_names.clone())).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        books
+            .update(UpdateBook {
+                author: "Contributors".into(),
+                ..update(long_names)
+            })
+            .await?;
+        let book = books.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.author, "Contributors");
+        assert_eq!(book.authors.len(), 30);
+
+        Ok(())
+    }
+}
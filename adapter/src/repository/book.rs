@@ -1,53 +1,133 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
+use chrono::Utc;
 use derive_new::new;
 use kernel::{
     model::{
+        author::{event::BookAuthorEntry, BookAuthor},
         book::{
-            event::{CreateBook, DeleteBook, UpdateBook},
-            Book, BookListOptions, Checkout,
+            event::{
+                CreateBook, CreateBookCopy, DeleteBook, DeleteBookCopy, UpdateBook, UpdateBookCopy,
+                UpdateBookCover,
+            },
+            Book, BookCopy, BookFacets, BookListOptions, CoverImage, CoverVariant, OwnerFacet,
         },
-        id::{BookId, UserId},
+        id::{BookCopyId, BookId, CheckoutId, UserId},
         list::PaginatedList,
+        outbox::DomainEvent,
+        tag::Tag,
     },
-    repository::book::BookRepository,
+    repository::{blob::BlobStore, book::BookRepository},
 };
 use shared::error::{AppError, AppResult};
+use uuid::Uuid;
 
-use crate::database::{
-    model::book::{BookCheckoutRow, BookRow, PaginatedBookRow},
-    ConnectionPool,
+use crate::{
+    database::{
+        model::{
+            author::BookAuthorRow,
+            book::{
+                BookCopyRow, BookOwnerFacetRow, BookRow, BookSearchFilter, BookStatusFacetRow,
+                PaginatedBookRow,
+            },
+            tag::{BookTagRow, TagRow},
+        },
+        set_transaction_serializable, ConnectionPool,
+    },
+    repository::{
+        author::{display_author, replace_book_authors, resolve_book_authors},
+        outbox::record_event,
+        reservation::promote_next_reservation,
+    },
 };
 
 #[derive(new)]
 pub struct BookRepositoryImpl {
     db: ConnectionPool,
+    blob_store: Arc<dyn BlobStore>,
 }
 
 #[async_trait]
 impl BookRepository for BookRepositoryImpl {
-    async fn create(&self, event: CreateBook, user_id: UserId) -> AppResult<()> {
-        sqlx::query!(
+    async fn create(&self, event: CreateBook, user_id: UserId) -> AppResult<BookId> {
+        let mut tx = self.db.begin().await?;
+
+        let authors =
+            resolve_book_authors(&mut tx, author_entries(&event.author, event.authors)).await?;
+        let author = if event.author.trim().is_empty() {
+            display_author(&authors)?
+        } else {
+            event.author
+        };
+
+        let book_id = sqlx::query_scalar!(
             r#"
                 INSERT INTO books (title, author, isbn, description, user_id)
-                VALUES ($1, $2, $3, $4, $5);
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING book_id AS "book_id: BookId";
             "#,
             event.title,
-            event.author,
+            author,
             event.isbn,
             event.description,
             user_id as _
         )
-        .execute(self.db.inner_ref())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| map_isbn_error(e, &event.isbn))?;
+
+        replace_book_authors(&mut tx, book_id, &authors).await?;
+
+        // 登録した蔵書をすぐに貸し出せるよう、移行時と同じく 1 冊の複本を持たせる
+        let copy = sqlx::query!(
+            r#"
+                INSERT INTO book_copies (book_id, barcode)
+                VALUES ($1, replace($1::uuid::text, '-', ''))
+                RETURNING
+                    copy_id AS "copy_id: BookCopyId",
+                    barcode,
+                    shelf_location,
+                    condition;
+            "#,
+            book_id as _
+        )
+        .fetch_one(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
 
-        Ok(())
+        record_event(
+            &mut tx,
+            DomainEvent::BookCreated {
+                book_id,
+                owned_by: user_id,
+                title: event.title,
+                author,
+                isbn: event.isbn,
+            },
+        )
+        .await?;
+        record_event(
+            &mut tx,
+            DomainEvent::BookCopyAdded {
+                book_id,
+                copy_id: copy.copy_id,
+                added_by: user_id,
+                barcode: copy.barcode,
+                shelf_location: copy.shelf_location,
+                condition: copy.condition,
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(book_id)
     }
 
     async fn find_all(&self, options: BookListOptions) -> AppResult<PaginatedList<Book>> {
-        let BookListOptions { limit, offset } = options;
+        let BookListOptions { limit, offset, .. } = options;
+        let filter = BookSearchFilter::from(&options);
 
         let rows: Vec<PaginatedBookRow> = sqlx::query_as!(
             PaginatedBookRow,
@@ -55,12 +135,67 @@ impl BookRepository for BookRepositoryImpl {
                 SELECT COUNT(*) OVER() as "total!",
                     b.book_id AS id
                 FROM books AS b
-                ORDER BY b.created_at DESC
+                CROSS JOIN LATERAL (
+                    SELECT
+                        COUNT(*) AS total_copies,
+                        COUNT(*) FILTER (WHERE c.checkout_id IS NULL) AS available_copies
+                    FROM book_copies AS bc
+                    LEFT OUTER JOIN checkouts AS c USING (copy_id)
+                    WHERE bc.book_id = b.book_id
+                ) AS s
+                WHERE (
+                    $3::text IS NULL
+                    OR b.search_vector @@ websearch_to_tsquery('simple', $3)
+                    OR b.title ILIKE $4
+                    OR b.author ILIKE $4
+                    OR b.description ILIKE $4
+                )
+                AND ($5::uuid IS NULL OR b.user_id = $5)
+                AND (
+                    $6::text IS NULL
+                    OR ($6 = 'available' AND s.available_copies > 0)
+                    OR ($6 = 'checked_out' AND s.total_copies > 0 AND s.available_copies = 0)
+                )
+                AND ($7::text IS NULL OR b.isbn LIKE $7)
+                AND ($8::uuid IS NULL OR EXISTS (
+                    SELECT 1 FROM book_tags AS bt
+                    WHERE bt.book_id = b.book_id AND bt.tag_id = $8
+                ))
+                AND ($9::uuid IS NULL OR EXISTS (
+                    SELECT 1 FROM collection_books AS cb
+                    WHERE cb.book_id = b.book_id AND cb.collection_id = $9
+                ))
+                AND ($10::uuid IS NULL OR EXISTS (
+                    SELECT 1 FROM book_authors AS ba
+                    WHERE ba.book_id = b.book_id AND ba.author_id = $10
+                ))
+                AND (
+                    $11::timestamptz IS NULL
+                    OR b.created_at < $11
+                    OR (b.created_at = $11 AND b.book_id > $12)
+                )
+                ORDER BY
+                    CASE
+                        WHEN $3 IS NULL THEN 0
+                        ELSE ts_rank(b.search_vector, websearch_to_tsquery('simple', $3))
+                    END DESC,
+                    b.created_at DESC,
+                    b.book_id
                 LIMIT $1
                 OFFSET $2;
             "#,
             limit,
-            offset
+            offset,
+            filter.query,
+            filter.query_pattern,
+            filter.owner_id as _,
+            filter.checkout_status,
+            filter.isbn_pattern,
+            filter.tag_id as _,
+            filter.collection_id as _,
+            filter.author_id as _,
+            filter.after_created_at,
+            filter.after_book_id as _,
         )
         .fetch_all(self.db.inner_ref())
         .await
@@ -73,6 +208,7 @@ impl BookRepository for BookRepositoryImpl {
             .unwrap_or_default();
         let book_ids = rows.into_iter().map(|r| r.id).collect::<Vec<BookId>>();
 
+        // 検索時は関連度順に並ぶため、1 つ目のクエリで得た ID の順序を維持する
         let rows: Vec<BookRow> = sqlx::query_as!(
             BookRow,
             r#"
@@ -83,11 +219,14 @@ impl BookRepository for BookRepositoryImpl {
                     b.isbn AS isbn,
                     b.description AS description,
                     u.user_id AS owned_by,
-                    u.name AS owner_name
+                    u.name AS owner_name,
+                    b.cover_content_type,
+                    b.cover_updated_at,
+                    b.created_at
                 FROM books AS b
                 INNER JOIN users AS u USING (user_id)
                 WHERE b.book_id IN (SELECT * FROM UNNEST($1::uuid[]))
-                ORDER BY b.created_at DESC
+                ORDER BY array_position($1::uuid[], b.book_id)
             "#,
             &book_ids as _
         )
@@ -96,12 +235,16 @@ impl BookRepository for BookRepositoryImpl {
         .map_err(AppError::SpecificOperationError)?;
 
         let book_ids = rows.iter().map(|book| book.book_id).collect::<Vec<_>>();
-        let mut checkouts = self.find_checkouts(&book_ids).await?;
+        let mut authors = self.find_authors(&book_ids).await?;
+        let mut copies = self.find_copies(&book_ids).await?;
+        let mut tags = self.find_tags(&book_ids).await?;
         let items = rows
             .into_iter()
             .map(|row| {
-                let checkout = checkouts.remove(&row.book_id);
-                row.into_book(checkout)
+                let authors = authors.remove(&row.book_id).unwrap_or_default();
+                let copies = copies.remove(&row.book_id).unwrap_or_default();
+                let tags = tags.remove(&row.book_id).unwrap_or_default();
+                row.into_book(authors, copies, tags)
             })
             .collect();
 
@@ -113,6 +256,129 @@ impl BookRepository for BookRepositoryImpl {
         })
     }
 
+    async fn find_facets(&self, options: &BookListOptions) -> AppResult<BookFacets> {
+        let filter = BookSearchFilter::from(options);
+
+        // 貸出状況のファセットは、貸出状況以外の条件で集計する
+        let status: BookStatusFacetRow = sqlx::query_as!(
+            BookStatusFacetRow,
+            r#"
+                SELECT
+                    COUNT(*) FILTER (WHERE s.available_copies > 0) AS "available!",
+                    COUNT(*) FILTER (
+                        WHERE s.total_copies > 0 AND s.available_copies = 0
+                    ) AS "checked_out!"
+                FROM books AS b
+                CROSS JOIN LATERAL (
+                    SELECT
+                        COUNT(*) AS total_copies,
+                        COUNT(*) FILTER (WHERE c.checkout_id IS NULL) AS available_copies
+                    FROM book_copies AS bc
+                    LEFT OUTER JOIN checkouts AS c USING (copy_id)
+                    WHERE bc.book_id = b.book_id
+                ) AS s
+                WHERE (
+                    $1::text IS NULL
+                    OR b.search_vector @@ websearch_to_tsquery('simple', $1)
+                    OR b.title ILIKE $2
+                    OR b.author ILIKE $2
+                    OR b.description ILIKE $2
+                )
+                AND ($3::uuid IS NULL OR b.user_id = $3)
+                AND ($4::text IS NULL OR b.isbn LIKE $4)
+                AND ($5::uuid IS NULL OR EXISTS (
+                    SELECT 1 FROM book_tags AS bt
+                    WHERE bt.book_id = b.book_id AND bt.tag_id = $5
+                ))
+                AND ($6::uuid IS NULL OR EXISTS (
+                    SELECT 1 FROM collection_books AS cb
+                    WHERE cb.book_id = b.book_id AND cb.collection_id = $6
+                ))
+                AND ($7::uuid IS NULL OR EXISTS (
+                    SELECT 1 FROM book_authors AS ba
+                    WHERE ba.book_id = b.book_id AND ba.author_id = $7
+                ));
+            "#,
+            filter.query,
+            filter.query_pattern,
+            filter.owner_id as _,
+            filter.isbn_pattern,
+            filter.tag_id as _,
+            filter.collection_id as _,
+            filter.author_id as _,
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // 所有者のファセットは、所有者以外の条件で集計する
+        let owners = sqlx::query_as!(
+            BookOwnerFacetRow,
+            r#"
+                SELECT
+                    u.user_id,
+                    u.name AS owner_name,
+                    COUNT(*) AS "count!"
+                FROM books AS b
+                INNER JOIN users AS u ON u.user_id = b.user_id
+                CROSS JOIN LATERAL (
+                    SELECT
+                        COUNT(*) AS total_copies,
+                        COUNT(*) FILTER (WHERE c.checkout_id IS NULL) AS available_copies
+                    FROM book_copies AS bc
+                    LEFT OUTER JOIN checkouts AS c USING (copy_id)
+                    WHERE bc.book_id = b.book_id
+                ) AS s
+                WHERE (
+                    $1::text IS NULL
+                    OR b.search_vector @@ websearch_to_tsquery('simple', $1)
+                    OR b.title ILIKE $2
+                    OR b.author ILIKE $2
+                    OR b.description ILIKE $2
+                )
+                AND (
+                    $3::text IS NULL
+                    OR ($3 = 'available' AND s.available_copies > 0)
+                    OR ($3 = 'checked_out' AND s.total_copies > 0 AND s.available_copies = 0)
+                )
+                AND ($4::text IS NULL OR b.isbn LIKE $4)
+                AND ($5::uuid IS NULL OR EXISTS (
+                    SELECT 1 FROM book_tags AS bt
+                    WHERE bt.book_id = b.book_id AND bt.tag_id = $5
+                ))
+                AND ($6::uuid IS NULL OR EXISTS (
+                    SELECT 1 FROM collection_books AS cb
+                    WHERE cb.book_id = b.book_id AND cb.collection_id = $6
+                ))
+                AND ($7::uuid IS NULL OR EXISTS (
+                    SELECT 1 FROM book_authors AS ba
+                    WHERE ba.book_id = b.book_id AND ba.author_id = $7
+                ))
+                GROUP BY u.user_id, u.name
+                ORDER BY COUNT(*) DESC, u.name ASC;
+            "#,
+            filter.query,
+            filter.query_pattern,
+            filter.checkout_status,
+            filter.isbn_pattern,
+            filter.tag_id as _,
+            filter.collection_id as _,
+            filter.author_id as _,
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(OwnerFacet::from)
+        .collect();
+
+        Ok(BookFacets {
+            available: status.available,
+            checked_out: status.checked_out,
+            owners,
+        })
+    }
+
     async fn find_by_id(&self, book_id: BookId) -> AppResult<Option<Book>> {
         let row: Option<BookRow> = sqlx::query_as!(
             BookRow,
@@ -124,7 +390,10 @@ impl BookRepository for BookRepositoryImpl {
                     b.isbn,
                     b.description,
                     u.user_id AS owned_by,
-                    u.name AS owner_name
+                    u.name AS owner_name,
+                    b.cover_content_type,
+                    b.cover_updated_at,
+                    b.created_at
                 FROM books AS b
                 INNER JOIN users AS u USING (user_id)
                 WHERE book_id = $1;
@@ -137,14 +406,53 @@ impl BookRepository for BookRepositoryImpl {
 
         match row {
             Some(r) => {
-                let checkout = self.find_checkouts(&[r.book_id]).await?.remove(&r.book_id);
-                Ok(Some(r.into_book(checkout)))
+                let authors = self
+                    .find_authors(&[r.book_id])
+                    .await?
+                    .remove(&r.book_id)
+                    .unwrap_or_default();
+                let copies = self
+                    .find_copies(&[r.book_id])
+                    .await?
+                    .remove(&r.book_id)
+                    .unwrap_or_default();
+                let tags = self
+                    .find_tags(&[r.book_id])
+                    .await?
+                    .remove(&r.book_id)
+                    .unwrap_or_default();
+                Ok(Some(r.into_book(authors, copies, tags)))
             }
             None => Ok(None),
         }
     }
 
+    async fn exists_by_isbn(&self, user_id: UserId, isbn: &str) -> AppResult<bool> {
+        sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM books WHERE user_id = $1 AND isbn = $2
+                ) AS "exists!";
+            "#,
+            user_id as _,
+            isbn
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)
+    }
+
     async fn update(&self, event: UpdateBook) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let authors =
+            resolve_book_authors(&mut tx, author_entries(&event.author, event.authors)).await?;
+        let author = if event.author.trim().is_empty() {
+            display_author(&authors)?
+        } else {
+            event.author
+        };
+
         let res = sqlx::query!(
             r#"
                 UPDATE books
@@ -154,89 +462,595 @@ impl BookRepository for BookRepositoryImpl {
                     isbn = $3,
                     description = $4
                 WHERE book_id = $5
-                AND user_id = $6;
+                AND (user_id = $6 OR $7);
             "#,
             event.title,
-            event.author,
+            author,
             event.isbn,
             event.description,
             event.book_id as _,
-            event.requested_user as _
+            event.requested_user as _,
+            event.any_owner
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
-        .map_err(AppError::SpecificOperationError)?;
+        .map_err(|e| map_isbn_error(e, &event.isbn))?;
 
         if res.rows_affected() == 0 {
             return Err(AppError::EntityNotFound("Specified book not found".into()));
         }
 
+        replace_book_authors(&mut tx, event.book_id, &authors).await?;
+
+        record_event(
+            &mut tx,
+            DomainEvent::BookUpdated {
+                book_id: event.book_id,
+                updated_by: event.requested_user,
+                title: event.title,
+                author,
+                isbn: event.isbn,
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
         Ok(())
     }
 
     async fn delete(&self, event: DeleteBook) -> AppResult<()> {
-        let res = sqlx::query!(
+        let mut tx = self.db.begin().await?;
+
+        let cover_key = sqlx::query_scalar!(
             r#"
                 DELETE FROM books
                 WHERE book_id = $1
-                AND user_id = $2;
+                AND (user_id = $2 OR $3)
+                RETURNING cover_key;
+            "#,
+            event.book_id as _,
+            event.requested_user as _,
+            event.any_owner
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| AppError::EntityNotFound("Specified book not found".into()))?;
+
+        record_event(
+            &mut tx,
+            DomainEvent::BookDeleted {
+                book_id: event.book_id,
+                deleted_by: event.requested_user,
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        // 蔵書は削除済みのため、ファイルを削除できなくても処理は失敗させない
+        if let Some(cover_key) = cover_key {
+            delete_cover_images(self.blob_store.as_ref(), &cover_key).await;
+        }
+
+        Ok(())
+    }
+
+    async fn create_copy(&self, event: CreateBookCopy) -> AppResult<BookCopyId> {
+        let mut tx = self.db.begin().await?;
+
+        set_transaction_serializable(&mut tx).await?;
+
+        ensure_book_writable(
+            &mut tx,
+            event.book_id,
+            event.requested_user,
+            event.any_owner,
+        )
+        .await?;
+
+        let copy_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO book_copies (book_id, barcode, shelf_location, condition)
+                VALUES ($1, $2, $3, $4)
+                RETURNING copy_id AS "copy_id: BookCopyId";
+            "#,
+            event.book_id as _,
+            event.barcode,
+            event.shelf_location,
+            event.condition.as_ref()
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| map_barcode_error(e, &event.barcode))?;
+
+        // 予約待ちの利用者がいれば、追加した複本を先頭の利用者のために取り置く
+        promote_next_reservation(&mut tx, event.book_id, Utc::now()).await?;
+
+        record_event(
+            &mut tx,
+            DomainEvent::BookCopyAdded {
+                book_id: event.book_id,
+                copy_id,
+                added_by: event.requested_user,
+                barcode: event.barcode,
+                shelf_location: event.shelf_location,
+                condition: event.condition.as_ref().to_string(),
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(copy_id)
+    }
+
+    async fn update_copy(&self, event: UpdateBookCopy) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        ensure_book_writable(
+            &mut tx,
+            event.book_id,
+            event.requested_user,
+            event.any_owner,
+        )
+        .await?;
+
+        let res = sqlx::query!(
+            r#"
+                UPDATE book_copies
+                SET
+                    barcode = $3,
+                    shelf_location = $4,
+                    condition = $5
+                WHERE copy_id = $1
+                AND book_id = $2;
             "#,
+            event.copy_id as _,
             event.book_id as _,
-            event.requested_user as _
+            event.barcode,
+            event.shelf_location,
+            event.condition.as_ref()
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| map_barcode_error(e, &event.barcode))?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound("Specified copy not found".into()));
+        }
+
+        record_event(
+            &mut tx,
+            DomainEvent::BookCopyUpdated {
+                book_id: event.book_id,
+                copy_id: event.copy_id,
+                updated_by: event.requested_user,
+                barcode: event.barcode,
+                shelf_location: event.shelf_location,
+                condition: event.condition.as_ref().to_string(),
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn delete_copy(&self, event: DeleteBookCopy) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        set_transaction_serializable(&mut tx).await?;
+
+        ensure_book_writable(
+            &mut tx,
+            event.book_id,
+            event.requested_user,
+            event.any_owner,
+        )
+        .await?;
+
+        let checked_out = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (SELECT 1 FROM checkouts WHERE copy_id = $1) AS "checked_out!";
+            "#,
+            event.copy_id as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if checked_out {
+            return Err(AppError::UnprocessableEntity(format!(
+                "Copy is checked out: copy_id={}",
+                event.copy_id
+            )));
+        }
+
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM book_copies
+                WHERE copy_id = $1
+                AND book_id = $2;
+            "#,
+            event.copy_id as _,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
 
         if res.rows_affected() == 0 {
-            return Err(AppError::EntityNotFound("Specified book not found".into()));
+            return Err(AppError::EntityNotFound("Specified copy not found".into()));
         }
 
+        record_event(
+            &mut tx,
+            DomainEvent::BookCopyRemoved {
+                book_id: event.book_id,
+                copy_id: event.copy_id,
+                removed_by: event.requested_user,
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
         Ok(())
     }
+
+    async fn update_cover(&self, event: UpdateBookCover) -> AppResult<()> {
+        // 同時にアップロードされても画像が混ざらないよう、アップロードごとに異なるキーに保存する
+        let cover_key = format!("books/{}/covers/{}", event.book_id.raw(), Uuid::new_v4());
+
+        match self.replace_cover(&cover_key, event).await {
+            Ok(previous) => {
+                // 置き換えた画像は参照されなくなるため、コミットの後に削除する
+                if let Some(previous) = previous {
+                    delete_cover_images(self.blob_store.as_ref(), &previous).await;
+                }
+                Ok(())
+            }
+            Err(e) => {
+                // 登録に失敗した場合は、保存した画像が残らないようにする
+                delete_cover_images(self.blob_store.as_ref(), &cover_key).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn find_cover(
+        &self,
+        book_id: BookId,
+        variant: CoverVariant,
+    ) -> AppResult<Option<CoverImage>> {
+        let row = sqlx::query!(
+            r#"
+                SELECT cover_key, cover_content_type FROM books
+                WHERE book_id = $1;
+            "#,
+            book_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let Some((cover_key, content_type)) =
+            row.and_then(|r| r.cover_key.zip(r.cover_content_type))
+        else {
+            return Ok(None);
+        };
+
+        Ok(self
+            .blob_store
+            .get(&cover_variant_key(&cover_key, variant))
+            .await?
+            .map(|data| CoverImage { content_type, data }))
+    }
 }
 
 impl BookRepositoryImpl {
-    async fn find_checkouts(&self, book_ids: &[BookId]) -> AppResult<HashMap<BookId, Checkout>> {
-        let res = sqlx::query_as!(
-            BookCheckoutRow,
+    /// 表紙画像を保存し、蔵書が参照する画像を置き換える。置き換える前の画像のキーを返す。
+    async fn replace_cover(
+        &self,
+        cover_key: &str,
+        event: UpdateBookCover,
+    ) -> AppResult<Option<String>> {
+        // 保存には時間がかかりうるため、行のロックやコネクションを保持しないようトランザクションの前に済ませる
+        for (variant, data) in [
+            (CoverVariant::Original, event.image),
+            (CoverVariant::Thumbnail, event.thumbnail),
+        ] {
+            self.blob_store
+                .put(
+                    &cover_variant_key(cover_key, variant),
+                    &event.content_type,
+                    data,
+                )
+                .await?;
+        }
+
+        let mut tx = self.db.begin().await?;
+
+        ensure_book_writable(
+            &mut tx,
+            event.book_id,
+            event.requested_user,
+            event.any_owner,
+        )
+        .await?;
+
+        // 置き換える前の画像を確実に削除できるよう、コミットまで蔵書の行をロックしておく
+        let previous = sqlx::query_scalar!(
+            r#"
+                SELECT cover_key FROM books
+                WHERE book_id = $1
+                FOR UPDATE;
+            "#,
+            event.book_id as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        sqlx::query!(
+            r#"
+                UPDATE books
+                SET
+                    cover_key = $1,
+                    cover_content_type = $2,
+                    cover_updated_at = CURRENT_TIMESTAMP(3)
+                WHERE book_id = $3;
+            "#,
+            cover_key,
+            event.content_type,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        record_event(
+            &mut tx,
+            DomainEvent::BookCoverUpdated {
+                book_id: event.book_id,
+                updated_by: event.requested_user,
+                content_type: event.content_type,
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(previous)
+    }
+
+    async fn find_authors(
+        &self,
+        book_ids: &[BookId],
+    ) -> AppResult<HashMap<BookId, Vec<BookAuthor>>> {
+        let rows = sqlx::query_as!(
+            BookAuthorRow,
+            r#"
+                SELECT ba.book_id, a.author_id, a.name, ba.role
+                FROM book_authors AS ba
+                INNER JOIN authors AS a USING (author_id)
+                WHERE ba.book_id = ANY($1)
+                ORDER BY ba.book_id, ba.position ASC;
+            "#,
+            book_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let mut res: HashMap<BookId, Vec<BookAuthor>> = HashMap::new();
+        for row in rows {
+            let book_id = row.book_id;
+            res.entry(book_id)
+                .or_default()
+                .push(BookAuthor::try_from(row)?);
+        }
+
+        Ok(res)
+    }
+
+    async fn find_copies(&self, book_ids: &[BookId]) -> AppResult<HashMap<BookId, Vec<BookCopy>>> {
+        let rows = sqlx::query_as!(
+            BookCopyRow,
             r#"
                 SELECT
-                    c.checkout_id,
-                    c.book_id,
-                    u.user_id,
-                    u.name AS user_name,
-                    c.checked_out_at
-                FROM checkouts AS c
-                INNER JOIN users AS u USING (user_id)
-                WHERE book_id = ANY($1);
+                    bc.copy_id,
+                    bc.book_id,
+                    bc.barcode,
+                    bc.shelf_location,
+                    bc.condition,
+                    c.checkout_id AS "checkout_id?: CheckoutId",
+                    u.user_id AS "user_id?: UserId",
+                    u.name AS "user_name?",
+                    c.checked_out_at AS "checked_out_at?",
+                    c.due_at AS "due_at?"
+                FROM book_copies AS bc
+                LEFT OUTER JOIN checkouts AS c USING (copy_id)
+                LEFT OUTER JOIN users AS u ON u.user_id = c.user_id
+                WHERE bc.book_id = ANY($1)
+                ORDER BY bc.barcode ASC;
             "#,
             book_ids as _
         )
         .fetch_all(self.db.inner_ref())
         .await
-        .map_err(AppError::SpecificOperationError)?
-        .into_iter()
-        .map(|checkout| (checkout.book_id, Checkout::from(checkout)))
-        .collect();
+        .map_err(AppError::SpecificOperationError)?;
+
+        let mut res: HashMap<BookId, Vec<BookCopy>> = HashMap::new();
+        for row in rows {
+            let book_id = row.book_id;
+            res.entry(book_id)
+                .or_default()
+                .push(BookCopy::try_from(row)?);
+        }
 
         Ok(res)
     }
+
+    async fn find_tags(&self, book_ids: &[BookId]) -> AppResult<HashMap<BookId, Vec<Tag>>> {
+        let rows = sqlx::query_as!(
+            BookTagRow,
+            r#"
+                SELECT bt.book_id, t.tag_id, t.name
+                FROM book_tags AS bt
+                INNER JOIN tags AS t USING (tag_id)
+                WHERE bt.book_id = ANY($1)
+                ORDER BY lower(t.name) ASC;
+            "#,
+            book_ids as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let mut res: HashMap<BookId, Vec<Tag>> = HashMap::new();
+        for BookTagRow {
+            book_id,
+            tag_id,
+            name,
+        } in rows
+        {
+            res.entry(book_id)
+                .or_default()
+                .push(TagRow { tag_id, name }.into());
+        }
+
+        Ok(res)
+    }
+}
+
+/// 著者の並びが指定されていなければ、1 つの文字列で書かれた著者を区切って使う。
+fn author_entries(author: &str, entries: Vec<BookAuthorEntry>) -> Vec<BookAuthorEntry> {
+    if entries.is_empty() {
+        BookAuthorEntry::from_display_name(author)
+    } else {
+        entries
+    }
+}
+
+/// 複本やタグの追加・変更・削除の前に、操作できる蔵書であることを確かめる。
+pub(crate) async fn ensure_book_writable(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    book_id: BookId,
+    requested_user: UserId,
+    any_owner: bool,
+) -> AppResult<()> {
+    let writable = sqlx::query_scalar!(
+        r#"
+            SELECT EXISTS (
+                SELECT 1 FROM books
+                WHERE book_id = $1
+                AND (user_id = $2 OR $3)
+            ) AS "writable!";
+        "#,
+        book_id as _,
+        requested_user as _,
+        any_owner
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    if !writable {
+        return Err(AppError::EntityNotFound("Specified book not found".into()));
+    }
+
+    Ok(())
+}
+
+/// 指定した蔵書が存在しなければ `EntityNotFound` を返す。
+pub(crate) async fn ensure_book_exists(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    book_id: BookId,
+) -> AppResult<()> {
+    let exists = sqlx::query_scalar!(
+        r#"
+            SELECT EXISTS (SELECT 1 FROM books WHERE book_id = $1) AS "exists!";
+        "#,
+        book_id as _
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    if !exists {
+        return Err(AppError::EntityNotFound(format!(
+            "Book not found: book_id={book_id}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// 蔵書に記録したキーから、表紙画像の種類ごとの保存先のキーを決める。
+/// 表紙画像を削除する。削除できなくても蔵書やユーザーの操作は失敗させない。
+pub(crate) async fn delete_cover_images(blob_store: &dyn BlobStore, cover_key: &str) {
+    for variant in [CoverVariant::Original, CoverVariant::Thumbnail] {
+        let key = cover_variant_key(cover_key, variant);
+        if let Err(e) = blob_store.delete(&key).await {
+            tracing::warn!(error.message = %e, key, "Failed to delete cover image");
+        }
+    }
+}
+
+fn cover_variant_key(cover_key: &str, variant: CoverVariant) -> String {
+    match variant {
+        CoverVariant::Original => cover_key.to_string(),
+        CoverVariant::Thumbnail => format!("{cover_key}_thumbnail"),
+    }
+}
+
+/// 同じ所有者が同じ ISBN の蔵書を登録しようとした場合は、重複として扱う。
+fn map_isbn_error(e: sqlx::Error, isbn: &str) -> AppError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AppError::ConflictError(format!("Book with ISBN {isbn} already registered"))
+        }
+        _ => AppError::SpecificOperationError(e),
+    }
+}
+
+/// バーコードの一意制約に違反した場合は、重複として扱う。
+fn map_barcode_error(e: sqlx::Error, barcode: &str) -> AppError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AppError::UnprocessableEntity(format!("Barcode already registered: {barcode}"))
+        }
+        _ => AppError::SpecificOperationError(e),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
+    use kernel::{
+        model::book::{BookCursor, CheckoutStatus, CopyCondition},
+        repository::blob::MockBlobStore,
+    };
+
     use super::*;
+    use crate::blob::memory::InMemoryBlobStore;
 
     #[sqlx::test(fixtures("common"))]
     async fn test_register_book(pool: sqlx::PgPool) -> anyhow::Result<()> {
-        let repo = BookRepositoryImpl::new(ConnectionPool::new(pool));
+        let repo = BookRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            Arc::new(InMemoryBlobStore::default()),
+        );
 
         let book = CreateBook {
             title: "Test Title".into(),
             author: "Test Author".into(),
+            authors: vec![],
             isbn: "Test ISBN".into(),
             description: "Test Description".into(),
         };
@@ -247,6 +1061,7 @@ mod tests {
         let options = BookListOptions {
             limit: 20,
             offset: 0,
+            ..Default::default()
         };
         let res = repo.find_all(options).await?;
         assert_eq!(res.items.len(), 1);
@@ -275,9 +1090,40 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test(fixtures("common", "user", "book"))]
+    async fn test_register_duplicate_isbn(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            Arc::new(InMemoryBlobStore::default()),
+        );
+        let book = || CreateBook {
+            title: "実践Rustプログラミング入門".into(),
+            author: "初田直也他".into(),
+            authors: vec![],
+            isbn: "9784798061702".into(),
+            description: "".into(),
+        };
+
+        // 同じ所有者が同じ ISBN の蔵書を登録しようとすると競合する
+        let owner = UserId::from_str("2bbd820c-7a88-450c-b056-19dcbadd527d")?;
+        assert!(repo.exists_by_isbn(owner, "9784798061702").await?);
+        let res = repo.create(book(), owner).await;
+        assert!(matches!(res, Err(AppError::ConflictError(_))));
+
+        // 所有者が異なれば登録できる
+        let another_user = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        assert!(!repo.exists_by_isbn(another_user, "9784798061702").await?);
+        repo.create(book(), another_user).await?;
+
+        Ok(())
+    }
+
     #[sqlx::test(fixtures("common", "book"))]
     async fn test_update_book(pool: sqlx::PgPool) -> anyhow::Result<()> {
-        let repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let repo = BookRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            Arc::new(InMemoryBlobStore::default()),
+        );
 
         let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
         let book = repo.find_by_id(book_id).await?.unwrap();
@@ -288,9 +1134,11 @@ mod tests {
             book_id: book.id,
             title: book.title,
             author: NEW_AUTHOR.into(), // このフィールドを変更
+            authors: vec![],
             isbn: book.isbn,
             description: book.description,
             requested_user: UserId::from_str("2bbd820c-7a88-450c-b056-19dcbadd527d").unwrap(),
+            any_owner: false,
         };
         repo.update(update_book).await.unwrap();
 
@@ -299,4 +1147,336 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_delete_book_owned_by_another_user(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            Arc::new(InMemoryBlobStore::default()),
+        );
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let another_user = UserId::new();
+
+        // 権限がなければ、他のユーザーの蔵書は見つからない扱いとする
+        let res = repo
+            .delete(DeleteBook {
+                book_id,
+                requested_user: another_user,
+                any_owner: false,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.delete(DeleteBook {
+            book_id,
+            requested_user: another_user,
+            any_owner: true,
+        })
+        .await?;
+        assert!(repo.find_by_id(book_id).await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_book_cover(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let blob_store = Arc::new(InMemoryBlobStore::default());
+        let repo = BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), blob_store.clone());
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let owner = UserId::from_str("2bbd820c-7a88-450c-b056-19dcbadd527d")?;
+        let cover = |requested_user, any_owner| {
+            UpdateBookCover::new(
+                book_id,
+                "image/png".into(),
+                b"image".to_vec(),
+                b"thumbnail".to_vec(),
+                requested_user,
+                any_owner,
+            )
+        };
+
+        // 権限がなければ、他のユーザーの蔵書の表紙画像は登録できない
+        let res = repo.update_cover(cover(UserId::new(), false)).await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        assert!(blob_store.keys().is_empty());
+
+        repo.update_cover(cover(owner, false)).await?;
+        let book = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.cover.unwrap().content_type, "image/png");
+
+        let thumbnail = repo
+            .find_cover(book_id, CoverVariant::Thumbnail)
+            .await?
+            .unwrap();
+        assert_eq!(thumbnail.content_type, "image/png");
+        assert_eq!(thumbnail.data, b"thumbnail");
+        let first_keys = blob_store.keys();
+        assert_eq!(first_keys.len(), 2);
+
+        // 登録し直すと新しいキーに保存され、置き換えられた画像は削除される
+        repo.update_cover(cover(owner, false)).await?;
+        let keys = blob_store.keys();
+        assert_eq!(keys.len(), 2);
+        assert!(keys.iter().all(|key| !first_keys.contains(key)));
+
+        // 画像を保存できなかった場合は、途中まで保存した画像を残さず、登録済みの画像も変えない
+        let mut failing = MockBlobStore::new();
+        failing
+            .expect_put()
+            .withf(|key, _, _| !key.ends_with("_thumbnail"))
+            .returning(|_, _, _| Ok(()));
+        failing
+            .expect_put()
+            .returning(|_, _, _| Err(AppError::BlobStoreError("unavailable".into())));
+        failing.expect_delete().times(2).returning(|_| Ok(()));
+        let failing_repo =
+            BookRepositoryImpl::new(ConnectionPool::new(pool.clone()), Arc::new(failing));
+        let res = failing_repo.update_cover(cover(owner, false)).await;
+        assert!(matches!(res, Err(AppError::BlobStoreError(_))));
+        assert_eq!(blob_store.keys(), keys);
+        assert_eq!(
+            repo.find_cover(book_id, CoverVariant::Original)
+                .await?
+                .unwrap()
+                .data,
+            b"image"
+        );
+
+        let events: Vec<String> = sqlx::query_scalar!(
+            r#"SELECT event_type FROM outbox WHERE event_type = 'book.cover_updated'"#
+        )
+        .fetch_all(&pool)
+        .await?;
+        assert_eq!(events.len(), 2);
+
+        // 蔵書を削除すると、保存していた画像も削除される
+        repo.delete(DeleteBook {
+            book_id,
+            requested_user: owner,
+            any_owner: false,
+        })
+        .await?;
+        assert!(blob_store.keys().is_empty());
+        assert!(repo
+            .find_cover(book_id, CoverVariant::Original)
+            .await?
+            .is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_find_all_after_cursor(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            Arc::new(InMemoryBlobStore::default()),
+        );
+        let user_id = UserId::from_str("2bbd820c-7a88-450c-b056-19dcbadd527d")?;
+        let book = |i: usize| CreateBook {
+            title: format!("Book {i}"),
+            author: "Test Author".into(),
+            authors: vec![],
+            isbn: format!("ISBN {i}"),
+            description: "".into(),
+        };
+        for i in 0..5 {
+            repo.create(book(i), user_id).await?;
+        }
+        let page = |after| BookListOptions {
+            limit: 2,
+            after,
+            ..Default::default()
+        };
+        let mut all = Vec::new();
+        let mut after = None;
+        loop {
+            let items = repo.find_all(page(after)).await?.items;
+            all.extend(items.iter().map(|b| b.id));
+            match items.last() {
+                Some(last) => after = Some(BookCursor::from(last)),
+                None => break,
+            }
+        }
+        assert_eq!(all.len(), 5);
+
+        // ページを辿る間に蔵書が追加・削除されても、残りの蔵書を重複も漏れもなく取得できる
+        let first = repo.find_all(page(None)).await?.items;
+        repo.delete(DeleteBook {
+            book_id: first[0].id,
+            requested_user: user_id,
+            any_owner: false,
+        })
+        .await?;
+        repo.create(book(5), user_id).await?;
+        let mut ids = first.iter().map(|b| b.id).collect::<Vec<_>>();
+        let mut after = first.last().map(BookCursor::from);
+        while let Some(cursor) = after {
+            let items = repo.find_all(page(Some(cursor))).await?.items;
+            after = (items.len() == 2).then(|| BookCursor::from(&items[1]));
+            ids.extend(items.iter().map(|b| b.id));
+        }
+        assert_eq!(ids, all);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book", "checkout"))]
+    async fn test_search_books(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            Arc::new(InMemoryBlobStore::default()),
+        );
+
+        let options = BookListOptions {
+            limit: 20,
+            offset: 0,
+            query: Some("web".into()),
+            ..Default::default()
+        };
+        let res = repo.find_all(options).await?;
+        assert_eq!(res.total, 1);
+        assert_eq!(
+            res.items[0].id,
+            BookId::from_str("17afb850-c786-49c5-a303-a3a443a2212c")?
+        );
+
+        let options = BookListOptions {
+            limit: 20,
+            offset: 0,
+            isbn_prefix: Some("978-4-0653".into()),
+            ..Default::default()
+        };
+        let res = repo.find_all(options).await?;
+        assert_eq!(res.total, 2);
+
+        let options = BookListOptions {
+            limit: 20,
+            offset: 0,
+            checkout_status: Some(CheckoutStatus::CheckedOut),
+            ..Default::default()
+        };
+        let res = repo.find_all(options).await?;
+        assert_eq!(res.total, 1);
+        assert_eq!(res.items[0].available_copies(), 0);
+        assert!(res.items[0].copies[0].checkout.is_some());
+
+        let options = BookListOptions {
+            limit: 20,
+            offset: 0,
+            owner_id: Some(UserId::new()),
+            ..Default::default()
+        };
+        let res = repo.find_all(options).await?;
+        assert_eq!(res.total, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book", "checkout"))]
+    async fn test_book_copies(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            Arc::new(InMemoryBlobStore::default()),
+        );
+
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let checked_out_copy = BookCopyId::from_str("0c8f3b1e-7d5a-4f2e-9b6c-1a2d3e4f5a61")?;
+        let owner = UserId::from_str("2bbd820c-7a88-450c-b056-19dcbadd527d")?;
+
+        let book = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!((book.total_copies(), book.available_copies()), (1, 0));
+
+        // 所有者以外は複本を追加できない
+        let res = repo
+            .create_copy(CreateBookCopy::new(
+                book_id,
+                "RBM-0004".into(),
+                "A-1".into(),
+                CopyCondition::New,
+                UserId::new(),
+                false,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let copy_id = repo
+            .create_copy(CreateBookCopy::new(
+                book_id,
+                "RBM-0004".into(),
+                "A-1".into(),
+                CopyCondition::New,
+                owner,
+                false,
+            ))
+            .await?;
+
+        let book = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!((book.total_copies(), book.available_copies()), (2, 1));
+
+        // バーコードは重複できない
+        let res = repo
+            .create_copy(CreateBookCopy::new(
+                book_id,
+                "RBM-0004".into(),
+                "A-1".into(),
+                CopyCondition::Good,
+                owner,
+                false,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        repo.update_copy(UpdateBookCopy::new(
+            book_id,
+            copy_id,
+            "RBM-0004".into(),
+            "B-3".into(),
+            CopyCondition::Damaged,
+            owner,
+            false,
+        ))
+        .await?;
+        let book = repo.find_by_id(book_id).await?.unwrap();
+        let copy = book.copies.iter().find(|c| c.id == copy_id).unwrap();
+        assert_eq!(copy.shelf_location, "B-3");
+        assert_eq!(copy.condition, CopyCondition::Damaged);
+
+        // 貸出中の複本は削除できない
+        let res = repo
+            .delete_copy(DeleteBookCopy::new(book_id, checked_out_copy, owner, false))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        repo.delete_copy(DeleteBookCopy::new(book_id, copy_id, owner, false))
+            .await?;
+        let book = repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.total_copies(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book", "checkout"))]
+    async fn test_find_book_facets(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            Arc::new(InMemoryBlobStore::default()),
+        );
+
+        // 貸出状況のファセットは貸出状況による絞り込みの影響を受けない
+        let options = BookListOptions {
+            limit: 20,
+            offset: 0,
+            query: Some("rust".into()),
+            checkout_status: Some(CheckoutStatus::Available),
+            ..Default::default()
+        };
+        let facets = repo.find_facets(&options).await?;
+        assert_eq!(facets.available, 2);
+        assert_eq!(facets.checked_out, 1);
+        assert_eq!(facets.owners.len(), 1);
+        assert_eq!(facets.owners[0].owner.name, "Eleazar Fig");
+        assert_eq!(facets.owners[0].count, 2);
+
+        Ok(())
+    }
 }
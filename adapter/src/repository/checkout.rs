@@ -1,25 +1,40 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
 use derive_new::new;
 use kernel::{
     model::{
         checkout::{
-            event::{CreateCheckout, UpdateReturned},
+            event::{CreateCheckout, UpdateRenewed, UpdateReturned},
             Checkout,
         },
-        id::{BookId, CheckoutId, UserId},
+        id::{BookCopyId, BookId, CheckoutId, UserId},
+        notification::{event::CreateNotification, NotificationKind},
+        outbox::DomainEvent,
     },
-    repository::checkout::CheckoutRepository,
+    repository::{checkout::CheckoutRepository, notification::NotificationRepository},
 };
 use shared::error::{AppError, AppResult};
 
-use crate::database::{
-    model::checkout::{CheckoutRow, CheckoutStateRow, ReturnedCheckoutRow},
-    ConnectionPool,
+use crate::{
+    database::{
+        model::checkout::{CheckoutRenewalRow, CheckoutRow, CopyStateRow, ReturnedCheckoutRow},
+        set_transaction_serializable, ConnectionPool,
+    },
+    repository::{
+        book::ensure_book_exists,
+        outbox::record_event,
+        reservation::{find_active_holds, promote_next_reservation},
+    },
 };
 
 #[derive(new)]
 pub struct CheckoutRepositoryImpl {
     db: ConnectionPool,
+    loan_period_days: i64,
+    max_renewals: i32,
+    notification: Arc<dyn NotificationRepository>,
 }
 
 #[async_trait]
@@ -27,59 +42,119 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
     async fn create(&self, event: CreateCheckout) -> AppResult<()> {
         let mut tx = self.db.begin().await?;
 
-        self.set_transaction_serializable(&mut tx).await?;
+        set_transaction_serializable(&mut tx).await?;
 
-        {
-            let res = sqlx::query_as!(
-                CheckoutStateRow,
+        ensure_book_exists(&mut tx, event.book_id).await?;
+
+        // 他のユーザーに代わって貸し出す場合は、借り手が存在することを確かめる
+        if event.checked_out_by != event.processed_by {
+            let exists = sqlx::query_scalar!(
                 r#"
-                    SELECT
-                        b.book_id,
-                        c.checkout_id AS "checkout_id?: CheckoutId",
-                        NULL AS "user_id?: UserId"
-                    FROM books AS b
-                    LEFT OUTER JOIN checkouts AS c USING (book_id)
-                    WHERE book_id = $1;
+                    SELECT EXISTS (SELECT 1 FROM users WHERE user_id = $1) AS "exists!";
                 "#,
-                event.book_id as _
+                event.checked_out_by as _
             )
-            .fetch_optional(self.db.inner_ref())
+            .fetch_one(&mut *tx)
             .await
             .map_err(AppError::SpecificOperationError)?;
 
-            match res {
-                // 指定した書籍が存在しない場合
-                None => {
-                    return Err(AppError::EntityNotFound(format!(
-                        "Book not found: book_id={}",
-                        event.book_id
-                    )))
-                }
-                // 指定した書籍が既に貸し出されている場合
-                Some(CheckoutStateRow {
-                    checkout_id: Some(_),
-                    ..
-                }) => {
-                    return Err(AppError::UnprocessableEntity(format!(
-                        "Book already checked out: book_id={}",
-                        event.book_id
-                    )))
-                }
-                _ => {}
+            if !exists {
+                return Err(AppError::EntityNotFound(format!(
+                    "User not found: user_id={}",
+                    event.checked_out_by
+                )));
             }
         }
 
+        let copies = find_copy_states(&mut tx, event.book_id).await?;
+
+        // 同じ蔵書の複本を、同じ利用者が同時に借りることはできない
+        if copies
+            .iter()
+            .any(|copy| copy.user_id == Some(event.checked_out_by))
+        {
+            return Err(AppError::UnprocessableEntity(format!(
+                "Book already checked out by the user: book_id={}",
+                event.book_id
+            )));
+        }
+
+        let available = copies
+            .iter()
+            .filter(|copy| copy.checkout_id.is_none())
+            .map(|copy| copy.copy_id)
+            .collect::<Vec<_>>();
+        let copy_id = match event.copy_id {
+            // 複本を指定した場合は、その複本が貸出中でないこと
+            Some(copy_id) if available.contains(&copy_id) => copy_id,
+            Some(copy_id) if copies.iter().any(|copy| copy.copy_id == copy_id) => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    "Copy already checked out: book_id={}, copy_id={}",
+                    event.book_id, copy_id
+                )))
+            }
+            Some(copy_id) => {
+                return Err(AppError::EntityNotFound(format!(
+                    "Copy not found: book_id={}, copy_id={}",
+                    event.book_id, copy_id
+                )))
+            }
+            // 複本が 1 冊もない蔵書は、貸出中ではなく貸し出せるものがない
+            None if copies.is_empty() => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    "Book has no copies: book_id={}",
+                    event.book_id
+                )))
+            }
+            None => *available.first().ok_or_else(|| {
+                AppError::UnprocessableEntity(format!(
+                    "Book already checked out: book_id={}",
+                    event.book_id
+                ))
+            })?,
+        };
+
+        // 取り置き中の複本は、取り置き対象の利用者しか借りることができない
+        let holds = find_active_holds(&mut tx, event.book_id, event.checked_out_at).await?;
+        match holds
+            .iter()
+            .find(|hold| hold.user_id == event.checked_out_by)
+        {
+            Some(hold) => {
+                sqlx::query!(
+                    r#"
+                        DELETE FROM reservations WHERE reservation_id = $1;
+                    "#,
+                    hold.reservation_id as _
+                )
+                .execute(&mut *tx)
+                .await
+                .map_err(AppError::SpecificOperationError)?;
+            }
+            None if available.len() <= holds.len() => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    "Book is on hold for another user: book_id={}",
+                    event.book_id
+                )));
+            }
+            None => {}
+        }
+
         let checkout_id = CheckoutId::new();
+        let due_at = event.checked_out_at + event.loan_period.unwrap_or(self.loan_period());
         let res = sqlx::query!(
             r#"
                 INSERT INTO checkouts
-                (checkout_id, book_id, user_id, checked_out_at)
-                VALUES ($1, $2, $3, $4);
+                (checkout_id, book_id, copy_id, user_id, processed_by, checked_out_at, due_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7);
             "#,
             checkout_id as _,
             event.book_id as _,
+            copy_id as _,
             event.checked_out_by as _,
-            event.checked_out_at
+            event.processed_by as _,
+            event.checked_out_at,
+            due_at
         )
         .execute(&mut *tx)
         .await
@@ -91,67 +166,90 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
             ));
         }
 
+        record_event(
+            &mut tx,
+            DomainEvent::BookCheckedOut {
+                checkout_id,
+                book_id: event.book_id,
+                copy_id,
+                checked_out_by: event.checked_out_by,
+                processed_by: event.processed_by,
+                checked_out_at: event.checked_out_at,
+                due_at,
+            },
+        )
+        .await?;
+
         tx.commit().await.map_err(AppError::TransactionError)?;
 
+        self.notify_book_event(
+            event.checked_out_by,
+            event.book_id,
+            NotificationKind::CheckedOut,
+            |title| {
+                (
+                    format!("Checked out: {title}"),
+                    format!(
+                        "You have checked out \"{title}\". Please return it by {}.",
+                        due_at.format("%Y-%m-%d")
+                    ),
+                )
+            },
+        )
+        .await;
+
         Ok(())
     }
 
     async fn update_returned(&self, event: UpdateReturned) -> AppResult<()> {
         let mut tx = self.db.begin().await?;
 
-        self.set_transaction_serializable(&mut tx).await?;
+        set_transaction_serializable(&mut tx).await?;
 
-        {
-            let res = sqlx::query_as!(
-                CheckoutStateRow,
-                r#"
-                    SELECT
-                        b.book_id,
-                        c.checkout_id AS "checkout_id?: CheckoutId",
-                        c.user_id AS "user_id?: UserId"
-                    FROM books AS b
-                    LEFT OUTER JOIN checkouts AS c USING (book_id)
-                    WHERE book_id = $1;
-                "#,
-                event.book_id as _
-            )
-            .fetch_optional(self.db.inner_ref())
-            .await
-            .map_err(AppError::SpecificOperationError)?;
+        let borrower = sqlx::query_scalar!(
+            r#"
+                SELECT user_id AS "user_id: UserId"
+                FROM checkouts
+                WHERE checkout_id = $1
+                AND book_id = $2;
+            "#,
+            event.checkout_id as _,
+            event.book_id as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
 
-            match res {
-                // 指定した書籍が存在しない場合
-                None => {
-                    return Err(AppError::EntityNotFound(format!(
-                        "Book not found: book_id={}",
-                        event.book_id
-                    )))
-                }
-                // 指定した書籍が貸し出されているが、貸出 ID またはユーザ ID が一致しない場合
-                Some(CheckoutStateRow {
-                    checkout_id: Some(c),
-                    user_id: Some(u),
-                    ..
-                }) if (c, u) != (event.checkout_id, event.returned_by) => {
-                    return Err(AppError::UnprocessableEntity(format!(
-                        "Book not checked out by the user: book_id={}, checkout_id={}, user_id={}",
-                        event.book_id, event.checkout_id, event.returned_by
-                    )))
-                }
-                _ => {}
+        let checked_out_by = match borrower {
+            // 権限を持たない利用者は、自身の貸出しか返却できない
+            Some(user_id) if user_id == event.returned_by || event.any_borrower => user_id,
+            // 指定した書籍が存在しない場合
+            None => {
+                ensure_book_exists(&mut tx, event.book_id).await?;
+                return Err(AppError::UnprocessableEntity(format!(
+                    "Book not checked out: book_id={}, checkout_id={}",
+                    event.book_id, event.checkout_id
+                )));
             }
-        }
+            Some(_) => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    "Book not checked out by the user: book_id={}, checkout_id={}, user_id={}",
+                    event.book_id, event.checkout_id, event.returned_by
+                )))
+            }
+        };
 
         let res = sqlx::query!(
             r#"
                 INSERT INTO returned_checkouts
-                (checkout_id, book_id, user_id, checked_out_at, returned_at)
-                SELECT checkout_id, book_id, user_id, checked_out_at, $2
+                (checkout_id, book_id, copy_id, user_id, processed_by, checked_out_at, due_at, renewal_count, returned_at, returned_by)
+                SELECT checkout_id, book_id, copy_id, user_id, processed_by, checked_out_at, due_at, renewal_count, $2, $3
                 FROM checkouts
                 WHERE checkout_id = $1
             "#,
             event.checkout_id as _,
             event.returned_at as _,
+            event.returned_by as _,
         )
         .execute(&mut *tx)
         .await
@@ -179,6 +277,137 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
             ));
         }
 
+        // 予約待ちの利用者がいれば、先頭の利用者のために取り置く
+        promote_next_reservation(&mut tx, event.book_id, event.returned_at).await?;
+
+        record_event(
+            &mut tx,
+            DomainEvent::BookReturned {
+                checkout_id: event.checkout_id,
+                book_id: event.book_id,
+                checked_out_by,
+                returned_by: event.returned_by,
+                returned_at: event.returned_at,
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        self.notify_book_event(
+            checked_out_by,
+            event.book_id,
+            NotificationKind::Returned,
+            |title| {
+                (
+                    format!("Returned: {title}"),
+                    format!("Thank you for returning \"{title}\"."),
+                )
+            },
+        )
+        .await;
+
+        Ok(())
+    }
+
+    async fn update_renewed(&self, event: UpdateRenewed) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        set_transaction_serializable(&mut tx).await?;
+
+        let res = sqlx::query_as!(
+            CheckoutRenewalRow,
+            r#"
+                SELECT
+                    c.user_id,
+                    c.due_at,
+                    c.renewal_count,
+                    EXISTS (
+                        SELECT 1 FROM reservations AS r WHERE r.book_id = c.book_id
+                    ) AS "has_reservations!"
+                FROM checkouts AS c
+                WHERE c.checkout_id = $1
+                AND c.book_id = $2;
+            "#,
+            event.checkout_id as _,
+            event.book_id as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let row = match res {
+            // 指定した貸出が存在しない場合
+            None => {
+                return Err(AppError::EntityNotFound(format!(
+                    "Checkout not found: book_id={}, checkout_id={}",
+                    event.book_id, event.checkout_id
+                )))
+            }
+            // 貸出を受けている本人以外が延長しようとした場合
+            Some(CheckoutRenewalRow { user_id, .. }) if user_id != event.renewed_by => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    "Book not checked out by the user: book_id={}, checkout_id={}, user_id={}",
+                    event.book_id, event.checkout_id, event.renewed_by
+                )))
+            }
+            // 延長回数の上限に達している場合
+            Some(CheckoutRenewalRow { renewal_count, .. })
+                if renewal_count >= self.max_renewals =>
+            {
+                return Err(AppError::UnprocessableEntity(format!(
+                    "Renewal limit reached: checkout_id={}, max_renewals={}",
+                    event.checkout_id, self.max_renewals
+                )))
+            }
+            // 予約待ちの利用者がいる場合は延長できない
+            Some(CheckoutRenewalRow {
+                has_reservations: true,
+                ..
+            }) => {
+                return Err(AppError::UnprocessableEntity(format!(
+                    "Book has pending reservations: book_id={}",
+                    event.book_id
+                )))
+            }
+            Some(row) => row,
+        };
+
+        // 延長した時点から貸出期間を数え直すが、元の返却期限より前倒しにはしない
+        let due_at = row.due_at.max(event.renewed_at + self.loan_period());
+        let res = sqlx::query!(
+            r#"
+                UPDATE checkouts
+                SET
+                    due_at = $2,
+                    renewal_count = renewal_count + 1
+                WHERE checkout_id = $1;
+            "#,
+            event.checkout_id as _,
+            due_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::NoRowsAffectedError(
+                "No checkout record has been renewed".to_string(),
+            ));
+        }
+
+        record_event(
+            &mut tx,
+            DomainEvent::CheckoutRenewed {
+                checkout_id: event.checkout_id,
+                book_id: event.book_id,
+                renewed_by: event.renewed_by,
+                renewed_at: event.renewed_at,
+                due_at,
+            },
+        )
+        .await?;
+
         tx.commit().await.map_err(AppError::TransactionError)?;
 
         Ok(())
@@ -191,8 +420,12 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
                 SELECT
                     c.checkout_id,
                     c.book_id,
+                    c.copy_id,
                     c.user_id,
+                    c.processed_by,
                     c.checked_out_at,
+                    c.due_at,
+                    c.renewal_count,
                     b.title,
                     b.author,
                     b.isbn
@@ -207,6 +440,35 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
         .map_err(AppError::SpecificOperationError)
     }
 
+    async fn find_overdue_all(&self, now: DateTime<Utc>) -> AppResult<Vec<Checkout>> {
+        sqlx::query_as!(
+            CheckoutRow,
+            r#"
+                SELECT
+                    c.checkout_id,
+                    c.book_id,
+                    c.copy_id,
+                    c.user_id,
+                    c.processed_by,
+                    c.checked_out_at,
+                    c.due_at,
+                    c.renewal_count,
+                    b.title,
+                    b.author,
+                    b.isbn
+                FROM checkouts AS c
+                INNER JOIN books AS b USING (book_id)
+                WHERE c.due_at < $1
+                ORDER BY c.due_at ASC;
+            "#,
+            now
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map(|rows| rows.into_iter().map(Checkout::from).collect())
+        .map_err(AppError::SpecificOperationError)
+    }
+
     async fn find_unreturned_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Checkout>> {
         sqlx::query_as!(
             CheckoutRow,
@@ -214,8 +476,12 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
                 SELECT
                     c.checkout_id,
                     c.book_id,
+                    c.copy_id,
                     c.user_id,
+                    c.processed_by,
                     c.checked_out_at,
+                    c.due_at,
+                    c.renewal_count,
                     b.title,
                     b.author,
                     b.isbn
@@ -233,19 +499,24 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
     }
 
     async fn find_history_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Checkout>> {
-        // 未返却の貸出情報を取得する
-        let checkout: Option<Checkout> = self.find_unreturned_by_book_id(book_id).await?;
+        // 未返却の貸出情報を取得する。複本ごとに貸し出すため、複数件になることがある
+        let mut checkouts: Vec<Checkout> = self.find_unreturned_by_book_id(book_id).await?;
 
         // 返却済みの貸出情報を取得する
-        let mut checkout_histories = sqlx::query_as!(
+        let checkout_histories = sqlx::query_as!(
             ReturnedCheckoutRow,
             r#"
                 SELECT
                     rc.checkout_id,
                     rc.book_id,
+                    rc.copy_id AS "copy_id: BookCopyId",
                     rc.user_id,
+                    rc.processed_by,
                     rc.checked_out_at,
+                    rc.due_at,
+                    rc.renewal_count,
                     rc.returned_at,
+                    rc.returned_by,
                     b.title,
                     b.author,
                     b.isbn
@@ -263,54 +534,433 @@ impl CheckoutRepository for CheckoutRepositoryImpl {
         .map(Checkout::from)
         .collect::<Vec<Checkout>>();
 
-        // 貸出中の情報があれば先頭に追加する
-        if let Some(co) = checkout {
-            checkout_histories.insert(0, co);
-        }
+        // 貸出中の情報を先頭に並べる
+        checkouts.extend(checkout_histories);
 
-        Ok(checkout_histories)
+        Ok(checkouts)
     }
 }
 
+/// 指定した蔵書の複本ごとの貸出状況を、バーコード順に取得する。
+pub(crate) async fn find_copy_states(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    book_id: BookId,
+) -> AppResult<Vec<CopyStateRow>> {
+    sqlx::query_as!(
+        CopyStateRow,
+        r#"
+            SELECT
+                bc.copy_id,
+                c.checkout_id AS "checkout_id?: CheckoutId",
+                c.user_id AS "user_id?: UserId"
+            FROM book_copies AS bc
+            LEFT OUTER JOIN checkouts AS c USING (copy_id)
+            WHERE bc.book_id = $1
+            ORDER BY bc.barcode ASC;
+        "#,
+        book_id as _
+    )
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
 impl CheckoutRepositoryImpl {
-    /// トランザクション分離レベルを SERIALIZABLE に設定することで結果の整合性を担保する内部メソッド。
-    /// create や update_returned などのメソッドでトランザクションを利用する場合に呼び出だされる。
-    /// @see https://www.postgresql.jp/document/16/html/transaction-iso.html
-    async fn set_transaction_serializable(
+    fn loan_period(&self) -> Duration {
+        Duration::days(self.loan_period_days)
+    }
+
+    /// 貸出・返却の完了を利用者に通知する。通知の失敗で貸出処理自体は失敗させない。
+    async fn notify_book_event(
         &self,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
-    ) -> AppResult<()> {
-        sqlx::query!("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
-            .execute(&mut **tx)
-            .await
-            .map_err(AppError::SpecificOperationError)?;
+        user_id: UserId,
+        book_id: BookId,
+        kind: NotificationKind,
+        message: impl FnOnce(&str) -> (String, String),
+    ) {
+        let title = sqlx::query_scalar!(
+            r#"
+                SELECT title FROM books WHERE book_id = $1;
+            "#,
+            book_id as _
+        )
+        .fetch_one(self.db.inner_ref())
+        .await;
 
-        Ok(())
+        let res = match title {
+            Ok(title) => {
+                let (subject, body) = message(&title);
+                self.notification
+                    .notify(CreateNotification::new(user_id, kind, subject, body))
+                    .await
+            }
+            Err(e) => Err(AppError::SpecificOperationError(e)),
+        };
+
+        if let Err(e) = res {
+            tracing::warn!(
+                error.message = %e,
+                %user_id,
+                kind = kind.as_ref(),
+                "Failed to send notification"
+            );
+        }
     }
 
-    async fn find_unreturned_by_book_id(&self, book_id: BookId) -> AppResult<Option<Checkout>> {
-        let res = sqlx::query_as!(
+    async fn find_unreturned_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Checkout>> {
+        sqlx::query_as!(
             CheckoutRow,
             r#"
                 SELECT
                     c.checkout_id,
                     c.book_id,
+                    c.copy_id,
                     c.user_id,
+                    c.processed_by,
                     c.checked_out_at,
+                    c.due_at,
+                    c.renewal_count,
                     b.title,
                     b.author,
                     b.isbn
                 FROM checkouts AS c
                 INNER JOIN books AS b USING (book_id)
-                WHERE c.book_id = $1;
+                WHERE c.book_id = $1
+                ORDER BY c.checked_out_at ASC;
             "#,
             book_id as _
         )
-        .fetch_optional(self.db.inner_ref())
+        .fetch_all(self.db.inner_ref())
         .await
-        .map_err(AppError::SpecificOperationError)?
-        .map(Checkout::from);
+        .map(|rows| rows.into_iter().map(Checkout::from).collect())
+        .map_err(AppError::SpecificOperationError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use kernel::{
+        model::book::{event::CreateBook, BookListOptions, CheckoutStatus},
+        repository::book::BookRepository,
+    };
+
+    use super::*;
+    use crate::{
+        blob::memory::InMemoryBlobStore,
+        notification::memory::InMemoryNotificationSender,
+        repository::{book::BookRepositoryImpl, notification::NotificationRepositoryImpl},
+    };
+
+    const BOOK_ID: &str = "9890736e-a4e4-461a-a77d-eac3517ef11b";
+    const CHECKOUT_ID: &str = "a3a33a5e-2c27-4b6d-9a4b-1a6f2f3c8d10";
+    const OWNER_ID: &str = "2bbd820c-7a88-450c-b056-19dcbadd527d";
+    const BORROWER_ID: &str = "5b4c96ac-316a-4bee-8e69-cac5eb84ff4c";
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_checkout_due_date(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let sender = Arc::new(InMemoryNotificationSender::default());
+        let notification = Arc::new(NotificationRepositoryImpl::new(db.clone(), sender.clone()));
+        let repo = CheckoutRepositoryImpl::new(db, 14, 2, notification);
+
+        let book_id = BookId::from_str(BOOK_ID)?;
+        let user_id = UserId::from_str(OWNER_ID)?;
+        let now = Utc::now();
+
+        repo.create(CreateCheckout::new(
+            book_id,
+            None,
+            user_id,
+            user_id,
+            now,
+            Some(Duration::days(3)),
+        ))
+        .await?;
+
+        let checkout = repo.find_unreturned_by_user_id(user_id).await?.remove(0);
+        assert_eq!((checkout.due_at - checkout.checked_out_at).num_days(), 3);
+        assert!(!checkout.is_overdue(now));
+        assert!(checkout.is_overdue(now + Duration::days(4)));
+
+        // 貸出が完了すると利用者に通知される
+        let sent = sender.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].kind, NotificationKind::CheckedOut);
+        assert_eq!(sent[0].user_id, user_id);
 
-        Ok(res)
+        assert!(repo.find_overdue_all(now).await?.is_empty());
+        assert_eq!(
+            repo.find_overdue_all(now + Duration::days(4)).await?.len(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "user", "book"))]
+    async fn test_checkout_and_return_on_behalf_of_user(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let sender = Arc::new(InMemoryNotificationSender::default());
+        let notification = Arc::new(NotificationRepositoryImpl::new(db.clone(), sender.clone()));
+        let repo = CheckoutRepositoryImpl::new(db, 14, 2, notification);
+
+        let book_id = BookId::from_str(BOOK_ID)?;
+        let staff_id = UserId::from_str(OWNER_ID)?;
+        let borrower_id = UserId::from_str(BORROWER_ID)?;
+        let now = Utc::now();
+
+        // 存在しない利用者に代わって借りることはできない
+        let res = repo
+            .create(CreateCheckout::new(
+                book_id,
+                None,
+                UserId::new(),
+                staff_id,
+                now,
+                None,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.create(CreateCheckout::new(
+            book_id,
+            None,
+            borrower_id,
+            staff_id,
+            now,
+            None,
+        ))
+        .await?;
+
+        let checkout = repo
+            .find_unreturned_by_user_id(borrower_id)
+            .await?
+            .remove(0);
+        assert_eq!(checkout.checked_out_by, borrower_id);
+        assert_eq!(checkout.processed_by, staff_id);
+
+        // 権限がなければ、借りている本人以外は返却できない
+        let res = repo
+            .update_returned(UpdateReturned::new(
+                checkout.id,
+                book_id,
+                staff_id,
+                now,
+                false,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        repo.update_returned(UpdateReturned::new(
+            checkout.id,
+            book_id,
+            staff_id,
+            now,
+            true,
+        ))
+        .await?;
+
+        let history = repo.find_history_by_book_id(book_id).await?.remove(0);
+        assert_eq!(history.checked_out_by, borrower_id);
+        assert_eq!(history.processed_by, staff_id);
+        assert_eq!(history.returned_by, Some(staff_id));
+
+        // 貸出・返却の通知は、手続きをした利用者ではなく借り手に届く
+        let sent = sender.sent();
+        assert_eq!(sent.len(), 2);
+        assert!(sent.iter().all(|n| n.user_id == borrower_id));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "user", "book"))]
+    async fn test_checkout_multiple_copies(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let notification = Arc::new(NotificationRepositoryImpl::new(
+            db.clone(),
+            Arc::new(InMemoryNotificationSender::default()),
+        ));
+        let repo = CheckoutRepositoryImpl::new(db.clone(), 14, 2, notification);
+
+        let book_id = BookId::from_str(BOOK_ID)?;
+        let owner_id = UserId::from_str(OWNER_ID)?;
+        let borrower_id = UserId::from_str(BORROWER_ID)?;
+        let now = Utc::now();
+
+        let second_copy = sqlx::query_scalar!(
+            r#"
+                INSERT INTO book_copies (book_id, barcode)
+                VALUES ($1, 'RBM-0001-2')
+                RETURNING copy_id AS "copy_id: BookCopyId";
+            "#,
+            book_id as _
+        )
+        .fetch_one(db.inner_ref())
+        .await?;
+
+        // 複本を指定して借りる
+        repo.create(CreateCheckout::new(
+            book_id,
+            Some(second_copy),
+            owner_id,
+            owner_id,
+            now,
+            None,
+        ))
+        .await?;
+
+        // 同じ利用者は、同じ蔵書の別の複本を重ねて借りられない
+        let res = repo
+            .create(CreateCheckout::new(
+                book_id, None, owner_id, owner_id, now, None,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 複本を指定しなければ、貸出中でない複本が選ばれる
+        repo.create(CreateCheckout::new(
+            book_id,
+            None,
+            borrower_id,
+            borrower_id,
+            now,
+            None,
+        ))
+        .await?;
+        let checkout = repo
+            .find_unreturned_by_user_id(borrower_id)
+            .await?
+            .remove(0);
+        assert_ne!(checkout.book.copy_id, Some(second_copy));
+
+        // すべての複本が貸出中の場合は借りられない
+        let res = repo
+            .create(CreateCheckout::new(
+                book_id,
+                None,
+                UserId::new(),
+                owner_id,
+                now,
+                None,
+            ))
+            .await;
+        assert!(res.is_err());
+        assert_eq!(repo.find_history_by_book_id(book_id).await?.len(), 2);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_checkout_registered_book(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let notification = Arc::new(NotificationRepositoryImpl::new(
+            db.clone(),
+            Arc::new(InMemoryNotificationSender::default()),
+        ));
+        let repo = CheckoutRepositoryImpl::new(db.clone(), 14, 2, notification);
+        let books = BookRepositoryImpl::new(db.clone(), Arc::new(InMemoryBlobStore::default()));
+        let owner_id = UserId::from_str(OWNER_ID)?;
+        let borrower_id = UserId::from_str(BORROWER_ID)?;
+        let now = Utc::now();
+        let create_book = |isbn: &str| CreateBook {
+            title: "RustによるWebアプリケーション開発".into(),
+            author: "Yuki Toyoda".into(),
+            authors: vec![],
+            isbn: isbn.into(),
+            description: "".into(),
+        };
+
+        // 登録したばかりの蔵書も、そのまま貸し出せる
+        let book_id = books.create(create_book("9784065369579"), owner_id).await?;
+        repo.create(CreateCheckout::new(
+            book_id,
+            None,
+            borrower_id,
+            borrower_id,
+            now,
+            None,
+        ))
+        .await?;
+
+        // 複本が 1 冊もない蔵書は、貸出中ではなく複本がないものとして扱う
+        let empty_id = books.create(create_book("9784798061702"), owner_id).await?;
+        sqlx::query!("DELETE FROM book_copies WHERE book_id = $1", empty_id as _)
+            .execute(db.inner_ref())
+            .await?;
+        let res = repo
+            .create(CreateCheckout::new(
+                empty_id, None, owner_id, owner_id, now, None,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(m)) if m.contains("no copies")));
+
+        let options = BookListOptions {
+            limit: 10,
+            checkout_status: Some(CheckoutStatus::CheckedOut),
+            ..Default::default()
+        };
+        assert_eq!(books.find_facets(&options).await?.checked_out, 1);
+        let checked_out = books.find_all(options).await?;
+        assert_eq!(
+            checked_out.items.iter().map(|b| b.id).collect::<Vec<_>>(),
+            vec![book_id]
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book", "checkout"))]
+    async fn test_renew_checkout(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let notification = Arc::new(NotificationRepositoryImpl::new(
+            db.clone(),
+            Arc::new(InMemoryNotificationSender::default()),
+        ));
+        let repo = CheckoutRepositoryImpl::new(db, 14, 2, notification);
+
+        let book_id = BookId::from_str(BOOK_ID)?;
+        let checkout_id = CheckoutId::from_str(CHECKOUT_ID)?;
+        let user_id = UserId::from_str(OWNER_ID)?;
+        let renewed_at = Utc::now() + Duration::days(10);
+
+        for _ in 0..2 {
+            repo.update_renewed(UpdateRenewed::new(
+                checkout_id,
+                book_id,
+                user_id,
+                renewed_at,
+            ))
+            .await?;
+        }
+
+        let checkout = repo.find_unreturned_by_user_id(user_id).await?.remove(0);
+        assert_eq!(checkout.renewal_count, 2);
+        assert!(checkout.due_at >= renewed_at + Duration::days(14) - Duration::seconds(1));
+
+        // 延長回数の上限を超えて延長することはできない
+        let res = repo
+            .update_renewed(UpdateRenewed::new(
+                checkout_id,
+                book_id,
+                user_id,
+                renewed_at,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 借りている本人以外は延長できない
+        let res = repo
+            .update_renewed(UpdateRenewed::new(
+                checkout_id,
+                book_id,
+                UserId::new(),
+                renewed_at,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        Ok(())
     }
 }
@@ -0,0 +1,356 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        collection::{
+            event::{
+                AddCollectionBook, CreateCollection, DeleteCollection, RemoveCollectionBook,
+                UpdateCollection,
+            },
+            Collection,
+        },
+        id::{CollectionId, UserId},
+    },
+    repository::collection::CollectionRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    database::{model::collection::CollectionRow, ConnectionPool},
+    repository::book::ensure_book_exists,
+};
+
+#[derive(new)]
+pub struct CollectionRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl CollectionRepository for CollectionRepositoryImpl {
+    async fn create(&self, event: CreateCollection) -> AppResult<Collection> {
+        let collection_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO collections (user_id, name, description, shared)
+                VALUES ($1, $2, $3, $4)
+                RETURNING collection_id AS "collection_id: CollectionId";
+            "#,
+            event.requested_user as _,
+            event.name,
+            event.description,
+            event.shared
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        self.find_by_id(collection_id, event.requested_user)
+            .await?
+            .ok_or_else(|| AppError::EntityNotFound("Specified collection not found".into()))
+    }
+
+    async fn find_all(&self, user_id: UserId) -> AppResult<Vec<Collection>> {
+        let rows = sqlx::query_as!(
+            CollectionRow,
+            r#"
+                SELECT
+                    c.collection_id,
+                    c.name,
+                    c.description,
+                    c.shared,
+                    u.user_id AS owned_by,
+                    u.name AS owner_name,
+                    (
+                        SELECT COUNT(*) FROM collection_books AS cb
+                        WHERE cb.collection_id = c.collection_id
+                    ) AS "book_count!",
+                    c.created_at,
+                    c.updated_at
+                FROM collections AS c
+                INNER JOIN users AS u USING (user_id)
+                WHERE c.user_id = $1 OR c.shared
+                ORDER BY c.created_at DESC, c.collection_id;
+            "#,
+            user_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(rows.into_iter().map(Collection::from).collect())
+    }
+
+    async fn find_by_id(
+        &self,
+        collection_id: CollectionId,
+        user_id: UserId,
+    ) -> AppResult<Option<Collection>> {
+        let row = sqlx::query_as!(
+            CollectionRow,
+            r#"
+                SELECT
+                    c.collection_id,
+                    c.name,
+                    c.description,
+                    c.shared,
+                    u.user_id AS owned_by,
+                    u.name AS owner_name,
+                    (
+                        SELECT COUNT(*) FROM collection_books AS cb
+                        WHERE cb.collection_id = c.collection_id
+                    ) AS "book_count!",
+                    c.created_at,
+                    c.updated_at
+                FROM collections AS c
+                INNER JOIN users AS u USING (user_id)
+                WHERE c.collection_id = $1
+                AND (c.user_id = $2 OR c.shared);
+            "#,
+            collection_id as _,
+            user_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(row.map(Collection::from))
+    }
+
+    async fn update(&self, event: UpdateCollection) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE collections
+                SET
+                    name = $1,
+                    description = $2,
+                    shared = $3
+                WHERE collection_id = $4
+                AND user_id = $5;
+            "#,
+            event.name,
+            event.description,
+            event.shared,
+            event.collection_id as _,
+            event.requested_user as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound(
+                "Specified collection not found".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, event: DeleteCollection) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM collections
+                WHERE collection_id = $1
+                AND user_id = $2;
+            "#,
+            event.collection_id as _,
+            event.requested_user as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound(
+                "Specified collection not found".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn add_book(&self, event: AddCollectionBook) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        ensure_collection_editable(&mut tx, event.collection_id, event.requested_user).await?;
+        ensure_book_exists(&mut tx, event.book_id).await?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO collection_books (collection_id, book_id)
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING;
+            "#,
+            event.collection_id as _,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn remove_book(&self, event: RemoveCollectionBook) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        ensure_collection_editable(&mut tx, event.collection_id, event.requested_user).await?;
+
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM collection_books
+                WHERE collection_id = $1 AND book_id = $2;
+            "#,
+            event.collection_id as _,
+            event.book_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound(
+                "Specified book is not in the collection".into(),
+            ));
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+}
+
+/// 蔵書の出し入れは、所有者に加えて共有されたコレクションを参照できるユーザーにも許す。
+async fn ensure_collection_editable(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    collection_id: CollectionId,
+    requested_user: UserId,
+) -> AppResult<()> {
+    let editable = sqlx::query_scalar!(
+        r#"
+            SELECT EXISTS (
+                SELECT 1 FROM collections
+                WHERE collection_id = $1
+                AND (user_id = $2 OR shared)
+            ) AS "editable!";
+        "#,
+        collection_id as _,
+        requested_user as _
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    if !editable {
+        return Err(AppError::EntityNotFound(
+            "Specified collection not found".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, sync::Arc};
+
+    use kernel::{
+        model::{book::BookListOptions, id::BookId},
+        repository::book::BookRepository,
+    };
+
+    use super::*;
+    use crate::{blob::memory::InMemoryBlobStore, repository::book::BookRepositoryImpl};
+
+    #[sqlx::test(fixtures("common", "user", "book"))]
+    async fn test_collections(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let owner = UserId::from_str("2bbd820c-7a88-450c-b056-19dcbadd527d")?;
+        let other = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let repo = CollectionRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let books = BookRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            Arc::new(InMemoryBlobStore::default()),
+        );
+
+        let collection = repo
+            .create(CreateCollection::new(
+                "Reading list".into(),
+                "".into(),
+                false,
+                owner,
+            ))
+            .await?;
+        assert_eq!(collection.owner.id, owner);
+        assert_eq!(collection.book_count, 0);
+
+        // 共有していないコレクションは所有者以外には見えない
+        assert!(repo.find_by_id(collection.id, other).await?.is_none());
+        assert!(repo.find_all(other).await?.is_empty());
+        let res = repo
+            .add_book(AddCollectionBook::new(collection.id, book_id, other))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.add_book(AddCollectionBook::new(collection.id, book_id, owner))
+            .await?;
+        // 2 回目は何もしない
+        repo.add_book(AddCollectionBook::new(collection.id, book_id, owner))
+            .await?;
+        let res = repo
+            .add_book(AddCollectionBook::new(collection.id, BookId::new(), owner))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let found = repo.find_by_id(collection.id, owner).await?.unwrap();
+        assert_eq!(found.book_count, 1);
+        let in_collection = books
+            .find_all(BookListOptions {
+                limit: 10,
+                collection_id: Some(collection.id),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(in_collection.total, 1);
+        assert_eq!(in_collection.items[0].id, book_id);
+
+        // 共有すると他のユーザーも参照・蔵書の出し入れができるが、設定の変更や削除はできない
+        repo.update(UpdateCollection::new(
+            collection.id,
+            "Reading list".into(),
+            "Shared with everyone".into(),
+            true,
+            owner,
+        ))
+        .await?;
+        assert_eq!(repo.find_all(other).await?.len(), 1);
+        let res = repo
+            .update(UpdateCollection::new(
+                collection.id,
+                "Mine".into(),
+                "".into(),
+                false,
+                other,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+        let res = repo
+            .delete(DeleteCollection::new(collection.id, other))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.remove_book(RemoveCollectionBook::new(collection.id, book_id, other))
+            .await?;
+        let res = repo
+            .remove_book(RemoveCollectionBook::new(collection.id, book_id, other))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.delete(DeleteCollection::new(collection.id, owner))
+            .await?;
+        assert!(repo.find_by_id(collection.id, owner).await?.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+INSERT INTO
+    checkouts (
+        checkout_id,
+        book_id,
+        copy_id,
+        user_id,
+        processed_by,
+        checked_out_at,
+        due_at
+    )
+VALUES
+    (
+        'a3a33a5e-2c27-4b6d-9a4b-1a6f2f3c8d10',
+        '9890736e-a4e4-461a-a77d-eac3517ef11b',
+        '0c8f3b1e-7d5a-4f2e-9b6c-1a2d3e4f5a61',
+        '2bbd820c-7a88-450c-b056-19dcbadd527d',
+        '2bbd820c-7a88-450c-b056-19dcbadd527d',
+        now(),
+        now() + INTERVAL '14 days'
+    ) ON CONFLICT DO NOTHING;
@@ -1,5 +1,19 @@
 INSERT INTO roles (name) VALUES ('Admin'), ('User');
 
+INSERT INTO role_permissions (role_id, permission)
+SELECT role_id, permission
+FROM roles
+CROSS JOIN UNNEST(ARRAY[
+    'book:write:any',
+    'checkout:create:any',
+    'checkout:return:any',
+    'user:manage',
+    'role:manage',
+    'audit:read'
+]) AS permission
+WHERE name = 'Admin'
+ON CONFLICT DO NOTHING;
+
 INSERT INTO users (user_id, name, email, password_hash, role_id)
 SELECT
     '2bbd820c-7a88-450c-b056-19dcbadd527d'
@@ -0,0 +1,17 @@
+INSERT INTO users (user_id, name, email, password_hash, role_id)
+SELECT
+    '5b4c96ac-316a-4bee-8e69-cac5eb84ff4c'
+    , 'Anne Sallow'
+    , 'anne.sallow@example.com'
+    , '$2b$12$sGXC.3Ew9yBl9dCKsQTfgebvkbkg/mRz9BRpL5fQgSU5TDDzta.Ay'
+    , role_id
+FROM roles WHERE name = 'User';
+
+INSERT INTO users (user_id, name, email, password_hash, role_id)
+SELECT
+    '9582f9de-0fd1-4892-b20c-70139a7eb95b'
+    , 'Natsai Onai'
+    , 'natsai.onai@example.com'
+    , '$2b$12$sGXC.3Ew9yBl9dCKsQTfgebvkbkg/mRz9BRpL5fQgSU5TDDzta.Ay'
+    , role_id
+FROM roles WHERE name = 'User';
@@ -0,0 +1,294 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{auth::LoginAttempt, id::UserId},
+    repository::login_attempt::LoginAttemptRepository,
+};
+use shared::{
+    config::LoginThrottleConfig,
+    error::{AppError, AppResult},
+};
+
+use crate::{
+    database::{
+        model::auth::{account_lock_key, login_backoff_key, login_failures_key, LoginAttemptScope},
+        ConnectionPool,
+    },
+    redis::RedisClient,
+};
+
+#[derive(new)]
+pub struct LoginAttemptRepositoryImpl {
+    db: ConnectionPool,
+    kv: Arc<RedisClient>,
+    config: LoginThrottleConfig,
+}
+
+#[async_trait]
+impl LoginAttemptRepository for LoginAttemptRepositoryImpl {
+    async fn begin(&self, attempt: &LoginAttempt) -> AppResult<()> {
+        let email = LoginAttemptScope::email(&attempt.email);
+        if let Some(retry_after) = self.kv.ttl(&account_lock_key(&email)).await? {
+            return Err(AppError::AccountLockedError(retry_after));
+        }
+
+        let scopes = LoginAttemptScope::of(attempt);
+        for scope in &scopes {
+            if let Some(retry_after) = self.kv.ttl(&login_backoff_key(scope)).await? {
+                return Err(AppError::LoginThrottledError(retry_after));
+            }
+        }
+
+        // パスワードを照合する前に試行を数えることで、並行した試行も上限の内に収める
+        for (i, scope) in scopes.iter().enumerate() {
+            let attempts = self
+                .kv
+                .incr_with_ex(&login_failures_key(scope), self.config.failure_window)
+                .await?;
+
+            if let Err(e) = self.admit(scope, attempts).await {
+                // 受け付けなかった試行は数えない
+                for scope in &scopes[..=i] {
+                    self.kv.decr_if_exists(&login_failures_key(scope)).await?;
+                }
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn record_failure(&self, attempt: &LoginAttempt) -> AppResult<()> {
+        for scope in LoginAttemptScope::of(attempt) {
+            // 試行の開始時に数えているため、ここでは数え直さない
+            let failures = self.kv.get_count(&login_failures_key(&scope)).await?;
+
+            if matches!(scope, LoginAttemptScope::Email(_)) && failures >= self.config.max_failures
+            {
+                tracing::warn!(
+                    scope = %scope,
+                    failures,
+                    "too many failed login attempts; locking account"
+                );
+                self.kv
+                    .set_ex(
+                        &account_lock_key(&scope),
+                        &failures.to_string(),
+                        self.config.lockout_duration,
+                    )
+                    .await?;
+                // ロックが明けた後は、改めて上限までの試行を許す
+                self.reset(&scope).await?;
+                continue;
+            }
+
+            let delay = backoff_delay(failures, self.config.backoff_base, self.config.backoff_max);
+            if delay > 0 {
+                self.kv
+                    .set_ex(&login_backoff_key(&scope), &failures.to_string(), delay)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn record_success(&self, attempt: &LoginAttempt) -> AppResult<()> {
+        // メールアドレスと IP アドレスの両方の失敗回数と待機時間を解除する
+        for scope in LoginAttemptScope::of(attempt) {
+            self.reset(&scope).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn unlock(&self, user_id: UserId) -> AppResult<()> {
+        let email = sqlx::query_scalar!(
+            r#"
+                SELECT email FROM users
+                WHERE user_id = $1;
+            "#,
+            user_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| AppError::EntityNotFound("Specified user not found".to_string()))?;
+
+        let scope = LoginAttemptScope::email(&email);
+        self.kv.delete_key(&account_lock_key(&scope)).await?;
+        self.reset(&scope).await
+    }
+}
+
+impl LoginAttemptRepositoryImpl {
+    /// `attempts` 回目の試行を受け付けるかを判定する。`attempts` には結果の出ていない試行も含む。
+    async fn admit(&self, scope: &LoginAttemptScope, attempts: u64) -> AppResult<()> {
+        let delay = backoff_delay(attempts, self.config.backoff_base, self.config.backoff_max);
+
+        // 結果を待たずに並行して試行しても、ロックされるまでの試行回数を超えられないようにする
+        if matches!(scope, LoginAttemptScope::Email(_)) && attempts > self.config.max_failures {
+            return Err(AppError::LoginThrottledError(delay.max(1)));
+        }
+
+        // 先行する試行があれば、次に試行できるまでの待機時間をこの試行が確保する。
+        // 確保できなければ、待機時間中の試行と同様に拒否する。
+        if attempts > 1
+            && delay > 0
+            && !self
+                .kv
+                .set_nx_ex(&login_backoff_key(scope), &attempts.to_string(), delay)
+                .await?
+        {
+            let retry_after = self.kv.ttl(&login_backoff_key(scope)).await?;
+            return Err(AppError::LoginThrottledError(retry_after.unwrap_or(delay)));
+        }
+
+        Ok(())
+    }
+
+    async fn reset(&self, scope: &LoginAttemptScope) -> AppResult<()> {
+        self.kv.delete_key(&login_failures_key(scope)).await?;
+        self.kv.delete_key(&login_backoff_key(scope)).await
+    }
+}
+
+/// `failures` 回目の失敗の後に待たせる秒数。失敗のたびに倍にし、`max` で頭打ちにする。
+fn backoff_delay(failures: u64, base: u64, max: u64) -> u64 {
+    let exponent = failures.saturating_sub(1).min(u32::MAX as u64) as u32;
+    base.saturating_mul(2u64.saturating_pow(exponent)).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use shared::config::RedisConfig;
+    use tokio::task::JoinSet;
+
+    use super::*;
+
+    fn repository(
+        pool: sqlx::PgPool,
+        config: LoginThrottleConfig,
+    ) -> Arc<LoginAttemptRepositoryImpl> {
+        let kv = RedisClient::new(&RedisConfig {
+            host: std::env::var("REDIS_HOST").unwrap_or_else(|_| "localhost".into()),
+            port: std::env::var("REDIS_PORT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(6379),
+        })
+        .unwrap();
+        Arc::new(LoginAttemptRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            Arc::new(kv),
+            config,
+        ))
+    }
+
+    /// 同じメールアドレスと IP アドレスから、結果を待たずに `n` 回のログインを同時に試行する
+    async fn begin_concurrently(
+        repo: &Arc<LoginAttemptRepositoryImpl>,
+        attempt: &LoginAttempt,
+        n: usize,
+    ) -> anyhow::Result<Vec<AppResult<()>>> {
+        let mut set = JoinSet::new();
+        for _ in 0..n {
+            let repo = repo.clone();
+            let attempt = LoginAttempt::new(attempt.email.clone(), attempt.client_ip);
+            set.spawn(async move { repo.begin(&attempt).await });
+        }
+
+        let mut results = Vec::new();
+        while let Some(res) = set.join_next().await {
+            results.push(res?);
+        }
+        Ok(results)
+    }
+
+    fn unique_attempt() -> LoginAttempt {
+        LoginAttempt::new(
+            format!("{}@example.com", uuid::Uuid::new_v4().simple()),
+            Some(std::net::IpAddr::from(rand::random::<[u8; 16]>())),
+        )
+    }
+
+    #[sqlx::test]
+    async fn test_concurrent_attempts_do_not_exceed_max_failures(
+        pool: sqlx::PgPool,
+    ) -> anyhow::Result<()> {
+        let repo = repository(
+            pool,
+            LoginThrottleConfig {
+                max_failures: 3,
+                lockout_duration: 60,
+                backoff_base: 0,
+                backoff_max: 0,
+                failure_window: 60,
+            },
+        );
+        let attempt = LoginAttempt::new(
+            format!("{}@example.com", uuid::Uuid::new_v4().simple()),
+            None,
+        );
+
+        let results = begin_concurrently(&repo, &attempt, 10).await?;
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 3);
+        assert!(results
+            .iter()
+            .filter_map(|r| r.as_ref().err())
+            .all(|e| matches!(e, AppError::LoginThrottledError(_))));
+
+        // 受け付けた試行がすべて失敗すると、アカウントがロックされる
+        for _ in 0..3 {
+            repo.record_failure(&attempt).await?;
+        }
+        assert!(matches!(
+            repo.begin(&attempt).await,
+            Err(AppError::AccountLockedError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_concurrent_attempts_respect_backoff(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = repository(
+            pool,
+            LoginThrottleConfig {
+                max_failures: 100,
+                lockout_duration: 60,
+                backoff_base: 30,
+                backoff_max: 60,
+                failure_window: 60,
+            },
+        );
+        let attempt = unique_attempt();
+
+        // 最初の試行と、その待機時間を確保した試行のみを受け付ける
+        let results = begin_concurrently(&repo, &attempt, 10).await?;
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 2);
+
+        // 成功するとメールアドレスと IP アドレスの失敗回数と待機時間が解除される
+        repo.record_success(&attempt).await?;
+        for scope in LoginAttemptScope::of(&attempt) {
+            assert_eq!(repo.kv.get_count(&login_failures_key(&scope)).await?, 0);
+            assert_eq!(repo.kv.ttl(&login_backoff_key(&scope)).await?, None);
+        }
+        assert!(repo.begin(&attempt).await.is_ok());
+        repo.record_success(&attempt).await?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn backoff_delay_doubles_up_to_max() {
+        assert_eq!(backoff_delay(1, 1, 60), 1);
+        assert_eq!(backoff_delay(2, 1, 60), 2);
+        assert_eq!(backoff_delay(4, 1, 60), 8);
+        assert_eq!(backoff_delay(7, 1, 60), 60);
+        assert_eq!(backoff_delay(100, 1, 60), 60);
+        assert_eq!(backoff_delay(3, 0, 60), 0);
+    }
+}
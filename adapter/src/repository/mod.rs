@@ -1,5 +1,18 @@
+pub mod api_key;
+pub mod audit;
 pub mod auth;
+pub mod author;
 pub mod book;
 pub mod checkout;
+pub mod collection;
 pub mod health;
+pub mod login_attempt;
+pub mod notification;
+pub mod oidc;
+pub mod outbox;
+pub mod password_reset;
+pub mod reservation;
+pub mod role;
+pub mod tag;
+pub mod two_factor;
 pub mod user;
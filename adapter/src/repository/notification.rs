@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::UserId,
+        notification::{
+            event::{CreateNotification, UpdateNotificationPreferences},
+            Notification, NotificationKind, NotificationPreference,
+        },
+    },
+    repository::notification::{NotificationRepository, NotificationSender},
+};
+use shared::error::{AppError, AppResult};
+use strum::IntoEnumIterator;
+
+use crate::database::{
+    model::notification::{NotificationPreferenceRow, NotificationRecipientRow},
+    ConnectionPool,
+};
+
+#[derive(new)]
+pub struct NotificationRepositoryImpl {
+    db: ConnectionPool,
+    sender: Arc<dyn NotificationSender>,
+}
+
+#[async_trait]
+impl NotificationRepository for NotificationRepositoryImpl {
+    async fn notify(&self, event: CreateNotification) -> AppResult<()> {
+        let recipient = sqlx::query_as!(
+            NotificationRecipientRow,
+            r#"
+                SELECT
+                    u.name,
+                    u.email,
+                    np.enabled AS "enabled?"
+                FROM users AS u
+                LEFT OUTER JOIN notification_preferences AS np
+                    ON np.user_id = u.user_id AND np.kind = $2
+                WHERE u.user_id = $1;
+            "#,
+            event.user_id as _,
+            event.kind.as_ref()
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| {
+            AppError::EntityNotFound(format!("User not found: user_id={}", event.user_id))
+        })?;
+
+        // 設定が未登録の種別は受け取るものとして扱う
+        if event.kind.is_optional() && recipient.enabled == Some(false) {
+            return Ok(());
+        }
+
+        self.sender
+            .send(&Notification {
+                user_id: event.user_id,
+                email: recipient.email,
+                user_name: recipient.name,
+                kind: event.kind,
+                subject: event.subject,
+                body: event.body,
+            })
+            .await
+    }
+
+    async fn find_preferences(&self, user_id: UserId) -> AppResult<Vec<NotificationPreference>> {
+        let stored = sqlx::query_as!(
+            NotificationPreferenceRow,
+            r#"
+                SELECT kind, enabled
+                FROM notification_preferences
+                WHERE user_id = $1;
+            "#,
+            user_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .filter_map(|row| NotificationPreference::try_from(row).ok())
+        .collect::<Vec<_>>();
+
+        // 停止できる全種別について、未登録のものは受け取る設定として返す
+        let preferences =
+            NotificationKind::iter()
+                .filter(NotificationKind::is_optional)
+                .map(|kind| {
+                    stored.iter().find(|p| p.kind == kind).copied().unwrap_or(
+                        NotificationPreference {
+                            kind,
+                            enabled: true,
+                        },
+                    )
+                })
+                .collect();
+
+        Ok(preferences)
+    }
+
+    async fn update_preferences(&self, event: UpdateNotificationPreferences) -> AppResult<()> {
+        if let Some(p) = event.preferences.iter().find(|p| !p.kind.is_optional()) {
+            return Err(AppError::UnprocessableEntity(format!(
+                "Notification kind cannot be disabled: {}",
+                p.kind.as_ref()
+            )));
+        }
+
+        let (kinds, enabled): (Vec<String>, Vec<bool>) = event
+            .preferences
+            .iter()
+            .map(|p| (p.kind.as_ref().to_string(), p.enabled))
+            .unzip();
+
+        sqlx::query!(
+            r#"
+                INSERT INTO notification_preferences (user_id, kind, enabled)
+                SELECT $1, p.kind, p.enabled
+                FROM UNNEST($2::VARCHAR[], $3::BOOLEAN[]) AS p(kind, enabled)
+                ON CONFLICT (user_id, kind) DO UPDATE SET enabled = EXCLUDED.enabled;
+            "#,
+            event.user_id as _,
+            &kinds,
+            &enabled
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::notification::memory::InMemoryNotificationSender;
+
+    use super::*;
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_notification_preferences(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let sender = Arc::new(InMemoryNotificationSender::default());
+        let repo = NotificationRepositoryImpl::new(ConnectionPool::new(pool), sender.clone());
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        // 未設定の場合はすべての種別を受け取る
+        let preferences = repo.find_preferences(user_id).await?;
+        assert_eq!(
+            preferences.len(),
+            NotificationKind::iter()
+                .filter(NotificationKind::is_optional)
+                .count()
+        );
+        assert!(preferences.iter().all(|p| p.enabled));
+
+        repo.update_preferences(UpdateNotificationPreferences {
+            user_id,
+            preferences: vec![NotificationPreference {
+                kind: NotificationKind::Overdue,
+                enabled: false,
+            }],
+        })
+        .await?;
+
+        let preferences = repo.find_preferences(user_id).await?;
+        assert!(preferences
+            .iter()
+            .all(|p| p.enabled == (p.kind != NotificationKind::Overdue)));
+
+        // 受け取りを停止した種別は送信されない
+        repo.notify(CreateNotification::new(
+            user_id,
+            NotificationKind::Overdue,
+            "Overdue".into(),
+            "Please return the book.".into(),
+        ))
+        .await?;
+        assert!(sender.sent().is_empty());
+
+        repo.notify(CreateNotification::new(
+            user_id,
+            NotificationKind::CheckedOut,
+            "Checked out".into(),
+            "You have checked out a book.".into(),
+        ))
+        .await?;
+        let sent = sender.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].kind, NotificationKind::CheckedOut);
+        assert_eq!(sent[0].user_name, "Anne Sallow");
+
+        // 停止できない種別は設定を変更できない
+        let res = repo
+            .update_preferences(UpdateNotificationPreferences {
+                user_id,
+                preferences: vec![NotificationPreference {
+                    kind: NotificationKind::PasswordReset,
+                    enabled: false,
+                }],
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        Ok(())
+    }
+}
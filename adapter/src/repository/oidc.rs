@@ -0,0 +1,330 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        auth::oidc::{OidcAuthorizationRequest, OidcCallback, OidcLogin},
+        id::UserId,
+        outbox::DomainEvent,
+        role::DEFAULT_ROLE_NAME,
+    },
+    repository::oidc::OidcRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    database::ConnectionPool,
+    oidc::{OidcClient, OidcIdentity},
+    repository::outbox::record_event,
+};
+
+#[derive(new)]
+pub struct OidcRepositoryImpl {
+    db: ConnectionPool,
+    /// OIDC が設定されていない場合は `None`
+    client: Option<Arc<OidcClient>>,
+}
+
+#[async_trait]
+impl OidcRepository for OidcRepositoryImpl {
+    async fn create_authorization_request(&self) -> AppResult<OidcAuthorizationRequest> {
+        let client = self.client()?;
+        let state = random_token();
+        let nonce = random_token();
+        // RFC 7636 の code_verifier は 43 〜 128 文字
+        let code_verifier = format!("{}{}", random_token(), random_token());
+
+        sqlx::query!(
+            r#"
+                DELETE FROM oidc_authorization_requests
+                WHERE created_at < CURRENT_TIMESTAMP - INTERVAL '10 minutes';
+            "#
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO oidc_authorization_requests (state, code_verifier, nonce)
+                VALUES ($1, $2, $3);
+            "#,
+            state,
+            code_verifier,
+            nonce
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let authorization_url = client
+            .authorization_url(&state, &nonce, &code_verifier)
+            .await?;
+
+        Ok(OidcAuthorizationRequest { authorization_url })
+    }
+
+    async fn callback(&self, event: OidcCallback) -> AppResult<OidcLogin> {
+        let client = self.client()?;
+
+        // state は一度きりしか使えないよう、取り出すと同時に削除する
+        let request = sqlx::query!(
+            r#"
+                DELETE FROM oidc_authorization_requests
+                WHERE state = $1
+                AND created_at >= CURRENT_TIMESTAMP - INTERVAL '10 minutes'
+                RETURNING code_verifier, nonce;
+            "#,
+            event.state
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or(AppError::UnauthenticatedError)?;
+
+        let identity = client
+            .exchange_code(&event.code, &request.code_verifier, &request.nonce)
+            .await?;
+
+        self.link_identity(identity, client.auto_provision()).await
+    }
+}
+
+impl OidcRepositoryImpl {
+    fn client(&self) -> AppResult<&OidcClient> {
+        self.client
+            .as_deref()
+            .ok_or_else(|| AppError::EntityNotFound("OIDC login is not configured".into()))
+    }
+
+    /// 外部アカウントに紐付くユーザーを探す。
+    /// 未連携の場合は、確認済みのメールアドレスが一致するユーザーに連携するか、自動登録する。
+    async fn link_identity(
+        &self,
+        identity: OidcIdentity,
+        auto_provision: bool,
+    ) -> AppResult<OidcLogin> {
+        let mut tx = self.db.begin().await?;
+
+        let linked = sqlx::query_scalar!(
+            r#"
+                SELECT user_id AS "user_id: UserId" FROM user_identities
+                WHERE issuer = $1 AND subject = $2;
+            "#,
+            identity.issuer,
+            identity.subject
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if let Some(user_id) = linked {
+            return Ok(OidcLogin {
+                user_id,
+                provisioned: false,
+            });
+        }
+
+        // 同じメールアドレスのユーザーへの連携や自動登録には、IdP で確認済みのメールアドレスが必要
+        let email = identity
+            .email
+            .as_deref()
+            .filter(|_| identity.email_verified)
+            .ok_or(AppError::UnauthenticatedError)?;
+
+        let existing = sqlx::query_scalar!(
+            r#"
+                SELECT user_id AS "user_id: UserId" FROM users WHERE email = $1;
+            "#,
+            email
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let (user_id, provisioned) = match existing {
+            Some(user_id) => (user_id, false),
+            None if auto_provision => (provision_user(&mut tx, &identity, email).await?, true),
+            None => return Err(AppError::UnauthenticatedError),
+        };
+
+        sqlx::query!(
+            r#"
+                INSERT INTO user_identities (user_id, issuer, subject, email)
+                VALUES ($1, $2, $3, $4);
+            "#,
+            user_id as _,
+            identity.issuer,
+            identity.subject,
+            identity.email
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(OidcLogin {
+            user_id,
+            provisioned,
+        })
+    }
+}
+
+/// 一般ユーザーとして登録する。パスワードでのログインはできないよう、推測できないパスワードを設定する。
+async fn provision_user(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    identity: &OidcIdentity,
+    email: &str,
+) -> AppResult<UserId> {
+    let user_id = UserId::new();
+    let name = identity
+        .name
+        .clone()
+        .unwrap_or_else(|| email.split('@').next().unwrap_or(email).to_string());
+    let password_hash = bcrypt::hash(random_token(), bcrypt::DEFAULT_COST)?;
+
+    let res = sqlx::query!(
+        r#"
+            INSERT INTO users (user_id, name, email, password_hash, role_id)
+            SELECT $1, $2, $3, $4, role_id FROM roles WHERE name = $5;
+        "#,
+        user_id as _,
+        name,
+        email,
+        password_hash,
+        DEFAULT_ROLE_NAME,
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    if res.rows_affected() == 0 {
+        return Err(AppError::NoRowsAffectedError(
+            "No rows has been created".into(),
+        ));
+    }
+
+    record_event(
+        tx,
+        DomainEvent::UserCreated {
+            user_id,
+            name,
+            email: email.to_string(),
+            role: DEFAULT_ROLE_NAME.to_string(),
+        },
+    )
+    .await?;
+
+    Ok(user_id)
+}
+
+fn random_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::oidc::mock::MockProvider;
+
+    async fn login(
+        repo: &OidcRepositoryImpl,
+        provider: &MockProvider,
+        user: serde_json::Value,
+    ) -> AppResult<OidcLogin> {
+        let request = repo.create_authorization_request().await?;
+        let (code, state) = provider.grant(&request.authorization_url, user);
+        repo.callback(OidcCallback::new(code, state)).await
+    }
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_oidc_login_links_and_provisions_users(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let provider = MockProvider::start().await?;
+        let repo = OidcRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            Some(Arc::new(OidcClient::new(&provider.config(true))?)),
+        );
+
+        // 確認済みのメールアドレスが一致する既存ユーザーに連携される
+        let anne = json!({
+            "sub": "anne",
+            "email": "anne.sallow@example.com",
+            "email_verified": true,
+        });
+        let res = login(&repo, &provider, anne.clone()).await?;
+        assert_eq!(
+            res.user_id,
+            UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?
+        );
+        assert!(!res.provisioned);
+
+        // 2 回目以降は連携済みの外部アカウントから引かれる
+        let res = login(&repo, &provider, anne).await?;
+        assert!(!res.provisioned);
+
+        // 未登録の利用者は一般ユーザーとして自動登録される
+        let new_user = json!({
+            "sub": "poppy",
+            "email": "poppy.sweeting@example.com",
+            "email_verified": true,
+            "name": "Poppy Sweeting",
+        });
+        let res = login(&repo, &provider, new_user).await?;
+        assert!(res.provisioned);
+
+        // 未確認のメールアドレスでは既存ユーザーに連携しない
+        let unverified = json!({
+            "sub": "intruder",
+            "email": "natsai.onai@example.com",
+        });
+        let res = login(&repo, &provider, unverified).await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_oidc_login_rejects_unknown_users_and_reused_state(
+        pool: sqlx::PgPool,
+    ) -> anyhow::Result<()> {
+        let provider = MockProvider::start().await?;
+        let repo = OidcRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            Some(Arc::new(OidcClient::new(&provider.config(false))?)),
+        );
+
+        // 自動登録が無効な場合、未登録の利用者はログインできない
+        let res = login(
+            &repo,
+            &provider,
+            json!({
+                "sub": "poppy",
+                "email": "poppy.sweeting@example.com",
+                "email_verified": true,
+            }),
+        )
+        .await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        // 一度使った state は再利用できない
+        let request = repo.create_authorization_request().await?;
+        let user = json!({
+            "sub": "anne",
+            "email": "anne.sallow@example.com",
+            "email_verified": true,
+        });
+        let (code, state) = provider.grant(&request.authorization_url, user.clone());
+        repo.callback(OidcCallback::new(code, state.clone()))
+            .await?;
+        let (code, _) = provider.grant(&request.authorization_url, user);
+        let res = repo.callback(OidcCallback::new(code, state)).await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        Ok(())
+    }
+}
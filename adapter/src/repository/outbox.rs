@@ -0,0 +1,335 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use kernel::{
+    model::outbox::{DomainEvent, OutboxEvent},
+    repository::outbox::{EventPublisher, OutboxRepository},
+};
+use shared::error::{AppError, AppResult};
+
+use crate::database::{model::outbox::OutboxEventRow, ConnectionPool};
+
+#[derive(new)]
+pub struct OutboxRepositoryImpl {
+    db: ConnectionPool,
+    publisher: Arc<dyn EventPublisher>,
+}
+
+#[async_trait]
+impl OutboxRepository for OutboxRepositoryImpl {
+    async fn publish_pending(&self, limit: i64) -> AppResult<usize> {
+        let mut tx = self.db.begin().await?;
+
+        // 複数のリレーが同時に動いても同じイベントを取り合わないよう、ロック済みの行は読み飛ばす
+        let events: Vec<OutboxEvent> = sqlx::query_as!(
+            OutboxEventRow,
+            r#"
+                SELECT
+                    outbox_id,
+                    aggregate_type,
+                    aggregate_id,
+                    event_type,
+                    payload::TEXT AS "payload!",
+                    occurred_at
+                FROM outbox
+                WHERE published_at IS NULL
+                ORDER BY outbox_id ASC
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED;
+            "#,
+            limit
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(OutboxEvent::from)
+        .collect();
+
+        let mut published = Vec::with_capacity(events.len());
+        let mut failure = None;
+        for event in &events {
+            match self.publisher.publish(event).await {
+                Ok(()) => published.push(event.id),
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+        }
+
+        sqlx::query!(
+            r#"
+                UPDATE outbox
+                SET published_at = CURRENT_TIMESTAMP(3)
+                WHERE outbox_id = ANY($1);
+            "#,
+            &published
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(published.len()),
+        }
+    }
+
+    async fn delete_published_before(&self, before: DateTime<Utc>) -> AppResult<u64> {
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM outbox
+                WHERE published_at < $1;
+            "#,
+            before
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(res.rows_affected())
+    }
+}
+
+/// 変更を行うトランザクションの中で、ドメインイベントをアウトボックスに記録する。
+pub(crate) async fn record_event(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    event: DomainEvent,
+) -> AppResult<()> {
+    let payload = serde_json::to_string(&event)
+        .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+
+    sqlx::query!(
+        r#"
+            INSERT INTO outbox (aggregate_type, aggregate_id, event_type, payload)
+            VALUES ($1, $2, $3, $4::TEXT::JSONB);
+        "#,
+        event.aggregate_type(),
+        event.aggregate_id(),
+        event.event_type(),
+        payload
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        str::FromStr,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use kernel::{
+        model::{
+            book::{
+                event::{CreateBook, CreateBookCopy, DeleteBook, DeleteBookCopy, UpdateBookCopy},
+                CopyCondition,
+            },
+            id::{BookId, UserId},
+        },
+        repository::{book::BookRepository, outbox::MockEventPublisher},
+    };
+
+    use super::*;
+    use crate::{
+        blob::memory::InMemoryBlobStore, event::memory::InMemoryEventPublisher,
+        repository::book::BookRepositoryImpl,
+    };
+
+    const OWNER_ID: &str = "2bbd820c-7a88-450c-b056-19dcbadd527d";
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_mutations_are_relayed_in_order(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let books = BookRepositoryImpl::new(db.clone(), Arc::new(InMemoryBlobStore::default()));
+        let publisher = Arc::new(InMemoryEventPublisher::default());
+        let outbox = OutboxRepositoryImpl::new(db.clone(), publisher.clone());
+        let user_id = UserId::from_str(OWNER_ID)?;
+
+        books
+            .create(
+                CreateBook {
+                    title: "Test Title".into(),
+                    author: "Test Author".into(),
+                    authors: vec![],
+                    isbn: "Test ISBN".into(),
+                    description: "Test Description".into(),
+                },
+                user_id,
+            )
+            .await?;
+        let book_id: BookId =
+            sqlx::query_scalar!(r#"SELECT book_id AS "book_id: BookId" FROM books"#)
+                .fetch_one(db.inner_ref())
+                .await?;
+        let copy_id = books
+            .create_copy(CreateBookCopy {
+                book_id,
+                barcode: "0001".into(),
+                shelf_location: "A-1".into(),
+                condition: CopyCondition::Good,
+                requested_user: user_id,
+                any_owner: false,
+            })
+            .await?;
+        books
+            .update_copy(UpdateBookCopy {
+                book_id,
+                copy_id,
+                barcode: "0001".into(),
+                shelf_location: "A-2".into(),
+                condition: CopyCondition::Fair,
+                requested_user: user_id,
+                any_owner: false,
+            })
+            .await?;
+        books
+            .delete_copy(DeleteBookCopy {
+                book_id,
+                copy_id,
+                requested_user: user_id,
+                any_owner: false,
+            })
+            .await?;
+        books
+            .delete(DeleteBook {
+                book_id,
+                requested_user: user_id,
+                any_owner: false,
+            })
+            .await?;
+
+        // 失敗した変更はイベントとして記録されない
+        assert!(books
+            .delete(DeleteBook {
+                book_id,
+                requested_user: user_id,
+                any_owner: false,
+            })
+            .await
+            .is_err());
+
+        assert_eq!(outbox.publish_pending(10).await?, 6);
+        let published = publisher.published();
+        assert_eq!(
+            published
+                .iter()
+                .map(|e| e.event_type.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "book.created",
+                // 登録時に持たせる複本
+                "book.copy_added",
+                "book.copy_added",
+                "book.copy_updated",
+                "book.copy_removed",
+                "book.deleted"
+            ]
+        );
+        assert!(published.iter().all(|e| e.aggregate_id == book_id.raw()));
+        assert!(published[0].payload.contains(r#""title": "Test Title""#));
+        assert!(published[3].payload.contains(r#""condition": "fair""#));
+
+        // 配信済みのイベントは再送しない
+        assert_eq!(outbox.publish_pending(10).await?, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_failed_event_is_retried(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let user_id = UserId::from_str(OWNER_ID)?;
+        for book_id in [BookId::new(), BookId::new()] {
+            let mut tx = db.begin().await?;
+            record_event(
+                &mut tx,
+                DomainEvent::BookDeleted {
+                    book_id,
+                    deleted_by: user_id,
+                },
+            )
+            .await?;
+            tx.commit().await?;
+        }
+
+        // 2 件目の配信に失敗させる
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut publisher = MockEventPublisher::new();
+        publisher.expect_publish().returning({
+            let calls = calls.clone();
+            move |_| match calls.fetch_add(1, Ordering::SeqCst) {
+                1 => Err(AppError::KeyValueStoreError(redis::RedisError::from((
+                    redis::ErrorKind::IoError,
+                    "stream unavailable",
+                )))),
+                _ => Ok(()),
+            }
+        });
+        let outbox = OutboxRepositoryImpl::new(db.clone(), Arc::new(publisher));
+
+        assert!(outbox.publish_pending(10).await.is_err());
+        // 配信できた 1 件目は配信済みとなり、失敗した 2 件目から再送される
+        assert_eq!(outbox.publish_pending(10).await?, 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_delete_published_before(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool.clone());
+        let user_id = UserId::from_str(OWNER_ID)?;
+        for _ in 0..3 {
+            let mut tx = db.begin().await?;
+            record_event(
+                &mut tx,
+                DomainEvent::BookDeleted {
+                    book_id: BookId::new(),
+                    deleted_by: user_id,
+                },
+            )
+            .await?;
+            tx.commit().await?;
+        }
+        let outbox = OutboxRepositoryImpl::new(db, Arc::new(InMemoryEventPublisher::default()));
+        assert_eq!(outbox.publish_pending(2).await?, 2);
+
+        // 配信済みの 2 件のうち 1 件だけを保持期間より前に配信したことにする
+        sqlx::query!(
+            r#"
+                UPDATE outbox SET published_at = published_at - INTERVAL '8 days'
+                WHERE outbox_id = (SELECT MIN(outbox_id) FROM outbox);
+            "#
+        )
+        .execute(&pool)
+        .await?;
+
+        let deleted = outbox
+            .delete_published_before(Utc::now() - chrono::Duration::days(7))
+            .await?;
+        assert_eq!(deleted, 1);
+        // 未配信のイベントは、どれだけ古くても削除しない
+        let deleted = outbox
+            .delete_published_before(Utc::now() + chrono::Duration::days(1))
+            .await?;
+        assert_eq!(deleted, 1);
+        let remaining: i64 = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM outbox WHERE published_at IS NULL"#
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(remaining, 1);
+
+        Ok(())
+    }
+}
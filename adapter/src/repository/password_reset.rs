@@ -0,0 +1,313 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use derive_new::new;
+use kernel::{
+    model::{
+        auth::event::{ConfirmPasswordReset, RequestPasswordReset},
+        id::UserId,
+        notification::{event::CreateNotification, NotificationKind},
+        user::event::ResetUserPassword,
+    },
+    repository::{
+        notification::NotificationRepository, password_reset::PasswordResetRepository,
+        user::UserRepository,
+    },
+};
+use sha2::{Digest, Sha256};
+use shared::error::{AppError, AppResult};
+
+use crate::database::ConnectionPool;
+
+#[derive(new)]
+pub struct PasswordResetRepositoryImpl {
+    db: ConnectionPool,
+    user: Arc<dyn UserRepository>,
+    notification: Arc<dyn NotificationRepository>,
+    ttl: u64,
+}
+
+#[async_trait]
+impl PasswordResetRepository for PasswordResetRepositoryImpl {
+    async fn request(&self, event: RequestPasswordReset) -> AppResult<Option<UserId>> {
+        let Some(user_id) = sqlx::query_scalar!(
+            r#"
+                SELECT user_id AS "user_id: UserId" FROM users
+                WHERE email = $1;
+            "#,
+            event.email
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        else {
+            return Ok(None);
+        };
+
+        let token = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let expires_at = Utc::now() + Duration::seconds(self.ttl as i64);
+
+        let mut tx = self.db.begin().await?;
+
+        // 有効なトークンは最後に発行したもののみとする
+        sqlx::query!(
+            r#"
+                DELETE FROM password_reset_tokens WHERE user_id = $1;
+            "#,
+            user_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO password_reset_tokens (token_hash, user_id, expires_at)
+                VALUES ($1, $2, $3);
+            "#,
+            hash_token(&token),
+            user_id as _,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        // 送信にかかる時間や送信の失敗がレスポンスに表れると登録の有無が分かってしまうため、
+        // 送信は応答を待たずに行い、失敗はログに残すのみとする
+        let notification = self.notification.clone();
+        let event = CreateNotification::new(
+            user_id,
+            NotificationKind::PasswordReset,
+            "Reset your password".into(),
+            format!(
+                "Use the following token to reset your password: {}\nThe token expires in {} minutes.",
+                token,
+                self.ttl / 60
+            ),
+        );
+        tokio::spawn(async move {
+            if let Err(e) = notification.notify(event).await {
+                tracing::error!(
+                    error.message = %e,
+                    user_id = %user_id,
+                    "Failed to send password reset notification"
+                );
+            }
+        });
+
+        Ok(Some(user_id))
+    }
+
+    async fn confirm(&self, event: ConfirmPasswordReset) -> AppResult<UserId> {
+        // 期限切れのトークンもあわせて削除し、同じトークンが二度使われないようにする
+        let row = sqlx::query!(
+            r#"
+                DELETE FROM password_reset_tokens
+                WHERE token_hash = $1
+                RETURNING user_id AS "user_id: UserId", expires_at;
+            "#,
+            hash_token(&event.token)
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let user_id = match row {
+            Some(row) if row.expires_at > Utc::now() => row.user_id,
+            _ => return Err(AppError::UnauthorizedError),
+        };
+
+        self.user
+            .reset_password(ResetUserPassword {
+                user_id,
+                new_password: event.new_password,
+            })
+            .await?;
+
+        Ok(user_id)
+    }
+}
+
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use kernel::{
+        model::notification::Notification,
+        repository::{notification::NotificationSender, user::MockUserRepository},
+    };
+
+    use crate::{
+        notification::memory::InMemoryNotificationSender,
+        repository::notification::NotificationRepositoryImpl,
+    };
+
+    use super::*;
+
+    fn repository(
+        pool: sqlx::PgPool,
+        sender: Arc<InMemoryNotificationSender>,
+        user: MockUserRepository,
+        ttl: u64,
+    ) -> PasswordResetRepositoryImpl {
+        let db = ConnectionPool::new(pool);
+        PasswordResetRepositoryImpl::new(
+            db.clone(),
+            Arc::new(user),
+            Arc::new(NotificationRepositoryImpl::new(db, sender)),
+            ttl,
+        )
+    }
+
+    /// `count` 件目の通知が送信されるまで待ち、その本文からトークンを取り出す
+    async fn sent_token(sender: &InMemoryNotificationSender, count: usize) -> String {
+        let sent = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                let sent = sender.sent();
+                if sent.len() >= count {
+                    return sent;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("notification should be sent");
+        let body = &sent[count - 1].body;
+        body.split_once(": ")
+            .and_then(|(_, rest)| rest.lines().next())
+            .expect("body should contain token")
+            .to_string()
+    }
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_password_reset(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let sender = Arc::new(InMemoryNotificationSender::default());
+        let mut user = MockUserRepository::new();
+        user.expect_reset_password()
+            .withf(move |e| e.user_id == user_id && e.new_password == "new_password")
+            .times(1)
+            .returning(|_| Ok(()));
+        let repo = repository(pool.clone(), sender.clone(), user, 3600);
+
+        // 登録されていないメールアドレスでもエラーにはならず、何も送信されない
+        let res = repo
+            .request(RequestPasswordReset::new("unknown@example.com".into()))
+            .await?;
+        assert!(res.is_none());
+        assert!(sender.sent().is_empty());
+
+        repo.request(RequestPasswordReset::new("anne.sallow@example.com".into()))
+            .await?;
+        let stale = sent_token(&sender, 1).await;
+        repo.request(RequestPasswordReset::new("anne.sallow@example.com".into()))
+            .await?;
+        let token = sent_token(&sender, 2).await;
+        assert_eq!(sender.sent()[0].kind, NotificationKind::PasswordReset);
+
+        // トークンはハッシュ値のみを保存する
+        let stored: Vec<String> =
+            sqlx::query_scalar!("SELECT token_hash FROM password_reset_tokens")
+                .fetch_all(&pool)
+                .await?;
+        assert_eq!(stored, vec![hash_token(&token)]);
+
+        // 再発行で古いトークンは無効になる
+        let res = repo
+            .confirm(ConfirmPasswordReset::new(stale, "new_password".into()))
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthorizedError)));
+
+        let confirmed = repo
+            .confirm(ConfirmPasswordReset::new(
+                token.clone(),
+                "new_password".into(),
+            ))
+            .await?;
+        assert_eq!(confirmed, user_id);
+
+        // 同じトークンは二度使えない
+        let res = repo
+            .confirm(ConfirmPasswordReset::new(token, "new_password".into()))
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthorizedError)));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_expired_password_reset_token(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let sender = Arc::new(InMemoryNotificationSender::default());
+        let mut user = MockUserRepository::new();
+        user.expect_reset_password().never();
+        let repo = repository(pool, sender.clone(), user, 0);
+
+        repo.request(RequestPasswordReset::new("anne.sallow@example.com".into()))
+            .await?;
+        let res = repo
+            .confirm(ConfirmPasswordReset::new(
+                sent_token(&sender, 1).await,
+                "new_password".into(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthorizedError)));
+
+        Ok(())
+    }
+
+    /// `release` が呼ばれるまで送信を終えない送信手段
+    #[derive(Default)]
+    struct StalledNotificationSender {
+        release: tokio::sync::Notify,
+        sent: InMemoryNotificationSender,
+    }
+
+    #[async_trait]
+    impl NotificationSender for StalledNotificationSender {
+        async fn send(&self, notification: &Notification) -> AppResult<()> {
+            self.release.notified().await;
+            self.sent.send(notification).await
+        }
+    }
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_request_does_not_wait_for_sender(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let sender = Arc::new(StalledNotificationSender::default());
+        let repo = PasswordResetRepositoryImpl::new(
+            db.clone(),
+            Arc::new(MockUserRepository::new()),
+            Arc::new(NotificationRepositoryImpl::new(db, sender.clone())),
+            3600,
+        );
+        let request = |email: &str| {
+            tokio::time::timeout(
+                std::time::Duration::from_secs(1),
+                repo.request(RequestPasswordReset::new(email.into())),
+            )
+        };
+
+        // 登録の有無にかかわらず、送信の完了を待たずに応答する
+        assert!(request("unknown@example.com").await??.is_none());
+        assert!(request("anne.sallow@example.com").await??.is_some());
+        assert!(sender.sent.sent().is_empty());
+
+        // 送信は応答の後に完了する
+        sender.release.notify_one();
+        sent_token(&sender.sent, 1).await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,411 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use kernel::{
+    model::{
+        id::BookId,
+        reservation::{
+            event::{CreateReservation, DeleteReservation},
+            pickup_deadline_from, Reservation,
+        },
+    },
+    repository::reservation::ReservationRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    database::{
+        model::reservation::{ReservationHoldRow, ReservationRow},
+        set_transaction_serializable, ConnectionPool,
+    },
+    repository::{book::ensure_book_exists, checkout::find_copy_states},
+};
+
+#[derive(new)]
+pub struct ReservationRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl ReservationRepository for ReservationRepositoryImpl {
+    async fn create(&self, event: CreateReservation) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        set_transaction_serializable(&mut tx).await?;
+
+        // 指定した書籍が存在しない場合
+        ensure_book_exists(&mut tx, event.book_id).await?;
+
+        let copies = find_copy_states(&mut tx, event.book_id).await?;
+        let holds = find_active_holds(&mut tx, event.book_id, event.reserved_at).await?;
+
+        // 予約者自身が借りている場合
+        if copies
+            .iter()
+            .any(|copy| copy.user_id == Some(event.reserved_by))
+        {
+            return Err(AppError::UnprocessableEntity(format!(
+                "Book already checked out by the user: book_id={}",
+                event.book_id
+            )));
+        }
+
+        // 取り置かれていない複本があり、そのまま借りられる場合
+        let available = copies
+            .iter()
+            .filter(|copy| copy.checkout_id.is_none())
+            .count();
+        if available > holds.len() {
+            return Err(AppError::UnprocessableEntity(format!(
+                "Book is available for checkout: book_id={}",
+                event.book_id
+            )));
+        }
+
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO reservations (book_id, user_id, reserved_at)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (book_id, user_id) DO NOTHING;
+            "#,
+            event.book_id as _,
+            event.reserved_by as _,
+            event.reserved_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::UnprocessableEntity(format!(
+                "Book already reserved by the user: book_id={}",
+                event.book_id
+            )));
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn find_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Reservation>> {
+        // 取り置き中の予約を先頭に、以降は予約日時の古い順に並べる
+        sqlx::query_as!(
+            ReservationRow,
+            r#"
+                SELECT
+                    r.reservation_id,
+                    r.book_id,
+                    r.user_id,
+                    r.reserved_at,
+                    ROW_NUMBER() OVER (
+                        ORDER BY r.pickup_deadline IS NULL, r.reserved_at
+                    ) AS "position!",
+                    r.pickup_deadline
+                FROM reservations AS r
+                WHERE r.book_id = $1
+                ORDER BY "position!" ASC;
+            "#,
+            book_id as _
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map(|rows| rows.into_iter().map(Reservation::from).collect())
+        .map_err(AppError::SpecificOperationError)
+    }
+
+    async fn delete(&self, event: DeleteReservation) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        set_transaction_serializable(&mut tx).await?;
+
+        let deleted = sqlx::query!(
+            r#"
+                DELETE FROM reservations
+                WHERE reservation_id = $1
+                AND book_id = $2
+                AND user_id = $3
+                RETURNING pickup_deadline;
+            "#,
+            event.reservation_id as _,
+            event.book_id as _,
+            event.requested_user as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        match deleted {
+            None => {
+                return Err(AppError::EntityNotFound(
+                    "Specified reservation not found".into(),
+                ))
+            }
+            // 取り置き中の予約が取り消された場合は、次の予約者に取り置きを移す
+            Some(row) if row.pickup_deadline.is_some() => {
+                promote_next_reservation(&mut tx, event.book_id, event.cancelled_at).await?;
+            }
+            _ => {}
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+}
+
+/// 予約待ちの列の先頭にいる利用者のために複本を 1 冊取り置き、受け取り期限を設定する。
+/// 予約待ちの利用者がいない場合は何もしない。
+pub(crate) async fn promote_next_reservation(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    book_id: BookId,
+    held_at: DateTime<Utc>,
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            UPDATE reservations
+            SET pickup_deadline = $2
+            WHERE reservation_id = (
+                SELECT reservation_id FROM reservations
+                WHERE book_id = $1
+                AND pickup_deadline IS NULL
+                ORDER BY reserved_at ASC
+                LIMIT 1
+            );
+        "#,
+        book_id as _,
+        pickup_deadline_from(held_at)
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
+/// 指定した書籍の取り置き中の予約を取得する。複本ごとに取り置くため、複数件になることがある。
+/// 受け取り期限を過ぎた取り置きは取り消し、期限の時点から次の予約者に取り置きを移す。
+pub(crate) async fn find_active_holds(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    book_id: BookId,
+    now: DateTime<Utc>,
+) -> AppResult<Vec<ReservationHoldRow>> {
+    loop {
+        let holds = sqlx::query_as!(
+            ReservationHoldRow,
+            r#"
+                SELECT
+                    reservation_id,
+                    user_id,
+                    pickup_deadline AS "pickup_deadline!"
+                FROM reservations
+                WHERE book_id = $1
+                AND pickup_deadline IS NOT NULL
+                ORDER BY pickup_deadline ASC;
+            "#,
+            book_id as _
+        )
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let (active, expired): (Vec<_>, Vec<_>) =
+            holds.into_iter().partition(|h| h.pickup_deadline >= now);
+        if expired.is_empty() {
+            return Ok(active);
+        }
+
+        for h in expired {
+            sqlx::query!(
+                r#"
+                    DELETE FROM reservations WHERE reservation_id = $1;
+                "#,
+                h.reservation_id as _
+            )
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+
+            promote_next_reservation(tx, book_id, h.pickup_deadline).await?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, sync::Arc};
+
+    use chrono::Duration;
+    use kernel::{
+        model::{
+            checkout::event::{CreateCheckout, UpdateReturned},
+            id::{CheckoutId, UserId},
+        },
+        repository::checkout::CheckoutRepository,
+    };
+
+    use super::*;
+    use crate::{
+        notification::memory::InMemoryNotificationSender,
+        repository::{checkout::CheckoutRepositoryImpl, notification::NotificationRepositoryImpl},
+    };
+
+    const BOOK_ID: &str = "9890736e-a4e4-461a-a77d-eac3517ef11b";
+    const CHECKOUT_ID: &str = "a3a33a5e-2c27-4b6d-9a4b-1a6f2f3c8d10";
+    const OWNER_ID: &str = "2bbd820c-7a88-450c-b056-19dcbadd527d";
+    const ANNE_ID: &str = "5b4c96ac-316a-4bee-8e69-cac5eb84ff4c";
+    const NATSAI_ID: &str = "9582f9de-0fd1-4892-b20c-70139a7eb95b";
+
+    #[sqlx::test(fixtures("common", "user", "book", "checkout"))]
+    async fn test_hold_is_given_to_first_reservation(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let reservations = ReservationRepositoryImpl::new(db.clone());
+        let notification = Arc::new(NotificationRepositoryImpl::new(
+            db.clone(),
+            Arc::new(InMemoryNotificationSender::default()),
+        ));
+        let checkouts = CheckoutRepositoryImpl::new(db, 14, 2, notification);
+
+        let book_id = BookId::from_str(BOOK_ID)?;
+        let anne = UserId::from_str(ANNE_ID)?;
+        let natsai = UserId::from_str(NATSAI_ID)?;
+        let now = Utc::now();
+
+        reservations
+            .create(CreateReservation::new(book_id, anne, now))
+            .await?;
+        reservations
+            .create(CreateReservation::new(
+                book_id,
+                natsai,
+                now + Duration::seconds(1),
+            ))
+            .await?;
+        // 同じ利用者は重複して予約できない
+        assert!(reservations
+            .create(CreateReservation::new(book_id, anne, now))
+            .await
+            .is_err());
+
+        checkouts
+            .update_returned(UpdateReturned::new(
+                CheckoutId::from_str(CHECKOUT_ID)?,
+                book_id,
+                UserId::from_str(OWNER_ID)?,
+                now,
+                false,
+            ))
+            .await?;
+
+        let list = reservations.find_by_book_id(book_id).await?;
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].reserved_by, anne);
+        let deadline = list[0].pickup_deadline.expect("book should be on hold");
+        // DB 上の日時はミリ秒精度に丸められる
+        assert!(
+            (deadline - pickup_deadline_from(now))
+                .num_milliseconds()
+                .abs()
+                <= 1
+        );
+        assert!(list[1].pickup_deadline.is_none());
+
+        // 取り置き対象以外の利用者は借りられない
+        assert!(checkouts
+            .create(CreateCheckout::new(
+                book_id, None, natsai, natsai, now, None
+            ))
+            .await
+            .is_err());
+        checkouts
+            .create(CreateCheckout::new(book_id, None, anne, anne, now, None))
+            .await?;
+
+        let list = reservations.find_by_book_id(book_id).await?;
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].reserved_by, natsai);
+        assert_eq!(list[0].position, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "user", "book", "checkout"))]
+    async fn test_expired_or_cancelled_hold_moves_to_next(
+        pool: sqlx::PgPool,
+    ) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let reservations = ReservationRepositoryImpl::new(db.clone());
+        let notification = Arc::new(NotificationRepositoryImpl::new(
+            db.clone(),
+            Arc::new(InMemoryNotificationSender::default()),
+        ));
+        let checkouts = CheckoutRepositoryImpl::new(db, 14, 2, notification);
+
+        let book_id = BookId::from_str(BOOK_ID)?;
+        let anne = UserId::from_str(ANNE_ID)?;
+        let natsai = UserId::from_str(NATSAI_ID)?;
+        let now = Utc::now();
+
+        reservations
+            .create(CreateReservation::new(book_id, anne, now))
+            .await?;
+        reservations
+            .create(CreateReservation::new(
+                book_id,
+                natsai,
+                now + Duration::seconds(1),
+            ))
+            .await?;
+        checkouts
+            .update_returned(UpdateReturned::new(
+                CheckoutId::from_str(CHECKOUT_ID)?,
+                book_id,
+                UserId::from_str(OWNER_ID)?,
+                now,
+                false,
+            ))
+            .await?;
+
+        // 取り置き中の予約を取り消すと、次の予約者に取り置きが移る
+        let held = reservations.find_by_book_id(book_id).await?.remove(0);
+        reservations
+            .delete(DeleteReservation::new(held.id, book_id, anne, now))
+            .await?;
+        let list = reservations.find_by_book_id(book_id).await?;
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].reserved_by, natsai);
+        assert!(list[0].pickup_deadline.is_some());
+
+        // 受け取り期限を過ぎると、取り置きは解除されて誰でも借りられる
+        let after_deadline = pickup_deadline_from(now) + Duration::days(1);
+        checkouts
+            .create(CreateCheckout::new(
+                book_id,
+                None,
+                anne,
+                anne,
+                after_deadline,
+                None,
+            ))
+            .await?;
+        assert!(reservations.find_by_book_id(book_id).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "user", "book"))]
+    async fn test_cannot_reserve_available_book(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let reservations = ReservationRepositoryImpl::new(ConnectionPool::new(pool));
+
+        let res = reservations
+            .create(CreateReservation::new(
+                BookId::from_str(BOOK_ID)?,
+                UserId::from_str(ANNE_ID)?,
+                Utc::now(),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        Ok(())
+    }
+}
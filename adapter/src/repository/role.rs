@@ -0,0 +1,390 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::{RoleId, UserId},
+        role::{
+            event::{CreateRole, DeleteRole, UpdateRole},
+            Permission, Role,
+        },
+    },
+    repository::{auth::AuthRepository, role::RoleRepository},
+};
+use shared::error::{AppError, AppResult};
+
+use crate::database::{model::role::RoleRow, ConnectionPool};
+
+#[derive(new)]
+pub struct RoleRepositoryImpl {
+    db: ConnectionPool,
+    auth: Arc<dyn AuthRepository>,
+}
+
+#[async_trait]
+impl RoleRepository for RoleRepositoryImpl {
+    async fn find_all(&self) -> AppResult<Vec<Role>> {
+        sqlx::query_as!(
+            RoleRow,
+            r#"
+                SELECT
+                    r.role_id,
+                    r.name,
+                    ARRAY(
+                        SELECT permission FROM role_permissions AS p
+                        WHERE p.role_id = r.role_id
+                    ) AS "permissions!"
+                FROM roles AS r
+                ORDER BY r.name;
+            "#
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(Role::try_from)
+        .collect()
+    }
+
+    async fn find_by_id(&self, role_id: RoleId) -> AppResult<Option<Role>> {
+        let mut tx = self.db.begin().await?;
+        find_role(&mut tx, RoleKey::Id(role_id)).await
+    }
+
+    async fn create(&self, event: CreateRole) -> AppResult<Role> {
+        let mut tx = self.db.begin().await?;
+
+        ensure_name_available(&mut tx, &event.name, None).await?;
+
+        let role_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO roles (name) VALUES ($1)
+                RETURNING role_id AS "role_id: RoleId";
+            "#,
+            event.name
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        replace_permissions(&mut tx, role_id, &event.permissions).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        let mut permissions = event.permissions;
+        permissions.sort();
+        permissions.dedup();
+
+        Ok(Role {
+            id: role_id,
+            name: event.name,
+            permissions,
+        })
+    }
+
+    async fn update(&self, event: UpdateRole) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        find_mutable_role(&mut tx, event.role_id).await?;
+        ensure_name_available(&mut tx, &event.name, Some(event.role_id)).await?;
+
+        sqlx::query!(
+            r#"
+                UPDATE roles SET name = $2 WHERE role_id = $1;
+            "#,
+            event.role_id as _,
+            event.name
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        replace_permissions(&mut tx, event.role_id, &event.permissions).await?;
+
+        let user_ids = sqlx::query_scalar!(
+            r#"
+                SELECT user_id AS "user_id: UserId" FROM users WHERE role_id = $1;
+            "#,
+            event.role_id as _
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        // JWT はロールの権限をクレームに含むため、ロールを持つユーザーの発行済みトークンを失効させる
+        for user_id in user_ids {
+            self.revoke_all_sessions(user_id).await;
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, event: DeleteRole) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        find_mutable_role(&mut tx, event.role_id).await?;
+
+        let assigned = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (SELECT 1 FROM users WHERE role_id = $1) AS "assigned!";
+            "#,
+            event.role_id as _
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        // 割り当て中のロールは削除できないため、失効させるべきトークンも存在しない
+        if assigned {
+            return Err(AppError::UnprocessableEntity(
+                "The role is still assigned to users".to_string(),
+            ));
+        }
+
+        sqlx::query!(
+            r#"
+                DELETE FROM roles WHERE role_id = $1;
+            "#,
+            event.role_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+}
+
+impl RoleRepositoryImpl {
+    /// セッションの失効に失敗しても、コミット済みの変更自体は失敗させない
+    async fn revoke_all_sessions(&self, user_id: UserId) {
+        if let Err(e) = self.auth.revoke_all_sessions(user_id).await {
+            tracing::error!(
+                error.message = %e,
+                user_id = %user_id,
+                "Failed to revoke sessions"
+            );
+        }
+    }
+}
+
+pub(crate) enum RoleKey<'a> {
+    Id(RoleId),
+    Name(&'a str),
+}
+
+/// ID または名前でロールを探す。ユーザーへのロールの割り当てなど、他のリポジトリからも用いる。
+pub(crate) async fn find_role(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    key: RoleKey<'_>,
+) -> AppResult<Option<Role>> {
+    let (role_id, name) = match key {
+        RoleKey::Id(role_id) => (Some(role_id), None),
+        RoleKey::Name(name) => (None, Some(name)),
+    };
+
+    sqlx::query_as!(
+        RoleRow,
+        r#"
+            SELECT
+                r.role_id,
+                r.name,
+                ARRAY(
+                    SELECT permission FROM role_permissions AS p
+                    WHERE p.role_id = r.role_id
+                ) AS "permissions!"
+            FROM roles AS r
+            WHERE r.role_id = $1 OR r.name = $2;
+        "#,
+        role_id as _,
+        name
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?
+    .map(Role::try_from)
+    .transpose()
+}
+
+async fn find_mutable_role(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    role_id: RoleId,
+) -> AppResult<Role> {
+    let role = find_role(tx, RoleKey::Id(role_id))
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("Specified role not found".to_string()))?;
+
+    if role.is_builtin() {
+        return Err(AppError::UnprocessableEntity(
+            "Built-in roles cannot be modified".to_string(),
+        ));
+    }
+
+    Ok(role)
+}
+
+async fn ensure_name_available(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    name: &str,
+    current: Option<RoleId>,
+) -> AppResult<()> {
+    match find_role(tx, RoleKey::Name(name)).await? {
+        Some(role) if Some(role.id) != current => Err(AppError::UnprocessableEntity(format!(
+            "Role {} already exists",
+            name
+        ))),
+        _ => Ok(()),
+    }
+}
+
+async fn replace_permissions(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    role_id: RoleId,
+    permissions: &[Permission],
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            DELETE FROM role_permissions WHERE role_id = $1;
+        "#,
+        role_id as _
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    let permissions: Vec<String> = permissions
+        .iter()
+        .map(|permission| permission.as_ref().to_string())
+        .collect();
+    sqlx::query!(
+        r#"
+            INSERT INTO role_permissions (role_id, permission)
+            SELECT $1, permission FROM UNNEST($2::VARCHAR[]) AS permission
+            ON CONFLICT DO NOTHING;
+        "#,
+        role_id as _,
+        &permissions
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use kernel::{
+        model::role::{ADMIN_ROLE_NAME, DEFAULT_ROLE_NAME},
+        repository::auth::MockAuthRepository,
+    };
+
+    use super::*;
+
+    const ANNE_ID: &str = "5b4c96ac-316a-4bee-8e69-cac5eb84ff4c";
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_custom_role(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        // ロールを割り当てたユーザーがいる状態で権限を変更したときだけ、そのユーザーのセッションを失効させる
+        let anne_id = UserId::from_str(ANNE_ID)?;
+        let mut auth = MockAuthRepository::new();
+        auth.expect_revoke_all_sessions()
+            .withf(move |id| *id == anne_id)
+            .times(1)
+            .returning(|_| Ok(()));
+        let repo = RoleRepositoryImpl::new(ConnectionPool::new(pool.clone()), Arc::new(auth));
+
+        let roles = repo.find_all().await?;
+        let admin = roles
+            .iter()
+            .find(|r| r.name == ADMIN_ROLE_NAME)
+            .expect("Admin role should exist");
+        assert!(admin.has_permission(Permission::UserManage));
+        assert!(roles
+            .iter()
+            .any(|r| r.name == DEFAULT_ROLE_NAME && r.permissions.is_empty()));
+
+        let librarian = repo
+            .create(CreateRole::new(
+                "Librarian".into(),
+                vec![Permission::CheckoutReturnAny, Permission::BookWriteAny],
+            ))
+            .await?;
+        assert_eq!(
+            repo.find_by_id(librarian.id).await?.as_ref(),
+            Some(&librarian)
+        );
+
+        // 名前の重複は許さない
+        let res = repo
+            .create(CreateRole::new("Librarian".into(), vec![]))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        repo.update(UpdateRole::new(
+            librarian.id,
+            "Head Librarian".into(),
+            vec![Permission::AuditRead],
+        ))
+        .await?;
+        let updated = repo.find_by_id(librarian.id).await?.unwrap();
+        assert_eq!(updated.name, "Head Librarian");
+        assert_eq!(updated.permissions, vec![Permission::AuditRead]);
+
+        // ユーザーに割り当てられている間は削除できない
+        sqlx::query!(
+            "UPDATE users SET role_id = $1 WHERE email = 'anne.sallow@example.com'",
+            librarian.id as _
+        )
+        .execute(&pool)
+        .await?;
+        repo.update(UpdateRole::new(
+            librarian.id,
+            "Head Librarian".into(),
+            vec![],
+        ))
+        .await?;
+        let res = repo.delete(DeleteRole::new(librarian.id)).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        sqlx::query!(
+            "UPDATE users SET role_id = $1 WHERE email = 'anne.sallow@example.com'",
+            admin.id as _
+        )
+        .execute(&pool)
+        .await?;
+        repo.delete(DeleteRole::new(librarian.id)).await?;
+        assert!(repo.find_by_id(librarian.id).await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_builtin_role_is_immutable(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = RoleRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            Arc::new(MockAuthRepository::new()),
+        );
+        let admin = repo
+            .find_all()
+            .await?
+            .into_iter()
+            .find(|r| r.name == ADMIN_ROLE_NAME)
+            .unwrap();
+
+        let res = repo
+            .update(UpdateRole::new(admin.id, "Owner".into(), vec![]))
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let res = repo.delete(DeleteRole::new(admin.id)).await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,297 @@
+use async_trait::async_trait;
+use derive_new::new;
+use kernel::{
+    model::{
+        id::TagId,
+        tag::{
+            event::{AddBookTag, CreateTag, DeleteTag, RemoveBookTag, UpdateTag},
+            Tag,
+        },
+    },
+    repository::tag::TagRepository,
+};
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    database::{model::tag::TagRow, ConnectionPool},
+    repository::book::ensure_book_writable,
+};
+
+#[derive(new)]
+pub struct TagRepositoryImpl {
+    db: ConnectionPool,
+}
+
+#[async_trait]
+impl TagRepository for TagRepositoryImpl {
+    async fn create(&self, event: CreateTag) -> AppResult<Tag> {
+        let row = sqlx::query_as!(
+            TagRow,
+            r#"
+                INSERT INTO tags (name)
+                VALUES ($1)
+                RETURNING tag_id, name;
+            "#,
+            event.name
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(|e| map_tag_name_error(e, &event.name))?;
+
+        Ok(row.into())
+    }
+
+    async fn find_all(&self) -> AppResult<Vec<Tag>> {
+        let rows = sqlx::query_as!(
+            TagRow,
+            r#"
+                SELECT tag_id, name
+                FROM tags
+                ORDER BY lower(name) ASC;
+            "#
+        )
+        .fetch_all(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(rows.into_iter().map(Tag::from).collect())
+    }
+
+    async fn find_by_id(&self, tag_id: TagId) -> AppResult<Option<Tag>> {
+        let row = sqlx::query_as!(
+            TagRow,
+            r#"
+                SELECT tag_id, name
+                FROM tags
+                WHERE tag_id = $1;
+            "#,
+            tag_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(row.map(Tag::from))
+    }
+
+    async fn update(&self, event: UpdateTag) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE tags
+                SET name = $2
+                WHERE tag_id = $1;
+            "#,
+            event.tag_id as _,
+            event.name
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(|e| map_tag_name_error(e, &event.name))?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound("Specified tag not found".into()));
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, event: DeleteTag) -> AppResult<()> {
+        // 蔵書との関連付けは外部キーの ON DELETE CASCADE で削除される
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM tags
+                WHERE tag_id = $1;
+            "#,
+            event.tag_id as _
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound("Specified tag not found".into()));
+        }
+
+        Ok(())
+    }
+
+    async fn add_to_book(&self, event: AddBookTag) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        ensure_book_writable(
+            &mut tx,
+            event.book_id,
+            event.requested_user,
+            event.any_owner,
+        )
+        .await?;
+
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO book_tags (book_id, tag_id)
+                SELECT $1, tag_id FROM tags WHERE tag_id = $2
+                ON CONFLICT DO NOTHING
+                RETURNING tag_id;
+            "#,
+            event.book_id as _,
+            event.tag_id as _
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // 既に付いていた場合も行は返らないため、タグ自体の有無を確かめる
+        if res.is_none() && !tag_exists(&mut tx, event.tag_id).await? {
+            return Err(AppError::EntityNotFound("Specified tag not found".into()));
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn remove_from_book(&self, event: RemoveBookTag) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        ensure_book_writable(
+            &mut tx,
+            event.book_id,
+            event.requested_user,
+            event.any_owner,
+        )
+        .await?;
+
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM book_tags
+                WHERE book_id = $1 AND tag_id = $2;
+            "#,
+            event.book_id as _,
+            event.tag_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound(
+                "Specified tag is not attached to the book".into(),
+            ));
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+}
+
+async fn tag_exists(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    tag_id: TagId,
+) -> AppResult<bool> {
+    sqlx::query_scalar!(
+        r#"
+            SELECT EXISTS (SELECT 1 FROM tags WHERE tag_id = $1) AS "exists!";
+        "#,
+        tag_id as _
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
+/// 大文字・小文字の違いだけのタグ名は、同じタグとして扱う。
+fn map_tag_name_error(e: sqlx::Error, name: &str) -> AppError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AppError::ConflictError(format!("Tag already exists: {name}"))
+        }
+        _ => AppError::SpecificOperationError(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{str::FromStr, sync::Arc};
+
+    use kernel::{
+        model::{
+            book::BookListOptions,
+            id::{BookId, UserId},
+        },
+        repository::book::BookRepository,
+    };
+
+    use super::*;
+    use crate::{blob::memory::InMemoryBlobStore, repository::book::BookRepositoryImpl};
+
+    #[sqlx::test(fixtures("common", "user", "book"))]
+    async fn test_tag_books(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let owner = UserId::from_str("2bbd820c-7a88-450c-b056-19dcbadd527d")?;
+        let other = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let repo = TagRepositoryImpl::new(ConnectionPool::new(pool.clone()));
+        let books = BookRepositoryImpl::new(
+            ConnectionPool::new(pool.clone()),
+            Arc::new(InMemoryBlobStore::default()),
+        );
+
+        let rust = repo.create(CreateTag::new("Rust".into())).await?;
+        let res = repo.create(CreateTag::new("rust".into())).await;
+        assert!(matches!(res, Err(AppError::ConflictError(_))));
+        let databases = repo.create(CreateTag::new("Databases".into())).await?;
+        assert_eq!(
+            repo.find_all().await?,
+            vec![databases.clone(), rust.clone()]
+        );
+
+        // 他のユーザーの蔵書には、権限がなければタグを付けられない
+        let res = repo
+            .add_to_book(AddBookTag::new(rust.id, book_id, other, false))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.add_to_book(AddBookTag::new(rust.id, book_id, owner, false))
+            .await?;
+        // 2 回目は何もしない
+        repo.add_to_book(AddBookTag::new(rust.id, book_id, other, true))
+            .await?;
+        let res = repo
+            .add_to_book(AddBookTag::new(TagId::new(), book_id, owner, false))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let book = books.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.tags, vec![rust.clone()]);
+
+        let tagged = books
+            .find_all(BookListOptions {
+                limit: 10,
+                tag_id: Some(rust.id),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(tagged.total, 1);
+        assert_eq!(tagged.items[0].id, book_id);
+
+        repo.update(UpdateTag::new(rust.id, "Rust language".into()))
+            .await?;
+        let book = books.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.tags[0].name, "Rust language");
+
+        repo.remove_from_book(RemoveBookTag::new(rust.id, book_id, owner, false))
+            .await?;
+        let res = repo
+            .remove_from_book(RemoveBookTag::new(rust.id, book_id, owner, false))
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.add_to_book(AddBookTag::new(databases.id, book_id, owner, false))
+            .await?;
+        repo.delete(DeleteTag::new(databases.id)).await?;
+        assert!(repo.find_by_id(databases.id).await?.is_none());
+        assert!(books.find_by_id(book_id).await?.unwrap().tags.is_empty());
+
+        Ok(())
+    }
+}
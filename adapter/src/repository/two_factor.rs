@@ -0,0 +1,607 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use derive_new::new;
+use kernel::{
+    model::{
+        auth::two_factor::{
+            ConfirmTwoFactor, DisableTwoFactor, TwoFactorChallenge, TwoFactorEnrollment,
+            TwoFactorStatus, UpdateTwoFactorPolicy, VerifyTwoFactorChallenge,
+        },
+        id::UserId,
+    },
+    repository::two_factor::TwoFactorRepository,
+};
+use sha2::{Digest, Sha256};
+use shared::{
+    config::TwoFactorConfig,
+    error::{AppError, AppResult},
+};
+
+use crate::{database::ConnectionPool, totp};
+
+const RECOVERY_CODE_COUNT: usize = 10;
+/// チャレンジに対してコードを誤ってよい回数。超えた場合はチャレンジを破棄し、パスワードからやり直させる。
+const MAX_CHALLENGE_FAILURES: i32 = 5;
+
+#[derive(new)]
+pub struct TwoFactorRepositoryImpl {
+    db: ConnectionPool,
+    config: TwoFactorConfig,
+}
+
+struct TwoFactorSecret {
+    secret: String,
+    enabled: bool,
+    last_used_step: Option<i64>,
+}
+
+#[async_trait]
+impl TwoFactorRepository for TwoFactorRepositoryImpl {
+    async fn find_status(&self, user_id: UserId) -> AppResult<TwoFactorStatus> {
+        sqlx::query_as!(
+            TwoFactorStatus,
+            r#"
+                SELECT
+                    t.enabled_at IS NOT NULL AS "enabled!",
+                    r.two_factor_required AS required
+                FROM users AS u
+                INNER JOIN roles AS r USING(role_id)
+                LEFT OUTER JOIN user_two_factor AS t USING(user_id)
+                WHERE u.user_id = $1;
+            "#,
+            user_id as _
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or_else(|| AppError::EntityNotFound("Specified user not found".to_string()))
+    }
+
+    async fn enroll(&self, user_id: UserId) -> AppResult<TwoFactorEnrollment> {
+        if self.find_status(user_id).await?.enabled {
+            return Err(AppError::UnprocessableEntity(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+
+        let email = sqlx::query_scalar!(
+            r#"
+                SELECT email FROM users WHERE user_id = $1;
+            "#,
+            user_id as _
+        )
+        .fetch_one(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let secret = totp::generate_secret();
+        let recovery_codes = totp::generate_recovery_codes(RECOVERY_CODE_COUNT);
+        let code_hashes: Vec<String> = recovery_codes
+            .iter()
+            .map(|code| hash(&totp::normalize_recovery_code(code)))
+            .collect();
+
+        let mut tx = self.db.begin().await?;
+
+        // 登録をやり直した場合は、確認前の秘密鍵とリカバリーコードを置き換える
+        sqlx::query!(
+            r#"
+                INSERT INTO user_two_factor (user_id, secret)
+                VALUES ($1, $2)
+                ON CONFLICT (user_id) DO UPDATE
+                SET secret = EXCLUDED.secret, enabled_at = NULL, last_used_step = NULL;
+            "#,
+            user_id as _,
+            secret
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM two_factor_recovery_codes WHERE user_id = $1;
+            "#,
+            user_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO two_factor_recovery_codes (user_id, code_hash)
+                SELECT $1, code_hash FROM UNNEST($2::VARCHAR[]) AS code_hash;
+            "#,
+            user_id as _,
+            &code_hashes
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(TwoFactorEnrollment {
+            otpauth_uri: totp::otpauth_uri(&self.config.issuer, &email, &secret),
+            secret,
+            recovery_codes,
+        })
+    }
+
+    async fn confirm(&self, event: ConfirmTwoFactor) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let secret = find_secret(&mut tx, event.user_id).await?.ok_or_else(|| {
+            AppError::UnprocessableEntity("Two-factor authentication is not enrolled".to_string())
+        })?;
+        if secret.enabled {
+            return Err(AppError::UnprocessableEntity(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+
+        // 登録の確認では、認証アプリに正しく登録できたことを確かめるためリカバリーコードは受け付けない
+        let step = verify_totp(&secret, &event.code).ok_or(AppError::UnauthenticatedError)?;
+        use_step(&mut tx, event.user_id, step).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn disable(&self, event: DisableTwoFactor) -> AppResult<()> {
+        if self.find_status(event.user_id).await?.required {
+            return Err(AppError::ForbiddenOperationError);
+        }
+
+        let mut tx = self.db.begin().await?;
+
+        let secret = find_secret(&mut tx, event.user_id)
+            .await?
+            .filter(|s| s.enabled)
+            .ok_or_else(|| {
+                AppError::UnprocessableEntity(
+                    "Two-factor authentication is not enabled".to_string(),
+                )
+            })?;
+        if !verify_code(&mut tx, event.user_id, &secret, &event.code).await? {
+            return Err(AppError::UnauthenticatedError);
+        }
+
+        sqlx::query!(
+            r#"
+                DELETE FROM user_two_factor WHERE user_id = $1;
+            "#,
+            event.user_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM two_factor_recovery_codes WHERE user_id = $1;
+            "#,
+            event.user_id as _
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(())
+    }
+
+    async fn create_challenge(&self, user_id: UserId) -> AppResult<Option<TwoFactorChallenge>> {
+        let status = self.find_status(user_id).await?;
+        if !status.enabled && !status.required {
+            return Ok(None);
+        }
+
+        let challenge_token = uuid::Uuid::new_v4().simple().to_string();
+        let expires_at = Utc::now() + Duration::seconds(self.config.challenge_ttl as i64);
+
+        let mut tx = self.db.begin().await?;
+
+        // 放置されたチャレンジはここで掃除する
+        sqlx::query!(
+            r#"
+                DELETE FROM two_factor_challenges WHERE expires_at <= CURRENT_TIMESTAMP;
+            "#
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO two_factor_challenges (token_hash, user_id, expires_at)
+                VALUES ($1, $2, $3);
+            "#,
+            hash(&challenge_token),
+            user_id as _,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(Some(TwoFactorChallenge {
+            challenge_token,
+            enrollment_required: !status.enabled,
+            expires_in: self.config.challenge_ttl,
+        }))
+    }
+
+    async fn enroll_with_challenge(
+        &self,
+        challenge_token: &str,
+    ) -> AppResult<(UserId, TwoFactorEnrollment)> {
+        let user_id = sqlx::query_scalar!(
+            r#"
+                SELECT user_id AS "user_id: UserId" FROM two_factor_challenges
+                WHERE token_hash = $1 AND expires_at > CURRENT_TIMESTAMP;
+            "#,
+            hash(challenge_token)
+        )
+        .fetch_optional(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or(AppError::UnauthorizedError)?;
+
+        let enrollment = self.enroll(user_id).await?;
+
+        Ok((user_id, enrollment))
+    }
+
+    async fn verify_challenge(&self, event: VerifyTwoFactorChallenge) -> AppResult<UserId> {
+        let token_hash = hash(&event.challenge_token);
+        let mut tx = self.db.begin().await?;
+
+        let challenge = sqlx::query!(
+            r#"
+                SELECT user_id AS "user_id: UserId", failed_attempts
+                FROM two_factor_challenges
+                WHERE token_hash = $1 AND expires_at > CURRENT_TIMESTAMP
+                FOR UPDATE;
+            "#,
+            token_hash
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .ok_or(AppError::UnauthorizedError)?;
+        let user_id = challenge.user_id;
+
+        let secret = find_secret(&mut tx, user_id).await?.ok_or_else(|| {
+            AppError::UnprocessableEntity("Two-factor authentication is not enrolled".to_string())
+        })?;
+
+        let verified = if secret.enabled {
+            verify_code(&mut tx, user_id, &secret, &event.code).await?
+        } else {
+            // 登録中はリカバリーコードを受け付けず、認証アプリのコードで登録を完了させる
+            match verify_totp(&secret, &event.code) {
+                Some(step) => {
+                    use_step(&mut tx, user_id, step).await?;
+                    true
+                }
+                None => false,
+            }
+        };
+
+        if verified || challenge.failed_attempts + 1 >= MAX_CHALLENGE_FAILURES {
+            sqlx::query!(
+                r#"
+                    DELETE FROM two_factor_challenges WHERE token_hash = $1;
+                "#,
+                token_hash
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        } else {
+            sqlx::query!(
+                r#"
+                    UPDATE two_factor_challenges
+                    SET failed_attempts = failed_attempts + 1
+                    WHERE token_hash = $1;
+                "#,
+                token_hash
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        }
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        if !verified {
+            return Err(AppError::UnauthenticatedError);
+        }
+
+        Ok(user_id)
+    }
+
+    async fn update_policy(&self, event: UpdateTwoFactorPolicy) -> AppResult<()> {
+        let res = sqlx::query!(
+            r#"
+                UPDATE roles SET two_factor_required = $2 WHERE name = $1;
+            "#,
+            event.role_name,
+            event.required
+        )
+        .execute(self.db.inner_ref())
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::EntityNotFound(
+                "Specified role not found".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+async fn find_secret(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+) -> AppResult<Option<TwoFactorSecret>> {
+    sqlx::query_as!(
+        TwoFactorSecret,
+        r#"
+            SELECT
+                secret,
+                enabled_at IS NOT NULL AS "enabled!",
+                last_used_step
+            FROM user_two_factor
+            WHERE user_id = $1
+            FOR UPDATE;
+        "#,
+        user_id as _
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)
+}
+
+fn verify_totp(secret: &TwoFactorSecret, code: &str) -> Option<i64> {
+    totp::verify(
+        &secret.secret,
+        code,
+        Utc::now().timestamp(),
+        secret.last_used_step,
+    )
+}
+
+/// 認証アプリのコードを受け付けたタイムステップを記録する。登録中の場合はあわせて有効にする。
+async fn use_step(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+    step: i64,
+) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+            UPDATE user_two_factor
+            SET
+                last_used_step = $2,
+                enabled_at = COALESCE(enabled_at, CURRENT_TIMESTAMP)
+            WHERE user_id = $1;
+        "#,
+        user_id as _,
+        step
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(())
+}
+
+/// 認証アプリのコードか、未使用のリカバリーコードであれば受け付ける。
+/// 受け付けたリカバリーコードは削除する。
+async fn verify_code(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+    secret: &TwoFactorSecret,
+    code: &str,
+) -> AppResult<bool> {
+    if let Some(step) = verify_totp(secret, code) {
+        use_step(tx, user_id, step).await?;
+        return Ok(true);
+    }
+
+    let res = sqlx::query!(
+        r#"
+            DELETE FROM two_factor_recovery_codes
+            WHERE user_id = $1 AND code_hash = $2;
+        "#,
+        user_id as _,
+        hash(&totp::normalize_recovery_code(code))
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    Ok(res.rows_affected() > 0)
+}
+
+fn hash(value: &str) -> String {
+    format!("{:x}", Sha256::digest(value.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use kernel::model::role::DEFAULT_ROLE_NAME;
+
+    use super::*;
+
+    fn repository(pool: sqlx::PgPool) -> TwoFactorRepositoryImpl {
+        TwoFactorRepositoryImpl::new(
+            ConnectionPool::new(pool),
+            TwoFactorConfig {
+                issuer: "rusty-book-manager".to_string(),
+                challenge_ttl: 300,
+            },
+        )
+    }
+
+    /// 認証アプリが `offset` ステップ後に表示するコード
+    fn code(enrollment: &TwoFactorEnrollment, offset: i64) -> String {
+        totp::code_at(&enrollment.secret, Utc::now().timestamp() + offset * 30)
+    }
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_enroll_and_verify_challenge(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = repository(pool);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        // 未登録でロールでも必須でなければ、パスワードのみでログインできる
+        assert!(repo.create_challenge(user_id).await?.is_none());
+
+        let enrollment = repo.enroll(user_id).await?;
+        assert!(enrollment
+            .otpauth_uri
+            .contains("rusty-book-manager:anne.sallow@example.com"));
+        assert_eq!(enrollment.recovery_codes.len(), RECOVERY_CODE_COUNT);
+        // コードを確認するまでは有効にならない
+        assert!(!repo.find_status(user_id).await?.enabled);
+
+        let res = repo
+            .confirm(ConfirmTwoFactor::new(user_id, "000000".into()))
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+        let confirmed_code = code(&enrollment, 0);
+        repo.confirm(ConfirmTwoFactor::new(user_id, confirmed_code.clone()))
+            .await?;
+        assert!(repo.find_status(user_id).await?.enabled);
+
+        let challenge = repo
+            .create_challenge(user_id)
+            .await?
+            .expect("challenge should be issued");
+        assert!(!challenge.enrollment_required);
+
+        // 確認に使ったコードは再利用できない
+        let res = repo
+            .verify_challenge(VerifyTwoFactorChallenge::new(
+                challenge.challenge_token.clone(),
+                confirmed_code,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        let recovery_code = enrollment.recovery_codes[0].to_uppercase();
+        let verified = repo
+            .verify_challenge(VerifyTwoFactorChallenge::new(
+                challenge.challenge_token.clone(),
+                recovery_code.clone(),
+            ))
+            .await?;
+        assert_eq!(verified, user_id);
+
+        // チャレンジもリカバリーコードも使い捨て
+        let res = repo
+            .verify_challenge(VerifyTwoFactorChallenge::new(
+                challenge.challenge_token,
+                code(&enrollment, 1),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthorizedError)));
+        let challenge = repo.create_challenge(user_id).await?.unwrap();
+        let res = repo
+            .verify_challenge(VerifyTwoFactorChallenge::new(
+                challenge.challenge_token,
+                recovery_code,
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        repo.disable(DisableTwoFactor::new(user_id, code(&enrollment, 1)))
+            .await?;
+        assert!(!repo.find_status(user_id).await?.enabled);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_challenge_is_discarded_after_failures(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = repository(pool);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let enrollment = repo.enroll(user_id).await?;
+        repo.confirm(ConfirmTwoFactor::new(user_id, code(&enrollment, 0)))
+            .await?;
+
+        let challenge = repo.create_challenge(user_id).await?.unwrap();
+        for _ in 0..MAX_CHALLENGE_FAILURES {
+            let res = repo
+                .verify_challenge(VerifyTwoFactorChallenge::new(
+                    challenge.challenge_token.clone(),
+                    "wrong".into(),
+                ))
+                .await;
+            assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+        }
+
+        let res = repo
+            .verify_challenge(VerifyTwoFactorChallenge::new(
+                challenge.challenge_token,
+                code(&enrollment, 1),
+            ))
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthorizedError)));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_enforced_by_role(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = repository(pool);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        repo.update_policy(UpdateTwoFactorPolicy::new(DEFAULT_ROLE_NAME.into(), true))
+            .await?;
+
+        // 未登録でも必須であればチャレンジが発行され、その場で登録させる
+        let challenge = repo
+            .create_challenge(user_id)
+            .await?
+            .expect("challenge should be issued");
+        assert!(challenge.enrollment_required);
+
+        let (enrolled, enrollment) = repo
+            .enroll_with_challenge(&challenge.challenge_token)
+            .await?;
+        assert_eq!(enrolled, user_id);
+        let verified = repo
+            .verify_challenge(VerifyTwoFactorChallenge::new(
+                challenge.challenge_token,
+                code(&enrollment, 0),
+            ))
+            .await?;
+        assert_eq!(verified, user_id);
+        assert_eq!(
+            repo.find_status(user_id).await?,
+            TwoFactorStatus {
+                enabled: true,
+                required: true,
+            }
+        );
+
+        // 必須のロールでは無効にできない
+        let res = repo
+            .disable(DisableTwoFactor::new(user_id, code(&enrollment, 1)))
+            .await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperationError)));
+
+        Ok(())
+    }
+}
@@ -1,23 +1,42 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use derive_new::new;
 
-use crate::database::{model::user::UserRow, ConnectionPool};
+use crate::{
+    database::{model::user::UserRow, ConnectionPool},
+    repository::{
+        book::delete_cover_images,
+        outbox::record_event,
+        role::{find_role, RoleKey},
+    },
+};
 use kernel::{
     model::{
         id::UserId,
-        role::Role,
+        notification::{event::CreateNotification, NotificationKind},
+        outbox::DomainEvent,
+        role::DEFAULT_ROLE_NAME,
         user::{
-            event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+            event::{
+                CreateUser, DeleteUser, ResetUserPassword, UpdateUserPassword, UpdateUserRole,
+            },
             User,
         },
     },
-    repository::user::UserRepository,
+    repository::{
+        auth::AuthRepository, blob::BlobStore, notification::NotificationRepository,
+        user::UserRepository,
+    },
 };
 use shared::error::{AppError, AppResult};
 
 #[derive(new)]
 pub struct UserRepositoryImpl {
     db: ConnectionPool,
+    notification: Arc<dyn NotificationRepository>,
+    auth: Arc<dyn AuthRepository>,
+    blob_store: Arc<dyn BlobStore>,
 }
 
 #[async_trait]
@@ -30,7 +49,12 @@ impl UserRepository for UserRepositoryImpl {
                     u.user_id,
                     u.name,
                     u.email,
+                    r.role_id,
                     r.name AS role_name,
+                    ARRAY(
+                        SELECT permission FROM role_permissions AS p
+                        WHERE p.role_id = r.role_id
+                    ) AS "role_permissions!",
                     u.created_at,
                     u.updated_at
                 FROM users AS u
@@ -57,7 +81,12 @@ impl UserRepository for UserRepositoryImpl {
                     u.user_id,
                     u.name,
                     u.email,
+                    r.role_id,
                     r.name AS role_name,
+                    ARRAY(
+                        SELECT permission FROM role_permissions AS p
+                        WHERE p.role_id = r.role_id
+                    ) AS "role_permissions!",
                     u.created_at,
                     u.updated_at
                 FROM users AS u
@@ -78,20 +107,25 @@ impl UserRepository for UserRepositoryImpl {
     async fn create(&self, event: CreateUser) -> AppResult<User> {
         let user_id = UserId::new();
         let hashed_password = hash_password(&event.password)?;
-        let role = Role::User;
+
+        let mut tx = self.db.begin().await?;
+
+        let role = find_role(&mut tx, RoleKey::Name(DEFAULT_ROLE_NAME))
+            .await?
+            .ok_or_else(|| AppError::EntityNotFound("Default role not found".to_string()))?;
 
         let res = sqlx::query!(
             r#"
                 INSERT INTO users (user_id, name, email, password_hash, role_id)
-                SELECT $1, $2, $3, $4, role_id FROM roles WHERE name = $5;
+                VALUES ($1, $2, $3, $4, $5);
             "#,
             user_id as _,
             event.name,
             event.email,
             hashed_password,
-            role.as_ref(),
+            role.id as _,
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
 
@@ -101,6 +135,19 @@ impl UserRepository for UserRepositoryImpl {
             ));
         }
 
+        record_event(
+            &mut tx,
+            DomainEvent::UserCreated {
+                user_id,
+                name: event.name.clone(),
+                email: event.email.clone(),
+                role: role.name.clone(),
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
         Ok(User {
             id: user_id,
             name: event.name,
@@ -125,34 +172,45 @@ impl UserRepository for UserRepositoryImpl {
 
         verify_password(&event.current_password, &original_password_hash)?;
 
-        let new_password_hash = hash_password(&event.new_password)?;
-        sqlx::query!(
-            r#"
-                UPDATE users SET password_hash = $2 WHERE user_id = $1;
-            "#,
-            event.user_id as _,
-            new_password_hash
-        )
-        .execute(&mut *tx)
-        .await
-        .map_err(AppError::SpecificOperationError)?;
+        set_password(&mut tx, event.user_id, &event.new_password).await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        // パスワードの変更後は、すべての端末で再ログインを求める
+        self.revoke_all_sessions(event.user_id).await;
+
+        Ok(())
+    }
+
+    async fn reset_password(&self, event: ResetUserPassword) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        set_password(&mut tx, event.user_id, &event.new_password).await?;
 
         tx.commit().await.map_err(AppError::TransactionError)?;
 
+        self.revoke_all_sessions(event.user_id).await;
+
         Ok(())
     }
 
     async fn update_role(&self, event: UpdateUserRole) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        let role = find_role(&mut tx, RoleKey::Name(&event.role_name))
+            .await?
+            .ok_or_else(|| AppError::EntityNotFound("Specified role not found".to_string()))?;
+
         let res = sqlx::query!(
             r#"
                 UPDATE users
-                SET role_id = (SELECT role_id FROM roles WHERE name = $2)
+                SET role_id = $2
                 WHERE user_id = $1;
             "#,
             event.user_id as _,
-            event.role.as_ref(),
+            role.id as _,
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
 
@@ -162,30 +220,132 @@ impl UserRepository for UserRepositoryImpl {
             ));
         }
 
+        record_event(
+            &mut tx,
+            DomainEvent::UserRoleChanged {
+                user_id: event.user_id,
+                role: role.name.clone(),
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        // JWT はロールと権限をクレームに含むため、発行済みのトークンを失効させて再ログインを求める
+        self.revoke_all_sessions(event.user_id).await;
+
+        // 通知の失敗でロール変更自体は失敗させない
+        if let Err(e) = self
+            .notification
+            .notify(CreateNotification::new(
+                event.user_id,
+                NotificationKind::RoleChanged,
+                "Your role has been changed".into(),
+                format!("Your role is now {}.", role.name),
+            ))
+            .await
+        {
+            tracing::warn!(
+                error.message = %e,
+                user_id = %event.user_id,
+                "Failed to send notification"
+            );
+        }
+
         Ok(())
     }
 
     async fn delete(&self, event: DeleteUser) -> AppResult<()> {
+        let mut tx = self.db.begin().await?;
+
+        // 蔵書はユーザーとともに削除されるため、表紙画像のキーを先に控えておく
+        let cover_keys = sqlx::query_scalar!(
+            r#"
+                SELECT cover_key AS "cover_key!" FROM books
+                WHERE user_id = $1 AND cover_key IS NOT NULL;
+            "#,
+            event.user_id as _
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
         let res = sqlx::query!(
             r#"
                 DELETE FROM users WHERE user_id = $1;
             "#,
             event.user_id as _
         )
-        .execute(self.db.inner_ref())
+        .execute(&mut *tx)
         .await
         .map_err(AppError::SpecificOperationError)?;
 
-        if res.rows_affected() == 1 {
+        if res.rows_affected() == 0 {
             return Err(AppError::EntityNotFound(
                 "Specified user not found".to_string(),
             ));
         }
 
+        record_event(
+            &mut tx,
+            DomainEvent::UserDeleted {
+                user_id: event.user_id,
+            },
+        )
+        .await?;
+
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        self.revoke_all_sessions(event.user_id).await;
+
+        for cover_key in cover_keys {
+            delete_cover_images(self.blob_store.as_ref(), &cover_key).await;
+        }
+
         Ok(())
     }
 }
 
+impl UserRepositoryImpl {
+    /// セッションの失効に失敗しても、コミット済みの変更自体は失敗させない
+    async fn revoke_all_sessions(&self, user_id: UserId) {
+        if let Err(e) = self.auth.revoke_all_sessions(user_id).await {
+            tracing::error!(
+                error.message = %e,
+                user_id = %user_id,
+                "Failed to revoke sessions"
+            );
+        }
+    }
+}
+
+/// パスワードを更新し、変更のイベントを記録する。
+async fn set_password(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: UserId,
+    new_password: &str,
+) -> AppResult<()> {
+    let new_password_hash = hash_password(new_password)?;
+    let res = sqlx::query!(
+        r#"
+            UPDATE users SET password_hash = $2 WHERE user_id = $1;
+        "#,
+        user_id as _,
+        new_password_hash
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(AppError::SpecificOperationError)?;
+
+    if res.rows_affected() == 0 {
+        return Err(AppError::EntityNotFound(
+            "Specified user not found".to_string(),
+        ));
+    }
+
+    record_event(tx, DomainEvent::UserPasswordChanged { user_id }).await
+}
+
 fn hash_password(password: &str) -> AppResult<String> {
     bcrypt::hash(password, bcrypt::DEFAULT_COST).map_err(AppError::from)
 }
@@ -198,3 +358,134 @@ fn verify_password(password: &str, hash: &str) -> AppResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        str::FromStr,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    use kernel::{
+        model::{
+            auth::AccessToken, book::event::UpdateBookCover, id::BookId, role::ADMIN_ROLE_NAME,
+        },
+        repository::{auth::MockAuthRepository, book::BookRepository},
+    };
+
+    use super::*;
+    use crate::{
+        blob::memory::InMemoryBlobStore,
+        notification::memory::InMemoryNotificationSender,
+        repository::{book::BookRepositoryImpl, notification::NotificationRepositoryImpl},
+    };
+
+    const ADMIN_ID: &str = "2bbd820c-7a88-450c-b056-19dcbadd527d";
+
+    #[sqlx::test(fixtures("common", "user"))]
+    async fn test_update_role_revokes_issued_tokens(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let notification = Arc::new(NotificationRepositoryImpl::new(
+            db.clone(),
+            Arc::new(InMemoryNotificationSender::default()),
+        ));
+
+        let user_id = UserId::from_str(ADMIN_ID)?;
+        let admin = UserRepositoryImpl::new(
+            db.clone(),
+            notification.clone(),
+            Arc::new(MockAuthRepository::new()),
+            Arc::new(InMemoryBlobStore::default()),
+        )
+        .find_current_user(user_id)
+        .await?
+        .unwrap();
+        assert_eq!(admin.role.name, ADMIN_ROLE_NAME);
+
+        // 失効するまでは、ロール変更前に発行したトークンで管理者として認可される
+        let revoked = Arc::new(AtomicBool::new(false));
+        let mut auth = MockAuthRepository::new();
+        auth.expect_fetch_user_from_token().returning({
+            let revoked = revoked.clone();
+            move |_| {
+                Ok((!revoked.load(Ordering::SeqCst)).then(|| User {
+                    id: admin.id,
+                    name: admin.name.clone(),
+                    email: admin.email.clone(),
+                    role: admin.role.clone(),
+                }))
+            }
+        });
+        auth.expect_revoke_all_sessions()
+            .withf(move |id| *id == user_id)
+            .times(1)
+            .returning({
+                let revoked = revoked.clone();
+                move |_| {
+                    revoked.store(true, Ordering::SeqCst);
+                    Ok(())
+                }
+            });
+        let auth = Arc::new(auth);
+        let repo = UserRepositoryImpl::new(
+            db,
+            notification,
+            auth.clone(),
+            Arc::new(InMemoryBlobStore::default()),
+        );
+
+        let issued = AccessToken("issued-before-role-change".into());
+        assert!(auth.fetch_user_from_token(&issued).await?.is_some());
+
+        repo.update_role(UpdateUserRole {
+            user_id,
+            role_name: DEFAULT_ROLE_NAME.into(),
+        })
+        .await?;
+
+        assert!(auth.fetch_user_from_token(&issued).await?.is_none());
+        let user = repo.find_current_user(user_id).await?.unwrap();
+        assert_eq!(user.role.name, DEFAULT_ROLE_NAME);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_delete_removes_cover_images(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let db = ConnectionPool::new(pool);
+        let blob_store = Arc::new(InMemoryBlobStore::default());
+        let user_id = UserId::from_str(ADMIN_ID)?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+
+        BookRepositoryImpl::new(db.clone(), blob_store.clone())
+            .update_cover(UpdateBookCover::new(
+                book_id,
+                "image/png".into(),
+                b"image".to_vec(),
+                b"thumbnail".to_vec(),
+                user_id,
+                false,
+            ))
+            .await?;
+        assert_eq!(blob_store.keys().len(), 2);
+
+        let mut auth = MockAuthRepository::new();
+        auth.expect_revoke_all_sessions().returning(|_| Ok(()));
+        let repo = UserRepositoryImpl::new(
+            db.clone(),
+            Arc::new(NotificationRepositoryImpl::new(
+                db,
+                Arc::new(InMemoryNotificationSender::default()),
+            )),
+            Arc::new(auth),
+            blob_store.clone(),
+        );
+
+        // ユーザーとともに削除される蔵書の表紙画像も削除する
+        repo.delete(DeleteUser { user_id }).await?;
+        assert!(repo.find_current_user(user_id).await?.is_none());
+        assert!(blob_store.keys().is_empty());
+
+        Ok(())
+    }
+}
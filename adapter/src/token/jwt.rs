@@ -0,0 +1,341 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use kernel::{
+    model::{
+        auth::{AccessToken, AccessTokenClaims},
+        id::{RoleId, SessionId, UserId},
+        role::Role,
+        user::User,
+    },
+    repository::auth::AccessTokenStore,
+};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use shared::{
+    config::JwtConfig,
+    error::{AppError, AppResult},
+};
+
+use crate::{database::model::role::parse_permissions, redis::RedisClient};
+
+/// 失効させた JWT の `jti` を、有効期限をスコアとして保持する Sorted Set のキー
+const JWT_DENYLIST_KEY: &str = "jwt_denylist";
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    alg: String,
+    typ: String,
+    kid: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: UserId,
+    name: String,
+    email: String,
+    role: String,
+    role_id: RoleId,
+    /// 権限の変更は、トークンを再発行するまで反映されない
+    permissions: Vec<String>,
+    sid: SessionId,
+    jti: String,
+    iat: i64,
+    exp: i64,
+}
+
+/// HS256 で JWT を署名・検証する。
+/// 署名には `current_kid` の鍵を用い、検証はヘッダーの `kid` に対応する鍵で行うため、
+/// 旧い鍵を残したまま `current_kid` を切り替えることで鍵をローテーションできる。
+struct JwtCodec {
+    keys: HashMap<String, Vec<u8>>,
+    current_kid: String,
+}
+
+impl JwtCodec {
+    fn new(config: &JwtConfig) -> AppResult<Self> {
+        let keys: HashMap<String, Vec<u8>> = config
+            .keys
+            .iter()
+            .map(|k| (k.kid.clone(), k.secret.as_bytes().to_vec()))
+            .collect();
+        if !keys.contains_key(&config.current_kid) {
+            return Err(AppError::ConversionEntityError(format!(
+                "Unknown JWT key id: {}",
+                config.current_kid
+            )));
+        }
+
+        Ok(Self {
+            keys,
+            current_kid: config.current_kid.clone(),
+        })
+    }
+
+    fn mac(&self, kid: &str) -> Option<HmacSha256> {
+        let key = self.keys.get(kid)?;
+        // HMAC はどの長さの鍵も受け付けるため失敗しない
+        HmacSha256::new_from_slice(key).ok()
+    }
+
+    fn encode(&self, claims: &Claims) -> AppResult<String> {
+        let header = Header {
+            alg: "HS256".into(),
+            typ: "JWT".into(),
+            kid: self.current_kid.clone(),
+        };
+        let signing_input = format!("{}.{}", to_segment(&header)?, to_segment(claims)?);
+
+        let mut mac = self
+            .mac(&self.current_kid)
+            .ok_or_else(|| AppError::ConversionEntityError("Invalid JWT key".into()))?;
+        mac.update(signing_input.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+
+        Ok(format!("{}.{}", signing_input, signature))
+    }
+
+    /// 署名と有効期限を検証する。改ざんや期限切れ、未知の `kid` の場合は `None` を返す。
+    fn decode(&self, token: &str, now: i64) -> Option<Claims> {
+        let (signing_input, signature) = token.rsplit_once('.')?;
+        let (header, claims) = signing_input.split_once('.')?;
+
+        let header: Header = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header).ok()?).ok()?;
+        if header.alg != "HS256" {
+            return None;
+        }
+        let mut mac = self.mac(&header.kid)?;
+        mac.update(signing_input.as_bytes());
+        mac.verify_slice(&URL_SAFE_NO_PAD.decode(signature).ok()?)
+            .ok()?;
+
+        let claims: Claims = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(claims).ok()?).ok()?;
+        (claims.exp > now).then_some(claims)
+    }
+}
+
+fn to_segment<T: Serialize>(value: &T) -> AppResult<String> {
+    let json =
+        serde_json::to_vec(value).map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+    Ok(URL_SAFE_NO_PAD.encode(json))
+}
+
+#[derive(Default)]
+struct Denylist {
+    jtis: HashSet<String>,
+    synced_at: Option<Instant>,
+}
+
+/// 署名付きの JWT をアクセストークンとして発行する。
+/// 検証はトークン自体と手元に同期した失効リストのみで行い、リクエストごとの I/O を発生させない。
+pub struct JwtAccessTokenStore {
+    codec: JwtCodec,
+    kv: Arc<RedisClient>,
+    ttl: u64,
+    denylist: Mutex<Denylist>,
+    sync_interval: Duration,
+}
+
+impl JwtAccessTokenStore {
+    pub fn new(config: &JwtConfig, kv: Arc<RedisClient>, ttl: u64) -> AppResult<Self> {
+        Ok(Self {
+            codec: JwtCodec::new(config)?,
+            kv,
+            ttl,
+            denylist: Mutex::new(Denylist::default()),
+            sync_interval: Duration::from_secs(config.denylist_sync_interval),
+        })
+    }
+
+    /// 前回の同期から `sync_interval` 以上経過していれば、Redis から失効リストを取り込む。
+    /// 同期に失敗した場合は手元のリストを使い続け、次の間隔で再試行する。
+    async fn sync_denylist(&self, now: i64) {
+        let needs_sync = {
+            let denylist = self.denylist.lock().unwrap();
+            denylist
+                .synced_at
+                .map_or(true, |t| t.elapsed() >= self.sync_interval)
+        };
+        if !needs_sync {
+            return;
+        }
+
+        let fetched = self.kv.zrangebyscore_from(JWT_DENYLIST_KEY, now).await;
+        let mut denylist = self.denylist.lock().unwrap();
+        match fetched {
+            Ok(jtis) => denylist.jtis = jtis.into_iter().collect(),
+            Err(e) => tracing::warn!(
+                error.message = %e,
+                "Failed to sync JWT denylist"
+            ),
+        }
+        denylist.synced_at = Some(Instant::now());
+    }
+
+    fn is_denied(&self, jti: &str) -> bool {
+        self.denylist.lock().unwrap().jtis.contains(jti)
+    }
+}
+
+#[async_trait]
+impl AccessTokenStore for JwtAccessTokenStore {
+    async fn issue(&self, user: &User, session_id: SessionId) -> AppResult<AccessToken> {
+        let now = Utc::now().timestamp();
+        let claims = Claims {
+            sub: user.id,
+            name: user.name.clone(),
+            email: user.email.clone(),
+            role: user.role.name.clone(),
+            role_id: user.role.id,
+            permissions: user
+                .role
+                .permissions
+                .iter()
+                .map(|permission| permission.as_ref().to_string())
+                .collect(),
+            sid: session_id,
+            jti: uuid::Uuid::new_v4().simple().to_string(),
+            iat: now,
+            exp: now + self.ttl as i64,
+        };
+
+        self.codec.encode(&claims).map(AccessToken)
+    }
+
+    async fn verify(&self, access_token: &AccessToken) -> AppResult<Option<AccessTokenClaims>> {
+        let now = Utc::now().timestamp();
+        let Some(claims) = self.codec.decode(&access_token.0, now) else {
+            return Ok(None);
+        };
+
+        self.sync_denylist(now).await;
+        if self.is_denied(&claims.jti) {
+            return Ok(None);
+        }
+
+        let Ok(permissions) = parse_permissions(&claims.permissions) else {
+            return Ok(None);
+        };
+        let role = Role {
+            id: claims.role_id,
+            name: claims.role,
+            permissions,
+        };
+
+        Ok(Some(AccessTokenClaims {
+            user_id: claims.sub,
+            session_id: Some(claims.sid),
+            user: Some(User {
+                id: claims.sub,
+                name: claims.name,
+                email: claims.email,
+                role,
+            }),
+        }))
+    }
+
+    async fn revoke(&self, access_token: &AccessToken) -> AppResult<()> {
+        let now = Utc::now().timestamp();
+        // 改ざんされたトークンや期限切れのトークンは、失効させるまでもなく無効
+        let Some(claims) = self.codec.decode(&access_token.0, now) else {
+            return Ok(());
+        };
+
+        self.kv
+            .zadd(JWT_DENYLIST_KEY, &claims.jti, claims.exp)
+            .await?;
+        // 期限切れのエントリはもう参照されないので、ついでに掃除しておく
+        self.kv
+            .zremrangebyscore_before(JWT_DENYLIST_KEY, now)
+            .await?;
+        self.denylist.lock().unwrap().jtis.insert(claims.jti);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kernel::model::role::DEFAULT_ROLE_NAME;
+    use shared::config::JwtSigningKey;
+
+    use super::*;
+
+    fn config(keys: &[(&str, &str)], current_kid: &str) -> JwtConfig {
+        JwtConfig {
+            keys: keys
+                .iter()
+                .map(|(kid, secret)| JwtSigningKey {
+                    kid: kid.to_string(),
+                    secret: secret.to_string(),
+                })
+                .collect(),
+            current_kid: current_kid.to_string(),
+            denylist_sync_interval: 5,
+        }
+    }
+
+    fn claims(now: i64) -> Claims {
+        Claims {
+            sub: UserId::new(),
+            name: "Anne Sallow".into(),
+            email: "anne@example.com".into(),
+            role: DEFAULT_ROLE_NAME.into(),
+            role_id: RoleId::new(),
+            permissions: vec![],
+            sid: SessionId::new(),
+            jti: "jti".into(),
+            iat: now,
+            exp: now + 60,
+        }
+    }
+
+    #[test]
+    fn encode_and_decode_round_trip() -> anyhow::Result<()> {
+        let codec = JwtCodec::new(&config(&[("k1", "secret1")], "k1"))?;
+        let now = Utc::now().timestamp();
+        let original = claims(now);
+
+        let token = codec.encode(&original)?;
+        let decoded = codec.decode(&token, now).expect("token should be valid");
+        assert_eq!(decoded.sub, original.sub);
+        assert_eq!(decoded.sid, original.sid);
+
+        // 期限切れ
+        assert!(codec.decode(&token, now + 60).is_none());
+
+        // 署名の改ざん
+        let tampered = format!("{}x", &token[..token.len() - 1]);
+        assert!(codec.decode(&tampered, now).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn tokens_signed_with_rotated_key_remain_valid_while_key_is_configured() -> anyhow::Result<()> {
+        let now = Utc::now().timestamp();
+        let old = JwtCodec::new(&config(&[("k1", "secret1")], "k1"))?;
+        let token = old.encode(&claims(now))?;
+
+        let rotated = JwtCodec::new(&config(&[("k1", "secret1"), ("k2", "secret2")], "k2"))?;
+        assert!(rotated.decode(&token, now).is_some());
+        let new_token = rotated.encode(&claims(now))?;
+        assert!(new_token
+            .starts_with(&URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT","kid":"k2"}"#)));
+
+        let retired = JwtCodec::new(&config(&[("k2", "secret2")], "k2"))?;
+        assert!(retired.decode(&token, now).is_none());
+        assert!(retired.decode(&new_token, now).is_some());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use kernel::repository::auth::AccessTokenStore;
+use shared::{
+    config::{AuthBackendConfig, AuthConfig},
+    error::AppResult,
+};
+
+use crate::redis::RedisClient;
+
+pub mod jwt;
+pub mod redis;
+
+/// 設定に応じたアクセストークンの保存方式を生成する。
+pub fn build_access_token_store(
+    config: &AuthConfig,
+    kv: Arc<RedisClient>,
+) -> AppResult<Arc<dyn AccessTokenStore>> {
+    let store: Arc<dyn AccessTokenStore> = match &config.backend {
+        AuthBackendConfig::Redis => Arc::new(redis::RedisAccessTokenStore::new(
+            kv,
+            config.ttl,
+            config.refresh_ttl,
+        )),
+        AuthBackendConfig::Jwt(cfg) => {
+            Arc::new(jwt::JwtAccessTokenStore::new(cfg, kv, config.ttl)?)
+        }
+    };
+
+    Ok(store)
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use derive_new::new;
+use kernel::{
+    model::{
+        auth::{AccessToken, AccessTokenClaims},
+        id::SessionId,
+        user::User,
+    },
+    repository::auth::AccessTokenStore,
+};
+use shared::error::AppResult;
+
+use crate::{
+    database::model::auth::{
+        session_key, AccessTokenFamilyKey, AuthorizationKey, AuthorizedUserId, LAST_SEEN_AT_FIELD,
+    },
+    redis::RedisClient,
+};
+
+/// ランダムな不透明トークンを発行し、トークンからユーザーへの対応を Redis に保存する。
+#[derive(new)]
+pub struct RedisAccessTokenStore {
+    kv: Arc<RedisClient>,
+    ttl: u64,
+    refresh_ttl: u64,
+}
+
+#[async_trait]
+impl AccessTokenStore for RedisAccessTokenStore {
+    async fn issue(&self, user: &User, session_id: SessionId) -> AppResult<AccessToken> {
+        let access_token = AccessToken(uuid::Uuid::new_v4().simple().to_string());
+        self.kv
+            .set_with_ex(
+                &AuthorizationKey::from(&access_token),
+                &AuthorizedUserId::new(user.id),
+                self.ttl,
+            )
+            .await?;
+        self.kv
+            .set_with_ex(
+                &AccessTokenFamilyKey::from(&access_token),
+                &session_id.into(),
+                self.ttl,
+            )
+            .await?;
+
+        Ok(access_token)
+    }
+
+    async fn verify(&self, access_token: &AccessToken) -> AppResult<Option<AccessTokenClaims>> {
+        let Some(user_id) = self
+            .kv
+            .get(&AuthorizationKey::from(access_token))
+            .await?
+            .map(AuthorizedUserId::into_inner)
+        else {
+            return Ok(None);
+        };
+
+        let session_id = self
+            .kv
+            .get(&AccessTokenFamilyKey::from(access_token))
+            .await?
+            .map(|family_id| family_id.into_inner());
+        // どうせ Redis を参照するので、ついでにセッションの最終アクセス日時を更新する
+        if let Some(session_id) = session_id {
+            let now = Utc::now().to_rfc3339();
+            self.kv
+                .hset_multiple_with_ex(
+                    &session_key(session_id),
+                    &[(LAST_SEEN_AT_FIELD, &now)],
+                    self.refresh_ttl,
+                )
+                .await?;
+        }
+
+        Ok(Some(AccessTokenClaims {
+            user_id,
+            session_id,
+            user: None,
+        }))
+    }
+
+    async fn revoke(&self, access_token: &AccessToken) -> AppResult<()> {
+        self.kv
+            .delete(&AuthorizationKey::from(access_token))
+            .await?;
+        self.kv
+            .delete(&AccessTokenFamilyKey::from(access_token))
+            .await
+    }
+}
@@ -0,0 +1,189 @@
+//! RFC 6238 の TOTP（HMAC-SHA1、6 桁、30 秒間隔）。Google Authenticator などの認証アプリと互換がある。
+
+use hmac::{Hmac, Mac};
+use rand::{distributions::Slice, Rng, RngCore};
+use sha1::Sha1;
+
+const DIGITS: u32 = 6;
+const PERIOD: i64 = 30;
+/// 端末の時計のずれを考慮し、前後 1 ステップまでのコードを受け付ける
+const SKEW: i64 = 1;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// 160 ビットの秘密鍵を生成し、Base32 で返す
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; 20];
+    rand::thread_rng().fill_bytes(&mut secret);
+    base32_encode(&secret)
+}
+
+/// 認証アプリに秘密鍵を登録するための `otpauth://` URI を組み立てる
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let mut url = reqwest::Url::parse("otpauth://totp/").expect("static URL should be valid");
+    url.set_path(&format!("{}:{}", issuer, account));
+    url.query_pairs_mut()
+        .append_pair("secret", secret)
+        .append_pair("issuer", issuer)
+        .append_pair("algorithm", "SHA1")
+        .append_pair("digits", &DIGITS.to_string())
+        .append_pair("period", &PERIOD.to_string());
+    url.into()
+}
+
+/// コードが `unix_time` 時点で有効であれば、一致したタイムステップを返す。
+/// `last_used_step` 以前のステップは、同じコードの再利用を防ぐため受け付けない。
+pub fn verify(
+    secret: &str,
+    code: &str,
+    unix_time: i64,
+    last_used_step: Option<i64>,
+) -> Option<i64> {
+    let key = base32_decode(secret)?;
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let current = unix_time.div_euclid(PERIOD);
+    (current - SKEW..=current + SKEW)
+        .filter(|step| last_used_step.map_or(true, |last| *step > last))
+        .find(|step| format_code(hotp(&key, *step as u64)) == code)
+}
+
+/// リカバリーコードを生成する。読み間違えやすい文字を避けるため Base32 の文字のみを使う。
+pub fn generate_recovery_codes(count: usize) -> Vec<String> {
+    let alphabet = Slice::new(BASE32_ALPHABET).expect("alphabet should not be empty");
+    let mut rng = rand::thread_rng();
+    (0..count)
+        .map(|_| {
+            let chars: String = (&mut rng)
+                .sample_iter(&alphabet)
+                .take(10)
+                .map(|b| (*b as char).to_ascii_lowercase())
+                .collect();
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect()
+}
+
+/// 入力の揺れを吸収するため、区切りの `-` や空白を除き小文字に揃える
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// `unix_time` 時点のコードを返す。認証アプリの代わりにテストで用いる。
+#[cfg(test)]
+pub(crate) fn code_at(secret: &str, unix_time: i64) -> String {
+    let key = base32_decode(secret).expect("secret should be valid Base32");
+    format_code(hotp(&key, unix_time.div_euclid(PERIOD) as u64))
+}
+
+fn hotp(key: &[u8], counter: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // RFC 4226 5.3 の dynamic truncation
+    let offset = (digest[19] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    binary % 10u32.pow(DIGITS)
+}
+
+fn format_code(value: u32) -> String {
+    format!("{:0width$}", value, width = DIGITS as usize)
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in s.trim_end_matches('=').bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|a| *a == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 6238 付録 B のテストベクターの秘密鍵（SHA-1）
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn hotp_matches_rfc_6238_vectors() {
+        // 8 桁の値の下 6 桁と一致する
+        for (time, expected) in [(59, 287082), (1111111109, 81804), (2000000000, 279037)] {
+            assert_eq!(hotp(RFC_SECRET, time / PERIOD as u64), expected);
+        }
+    }
+
+    #[test]
+    fn base32_round_trip() {
+        let encoded = base32_encode(RFC_SECRET);
+        assert_eq!(encoded, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(base32_decode(&encoded).as_deref(), Some(RFC_SECRET));
+        assert!(base32_decode("not base32!").is_none());
+    }
+
+    #[test]
+    fn verify_accepts_adjacent_steps_once() {
+        let secret = base32_encode(RFC_SECRET);
+
+        assert_eq!(verify(&secret, "287082", 59, None), Some(1));
+        // 1 ステップ後でも受け付ける
+        assert_eq!(verify(&secret, "287082", 89, None), Some(1));
+        assert_eq!(verify(&secret, "287082", 120, None), None);
+        // 使用済みのステップのコードは受け付けない
+        assert_eq!(verify(&secret, "287082", 59, Some(1)), None);
+        assert_eq!(verify(&secret, "28708", 59, None), None);
+    }
+
+    #[test]
+    fn recovery_codes_are_normalized() {
+        let codes = generate_recovery_codes(10);
+        assert_eq!(codes.len(), 10);
+        assert!(codes.iter().all(|c| c.len() == 11));
+        assert_eq!(normalize_recovery_code(" ABCDE-fghij "), "abcdefghij");
+    }
+
+    #[test]
+    fn otpauth_uri_contains_secret_and_issuer() {
+        let uri = otpauth_uri("rusty-book-manager", "alice@example.com", "ABC");
+        assert!(uri.starts_with("otpauth://totp/rusty-book-manager:alice@example.com?"));
+        assert!(uri.contains("secret=ABC"));
+        assert!(uri.contains("issuer=rusty-book-manager"));
+    }
+}
@@ -1,14 +1,35 @@
-use axum::{async_trait, extract::FromRequestParts, http::request::Parts, RequestPartsExt};
-use axum_extra::{
-    headers::{authorization::Bearer, Authorization},
-    TypedHeader,
+use std::{
+    convert::Infallible,
+    marker::PhantomData,
+    net::{IpAddr, SocketAddr},
+    ops::Deref,
+};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts, MatchedPath},
+    http::{header::AUTHORIZATION, request::Parts, Method},
+};
+use kernel::model::{
+    api_key::ApiKeyScope,
+    auth::AccessToken,
+    id::{ApiKeyId, UserId},
+    role::Permission,
+    user::User,
 };
-use kernel::model::{auth::AccessToken, id::UserId, role::Role, user::User};
 use registry::AppRegistry;
 use shared::error::AppError;
 
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// リクエストの認証に用いられた資格情報
+pub enum Credential {
+    AccessToken(AccessToken),
+    ApiKey(ApiKeyId),
+}
+
 pub struct AuthorizedUser {
-    pub access_token: AccessToken,
+    pub credential: Credential,
     pub user: User,
 }
 
@@ -17,8 +38,13 @@ impl AuthorizedUser {
         self.user.id
     }
 
-    pub fn is_admin(&self) -> bool {
-        self.user.role == Role::Admin
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.user.role.has_permission(permission)
+    }
+
+    /// API キーではなく、ログインして得たアクセストークンで認証されているか
+    pub fn is_session(&self) -> bool {
+        matches!(self.credential, Credential::AccessToken(_))
     }
 }
 
@@ -30,24 +56,198 @@ impl FromRequestParts<AppRegistry> for AuthorizedUser {
         parts: &mut Parts,
         registry: &AppRegistry,
     ) -> Result<Self, Self::Rejection> {
-        let TypedHeader(Authorization(bearer)) = parts
-            .extract::<TypedHeader<Authorization<Bearer>>>()
-            .await
-            .map_err(|_| AppError::UnauthorizedError)?;
-        let access_token = AccessToken(bearer.token().to_string());
+        let (scheme, token) = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split_once(' '))
+            .ok_or(AppError::UnauthorizedError)?;
+        let token = token.trim();
 
-        let user_id = registry
-            .auth_repository()
-            .fetch_user_id_from_token(&access_token)
-            .await?
-            .ok_or(AppError::UnauthenticatedError)?;
+        if scheme.eq_ignore_ascii_case("apikey") {
+            let principal = registry
+                .api_key_repository()
+                .authenticate(token)
+                .await?
+                .ok_or(AppError::UnauthenticatedError)?;
+
+            let path = parts
+                .extensions
+                .get::<MatchedPath>()
+                .map(MatchedPath::as_str)
+                .unwrap_or_else(|| parts.uri.path());
+            if !principal.has_scope(required_scope(&parts.method, path)) {
+                return Err(AppError::ForbiddenOperationError);
+            }
+
+            return Ok(Self {
+                credential: Credential::ApiKey(principal.api_key_id),
+                user: principal.user,
+            });
+        }
 
+        if !scheme.eq_ignore_ascii_case("bearer") {
+            return Err(AppError::UnauthorizedError);
+        }
+
+        let access_token = AccessToken(token.to_string());
         let user = registry
-            .user_repository()
-            .find_current_user(user_id)
+            .auth_repository()
+            .fetch_user_from_token(&access_token)
             .await?
             .ok_or(AppError::UnauthenticatedError)?;
 
-        Ok(Self { access_token, user })
+        Ok(Self {
+            credential: Credential::AccessToken(access_token),
+            user,
+        })
+    }
+}
+
+/// ルートに必要な権限を表す型。[`RequirePermission`] の型引数に指定する。
+pub trait RequiredPermission: Send + Sync + 'static {
+    const PERMISSION: Permission;
+}
+
+macro_rules! required_permission {
+    ($($name:ident),* $(,)?) => {
+        $(
+            pub struct $name;
+
+            impl RequiredPermission for $name {
+                const PERMISSION: Permission = Permission::$name;
+            }
+        )*
+    };
+}
+
+/// [`RequirePermission`] の型引数に指定する権限
+pub mod permission {
+    use super::{Permission, RequiredPermission};
+
+    required_permission!(
+        BookWriteAny,
+        CheckoutCreateAny,
+        CheckoutReturnAny,
+        UserManage,
+        RoleManage,
+        AuditRead
+    );
+}
+
+/// `P` の権限を持つユーザーとして認可されたリクエスト。
+/// ハンドラの引数に `RequirePermission<permission::UserManage>` のように宣言すると、
+/// 権限を持たないユーザーのリクエストはハンドラを呼ばずに 403 を返す。
+pub struct RequirePermission<P> {
+    authorized: AuthorizedUser,
+    _permission: PhantomData<P>,
+}
+
+impl<P> Deref for RequirePermission<P> {
+    type Target = AuthorizedUser;
+
+    fn deref(&self) -> &Self::Target {
+        &self.authorized
+    }
+}
+
+#[async_trait]
+impl<P: RequiredPermission> FromRequestParts<AppRegistry> for RequirePermission<P> {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        registry: &AppRegistry,
+    ) -> Result<Self, Self::Rejection> {
+        let authorized = AuthorizedUser::from_request_parts(parts, registry).await?;
+        if !authorized.has_permission(P::PERMISSION) {
+            return Err(AppError::ForbiddenOperationError);
+        }
+
+        Ok(Self {
+            authorized,
+            _permission: PhantomData,
+        })
+    }
+}
+
+/// `ReadBooks` スコープで参照できるリソースのパス。
+const READABLE_PATHS: &[&str] = &[
+    "/api/v1/books",
+    "/api/v1/tags",
+    "/api/v1/collections",
+    "/api/v1/authors",
+    "/api/v1/catalog",
+];
+
+/// API キーでの呼び出しに必要なスコープ。
+/// 蔵書とそれに付随するリソースの参照、貸出の操作以外は、すべての操作を許可する `Admin` スコープを要する。
+fn required_scope(method: &Method, path: &str) -> ApiKeyScope {
+    let under = |prefix: &str| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    };
+
+    if path == "/api/v1/users/me/checkouts"
+        || under("/api/v1/books")
+            && ["/checkouts", "/checkout-history", "/reservations"]
+                .iter()
+                .any(|segment| path.contains(segment))
+    {
+        ApiKeyScope::ManageCheckouts
+    } else if method == Method::GET && READABLE_PATHS.iter().any(|prefix| under(prefix)) {
+        ApiKeyScope::ReadBooks
+    } else {
+        ApiKeyScope::Admin
+    }
+}
+
+/// リクエストに付与された `x-request-id` ヘッダーの値。
+#[derive(Debug)]
+pub struct RequestId(pub Option<String>);
+
+impl RequestId {
+    pub fn into_inner(self) -> Option<String> {
+        self.0
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestId {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let request_id = parts
+            .headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+
+        Ok(Self(request_id))
+    }
+}
+
+/// 接続元の IP アドレス。
+/// `X-Forwarded-For` などのヘッダーはクライアントが自由に偽装できるため参照しない。
+#[derive(Debug)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl ClientIp {
+    pub fn into_inner(self) -> Option<IpAddr> {
+        self.0
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        Ok(Self(ip))
     }
 }
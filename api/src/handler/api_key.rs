@@ -0,0 +1,176 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    api_key::event::RevokeApiKey,
+    audit::{event::CreateAuditLog, AuditAction},
+    id::ApiKeyId,
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::{AuthorizedUser, RequestId},
+    handler::audit::record_audit_log,
+    model::api_key::{
+        ApiKeyResponse, ApiKeyScopeName, ApiKeysResponse, CreateApiKeyRequest,
+        CreateApiKeyRequestWithUserId, IssuedApiKeyResponse,
+    },
+};
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/users/me/api-keys",
+        responses(
+            (status = 200, description = "API キー一覧の取得に成功した場合。", body = ApiKeysResponse),
+            (status = 403, description = "API キーで認証されている場合。"),
+            (status = 500, description = "サーバーサイドエラーが発生した場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn show_api_keys(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<ApiKeysResponse>> {
+    ensure_session(&user)?;
+
+    let items = registry
+        .api_key_repository()
+        .find_all(user.id())
+        .await?
+        .into_iter()
+        .map(ApiKeyResponse::from)
+        .collect();
+
+    Ok(Json(ApiKeysResponse { items }))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/api/v1/users/me/api-keys",
+        request_body = CreateApiKeyRequest,
+        responses(
+            (status = 201, description = "API キーの発行に成功した場合。シークレットはこのレスポンスでのみ返されます。", body = IssuedApiKeyResponse),
+            (status = 400, description = "リクエストの形式に誤りがある場合。"),
+            (status = 403, description = "API キーで認証されている場合や、権限を持たないユーザーが admin スコープを指定した場合。"),
+            (status = 422, description = "有効期限が過去の日時の場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn create_api_key(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateApiKeyRequest>,
+) -> AppResult<(StatusCode, Json<IssuedApiKeyResponse>)> {
+    ensure_session(&user)?;
+    req.validate()?;
+
+    // admin スコープは発行したユーザーの権限をすべて委ねるため、権限を持つユーザーに限る
+    if user.user.role.permissions.is_empty()
+        && req
+            .scopes
+            .iter()
+            .any(|scope| matches!(scope, ApiKeyScopeName::Admin))
+    {
+        return Err(AppError::ForbiddenOperationError);
+    }
+
+    let issued = registry
+        .api_key_repository()
+        .create(CreateApiKeyRequestWithUserId::new(user.id(), req).into())
+        .await
+        .map(IssuedApiKeyResponse::from)?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::ApiKeyCreated,
+            issued.api_key.id.raw(),
+            None,
+            serde_json::to_value(&issued.api_key).ok(),
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(issued)))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        delete,
+        path="/api/v1/users/me/api-keys/{api_key_id}",
+        params(
+            ("api_key_id" = ApiKeyId, Path, description = "失効させる API キーの ID")
+        ),
+        responses(
+            (status = 204, description = "API キーの失効に成功した場合。"),
+            (status = 403, description = "API キーで認証されている場合。"),
+            (status = 404, description = "指定された API キーが存在しない場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn revoke_api_key(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path(api_key_id): Path<ApiKeyId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    ensure_session(&user)?;
+
+    registry
+        .api_key_repository()
+        .revoke(RevokeApiKey::new(user.id(), api_key_id))
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::ApiKeyRevoked,
+            api_key_id.raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 漏えいした API キーから新たなキーを発行されないよう、キーの管理はログインしたユーザーに限る
+fn ensure_session(user: &AuthorizedUser) -> AppResult<()> {
+    if !user.is_session() {
+        return Err(AppError::ForbiddenOperationError);
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use garde::Validate;
+use kernel::model::audit::event::CreateAuditLog;
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use crate::{
+    extractor::{permission::AuditRead, RequirePermission},
+    model::audit::{AuditLogListQuery, PaginatedAuditLogResponse},
+};
+
+/// 変更操作の監査ログを記録する。
+/// 呼び出し時点で操作自体は完了しているため、記録に失敗してもエラーを返さずにログへ残す。
+pub(crate) async fn record_audit_log(registry: &AppRegistry, event: CreateAuditLog) {
+    let action = event.action;
+    if let Err(e) = registry.audit_repository().record(event).await {
+        tracing::error!(
+            error.message = %e,
+            action = action.as_ref(),
+            "Failed to record audit log"
+        );
+    }
+}
+
+// Admin only
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/audit-logs",
+        responses(
+            (status = 200, description = "監査ログの取得に成功した場合。", body = PaginatedAuditLogResponse),
+            (status = 400, description = "指定されたクエリの値に不備があった場合。"),
+            (status = 403, description = "audit:read の権限を持たないユーザーがアクセスした場合。")
+        ),
+        params(
+            ("limit" = i64, Query, description = "一度に取得する監査ログ数の上限値の指定"),
+            ("offset" = i64, Query, description = "取得対象とする監査ログの開始位置"),
+            ("actorId" = Option<Uuid>, Query, description = "操作したユーザーによる絞り込み"),
+            ("targetId" = Option<Uuid>, Query, description = "操作対象の ID による絞り込み"),
+            ("from" = Option<String>, Query, description = "この日時以降（RFC 3339）の操作に絞り込む"),
+            ("to" = Option<String>, Query, description = "この日時より前（RFC 3339）の操作に絞り込む")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn show_audit_logs(
+    user: RequirePermission<AuditRead>,
+    Query(query): Query<AuditLogListQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PaginatedAuditLogResponse>> {
+    query.validate()?;
+
+    registry
+        .audit_repository()
+        .find_all(query.into())
+        .await
+        .map(PaginatedAuditLogResponse::from)
+        .map(Json)
+}
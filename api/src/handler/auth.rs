@@ -1,11 +1,30 @@
-use axum::{extract::State, http::StatusCode, Json};
-use kernel::model::auth::event::CreateToken;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::Redirect,
+    Json,
+};
+use axum_extra::{headers::UserAgent, TypedHeader};
+use garde::Validate;
+use kernel::model::{
+    audit::{event::CreateAuditLog, AuditAction},
+    auth::{event::CreateToken, oidc::OidcCallback, LoginAttempt, RefreshToken},
+    id::UserId,
+};
 use registry::AppRegistry;
-use shared::error::AppResult;
+use shared::error::{AppError, AppResult};
 
 use crate::{
-    extractor::AuthorizedUser,
-    model::auth::{AccessTokenResponse, LoginRequest},
+    extractor::{AuthorizedUser, ClientIp, Credential, RequestId},
+    handler::audit::record_audit_log,
+    model::{
+        auth::{
+            AccessTokenResponse, LoginRequest, LoginResponse, OidcCallbackQuery,
+            PasswordResetConfirmRequest, PasswordResetRequest, RefreshTokenRequest,
+            TwoFactorEnrollChallengeRequest, VerifyTwoFactorRequest,
+        },
+        two_factor::TwoFactorEnrollmentResponse,
+    },
 };
 
 #[cfg_attr(
@@ -15,9 +34,11 @@ use crate::{
         path="/auth/login",
         request_body = LoginRequest,
         responses(
-            (status = 200, description = "ログインに成功した場合。", body = AccessTokenResponse),
+            (status = 200, description = "ログインに成功した場合。2 段階認証が必要なユーザーには、トークンの代わりにチャレンジを返します。", body = LoginResponse),
             (status = 400, description = "リクエストの内容に問題があった場合。"),
-            (status = 403, description = "ログイン認証が通らなかった場合。ユーザーIDないしはパスワードに誤りがある可能性があります。")
+            (status = 403, description = "ログイン認証が通らなかった場合。ユーザーIDないしはパスワードに誤りがある可能性があります。"),
+            (status = 423, description = "ログインの失敗が続いたため、アカウントが一時的にロックされている場合。Retry-After ヘッダーにロックが解除されるまでの秒数を返します。"),
+            (status = 429, description = "ログインの失敗直後で、再試行までの待機時間中の場合。Retry-After ヘッダーに待機が必要な秒数を返します。")
         )
     )
 )]
@@ -28,22 +49,72 @@ use crate::{
     )
 )]
 pub async fn login(
+    request_id: RequestId,
+    client_ip: ClientIp,
+    user_agent: Option<TypedHeader<UserAgent>>,
     State(registry): State<AppRegistry>,
     Json(req): Json<LoginRequest>,
-) -> AppResult<Json<AccessTokenResponse>> {
-    let user_id = registry
+) -> AppResult<Json<LoginResponse>> {
+    let attempt = LoginAttempt::new(req.email.clone(), client_ip.into_inner());
+    let login_attempts = registry.login_attempt_repository();
+    login_attempts.begin(&attempt).await?;
+
+    let user_id = match registry
         .auth_repository()
         .verify_user(&req.email, &req.password)
-        .await?;
-    let access_token = registry
+        .await
+    {
+        Err(AppError::UnauthenticatedError) => {
+            login_attempts.record_failure(&attempt).await?;
+            return Err(AppError::UnauthenticatedError);
+        }
+        result => result?,
+    };
+    login_attempts.record_success(&attempt).await?;
+
+    complete_login(&registry, user_id, user_agent, request_id.into_inner())
+        .await
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/auth/refresh",
+        request_body = RefreshTokenRequest,
+        responses(
+            (status = 200, description = "トークンの再発行に成功した場合。", body = AccessTokenResponse),
+            (status = 400, description = "リクエストの内容に問題があった場合。"),
+            (status = 401, description = "リフレッシュトークンが無効ないしは失効している場合。使用済みのトークンが再利用された場合は、同じログインで発行されたトークンがすべて失効します。")
+        )
+    )
+)]
+#[tracing::instrument(skip(req, registry))]
+pub async fn refresh(
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<RefreshTokenRequest>,
+) -> AppResult<Json<AccessTokenResponse>> {
+    let token = registry
         .auth_repository()
-        .create_token(CreateToken::new(user_id))
+        .refresh_token(RefreshToken(req.refresh_token))
         .await?;
 
-    Ok(Json(AccessTokenResponse {
-        user_id,
-        access_token: access_token.0,
-    }))
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            token.user_id,
+            AuditAction::TokenRefreshed,
+            token.user_id.raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(Json(token.into()))
 }
 
 #[cfg_attr(
@@ -53,6 +124,7 @@ pub async fn login(
         path="/auth/logout",
         responses(
             (status = 204, description = "ログアウトに成功した場合。"),
+            (status = 403, description = "API キーで認証されている場合。"),
         )
     )
 )]
@@ -65,12 +137,310 @@ pub async fn login(
 )]
 pub async fn logout(
     user: AuthorizedUser,
+    request_id: RequestId,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
+    let user_id = user.id();
+    // API キーはログアウトではなく、失効させることで無効にする
+    let Credential::AccessToken(access_token) = user.credential else {
+        return Err(AppError::ForbiddenOperationError);
+    };
     registry
         .auth_repository()
-        .delete_token(user.access_token)
+        .delete_token(access_token)
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user_id,
+            AuditAction::LoggedOut,
+            user_id.raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/auth/oidc/authorize",
+        responses(
+            (status = 303, description = "IdP の認可エンドポイントへリダイレクトする。"),
+            (status = 404, description = "OIDC によるログインが設定されていない場合。"),
+            (status = 502, description = "IdP の設定の取得に失敗した場合。")
+        )
+    )
+)]
+#[tracing::instrument(skip(registry))]
+pub async fn oidc_authorize(State(registry): State<AppRegistry>) -> AppResult<Redirect> {
+    let request = registry
+        .oidc_repository()
+        .create_authorization_request()
+        .await?;
+
+    Ok(Redirect::to(&request.authorization_url))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/auth/oidc/callback",
+        params(
+            ("code" = String, Query, description = "IdP が発行した認可コード"),
+            ("state" = String, Query, description = "認可リクエスト時に発行した state")
+        ),
+        responses(
+            (status = 200, description = "ログインに成功した場合。2 段階認証が必要なユーザーには、トークンの代わりにチャレンジを返します。", body = LoginResponse),
+            (status = 403, description = "state が無効な場合や、IdP のアカウントに紐付くユーザーがいない場合。"),
+            (status = 404, description = "OIDC によるログインが設定されていない場合。"),
+            (status = 502, description = "IdP とのやりとりに失敗した場合。")
+        )
+    )
+)]
+#[tracing::instrument(skip(query, registry))]
+pub async fn oidc_callback(
+    request_id: RequestId,
+    user_agent: Option<TypedHeader<UserAgent>>,
+    State(registry): State<AppRegistry>,
+    Query(query): Query<OidcCallbackQuery>,
+) -> AppResult<Json<LoginResponse>> {
+    let login = registry
+        .oidc_repository()
+        .callback(OidcCallback::new(query.code, query.state))
+        .await?;
+    let user_id = login.user_id;
+
+    let request_id = request_id.into_inner();
+    if login.provisioned {
+        record_audit_log(
+            &registry,
+            CreateAuditLog::new(
+                user_id,
+                AuditAction::UserCreated,
+                user_id.raw(),
+                None,
+                None,
+                request_id.clone(),
+            ),
+        )
+        .await;
+    }
+
+    complete_login(&registry, user_id, user_agent, request_id)
+        .await
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/auth/password-reset/request",
+        request_body = PasswordResetRequest,
+        responses(
+            (status = 202, description = "再設定用のトークンの送信を受け付けた場合。登録の有無が分からないよう、該当するユーザーがいない場合も同じ応答を返します。"),
+            (status = 400, description = "リクエストの内容に問題があった場合。")
+        )
+    )
+)]
+#[tracing::instrument(skip(req, registry))]
+pub async fn request_password_reset(
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<PasswordResetRequest>,
+) -> AppResult<StatusCode> {
+    req.validate()?;
+
+    // 該当するユーザーがいない場合も応答は変えず、監査ログにのみ差が出る
+    if let Some(user_id) = registry
+        .password_reset_repository()
+        .request(req.into())
+        .await?
+    {
+        record_audit_log(
+            &registry,
+            CreateAuditLog::new(
+                user_id,
+                AuditAction::PasswordResetRequested,
+                user_id.raw(),
+                None,
+                None,
+                request_id.into_inner(),
+            ),
+        )
+        .await;
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/auth/password-reset/confirm",
+        request_body = PasswordResetConfirmRequest,
+        responses(
+            (status = 204, description = "パスワードの再設定に成功した場合。発行済みのトークンはすべて失効します。"),
+            (status = 400, description = "リクエストの内容に問題があった場合。"),
+            (status = 401, description = "トークンが無効、使用済み、または期限切れの場合。")
+        )
+    )
+)]
+#[tracing::instrument(skip(req, registry))]
+pub async fn confirm_password_reset(
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<PasswordResetConfirmRequest>,
+) -> AppResult<StatusCode> {
+    req.validate()?;
+
+    let user_id = registry
+        .password_reset_repository()
+        .confirm(req.into())
         .await?;
 
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user_id,
+            AuditAction::PasswordReset,
+            user_id.raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/auth/2fa/enroll",
+        request_body = TwoFactorEnrollChallengeRequest,
+        responses(
+            (status = 200, description = "ロールで 2 段階認証が必須とされているユーザーが、ログインの途中で認証アプリを登録する場合。", body = TwoFactorEnrollmentResponse),
+            (status = 401, description = "チャレンジが無効または期限切れの場合。"),
+            (status = 422, description = "2 段階認証が既に有効な場合。")
+        )
+    )
+)]
+#[tracing::instrument(skip(req, registry))]
+pub async fn enroll_two_factor_challenge(
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<TwoFactorEnrollChallengeRequest>,
+) -> AppResult<Json<TwoFactorEnrollmentResponse>> {
+    let (user_id, enrollment) = registry
+        .two_factor_repository()
+        .enroll_with_challenge(&req.challenge_token)
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user_id,
+            AuditAction::TwoFactorEnrolled,
+            user_id.raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(Json(enrollment.into()))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/auth/2fa/verify",
+        request_body = VerifyTwoFactorRequest,
+        responses(
+            (status = 200, description = "コードの確認に成功した場合。", body = AccessTokenResponse),
+            (status = 401, description = "チャレンジが無効、期限切れ、またはコードを誤った回数が上限に達した場合。パスワードの入力からやり直す必要があります。"),
+            (status = 403, description = "コードが誤っている場合。")
+        )
+    )
+)]
+#[tracing::instrument(skip(req, registry))]
+pub async fn verify_two_factor(
+    request_id: RequestId,
+    user_agent: Option<TypedHeader<UserAgent>>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<VerifyTwoFactorRequest>,
+) -> AppResult<Json<AccessTokenResponse>> {
+    let user_id = registry
+        .two_factor_repository()
+        .verify_challenge(req.into())
+        .await?;
+
+    issue_token(&registry, user_id, user_agent, request_id.into_inner())
+        .await
+        .map(Json)
+}
+
+/// 本人確認を終えたユーザーのログインを完了させる。
+/// 2 段階認証が必要な場合はトークンを発行せず、チャレンジを返す。
+async fn complete_login(
+    registry: &AppRegistry,
+    user_id: UserId,
+    user_agent: Option<TypedHeader<UserAgent>>,
+    request_id: Option<String>,
+) -> AppResult<LoginResponse> {
+    if let Some(challenge) = registry
+        .two_factor_repository()
+        .create_challenge(user_id)
+        .await?
+    {
+        return Ok(LoginResponse::TwoFactorRequired(challenge.into()));
+    }
+
+    issue_token(registry, user_id, user_agent, request_id)
+        .await
+        .map(LoginResponse::Authenticated)
+}
+
+async fn issue_token(
+    registry: &AppRegistry,
+    user_id: UserId,
+    user_agent: Option<TypedHeader<UserAgent>>,
+    request_id: Option<String>,
+) -> AppResult<AccessTokenResponse> {
+    let token = registry
+        .auth_repository()
+        .create_token(CreateToken::new(
+            user_id,
+            user_agent.map(|TypedHeader(ua)| ua.to_string()),
+        ))
+        .await?;
+
+    record_audit_log(
+        registry,
+        CreateAuditLog::new(
+            user_id,
+            AuditAction::LoggedIn,
+            user_id.raw(),
+            None,
+            None,
+            request_id,
+        ),
+    )
+    .await;
+
+    Ok(token.into())
+}
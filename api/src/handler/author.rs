@@ -0,0 +1,66 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use garde::Validate;
+use kernel::model::{book::BookListOptions, id::AuthorId};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{
+        author::AuthorResponse,
+        book::{BookListQuery, PaginatedBookResponse},
+    },
+};
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/authors/{author_id}",
+        params(
+            ("author_id" = AuthorId, Path, description = "著者 ID"),
+            ("limit" = i64, Query, description = "一度に取得する蔵書数の上限値の指定"),
+            ("offset" = i64, Query, description = "取得対象とする蔵書一覧の開始位置")
+        ),
+        responses(
+            (status = 200, description = "著者と、その著者が関わった蔵書の一覧の取得に成功した場合。", body = AuthorResponse),
+            (status = 400, description = "指定されたクエリの値に不備があった場合。"),
+            (status = 404, description = "指定された著者が見つからなかった場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(_user, registry),
+    fields(
+        user_id = %_user.user.id.to_string()
+    )
+)]
+pub async fn show_author(
+    _user: AuthorizedUser,
+    Path(author_id): Path<AuthorId>,
+    Query(query): Query<BookListQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<AuthorResponse>> {
+    query.validate()?;
+
+    let author = registry
+        .author_repository()
+        .find_by_id(author_id)
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("Specified author not found".into()))?;
+
+    let options = BookListOptions {
+        author_id: Some(author_id),
+        ..query.into()
+    };
+
+    registry
+        .book_repository()
+        .find_all(options)
+        .await
+        .map(PaginatedBookResponse::from)
+        .map(|books| Json(AuthorResponse::new(author, books)))
+}
@@ -1,16 +1,34 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use garde::Validate;
-use kernel::model::{book::event::DeleteBook, id::BookId};
+use kernel::model::{
+    audit::{event::CreateAuditLog, AuditAction},
+    book::{
+        event::{
+            CreateBookCopy, DeleteBook, DeleteBookCopy, UpdateBook, UpdateBookCopy, UpdateBookCover,
+        },
+        BookListOptions, CoverVariant,
+    },
+    id::{BookCopyId, BookId},
+    role::Permission,
+};
 
 use crate::{
-    extractor::AuthorizedUser,
-    model::book::{
-        BookListQuery, BookResponse, CreateBookRequest, PaginatedBookResponse, UpdateBookRequest,
-        UpdateBookRequestWithIds,
+    extractor::{AuthorizedUser, RequestId},
+    handler::{audit::record_audit_log, catalog::fill_from_catalog},
+    model::{
+        book::{
+            BookCopyCreatedResponse, BookCopyRequest, BookCopyResponse, BookListQuery,
+            BookResponse, CreateBookCopyRequestWithIds, CreateBookRequest, PaginatedBookResponse,
+            RegisterBookQuery, UpdateBookCopyRequestWithIds, UpdateBookRequest,
+            UpdateBookRequestWithIds,
+        },
+        book_transfer::BookFileFormat,
+        cover::{CoverUpload, MAX_COVER_SIZE},
     },
 };
 use registry::AppRegistry;
@@ -26,7 +44,12 @@ use shared::error::{AppError, AppResult};
             (status = 201, description = "蔵書の登録に成功した場合"),
             (status = 400, description = "リクエストのパラメータに不備があった場合"),
             (status = 401, description = "認証されていないユーザがアクセスした場合"),
-            (status = 422, description = "リクエストした蔵書の登録に失敗した場合")
+            (status = 409, description = "同じ ISBN の蔵書を既に登録していた場合"),
+            (status = 422, description = "リクエストした蔵書の登録に失敗した場合"),
+            (status = 502, description = "書誌情報の問い合わせ先でエラーが発生した場合")
+        ),
+        params(
+            ("autofill" = Option<bool>, Query, description = "true の場合、ISBN から引いた書誌情報で空欄の項目を埋めてから登録する")
         )
     )
 )]
@@ -38,16 +61,36 @@ use shared::error::{AppError, AppResult};
 )]
 pub async fn register_book(
     user: AuthorizedUser,
+    request_id: RequestId,
+    Query(query): Query<RegisterBookQuery>,
     State(registry): State<AppRegistry>,
-    Json(req): Json<CreateBookRequest>,
+    Json(mut req): Json<CreateBookRequest>,
 ) -> AppResult<StatusCode> {
+    if query.autofill {
+        fill_from_catalog(&registry, &mut req).await?;
+    }
     req.validate()?;
 
-    registry
+    let after = serde_json::to_value(&req).ok();
+    let book_id = registry
         .book_repository()
         .create(req.into(), user.id())
-        .await
-        .map(|_| StatusCode::CREATED)
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::BookCreated,
+            book_id.raw(),
+            None,
+            after,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::CREATED)
 }
 
 #[cfg_attr(
@@ -56,35 +99,74 @@ pub async fn register_book(
         get,
         path = "/api/v1/books",
         responses(
-            (status = 200, description = "蔵書一覧の取得に成功した場合", body = PaginatedBookResponse),
+            (status = 200, description = "蔵書一覧の取得に成功した場合。`Accept` ヘッダーで MARC21・MARCXML・Dublin Core などの形式を指定できる", content(
+                ("application/json" = PaginatedBookResponse),
+                ("application/marc" = Vec<u8>),
+                ("application/marcxml+xml" = String),
+                ("application/dc+xml" = String)
+            )),
             (status = 400, description = "指定されたクエリの値に不備があった場合"),
-            (status = 401, description = "認証されていないユーザがアクセスした場合")
+            (status = 401, description = "認証されていないユーザがアクセスした場合"),
+            (status = 404, description = "指定されたコレクションが見つからなかった場合")
         ),
         params(
             ("limit" = i64, Query, description = "一度に取得する蔵書数の上限値の指定"),
-            ("offset" = i64, Query, description = "取得対象とする蔵書一覧の開始位置")
+            ("offset" = i64, Query, description = "取得対象とする蔵書一覧の開始位置"),
+            ("q" = Option<String>, Query, description = "タイトル・著者・説明文を対象としたフリーテキスト検索"),
+            ("ownerId" = Option<Uuid>, Query, description = "蔵書の所有者による絞り込み"),
+            ("status" = Option<BookCheckoutStatus>, Query, description = "貸出状況による絞り込み"),
+            ("isbnPrefix" = Option<String>, Query, description = "ISBN の前方一致による絞り込み"),
+            ("tagId" = Option<Uuid>, Query, description = "タグによる絞り込み"),
+            ("collectionId" = Option<Uuid>, Query, description = "コレクションによる絞り込み。参照できないコレクションを指定した場合は 404 を返す"),
+            ("authorId" = Option<Uuid>, Query, description = "著者・編者・訳者などとして関わった人物による絞り込み")
         )
     )
 )]
 #[tracing::instrument(
-    skip(_user, registry),
+    skip(user, registry),
     fields(
-        user_id = %_user.user.id.to_string()
+        user_id = %user.user.id.to_string()
     )
 )]
 pub async fn show_book_list(
-    _user: AuthorizedUser,
+    user: AuthorizedUser,
+    headers: HeaderMap,
     Query(query): Query<BookListQuery>,
     State(registry): State<AppRegistry>,
-) -> AppResult<Json<PaginatedBookResponse>> {
+) -> AppResult<Response> {
     query.validate()?;
 
+    let options: BookListOptions = query.into();
+    // 共有されていない他のユーザーのコレクションの中身は見せない
+    if let Some(collection_id) = options.collection_id {
+        registry
+            .collection_repository()
+            .find_by_id(collection_id, user.id())
+            .await?
+            .ok_or_else(|| AppError::EntityNotFound("Specified collection not found".into()))?;
+    }
+
+    if let Some(format) = BookFileFormat::from_accept(&headers) {
+        let books = registry.book_repository().find_all(options).await?;
+        let mut data = format.encode_header()?;
+        data.extend(format.encode_books(&books.items)?);
+        data.extend(format.encode_footer());
+        return Ok(([(header::CONTENT_TYPE, format.content_type())], data).into_response());
+    }
+
+    // 絞り込み条件が指定された場合のみファセットを集計する
+    let facets = if options.is_search() {
+        Some(registry.book_repository().find_facets(&options).await?)
+    } else {
+        None
+    };
+
     registry
         .book_repository()
-        .find_all(query.into())
+        .find_all(options)
         .await
         .map(PaginatedBookResponse::from)
-        .map(Json)
+        .map(|res| Json(res.with_facets(facets)).into_response())
 }
 
 #[cfg_attr(
@@ -93,7 +175,12 @@ pub async fn show_book_list(
         get,
         path="/api/v1/books/{book_id}",
         responses(
-            (status = 200, description = "蔵書の更新に成功した場合。"),
+            (status = 200, description = "蔵書の取得に成功した場合。`Accept` ヘッダーで MARC21・MARCXML・Dublin Core などの形式を指定できる。", content(
+                ("application/json" = BookResponse),
+                ("application/marc" = Vec<u8>),
+                ("application/marcxml+xml" = String),
+                ("application/dc+xml" = String)
+            )),
             (status = 404, description = "対象の書籍が見つからなかった場合。")
         ),
         params(
@@ -109,17 +196,24 @@ pub async fn show_book_list(
 )]
 pub async fn show_book(
     _user: AuthorizedUser,
+    headers: HeaderMap,
     State(registry): State<AppRegistry>,
     Path(book_id): Path<BookId>,
-) -> AppResult<Json<BookResponse>> {
-    registry
+) -> AppResult<Response> {
+    let book = registry
         .book_repository()
         .find_by_id(book_id)
-        .await
-        .and_then(|bc| match bc {
-            Some(bc) => Ok(Json(BookResponse::from(bc))),
-            None => Err(AppError::EntityNotFound("not found".to_string())),
-        })
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("not found".to_string()))?;
+
+    match BookFileFormat::from_accept(&headers) {
+        Some(format) => Ok((
+            [(header::CONTENT_TYPE, format.content_type())],
+            format.encode_book(&book)?,
+        )
+            .into_response()),
+        None => Ok(Json(BookResponse::from(book)).into_response()),
+    }
 }
 
 #[cfg_attr(
@@ -131,7 +225,8 @@ pub async fn show_book(
         responses(
             (status = 200, description = "蔵書の更新に成功した場合。"),
             (status = 400, description = "リクエストのパラメータに不備があった場合。"),
-            (status = 404, description = "変更対象の書籍が見つからなかった場合。")
+            (status = 404, description = "変更対象の書籍が見つからなかった場合。"),
+            (status = 409, description = "同じ ISBN の蔵書を既に登録していた場合。")
         ),
         params(
             ("book_id" = Uuid, Path, description = "蔵書ID")
@@ -146,19 +241,36 @@ pub async fn show_book(
 )]
 pub async fn update_book(
     user: AuthorizedUser,
+    request_id: RequestId,
     Path(book_id): Path<BookId>,
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateBookRequest>,
 ) -> AppResult<StatusCode> {
     req.validate()?;
 
-    let update_book = UpdateBookRequestWithIds::new(book_id, user.id(), req);
+    let before = find_book_snapshot(&registry, book_id).await?;
+    let after = serde_json::to_value(&req).ok();
+    let update_book = UpdateBook {
+        any_owner: user.has_permission(Permission::BookWriteAny),
+        ..UpdateBookRequestWithIds::new(book_id, user.id(), req).into()
+    };
 
-    registry
-        .book_repository()
-        .update(update_book.into())
-        .await
-        .map(|_| StatusCode::OK)
+    registry.book_repository().update(update_book).await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::BookUpdated,
+            book_id.raw(),
+            before,
+            after,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::OK)
 }
 
 #[cfg_attr(
@@ -182,17 +294,386 @@ pub async fn update_book(
 )]
 pub async fn delete_book(
     user: AuthorizedUser,
+    request_id: RequestId,
     Path(book_id): Path<BookId>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
+    let before = find_book_snapshot(&registry, book_id).await?;
     let delete_book = DeleteBook {
         book_id,
         requested_user: user.id(),
+        any_owner: user.has_permission(Permission::BookWriteAny),
     };
 
+    registry.book_repository().delete(delete_book).await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::BookDeleted,
+            book_id.raw(),
+            before,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/api/v1/books/{book_id}/copies",
+        request_body = BookCopyRequest,
+        responses(
+            (status = 201, description = "複本の登録に成功した場合。", body = BookCopyCreatedResponse),
+            (status = 400, description = "リクエストのパラメータに不備があった場合。"),
+            (status = 404, description = "対象の書籍が見つからなかった場合。"),
+            (status = 422, description = "バーコードが既に登録されている場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn register_book_copy(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<BookCopyRequest>,
+) -> AppResult<(StatusCode, Json<BookCopyCreatedResponse>)> {
+    req.validate()?;
+
+    let after = serde_json::to_value(&req).ok();
+    let create_copy = CreateBookCopy {
+        any_owner: user.has_permission(Permission::BookWriteAny),
+        ..CreateBookCopyRequestWithIds::new(book_id, user.id(), req).into()
+    };
+
+    let copy_id = registry.book_repository().create_copy(create_copy).await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::BookCopyCreated,
+            copy_id.raw(),
+            None,
+            after,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(BookCopyCreatedResponse { id: copy_id }),
+    ))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        put,
+        path="/api/v1/books/{book_id}/copies/{copy_id}",
+        request_body = BookCopyRequest,
+        responses(
+            (status = 200, description = "複本の更新に成功した場合。"),
+            (status = 400, description = "リクエストのパラメータに不備があった場合。"),
+            (status = 404, description = "対象の書籍または複本が見つからなかった場合。"),
+            (status = 422, description = "バーコードが既に登録されている場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID"),
+            ("copy_id" = Uuid, Path, description = "複本ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn update_book_copy(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path((book_id, copy_id)): Path<(BookId, BookCopyId)>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<BookCopyRequest>,
+) -> AppResult<StatusCode> {
+    req.validate()?;
+
+    let before = find_copy_snapshot(&registry, book_id, copy_id).await?;
+    let after = serde_json::to_value(&req).ok();
+    let update_copy = UpdateBookCopy {
+        any_owner: user.has_permission(Permission::BookWriteAny),
+        ..UpdateBookCopyRequestWithIds::new(book_id, copy_id, user.id(), req).into()
+    };
+
+    registry.book_repository().update_copy(update_copy).await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::BookCopyUpdated,
+            copy_id.raw(),
+            before,
+            after,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        delete,
+        path="/api/v1/books/{book_id}/copies/{copy_id}",
+        responses(
+            (status = 204, description = "複本の削除に成功した場合。"),
+            (status = 404, description = "対象の書籍または複本が見つからなかった場合。"),
+            (status = 422, description = "複本が貸出中の場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID"),
+            ("copy_id" = Uuid, Path, description = "複本ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn delete_book_copy(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path((book_id, copy_id)): Path<(BookId, BookCopyId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let before = find_copy_snapshot(&registry, book_id, copy_id).await?;
+    let delete_copy = DeleteBookCopy::new(
+        book_id,
+        copy_id,
+        user.id(),
+        user.has_permission(Permission::BookWriteAny),
+    );
+
+    registry.book_repository().delete_copy(delete_copy).await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::BookCopyDeleted,
+            copy_id.raw(),
+            before,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        put,
+        path="/api/v1/books/{book_id}/cover",
+        request_body(content = Vec<u8>, content_type = "multipart/form-data", description = "`file` フィールドに PNG または JPEG の画像を指定する"),
+        responses(
+            (status = 200, description = "表紙画像の登録に成功した場合。"),
+            (status = 404, description = "対象の書籍が見つからなかった場合。"),
+            (status = 413, description = "画像のサイズが上限を超えていた場合。"),
+            (status = 422, description = "PNG または JPEG の画像ではなかった場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, multipart),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn upload_book_cover(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    mut multipart: Multipart,
+) -> AppResult<StatusCode> {
+    let mut data = None;
+    while let Some(field) = multipart.next_field().await.map_err(map_multipart_error)? {
+        if field.name() == Some("file") {
+            data = Some(field.bytes().await.map_err(map_multipart_error)?);
+            break;
+        }
+    }
+    let data = data.ok_or_else(|| {
+        AppError::UnprocessableEntity("Multipart field `file` is required".into())
+    })?;
+
+    let cover = tokio::task::spawn_blocking(move || CoverUpload::from_bytes(data.to_vec()))
+        .await
+        .map_err(|e| {
+            AppError::UnprocessableEntity(format!("Failed to process cover image: {e}"))
+        })??;
+    let after = serde_json::json!({
+        "contentType": cover.content_type,
+        "size": cover.image.len(),
+    });
+
     registry
         .book_repository()
-        .delete(delete_book)
-        .await
-        .map(|_| StatusCode::NO_CONTENT)
+        .update_cover(UpdateBookCover::new(
+            book_id,
+            cover.content_type,
+            cover.image,
+            cover.thumbnail,
+            user.id(),
+            user.has_permission(Permission::BookWriteAny),
+        ))
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::BookCoverUpdated,
+            book_id.raw(),
+            None,
+            Some(after),
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/books/{book_id}/cover",
+        responses(
+            (status = 200, description = "表紙画像の取得に成功した場合。", body = Vec<u8>, content_type = "image/*"),
+            (status = 404, description = "書籍または表紙画像が見つからなかった場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(_user, registry),
+    fields(
+        user_id = %_user.user.id.to_string()
+    )
+)]
+pub async fn show_book_cover(
+    _user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<impl IntoResponse> {
+    find_cover(&registry, book_id, CoverVariant::Original).await
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/books/{book_id}/cover/thumbnail",
+        responses(
+            (status = 200, description = "縮小した表紙画像の取得に成功した場合。", body = Vec<u8>, content_type = "image/*"),
+            (status = 404, description = "書籍または表紙画像が見つからなかった場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(_user, registry),
+    fields(
+        user_id = %_user.user.id.to_string()
+    )
+)]
+pub async fn show_book_cover_thumbnail(
+    _user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<impl IntoResponse> {
+    find_cover(&registry, book_id, CoverVariant::Thumbnail).await
+}
+
+async fn find_cover(
+    registry: &AppRegistry,
+    book_id: BookId,
+    variant: CoverVariant,
+) -> AppResult<impl IntoResponse> {
+    let cover = registry
+        .book_repository()
+        .find_cover(book_id, variant)
+        .await?
+        .ok_or_else(|| AppError::EntityNotFound("Cover image not found".into()))?;
+
+    Ok(([(header::CONTENT_TYPE, cover.content_type)], cover.data))
+}
+
+/// 上限を超えたボディの読み込みで失敗した場合は、サイズ超過として扱う。
+fn map_multipart_error(e: axum::extract::multipart::MultipartError) -> AppError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AppError::PayloadTooLargeError(MAX_COVER_SIZE)
+    } else {
+        AppError::UnprocessableEntity(e.body_text())
+    }
+}
+
+/// 監査ログに変更前の状態として残すため、蔵書の現在の内容を取得する。
+async fn find_book_snapshot(
+    registry: &AppRegistry,
+    book_id: BookId,
+) -> AppResult<Option<serde_json::Value>> {
+    let book = registry.book_repository().find_by_id(book_id).await?;
+
+    Ok(book
+        .map(BookResponse::from)
+        .and_then(|book| serde_json::to_value(book).ok()))
+}
+
+/// 監査ログに変更前の状態として残すため、複本の現在の内容を取得する。
+async fn find_copy_snapshot(
+    registry: &AppRegistry,
+    book_id: BookId,
+    copy_id: BookCopyId,
+) -> AppResult<Option<serde_json::Value>> {
+    let book = registry.book_repository().find_by_id(book_id).await?;
+
+    Ok(book
+        .and_then(|book| book.copies.into_iter().find(|copy| copy.id == copy_id))
+        .map(BookCopyResponse::from)
+        .and_then(|copy| serde_json::to_value(copy).ok()))
 }
@@ -0,0 +1,229 @@
+use std::{collections::HashSet, io::BufReader};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Query, State},
+    http::header,
+    response::IntoResponse,
+    Json,
+};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use garde::Validate;
+use kernel::model::{
+    audit::{event::CreateAuditLog, AuditAction},
+    book::{isbn::normalize_isbn, BookCursor, BookListOptions},
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+use tokio::sync::mpsc;
+use tokio_util::io::{StreamReader, SyncIoBridge};
+
+use crate::{
+    extractor::{AuthorizedUser, RequestId},
+    handler::audit::record_audit_log,
+    model::book_transfer::{
+        BookExportQuery, BookFileFormat, BookImportMode, BookImportQuery, BookImportResponse,
+        BookImportRowError, ParsedBookRow,
+    },
+};
+
+/// エクスポート時に 1 度に読み込む蔵書の数
+const EXPORT_PAGE_SIZE: i64 = 100;
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/api/v1/books/import",
+        request_body(content = String, content_type = "text/csv", description = "CSV の場合は `title,author,isbn,description` をヘッダーとする。JSON Lines の場合は 1 行に蔵書の登録リクエストを 1 件ずつ記述する。MARC21・MARCXML・Dublin Core の場合は書名・著者・ISBN・解題を取り込む"),
+        responses(
+            (status = 200, description = "すべての行を処理した場合。エラーとなった行は `errors` に含まれる。", body = BookImportResponse),
+            (status = 400, description = "クエリのパラメータに不備があった場合。")
+        ),
+        params(
+            ("format" = BookFileFormat, Query, description = "ファイルの形式"),
+            ("mode" = Option<BookImportMode>, Query, description = "指定しない場合はドライランとなる")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, body),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn import_books(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Query(query): Query<BookImportQuery>,
+    State(registry): State<AppRegistry>,
+    body: Body,
+) -> AppResult<Json<BookImportResponse>> {
+    let request_id = request_id.into_inner();
+    let mut rows = spawn_row_parser(body, query.format);
+    let mut report = BookImportResponse::new(query.mode);
+    let mut seen_isbns = HashSet::new();
+
+    while let Some(parsed) = rows.recv().await {
+        report.total += 1;
+        let row = report.total;
+        let mut reject = |isbn: Option<String>, message: String| {
+            report
+                .errors
+                .push(BookImportRowError { row, isbn, message })
+        };
+
+        let req = match parsed {
+            Ok(req) => req,
+            Err(message) => {
+                reject(None, message);
+                continue;
+            }
+        };
+        if let Err(e) = req.validate() {
+            reject(Some(req.isbn), e.to_string().trim().to_string());
+            continue;
+        }
+        // 同じファイル内での重複は、登録済みの蔵書との重複と同様に扱う
+        let isbn = normalize_isbn(&req.isbn).unwrap_or_else(|| req.isbn.clone());
+        if !seen_isbns.insert(isbn.clone()) {
+            reject(Some(isbn), "Duplicate ISBN in the imported file".into());
+            continue;
+        }
+
+        if query.mode == BookImportMode::DryRun {
+            // 登録済みの蔵書との重複も、実際に取り込む場合と同じくエラーとして報告する
+            if registry
+                .book_repository()
+                .exists_by_isbn(user.id(), &isbn)
+                .await?
+            {
+                reject(
+                    Some(isbn.clone()),
+                    format!("Book with ISBN {isbn} already registered"),
+                );
+            } else {
+                report.imported += 1;
+            }
+            continue;
+        }
+
+        let after = serde_json::to_value(&req).ok();
+        match registry
+            .book_repository()
+            .create(req.into(), user.id())
+            .await
+        {
+            Ok(book_id) => {
+                report.imported += 1;
+                record_audit_log(
+                    &registry,
+                    CreateAuditLog::new(
+                        user.id(),
+                        AuditAction::BookCreated,
+                        book_id.raw(),
+                        None,
+                        after,
+                        request_id.clone(),
+                    ),
+                )
+                .await;
+            }
+            Err(AppError::ConflictError(message) | AppError::UnprocessableEntity(message)) => {
+                reject(Some(isbn), message);
+            }
+            // 行の内容によらないエラーでは、以降の行も登録できないため中断する
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(Json(report))
+}
+
+/// パーサーは同期的に動くため、別のスレッドで動かしてチャネル経由で受け渡す。
+fn spawn_row_parser(body: Body, format: BookFileFormat) -> mpsc::Receiver<ParsedBookRow> {
+    let (tx, rx) = mpsc::channel(64);
+    let stream = body.into_data_stream().map_err(std::io::Error::other);
+    let reader = SyncIoBridge::new(StreamReader::new(stream));
+
+    tokio::task::spawn_blocking(move || {
+        for row in format.read_books(BufReader::new(reader)) {
+            if tx.blocking_send(row).is_err() {
+                break;
+            }
+        }
+    });
+
+    rx
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/books/export",
+        responses(
+            (status = 200, description = "蔵書の一覧を、所有者と貸出の状況を含めて出力する。", body = String, content_type = "text/csv"),
+            (status = 400, description = "クエリのパラメータに不備があった場合。")
+        ),
+        params(
+            ("format" = BookFileFormat, Query, description = "ファイルの形式")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn export_books(
+    user: AuthorizedUser,
+    Query(query): Query<BookExportQuery>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<impl IntoResponse> {
+    let format = query.format;
+
+    // 一覧を一度に読み込まないよう、1 ページずつ取得しながら書き出す。
+    // 書き出している間に蔵書が追加・削除されても行がずれないよう、直前のページの末尾の蔵書を起点に取得する
+    let pages = stream::try_unfold(Some(None), move |cursor: Option<Option<BookCursor>>| {
+        let registry = registry.clone();
+        async move {
+            let Some(after) = cursor else {
+                return Ok(None);
+            };
+
+            let page = registry
+                .book_repository()
+                .find_all(BookListOptions {
+                    limit: EXPORT_PAGE_SIZE,
+                    after,
+                    ..Default::default()
+                })
+                .await?;
+            let next = (page.items.len() as i64 == EXPORT_PAGE_SIZE)
+                .then(|| page.items.last().map(BookCursor::from));
+            let mut chunk = if after.is_none() {
+                format.encode_header()?
+            } else {
+                Vec::new()
+            };
+            chunk.extend(format.encode_books(&page.items)?);
+
+            Ok::<_, AppError>(Some((Bytes::from(chunk), next)))
+        }
+    })
+    .chain(stream::once(async move {
+        Ok(Bytes::from_static(format.encode_footer()))
+    }));
+
+    let filename = format!("attachment; filename=\"books.{}\"", format.extension());
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, filename),
+        ],
+        Body::from_stream(pages),
+    ))
+}
@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use garde::Validate;
+use kernel::model::book::isbn::normalize_isbn;
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::AuthorizedUser,
+    model::{book::CreateBookRequest, catalog::CatalogIsbnPath},
+};
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/catalog/isbn/{isbn}",
+        responses(
+            (status = 200, description = "書誌情報の取得に成功した場合。蔵書の登録リクエストとしてそのまま使える。", body = CreateBookRequest),
+            (status = 400, description = "ISBN が不正だった場合。"),
+            (status = 404, description = "書誌情報が見つからなかった場合。"),
+            (status = 502, description = "書誌情報の問い合わせ先でエラーが発生した場合。")
+        ),
+        params(
+            ("isbn" = String, Path, description = "ISBN-10 または ISBN-13。ハイフンを含んでもよい")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn show_catalog_entry(
+    user: AuthorizedUser,
+    Path(path): Path<CatalogIsbnPath>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CreateBookRequest>> {
+    path.validate()?;
+
+    let isbn = normalize_isbn(&path.isbn).unwrap_or(path.isbn);
+    registry
+        .catalog_lookup()
+        .find_by_isbn(&isbn)
+        .await?
+        .map(CreateBookRequest::from)
+        .map(Json)
+        .ok_or_else(|| AppError::EntityNotFound("Catalog entry not found".into()))
+}
+
+/// 登録リクエストの空欄の項目を、ISBN から引いた書誌情報で埋める。
+/// ISBN が不正な場合は何もせず、後続の検証で弾く。
+pub(crate) async fn fill_from_catalog(
+    registry: &AppRegistry,
+    req: &mut CreateBookRequest,
+) -> AppResult<()> {
+    let Some(isbn) = normalize_isbn(&req.isbn) else {
+        return Ok(());
+    };
+
+    if let Some(entry) = registry.catalog_lookup().find_by_isbn(&isbn).await? {
+        req.fill_missing(entry);
+    }
+
+    Ok(())
+}
@@ -1,16 +1,24 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
+use chrono::Duration;
+use garde::Validate;
 use kernel::model::{
-    checkout::event::{CreateCheckout, UpdateReturned},
+    audit::{event::CreateAuditLog, AuditAction},
+    checkout::event::{CreateCheckout, UpdateRenewed, UpdateReturned},
     id::{BookId, CheckoutId},
+    role::Permission,
 };
 use registry::AppRegistry;
-use shared::error::AppResult;
+use shared::error::{AppError, AppResult};
 
-use crate::{extractor::AuthorizedUser, model::checkout::CheckoutsResponse};
+use crate::{
+    extractor::{AuthorizedUser, RequestId},
+    handler::audit::record_audit_log,
+    model::checkout::{CheckoutQuery, CheckoutsResponse},
+};
 
 #[cfg_attr(
     debug_assertions,
@@ -20,11 +28,16 @@ use crate::{extractor::AuthorizedUser, model::checkout::CheckoutsResponse};
         responses(
             (status = 201, description = "貸出の登録に成功した場合。"),
             (status = 400, description = "リクエストのパラメータが不正な場合。"),
+            (status = 403, description = "checkout:create:any の権限を持たないユーザーが、他のユーザーに代わって借りようとした場合や、貸出期間を指定した場合。"),
+            (status = 404, description = "対象の書籍や複本、借り手として指定したユーザーが見つからなかった場合。"),
             (status = 422, description = "リクエストされた処理が実行できない場合。"),
             (status = 500, description = "貸出の登録に失敗した場合。")
         ),
         params(
-            ("book_id" = Uuid, Path, description = "蔵書ID")
+            ("book_id" = Uuid, Path, description = "蔵書ID"),
+            ("loanPeriodDays" = Option<i64>, Query, description = "貸出期間の日数。checkout:create:any の権限が必要。指定しない場合は既定の貸出期間となる"),
+            ("userId" = Option<Uuid>, Query, description = "借り手のユーザーID。指定しない場合はリクエストしたユーザー自身が借りる"),
+            ("copyId" = Option<Uuid>, Query, description = "借りる複本のID。指定しない場合は貸出中でない複本が選ばれる")
         )
     )
 )]
@@ -36,16 +49,61 @@ use crate::{extractor::AuthorizedUser, model::checkout::CheckoutsResponse};
 )]
 pub async fn checkout_book(
     user: AuthorizedUser,
+    request_id: RequestId,
     Path(book_id): Path<BookId>,
+    Query(query): Query<CheckoutQuery>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
-    let create_checkout_history = CreateCheckout::new(book_id, user.id(), chrono::Utc::now());
+    query.validate()?;
+
+    // 窓口での貸出のように、他のユーザーに代わって借りるには権限が必要
+    let checked_out_by = match query.user_id {
+        Some(user_id) if user_id != user.id() => {
+            if !user.has_permission(Permission::CheckoutCreateAny) {
+                return Err(AppError::ForbiddenOperationError);
+            }
+            user_id
+        }
+        _ => user.id(),
+    };
+
+    // 既定の貸出期間を変更できるのも、貸出を管理する権限を持つユーザーに限る
+    if query.loan_period_days.is_some() && !user.has_permission(Permission::CheckoutCreateAny) {
+        return Err(AppError::ForbiddenOperationError);
+    }
+
+    let create_checkout_history = CreateCheckout::new(
+        book_id,
+        query.copy_id,
+        checked_out_by,
+        user.id(),
+        chrono::Utc::now(),
+        query.loan_period_days.map(Duration::days),
+    );
 
     registry
         .checkout_repository()
         .create(create_checkout_history)
-        .await
-        .map(|_| StatusCode::CREATED)
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::BookCheckedOut,
+            book_id.raw(),
+            None,
+            Some(serde_json::json!({
+                "loanPeriodDays": query.loan_period_days,
+                "checkedOutBy": checked_out_by,
+                "copyId": query.copy_id,
+            })),
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::CREATED)
 }
 
 #[cfg_attr(
@@ -56,7 +114,7 @@ pub async fn checkout_book(
         responses(
             (status = 200, description = "返却に成功した場合。"),
             (status = 400, description = "リクエストのパラメータが不正な場合。"),
-            (status = 422, description = "リクエストされた処理が実行できない場合。"),
+            (status = 422, description = "リクエストされた処理が実行できない場合。checkout:return:any の権限を持たないユーザーが、他のユーザーの貸出を返却しようとした場合も含む。"),
             (status = 500, description = "返却の登録に失敗した場合。")
         ),
         params(
@@ -73,16 +131,89 @@ pub async fn checkout_book(
 )]
 pub async fn return_book(
     user: AuthorizedUser,
+    request_id: RequestId,
     Path((book_id, checkout_id)): Path<(BookId, CheckoutId)>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
-    let update_returned = UpdateReturned::new(checkout_id, book_id, user.id(), chrono::Utc::now());
+    let update_returned = UpdateReturned::new(
+        checkout_id,
+        book_id,
+        user.id(),
+        chrono::Utc::now(),
+        user.has_permission(Permission::CheckoutReturnAny),
+    );
 
     registry
         .checkout_repository()
         .update_returned(update_returned)
-        .await
-        .map(|_| StatusCode::OK)
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::BookReturned,
+            checkout_id.raw(),
+            None,
+            Some(serde_json::json!({ "bookId": book_id })),
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        put,
+        path="/api/v1/books/{book_id}/checkouts/{checkout_id}/renewal",
+        responses(
+            (status = 200, description = "貸出の延長に成功した場合。"),
+            (status = 400, description = "リクエストのパラメータが不正な場合。"),
+            (status = 404, description = "対象の貸出が見つからなかった場合。"),
+            (status = 422, description = "延長回数の上限に達しているか、予約待ちの利用者がいる場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID"),
+            ("checkout_id" = Uuid, Path, description = "貸出ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn renew_checkout(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path((book_id, checkout_id)): Path<(BookId, CheckoutId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let update_renewed = UpdateRenewed::new(checkout_id, book_id, user.id(), chrono::Utc::now());
+
+    registry
+        .checkout_repository()
+        .update_renewed(update_renewed)
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::CheckoutRenewed,
+            checkout_id.raw(),
+            None,
+            Some(serde_json::json!({ "bookId": book_id })),
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::OK)
 }
 
 #[cfg_attr(
@@ -113,6 +244,34 @@ pub async fn show_checked_out_list(
         .map(Json)
 }
 
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/books/checkouts/overdue",
+        responses(
+            (status = 200, description = "返却期限を過ぎた貸出の一覧取得に成功した場合。", body = CheckoutsResponse),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(_user, registry),
+    fields(
+        user_id = %_user.user.id.to_string()
+    )
+)]
+pub async fn show_overdue_list(
+    _user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CheckoutsResponse>> {
+    registry
+        .checkout_repository()
+        .find_overdue_all(chrono::Utc::now())
+        .await
+        .map(CheckoutsResponse::from)
+        .map(Json)
+}
+
 #[cfg_attr(
     debug_assertions,
     utoipa::path(
@@ -0,0 +1,352 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    audit::{event::CreateAuditLog, AuditAction},
+    collection::event::{AddCollectionBook, DeleteCollection, RemoveCollectionBook},
+    id::{BookId, CollectionId, UserId},
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use crate::{
+    extractor::{AuthorizedUser, RequestId},
+    handler::audit::record_audit_log,
+    model::collection::{
+        CollectionRequest, CollectionResponse, CollectionsResponse,
+        CreateCollectionRequestWithUserId, UpdateCollectionRequestWithIds,
+    },
+};
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/collections",
+        responses(
+            (status = 200, description = "自分のコレクションと共有されたコレクションの一覧の取得に成功した場合。", body = CollectionsResponse),
+            (status = 401, description = "認証されていないユーザがアクセスした場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn show_collections(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CollectionsResponse>> {
+    let items = registry
+        .collection_repository()
+        .find_all(user.id())
+        .await?
+        .into_iter()
+        .map(CollectionResponse::from)
+        .collect();
+
+    Ok(Json(CollectionsResponse { items }))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/api/v1/collections",
+        request_body = CollectionRequest,
+        responses(
+            (status = 201, description = "コレクションの作成に成功した場合。", body = CollectionResponse),
+            (status = 400, description = "リクエストのパラメータに不備があった場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn create_collection(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CollectionRequest>,
+) -> AppResult<(StatusCode, Json<CollectionResponse>)> {
+    req.validate()?;
+
+    let collection = registry
+        .collection_repository()
+        .create(CreateCollectionRequestWithUserId::new(user.id(), req).into())
+        .await
+        .map(CollectionResponse::from)?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::CollectionCreated,
+            collection.id.raw(),
+            None,
+            serde_json::to_value(&collection).ok(),
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(collection)))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/collections/{collection_id}",
+        params(
+            ("collection_id" = CollectionId, Path, description = "コレクション ID")
+        ),
+        responses(
+            (status = 200, description = "コレクションの取得に成功した場合。蔵書は `GET /api/v1/books?collectionId=` で取得する。", body = CollectionResponse),
+            (status = 404, description = "コレクションが存在しないか、共有されていない他のユーザーのコレクションの場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn show_collection(
+    user: AuthorizedUser,
+    Path(collection_id): Path<CollectionId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CollectionResponse>> {
+    registry
+        .collection_repository()
+        .find_by_id(collection_id, user.id())
+        .await?
+        .map(CollectionResponse::from)
+        .map(Json)
+        .ok_or_else(|| AppError::EntityNotFound("Specified collection not found".into()))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        put,
+        path="/api/v1/collections/{collection_id}",
+        request_body = CollectionRequest,
+        params(
+            ("collection_id" = CollectionId, Path, description = "コレクション ID")
+        ),
+        responses(
+            (status = 200, description = "コレクションの更新に成功した場合。"),
+            (status = 400, description = "リクエストのパラメータに不備があった場合。"),
+            (status = 404, description = "コレクションが存在しないか、他のユーザーのコレクションの場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn update_collection(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path(collection_id): Path<CollectionId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CollectionRequest>,
+) -> AppResult<StatusCode> {
+    req.validate()?;
+
+    let before = find_collection_snapshot(&registry, collection_id, user.id()).await?;
+    let after = serde_json::to_value(&req).ok();
+
+    registry
+        .collection_repository()
+        .update(UpdateCollectionRequestWithIds::new(collection_id, user.id(), req).into())
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::CollectionUpdated,
+            collection_id.raw(),
+            before,
+            after,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        delete,
+        path="/api/v1/collections/{collection_id}",
+        params(
+            ("collection_id" = CollectionId, Path, description = "コレクション ID")
+        ),
+        responses(
+            (status = 204, description = "コレクションの削除に成功した場合。蔵書そのものは削除されない。"),
+            (status = 404, description = "コレクションが存在しないか、他のユーザーのコレクションの場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn delete_collection(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path(collection_id): Path<CollectionId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let before = find_collection_snapshot(&registry, collection_id, user.id()).await?;
+
+    registry
+        .collection_repository()
+        .delete(DeleteCollection::new(collection_id, user.id()))
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::CollectionDeleted,
+            collection_id.raw(),
+            before,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        put,
+        path="/api/v1/collections/{collection_id}/books/{book_id}",
+        params(
+            ("collection_id" = CollectionId, Path, description = "コレクション ID"),
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        ),
+        responses(
+            (status = 204, description = "コレクションに蔵書を加えた場合。既に含まれていた場合も含む。"),
+            (status = 404, description = "蔵書が存在しない場合や、コレクションを参照できない場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn add_collection_book(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path((collection_id, book_id)): Path<(CollectionId, BookId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .collection_repository()
+        .add_book(AddCollectionBook::new(collection_id, book_id, user.id()))
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::CollectionBookAdded,
+            collection_id.raw(),
+            None,
+            Some(serde_json::json!({ "bookId": book_id })),
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        delete,
+        path="/api/v1/collections/{collection_id}/books/{book_id}",
+        params(
+            ("collection_id" = CollectionId, Path, description = "コレクション ID"),
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        ),
+        responses(
+            (status = 204, description = "コレクションから蔵書を外した場合。"),
+            (status = 404, description = "蔵書がコレクションに含まれていない場合や、コレクションを参照できない場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn remove_collection_book(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path((collection_id, book_id)): Path<(CollectionId, BookId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .collection_repository()
+        .remove_book(RemoveCollectionBook::new(collection_id, book_id, user.id()))
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::CollectionBookRemoved,
+            collection_id.raw(),
+            Some(serde_json::json!({ "bookId": book_id })),
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 監査ログに変更前の状態として残すため、コレクションの現在の内容を取得する。
+async fn find_collection_snapshot(
+    registry: &AppRegistry,
+    collection_id: CollectionId,
+    user_id: UserId,
+) -> AppResult<Option<serde_json::Value>> {
+    let collection = registry
+        .collection_repository()
+        .find_by_id(collection_id, user_id)
+        .await?;
+
+    Ok(collection
+        .map(CollectionResponse::from)
+        .and_then(|collection| serde_json::to_value(collection).ok()))
+}
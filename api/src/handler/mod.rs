@@ -1,5 +1,17 @@
+pub mod api_key;
+pub mod audit;
 pub mod auth;
+pub mod author;
 pub mod book;
+pub mod book_transfer;
+pub mod catalog;
 pub mod checkout;
+pub mod collection;
 pub mod health;
+pub mod notification;
+pub mod reservation;
+pub mod role;
+pub mod session;
+pub mod tag;
+pub mod two_factor;
 pub mod user;
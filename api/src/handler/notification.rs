@@ -0,0 +1,96 @@
+use axum::{extract::State, http::StatusCode, Json};
+use kernel::model::audit::{event::CreateAuditLog, AuditAction};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use crate::{
+    extractor::{AuthorizedUser, RequestId},
+    handler::audit::record_audit_log,
+    model::notification::{
+        NotificationPreferencesResponse, UpdateNotificationPreferencesRequest,
+        UpdateNotificationPreferencesRequestWithUserId,
+    },
+};
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/users/me/notification-preferences",
+        responses(
+            (status = 200, description = "通知設定の取得に成功した場合。", body = NotificationPreferencesResponse),
+            (status = 500, description = "サーバーサイドエラーが発生した場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn show_notification_preferences(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<NotificationPreferencesResponse>> {
+    registry
+        .notification_repository()
+        .find_preferences(user.id())
+        .await
+        .map(NotificationPreferencesResponse::from)
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        put,
+        path="/api/v1/users/me/notification-preferences",
+        request_body = UpdateNotificationPreferencesRequest,
+        responses(
+            (status = 200, description = "通知設定の変更に成功した場合。"),
+            (status = 400, description = "リクエストの形式に誤りがある場合。"),
+            (status = 500, description = "サーバーサイドエラーが発生した場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn update_notification_preferences(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateNotificationPreferencesRequest>,
+) -> AppResult<StatusCode> {
+    let notification = registry.notification_repository();
+    let before = notification
+        .find_preferences(user.id())
+        .await
+        .map(NotificationPreferencesResponse::from)?;
+    let after = serde_json::to_value(&req).ok();
+
+    notification
+        .update_preferences(
+            UpdateNotificationPreferencesRequestWithUserId::new(user.id(), req).into(),
+        )
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::NotificationPreferencesUpdated,
+            user.id().raw(),
+            serde_json::to_value(before).ok(),
+            after,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,153 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use kernel::model::{
+    audit::{event::CreateAuditLog, AuditAction},
+    id::{BookId, ReservationId},
+    reservation::event::{CreateReservation, DeleteReservation},
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use crate::{
+    extractor::{AuthorizedUser, RequestId},
+    handler::audit::record_audit_log,
+    model::reservation::ReservationsResponse,
+};
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/api/v1/books/{book_id}/reservations",
+        responses(
+            (status = 201, description = "予約の登録に成功した場合。"),
+            (status = 400, description = "リクエストのパラメータが不正な場合。"),
+            (status = 404, description = "対象の書籍が見つからなかった場合。"),
+            (status = 422, description = "書籍が貸出可能な状態であるか、既に予約済みの場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn place_reservation(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let create_reservation = CreateReservation::new(book_id, user.id(), chrono::Utc::now());
+
+    registry
+        .reservation_repository()
+        .create(create_reservation)
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::ReservationPlaced,
+            book_id.raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::CREATED)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/books/{book_id}/reservations",
+        responses(
+            (status = 200, description = "蔵書の予約待ちの一覧取得に成功した場合。", body = ReservationsResponse),
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(_user, registry),
+    fields(
+        user_id = %_user.user.id.to_string()
+    )
+)]
+pub async fn show_reservations(
+    _user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<ReservationsResponse>> {
+    registry
+        .reservation_repository()
+        .find_by_book_id(book_id)
+        .await
+        .map(ReservationsResponse::from)
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        delete,
+        path="/api/v1/books/{book_id}/reservations/{reservation_id}",
+        responses(
+            (status = 204, description = "予約の取り消しに成功した場合。"),
+            (status = 400, description = "リクエストのパラメータが不正な場合。"),
+            (status = 404, description = "取り消し対象の予約が見つからなかった場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID"),
+            ("reservation_id" = Uuid, Path, description = "予約ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn cancel_reservation(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path((book_id, reservation_id)): Path<(BookId, ReservationId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let delete_reservation =
+        DeleteReservation::new(reservation_id, book_id, user.id(), chrono::Utc::now());
+
+    registry
+        .reservation_repository()
+        .delete(delete_reservation)
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::ReservationCancelled,
+            reservation_id.raw(),
+            Some(serde_json::json!({ "bookId": book_id })),
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
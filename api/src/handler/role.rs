@@ -0,0 +1,165 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    audit::{event::CreateAuditLog, AuditAction},
+    id::RoleId,
+    role::event::DeleteRole,
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use crate::{
+    extractor::{permission::RoleManage, RequestId, RequirePermission},
+    handler::audit::record_audit_log,
+    model::role::{
+        CreateRoleRequest, RoleResponse, RolesResponse, UpdateRoleRequest, UpdateRoleRequestWithId,
+    },
+};
+
+// Admin only
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn list_roles(
+    user: RequirePermission<RoleManage>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<RolesResponse>> {
+    let items = registry
+        .role_repository()
+        .find_all()
+        .await?
+        .into_iter()
+        .map(RoleResponse::from)
+        .collect();
+
+    Ok(Json(RolesResponse { items }))
+}
+
+// Admin only
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn create_role(
+    user: RequirePermission<RoleManage>,
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<CreateRoleRequest>,
+) -> AppResult<(StatusCode, Json<RoleResponse>)> {
+    req.validate()?;
+
+    let role = registry.role_repository().create(req.into()).await?;
+    let role = RoleResponse::from(role);
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::RoleCreated,
+            role.id.raw(),
+            None,
+            serde_json::to_value(&role).ok(),
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(role)))
+}
+
+// Admin only
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn update_role(
+    user: RequirePermission<RoleManage>,
+    request_id: RequestId,
+    Path(role_id): Path<RoleId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateRoleRequest>,
+) -> AppResult<StatusCode> {
+    req.validate()?;
+
+    let before = find_role_snapshot(&registry, role_id).await?;
+
+    registry
+        .role_repository()
+        .update(UpdateRoleRequestWithId::new(role_id, req).into())
+        .await?;
+
+    let after = find_role_snapshot(&registry, role_id).await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::RoleUpdated,
+            role_id.raw(),
+            before,
+            after,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::OK)
+}
+
+// Admin only
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn delete_role(
+    user: RequirePermission<RoleManage>,
+    request_id: RequestId,
+    Path(role_id): Path<RoleId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let before = find_role_snapshot(&registry, role_id).await?;
+
+    registry
+        .role_repository()
+        .delete(DeleteRole::new(role_id))
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::RoleDeleted,
+            role_id.raw(),
+            before,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn find_role_snapshot(
+    registry: &AppRegistry,
+    role_id: RoleId,
+) -> AppResult<Option<serde_json::Value>> {
+    let role = registry.role_repository().find_by_id(role_id).await?;
+
+    Ok(role
+        .map(RoleResponse::from)
+        .and_then(|role| serde_json::to_value(role).ok()))
+}
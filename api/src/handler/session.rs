@@ -0,0 +1,131 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use kernel::model::{
+    audit::{event::CreateAuditLog, AuditAction},
+    auth::event::RevokeSession,
+    id::{SessionId, UserId},
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use crate::{
+    extractor::{permission::UserManage, AuthorizedUser, RequestId, RequirePermission},
+    handler::audit::record_audit_log,
+    model::session::{SessionResponse, SessionsResponse},
+};
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/users/me/sessions",
+        responses(
+            (status = 200, description = "ログイン中のセッション一覧の取得に成功した場合。", body = SessionsResponse),
+            (status = 500, description = "サーバーサイドエラーが発生した場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn show_sessions(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<SessionsResponse>> {
+    let items = registry
+        .auth_repository()
+        .find_sessions(user.id())
+        .await?
+        .into_iter()
+        .map(SessionResponse::from)
+        .collect();
+
+    Ok(Json(SessionsResponse { items }))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        delete,
+        path="/api/v1/users/me/sessions/{session_id}",
+        params(
+            ("session_id" = SessionId, Path, description = "失効させるセッションの ID")
+        ),
+        responses(
+            (status = 204, description = "セッションの失効に成功した場合。"),
+            (status = 404, description = "指定されたセッションが存在しない場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn revoke_session(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path(session_id): Path<SessionId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .auth_repository()
+        .revoke_session(RevokeSession::new(session_id, user.id()))
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::SessionRevoked,
+            session_id.raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Admin only
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn revoke_user_sessions(
+    user: RequirePermission<UserManage>,
+    request_id: RequestId,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .auth_repository()
+        .revoke_all_sessions(user_id)
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::AllSessionsRevoked,
+            user_id.raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,322 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use garde::Validate;
+use kernel::model::{
+    audit::{event::CreateAuditLog, AuditAction},
+    id::{BookId, TagId},
+    role::Permission,
+    tag::event::{AddBookTag, DeleteTag, RemoveBookTag},
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use crate::{
+    extractor::{permission::BookWriteAny, AuthorizedUser, RequestId, RequirePermission},
+    handler::audit::record_audit_log,
+    model::tag::{TagRequest, TagResponse, TagsResponse, UpdateTagRequestWithId},
+};
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/tags",
+        responses(
+            (status = 200, description = "タグ一覧の取得に成功した場合。", body = TagsResponse),
+            (status = 401, description = "認証されていないユーザがアクセスした場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(_user, registry),
+    fields(
+        user_id = %_user.user.id.to_string()
+    )
+)]
+pub async fn show_tags(
+    _user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<TagsResponse>> {
+    let items = registry
+        .tag_repository()
+        .find_all()
+        .await?
+        .into_iter()
+        .map(TagResponse::from)
+        .collect();
+
+    Ok(Json(TagsResponse { items }))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/api/v1/tags",
+        request_body = TagRequest,
+        responses(
+            (status = 201, description = "タグの作成に成功した場合。", body = TagResponse),
+            (status = 400, description = "リクエストのパラメータに不備があった場合。"),
+            (status = 409, description = "大文字・小文字の違いを除いて同じ名前のタグが既にある場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn create_tag(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<TagRequest>,
+) -> AppResult<(StatusCode, Json<TagResponse>)> {
+    req.validate()?;
+
+    let tag = registry
+        .tag_repository()
+        .create(req.into())
+        .await
+        .map(TagResponse::from)?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::TagCreated,
+            tag.id.raw(),
+            None,
+            serde_json::to_value(&tag).ok(),
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(tag)))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        put,
+        path="/api/v1/tags/{tag_id}",
+        request_body = TagRequest,
+        params(
+            ("tag_id" = TagId, Path, description = "タグ ID")
+        ),
+        responses(
+            (status = 200, description = "タグ名の変更に成功した場合。"),
+            (status = 400, description = "リクエストのパラメータに不備があった場合。"),
+            (status = 403, description = "他のユーザーの蔵書を編集する権限を持たない場合。"),
+            (status = 404, description = "指定されたタグが存在しない場合。"),
+            (status = 409, description = "大文字・小文字の違いを除いて同じ名前のタグが既にある場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn update_tag(
+    user: RequirePermission<BookWriteAny>,
+    request_id: RequestId,
+    Path(tag_id): Path<TagId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<TagRequest>,
+) -> AppResult<StatusCode> {
+    req.validate()?;
+
+    let before = find_tag_snapshot(&registry, tag_id).await?;
+    let after = serde_json::to_value(&req).ok();
+
+    registry
+        .tag_repository()
+        .update(UpdateTagRequestWithId::new(tag_id, req).into())
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::TagUpdated,
+            tag_id.raw(),
+            before,
+            after,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        delete,
+        path="/api/v1/tags/{tag_id}",
+        params(
+            ("tag_id" = TagId, Path, description = "タグ ID")
+        ),
+        responses(
+            (status = 204, description = "タグの削除に成功した場合。蔵書に付いていたタグも外れる。"),
+            (status = 403, description = "他のユーザーの蔵書を編集する権限を持たない場合。"),
+            (status = 404, description = "指定されたタグが存在しない場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn delete_tag(
+    user: RequirePermission<BookWriteAny>,
+    request_id: RequestId,
+    Path(tag_id): Path<TagId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let before = find_tag_snapshot(&registry, tag_id).await?;
+
+    registry
+        .tag_repository()
+        .delete(DeleteTag::new(tag_id))
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::TagDeleted,
+            tag_id.raw(),
+            before,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        put,
+        path="/api/v1/tags/{tag_id}/books/{book_id}",
+        params(
+            ("tag_id" = TagId, Path, description = "タグ ID"),
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        ),
+        responses(
+            (status = 204, description = "蔵書にタグを付けた場合。既に付いていた場合も含む。"),
+            (status = 404, description = "タグか蔵書が存在しない場合や、他のユーザーの蔵書を編集する権限を持たない場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn add_book_tag(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path((tag_id, book_id)): Path<(TagId, BookId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let event = AddBookTag::new(
+        tag_id,
+        book_id,
+        user.id(),
+        user.has_permission(Permission::BookWriteAny),
+    );
+    registry.tag_repository().add_to_book(event).await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::BookTagAdded,
+            book_id.raw(),
+            None,
+            Some(serde_json::json!({ "tagId": tag_id })),
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        delete,
+        path="/api/v1/tags/{tag_id}/books/{book_id}",
+        params(
+            ("tag_id" = TagId, Path, description = "タグ ID"),
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        ),
+        responses(
+            (status = 204, description = "蔵書からタグを外した場合。"),
+            (status = 404, description = "蔵書にタグが付いていない場合や、他のユーザーの蔵書を編集する権限を持たない場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn remove_book_tag(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    Path((tag_id, book_id)): Path<(TagId, BookId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let event = RemoveBookTag::new(
+        tag_id,
+        book_id,
+        user.id(),
+        user.has_permission(Permission::BookWriteAny),
+    );
+    registry.tag_repository().remove_from_book(event).await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::BookTagRemoved,
+            book_id.raw(),
+            Some(serde_json::json!({ "tagId": tag_id })),
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 監査ログに変更前の状態として残すため、タグの現在の内容を取得する。
+async fn find_tag_snapshot(
+    registry: &AppRegistry,
+    tag_id: TagId,
+) -> AppResult<Option<serde_json::Value>> {
+    let tag = registry.tag_repository().find_by_id(tag_id).await?;
+
+    Ok(tag
+        .map(TagResponse::from)
+        .and_then(|tag| serde_json::to_value(tag).ok()))
+}
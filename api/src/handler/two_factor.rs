@@ -0,0 +1,222 @@
+use axum::{extract::State, http::StatusCode, Json};
+use garde::Validate;
+use kernel::model::{
+    audit::{event::CreateAuditLog, AuditAction},
+    auth::two_factor::{ConfirmTwoFactor, DisableTwoFactor},
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use crate::{
+    extractor::{permission::RoleManage, AuthorizedUser, RequestId, RequirePermission},
+    handler::audit::record_audit_log,
+    model::two_factor::{
+        TwoFactorCodeRequest, TwoFactorEnrollmentResponse, TwoFactorStatusResponse,
+        UpdateTwoFactorPolicyRequest,
+    },
+};
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/api/v1/users/me/2fa",
+        responses(
+            (status = 200, description = "2 段階認証の設定状況の取得に成功した場合。", body = TwoFactorStatusResponse),
+            (status = 500, description = "サーバーサイドエラーが発生した場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn show_two_factor_status(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<TwoFactorStatusResponse>> {
+    registry
+        .two_factor_repository()
+        .find_status(user.id())
+        .await
+        .map(TwoFactorStatusResponse::from)
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/api/v1/users/me/2fa",
+        responses(
+            (status = 200, description = "秘密鍵とリカバリーコードの発行に成功した場合。コードを確認するまで 2 段階認証は有効になりません。", body = TwoFactorEnrollmentResponse),
+            (status = 422, description = "2 段階認証が既に有効な場合。"),
+            (status = 500, description = "サーバーサイドエラーが発生した場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn enroll_two_factor(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<TwoFactorEnrollmentResponse>> {
+    let enrollment = registry.two_factor_repository().enroll(user.id()).await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::TwoFactorEnrolled,
+            user.id().raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(Json(enrollment.into()))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/api/v1/users/me/2fa/confirm",
+        request_body = TwoFactorCodeRequest,
+        responses(
+            (status = 204, description = "コードの確認に成功し、2 段階認証が有効になった場合。"),
+            (status = 400, description = "リクエストの形式に誤りがある場合。"),
+            (status = 403, description = "コードが誤っている場合。"),
+            (status = 422, description = "秘密鍵が発行されていない、または既に有効な場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn confirm_two_factor(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<TwoFactorCodeRequest>,
+) -> AppResult<StatusCode> {
+    req.validate()?;
+
+    registry
+        .two_factor_repository()
+        .confirm(ConfirmTwoFactor::new(user.id(), req.code))
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::TwoFactorEnabled,
+            user.id().raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        delete,
+        path="/api/v1/users/me/2fa",
+        request_body = TwoFactorCodeRequest,
+        responses(
+            (status = 204, description = "2 段階認証の無効化に成功した場合。"),
+            (status = 400, description = "リクエストの形式に誤りがある場合。"),
+            (status = 403, description = "コードが誤っている場合や、ロールで 2 段階認証が必須とされている場合。"),
+            (status = 422, description = "2 段階認証が有効でない場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn disable_two_factor(
+    user: AuthorizedUser,
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<TwoFactorCodeRequest>,
+) -> AppResult<StatusCode> {
+    req.validate()?;
+
+    registry
+        .two_factor_repository()
+        .disable(DisableTwoFactor::new(user.id(), req.code))
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::TwoFactorDisabled,
+            user.id().raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Admin only
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn update_two_factor_policy(
+    user: RequirePermission<RoleManage>,
+    request_id: RequestId,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateTwoFactorPolicyRequest>,
+) -> AppResult<StatusCode> {
+    // ロールは名前で指定するため、操作した管理者を対象として記録する
+    let after = serde_json::to_value(&req).ok();
+
+    registry
+        .two_factor_repository()
+        .update_policy(req.into())
+        .await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::TwoFactorPolicyChanged,
+            user.id().raw(),
+            None,
+            after,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::OK)
+}
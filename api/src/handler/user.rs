@@ -4,12 +4,17 @@ use axum::{
     Json,
 };
 use garde::Validate;
-use kernel::model::{id::UserId, user::event::DeleteUser};
+use kernel::model::{
+    audit::{event::CreateAuditLog, AuditAction},
+    id::UserId,
+    user::event::DeleteUser,
+};
 use registry::AppRegistry;
-use shared::error::{AppError, AppResult};
+use shared::error::AppResult;
 
 use crate::{
-    extractor::AuthorizedUser,
+    extractor::{permission::UserManage, AuthorizedUser, RequestId, RequirePermission},
+    handler::audit::record_audit_log,
     model::{
         checkout::CheckoutsResponse,
         user::{
@@ -27,18 +32,29 @@ use crate::{
     )
 )]
 pub async fn register_user(
-    user: AuthorizedUser,
+    user: RequirePermission<UserManage>,
+    request_id: RequestId,
     State(registry): State<AppRegistry>,
     Json(req): Json<CreateUserRequest>,
 ) -> AppResult<Json<UserResponse>> {
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperationError);
-    }
-
     req.validate()?;
 
-    let registered_user = registry.user_repository().create(req.into()).await?;
-    Ok(Json(registered_user.into()))
+    let registered_user = UserResponse::from(registry.user_repository().create(req.into()).await?);
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::UserCreated,
+            registered_user.id.raw(),
+            None,
+            serde_json::to_value(&registered_user).ok(),
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(Json(registered_user))
 }
 
 #[cfg_attr(
@@ -81,19 +97,31 @@ pub async fn list_users(
     )
 )]
 pub async fn delete_user(
-    user: AuthorizedUser,
+    user: RequirePermission<UserManage>,
+    request_id: RequestId,
     Path(user_id): Path<UserId>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperationError);
-    }
+    let before = find_user_snapshot(&registry, user_id).await?;
 
     registry
         .user_repository()
         .delete(DeleteUser { user_id })
         .await?;
 
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::UserDeleted,
+            user_id.raw(),
+            before,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -105,23 +133,67 @@ pub async fn delete_user(
     )
 )]
 pub async fn change_role(
-    user: AuthorizedUser,
+    user: RequirePermission<UserManage>,
+    request_id: RequestId,
     Path(user_id): Path<UserId>,
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateUserRoleRequest>,
 ) -> AppResult<StatusCode> {
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperationError);
-    }
+    let before = find_user_snapshot(&registry, user_id).await?;
+    let after = serde_json::to_value(&req).ok();
 
     registry
         .user_repository()
         .update_role(UpdateUserRoleRequestWithUserId::new(user_id, req).into())
         .await?;
 
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::UserRoleChanged,
+            user_id.raw(),
+            before,
+            after,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
     Ok(StatusCode::OK)
 }
 
+// Admin only
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.user.id.to_string()
+    )
+)]
+pub async fn unlock_user(
+    user: RequirePermission<UserManage>,
+    request_id: RequestId,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry.login_attempt_repository().unlock(user_id).await?;
+
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::AccountUnlocked,
+            user_id.raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 #[cfg_attr(
     debug_assertions,
     utoipa::path(
@@ -164,6 +236,7 @@ pub async fn get_current_user(user: AuthorizedUser) -> Json<UserResponse> {
 )]
 pub async fn change_password(
     user: AuthorizedUser,
+    request_id: RequestId,
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateUserPasswordRequest>,
 ) -> AppResult<StatusCode> {
@@ -174,6 +247,20 @@ pub async fn change_password(
         .update_password(UpdateUserPasswordRequestWithUserId::new(user.id(), req).into())
         .await?;
 
+    // パスワードは変更前後とも監査ログに残さない
+    record_audit_log(
+        &registry,
+        CreateAuditLog::new(
+            user.id(),
+            AuditAction::UserPasswordChanged,
+            user.id().raw(),
+            None,
+            None,
+            request_id.into_inner(),
+        ),
+    )
+    .await;
+
     Ok(StatusCode::OK)
 }
 
@@ -205,3 +292,18 @@ pub async fn get_checkouts(
         .map(CheckoutsResponse::from)
         .map(Json)
 }
+
+/// 監査ログに変更前の状態として残すため、ユーザーの現在の内容を取得する。
+async fn find_user_snapshot(
+    registry: &AppRegistry,
+    user_id: UserId,
+) -> AppResult<Option<serde_json::Value>> {
+    let user = registry
+        .user_repository()
+        .find_current_user(user_id)
+        .await?;
+
+    Ok(user
+        .map(UserResponse::from)
+        .and_then(|user| serde_json::to_value(user).ok()))
+}
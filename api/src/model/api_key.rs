@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    api_key::{event::CreateApiKey, ApiKey, ApiKeyScope, IssuedApiKey},
+    id::{ApiKeyId, UserId},
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScopeName {
+    ReadBooks,
+    ManageCheckouts,
+    Admin,
+}
+
+impl From<ApiKeyScope> for ApiKeyScopeName {
+    fn from(value: ApiKeyScope) -> Self {
+        match value {
+            ApiKeyScope::ReadBooks => Self::ReadBooks,
+            ApiKeyScope::ManageCheckouts => Self::ManageCheckouts,
+            ApiKeyScope::Admin => Self::Admin,
+        }
+    }
+}
+
+impl From<ApiKeyScopeName> for ApiKeyScope {
+    fn from(value: ApiKeyScopeName) -> Self {
+        match value {
+            ApiKeyScopeName::ReadBooks => Self::ReadBooks,
+            ApiKeyScopeName::ManageCheckouts => Self::ManageCheckouts,
+            ApiKeyScopeName::Admin => Self::Admin,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeyRequest {
+    #[garde(length(min = 1, max = 255))]
+    pub name: String,
+    #[garde(length(min = 1))]
+    pub scopes: Vec<ApiKeyScopeName>,
+    /// 省略した場合は、失効させるまで使える
+    #[garde(skip)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(new)]
+pub struct CreateApiKeyRequestWithUserId(UserId, CreateApiKeyRequest);
+
+impl From<CreateApiKeyRequestWithUserId> for CreateApiKey {
+    fn from(value: CreateApiKeyRequestWithUserId) -> Self {
+        let CreateApiKeyRequestWithUserId(
+            user_id,
+            CreateApiKeyRequest {
+                name,
+                scopes,
+                expires_at,
+            },
+        ) = value;
+
+        CreateApiKey {
+            user_id,
+            name,
+            scopes: scopes.into_iter().map(ApiKeyScope::from).collect(),
+            expires_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeysResponse {
+    pub items: Vec<ApiKeyResponse>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyResponse {
+    pub id: ApiKeyId,
+    pub name: String,
+    /// キーを見分けるための、シークレットの先頭部分
+    pub prefix: String,
+    pub scopes: Vec<ApiKeyScopeName>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(value: ApiKey) -> Self {
+        let ApiKey {
+            id,
+            name,
+            prefix,
+            scopes,
+            expires_at,
+            last_used_at,
+            created_at,
+            ..
+        } = value;
+
+        Self {
+            id,
+            name,
+            prefix,
+            scopes: scopes.into_iter().map(ApiKeyScopeName::from).collect(),
+            expires_at,
+            last_used_at,
+            created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+    /// `Authorization: ApiKey <secret>` として送信する。再表示はできない。
+    pub secret: String,
+}
+
+impl From<IssuedApiKey> for IssuedApiKeyResponse {
+    fn from(value: IssuedApiKey) -> Self {
+        let IssuedApiKey { api_key, secret } = value;
+
+        Self {
+            api_key: api_key.into(),
+            secret,
+        }
+    }
+}
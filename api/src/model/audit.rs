@@ -0,0 +1,230 @@
+use chrono::{DateTime, Utc};
+use garde::Validate;
+use kernel::model::{
+    audit::{AuditAction, AuditLog, AuditLogListOptions},
+    id::{AuditLogId, UserId},
+    list::PaginatedList,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogListQuery {
+    #[garde(range(min = 0))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(range(min = 0))]
+    #[serde(default)] // 0
+    pub offset: i64,
+    #[garde(skip)]
+    pub actor_id: Option<UserId>,
+    #[garde(skip)]
+    pub target_id: Option<Uuid>,
+    #[garde(skip)]
+    pub from: Option<DateTime<Utc>>,
+    #[garde(skip)]
+    pub to: Option<DateTime<Utc>>,
+}
+
+const DEFAULT_LIMIT: i64 = 20;
+const fn default_limit() -> i64 {
+    DEFAULT_LIMIT
+}
+
+impl From<AuditLogListQuery> for AuditLogListOptions {
+    fn from(value: AuditLogListQuery) -> Self {
+        let AuditLogListQuery {
+            limit,
+            offset,
+            actor_id,
+            target_id,
+            from,
+            to,
+        } = value;
+
+        Self {
+            actor_id,
+            target_id,
+            from,
+            to,
+            limit,
+            offset,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AuditActionName {
+    BookCreated,
+    BookUpdated,
+    BookDeleted,
+    BookCopyCreated,
+    BookCopyUpdated,
+    BookCopyDeleted,
+    BookCoverUpdated,
+    BookCheckedOut,
+    BookReturned,
+    CheckoutRenewed,
+    ReservationPlaced,
+    ReservationCancelled,
+    UserCreated,
+    UserDeleted,
+    UserRoleChanged,
+    UserPasswordChanged,
+    NotificationPreferencesUpdated,
+    LoggedIn,
+    LoggedOut,
+    TokenRefreshed,
+    SessionRevoked,
+    AllSessionsRevoked,
+    AccountUnlocked,
+    PasswordResetRequested,
+    PasswordReset,
+    TwoFactorEnrolled,
+    TwoFactorEnabled,
+    TwoFactorDisabled,
+    TwoFactorPolicyChanged,
+    ApiKeyCreated,
+    ApiKeyRevoked,
+    RoleCreated,
+    RoleUpdated,
+    RoleDeleted,
+    TagCreated,
+    TagUpdated,
+    TagDeleted,
+    BookTagAdded,
+    BookTagRemoved,
+    CollectionCreated,
+    CollectionUpdated,
+    CollectionDeleted,
+    CollectionBookAdded,
+    CollectionBookRemoved,
+}
+
+impl From<AuditAction> for AuditActionName {
+    fn from(value: AuditAction) -> Self {
+        match value {
+            AuditAction::BookCreated => Self::BookCreated,
+            AuditAction::BookUpdated => Self::BookUpdated,
+            AuditAction::BookDeleted => Self::BookDeleted,
+            AuditAction::BookCopyCreated => Self::BookCopyCreated,
+            AuditAction::BookCopyUpdated => Self::BookCopyUpdated,
+            AuditAction::BookCopyDeleted => Self::BookCopyDeleted,
+            AuditAction::BookCoverUpdated => Self::BookCoverUpdated,
+            AuditAction::BookCheckedOut => Self::BookCheckedOut,
+            AuditAction::BookReturned => Self::BookReturned,
+            AuditAction::CheckoutRenewed => Self::CheckoutRenewed,
+            AuditAction::ReservationPlaced => Self::ReservationPlaced,
+            AuditAction::ReservationCancelled => Self::ReservationCancelled,
+            AuditAction::UserCreated => Self::UserCreated,
+            AuditAction::UserDeleted => Self::UserDeleted,
+            AuditAction::UserRoleChanged => Self::UserRoleChanged,
+            AuditAction::UserPasswordChanged => Self::UserPasswordChanged,
+            AuditAction::NotificationPreferencesUpdated => Self::NotificationPreferencesUpdated,
+            AuditAction::LoggedIn => Self::LoggedIn,
+            AuditAction::LoggedOut => Self::LoggedOut,
+            AuditAction::TokenRefreshed => Self::TokenRefreshed,
+            AuditAction::SessionRevoked => Self::SessionRevoked,
+            AuditAction::AllSessionsRevoked => Self::AllSessionsRevoked,
+            AuditAction::AccountUnlocked => Self::AccountUnlocked,
+            AuditAction::PasswordResetRequested => Self::PasswordResetRequested,
+            AuditAction::PasswordReset => Self::PasswordReset,
+            AuditAction::TwoFactorEnrolled => Self::TwoFactorEnrolled,
+            AuditAction::TwoFactorEnabled => Self::TwoFactorEnabled,
+            AuditAction::TwoFactorDisabled => Self::TwoFactorDisabled,
+            AuditAction::TwoFactorPolicyChanged => Self::TwoFactorPolicyChanged,
+            AuditAction::ApiKeyCreated => Self::ApiKeyCreated,
+            AuditAction::ApiKeyRevoked => Self::ApiKeyRevoked,
+            AuditAction::RoleCreated => Self::RoleCreated,
+            AuditAction::RoleUpdated => Self::RoleUpdated,
+            AuditAction::RoleDeleted => Self::RoleDeleted,
+            AuditAction::TagCreated => Self::TagCreated,
+            AuditAction::TagUpdated => Self::TagUpdated,
+            AuditAction::TagDeleted => Self::TagDeleted,
+            AuditAction::BookTagAdded => Self::BookTagAdded,
+            AuditAction::BookTagRemoved => Self::BookTagRemoved,
+            AuditAction::CollectionCreated => Self::CollectionCreated,
+            AuditAction::CollectionUpdated => Self::CollectionUpdated,
+            AuditAction::CollectionDeleted => Self::CollectionDeleted,
+            AuditAction::CollectionBookAdded => Self::CollectionBookAdded,
+            AuditAction::CollectionBookRemoved => Self::CollectionBookRemoved,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedAuditLogResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub items: Vec<AuditLogResponse>,
+}
+
+impl From<PaginatedList<AuditLog>> for PaginatedAuditLogResponse {
+    fn from(value: PaginatedList<AuditLog>) -> Self {
+        let PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        } = value;
+
+        Self {
+            total,
+            limit,
+            offset,
+            items: items.into_iter().map(AuditLogResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogResponse {
+    pub id: AuditLogId,
+    pub actor_id: UserId,
+    pub action: AuditActionName,
+    pub target_id: Uuid,
+    #[cfg_attr(debug_assertions, schema(value_type = Option<Object>))]
+    pub before: Option<serde_json::Value>,
+    #[cfg_attr(debug_assertions, schema(value_type = Option<Object>))]
+    pub after: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl From<AuditLog> for AuditLogResponse {
+    fn from(value: AuditLog) -> Self {
+        let AuditLog {
+            id,
+            actor_id,
+            action,
+            target_id,
+            before,
+            after,
+            request_id,
+            occurred_at,
+        } = value;
+
+        Self {
+            id,
+            actor_id,
+            action: action.into(),
+            target_id,
+            before,
+            after,
+            request_id,
+            occurred_at,
+        }
+    }
+}
@@ -1,4 +1,12 @@
-use kernel::model::id::UserId;
+use garde::Validate;
+use kernel::model::{
+    auth::{
+        event::{ConfirmPasswordReset, RequestPasswordReset},
+        two_factor::{TwoFactorChallenge, VerifyTwoFactorChallenge},
+        AuthToken,
+    },
+    id::UserId,
+};
 use serde::{Deserialize, Serialize};
 
 #[cfg(debug_assertions)]
@@ -12,10 +20,140 @@ pub struct LoginRequest {
     pub password: String,
 }
 
-#[derive(Serialize)]
+#[derive(Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordResetRequest {
+    #[garde(email)]
+    email: String,
+}
+
+impl From<PasswordResetRequest> for RequestPasswordReset {
+    fn from(value: PasswordResetRequest) -> Self {
+        RequestPasswordReset::new(value.email)
+    }
+}
+
+#[derive(Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordResetConfirmRequest {
+    #[garde(length(min = 1))]
+    token: String,
+    #[garde(length(min = 8))]
+    new_password: String,
+}
+
+impl From<PasswordResetConfirmRequest> for ConfirmPasswordReset {
+    fn from(value: PasswordResetConfirmRequest) -> Self {
+        let PasswordResetConfirmRequest {
+            token,
+            new_password,
+        } = value;
+        ConfirmPasswordReset::new(token, new_password)
+    }
+}
+
+/// IdP から認可コードとともにリダイレクトされる際のクエリ。
+/// パラメーター名は OAuth 2.0 の仕様で決まっているため、camelCase には変換しない。
+#[derive(Debug, Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: String,
+    pub state: String,
+}
+
+#[derive(Serialize, Deserialize)]
 #[cfg_attr(debug_assertions, derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct AccessTokenResponse {
     pub user_id: UserId,
     pub access_token: String,
+    pub refresh_token: String,
+    /// アクセストークンの有効期間（秒）
+    pub expires_in: u64,
+}
+
+impl From<AuthToken> for AccessTokenResponse {
+    fn from(value: AuthToken) -> Self {
+        let AuthToken {
+            user_id,
+            access_token,
+            refresh_token,
+            expires_in,
+        } = value;
+        Self {
+            user_id,
+            access_token: access_token.0,
+            refresh_token: refresh_token.0,
+            expires_in,
+        }
+    }
+}
+
+/// ログインの結果。2 段階認証が必要な場合は、トークンの代わりにチャレンジを返す。
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Authenticated(AccessTokenResponse),
+    TwoFactorRequired(TwoFactorChallengeResponse),
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorChallengeResponse {
+    pub challenge_token: String,
+    /// `true` の場合は、コードを確認する前に `/auth/2fa/enroll` で認証アプリを登録する
+    pub enrollment_required: bool,
+    /// チャレンジの有効期間（秒）
+    pub expires_in: u64,
+}
+
+impl From<TwoFactorChallenge> for TwoFactorChallengeResponse {
+    fn from(value: TwoFactorChallenge) -> Self {
+        let TwoFactorChallenge {
+            challenge_token,
+            enrollment_required,
+            expires_in,
+        } = value;
+        Self {
+            challenge_token,
+            enrollment_required,
+            expires_in,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorEnrollChallengeRequest {
+    pub challenge_token: String,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyTwoFactorRequest {
+    pub challenge_token: String,
+    /// 認証アプリに表示されたコード、またはリカバリーコード
+    pub code: String,
+}
+
+impl From<VerifyTwoFactorRequest> for VerifyTwoFactorChallenge {
+    fn from(value: VerifyTwoFactorRequest) -> Self {
+        let VerifyTwoFactorRequest {
+            challenge_token,
+            code,
+        } = value;
+        VerifyTwoFactorChallenge::new(challenge_token, code)
+    }
 }
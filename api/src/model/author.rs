@@ -0,0 +1,122 @@
+use garde::Validate;
+use kernel::model::{
+    author::{
+        event::{AuthorRef, BookAuthorEntry},
+        Author, AuthorRole, BookAuthor,
+    },
+    id::AuthorId,
+};
+use serde::{Deserialize, Serialize};
+
+use super::book::PaginatedBookResponse;
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorRoleName {
+    #[default]
+    Author,
+    Editor,
+    Translator,
+    Illustrator,
+}
+
+impl From<AuthorRole> for AuthorRoleName {
+    fn from(value: AuthorRole) -> Self {
+        match value {
+            AuthorRole::Author => Self::Author,
+            AuthorRole::Editor => Self::Editor,
+            AuthorRole::Translator => Self::Translator,
+            AuthorRole::Illustrator => Self::Illustrator,
+        }
+    }
+}
+
+impl From<AuthorRoleName> for AuthorRole {
+    fn from(value: AuthorRoleName) -> Self {
+        match value {
+            AuthorRoleName::Author => Self::Author,
+            AuthorRoleName::Editor => Self::Editor,
+            AuthorRoleName::Translator => Self::Translator,
+            AuthorRoleName::Illustrator => Self::Illustrator,
+        }
+    }
+}
+
+/// 蔵書に関わった人物を、登録済みの著者の ID か名前で指定する。両方ある場合は ID を使う。
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BookAuthorRequest {
+    #[garde(skip)]
+    pub id: Option<AuthorId>,
+    #[garde(custom(require_id_or_name(&self.id)), inner(length(min = 1, max = 255)))]
+    pub name: Option<String>,
+    #[garde(skip)]
+    #[serde(default)]
+    pub role: AuthorRoleName,
+}
+
+fn require_id_or_name(
+    id: &Option<AuthorId>,
+) -> impl FnOnce(&Option<String>, &()) -> garde::Result + '_ {
+    move |name, _| match (id, name) {
+        (None, None) => Err(garde::Error::new("either id or name is required")),
+        _ => Ok(()),
+    }
+}
+
+impl From<BookAuthorRequest> for BookAuthorEntry {
+    fn from(value: BookAuthorRequest) -> Self {
+        let BookAuthorRequest { id, name, role } = value;
+
+        let author = match id {
+            Some(id) => AuthorRef::Id(id),
+            None => AuthorRef::Name(name.unwrap_or_default()),
+        };
+
+        Self::new(author, role.into())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BookAuthorResponse {
+    pub id: AuthorId,
+    pub name: String,
+    pub role: AuthorRoleName,
+}
+
+impl From<BookAuthor> for BookAuthorResponse {
+    fn from(value: BookAuthor) -> Self {
+        let BookAuthor { id, name, role } = value;
+
+        Self {
+            id,
+            name,
+            role: role.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorResponse {
+    pub id: AuthorId,
+    pub name: String,
+    /// 著者・編者・訳者などとして関わった蔵書
+    pub books: PaginatedBookResponse,
+}
+
+impl AuthorResponse {
+    pub fn new(author: Author, books: PaginatedBookResponse) -> Self {
+        let Author { id, name } = author;
+
+        Self { id, name, books }
+    }
+}
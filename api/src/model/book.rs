@@ -2,39 +2,123 @@ use chrono::{DateTime, Utc};
 use derive_new::new;
 use garde::Validate;
 use kernel::model::{
+    author::event::BookAuthorEntry,
     book::{
-        event::{CreateBook, UpdateBook},
-        Book, BookListOptions, Checkout,
+        event::{CreateBook, CreateBookCopy, UpdateBook, UpdateBookCopy},
+        isbn::normalize_isbn,
+        Book, BookCopy, BookFacets, BookListOptions, Checkout, CheckoutStatus, CopyCondition,
+        OwnerFacet,
     },
-    id::{BookId, CheckoutId, UserId},
+    catalog::CatalogEntry,
+    id::{AuthorId, BookCopyId, BookId, CheckoutId, CollectionId, TagId, UserId},
     list::PaginatedList,
 };
 use serde::{Deserialize, Serialize};
 
-use super::user::{BookOwner, CheckoutUser};
+use super::{
+    author::{BookAuthorRequest, BookAuthorResponse},
+    tag::TagResponse,
+    user::{BookOwner, CheckoutUser},
+};
 
 #[cfg(debug_assertions)]
 use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 #[cfg_attr(debug_assertions, derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct CreateBookRequest {
     #[garde(length(min = 1))]
+    #[serde(default)]
     pub title: String,
-    #[garde(length(min = 1))]
+    /// 表示用の著者。`authors` を指定しない場合は、`,` や `、` で区切って著者を登録する
+    #[garde(custom(require_author(&self.authors)))]
+    #[serde(default)]
     pub author: String,
-    #[garde(length(min = 1))]
+    #[garde(dive)]
+    #[serde(default)]
+    pub authors: Vec<BookAuthorRequest>,
+    #[garde(custom(validate_isbn))]
     pub isbn: String,
     #[garde(skip)]
+    #[serde(default)]
     pub description: String,
 }
 
+impl CreateBookRequest {
+    /// 空欄のままの項目だけを書誌情報で埋める。入力済みの項目は書き換えない。
+    pub fn fill_missing(&mut self, entry: CatalogEntry) {
+        let fill = |field: &mut String, value: String| {
+            if field.trim().is_empty() {
+                *field = value;
+            }
+        };
+        fill(&mut self.title, entry.title);
+        if self.authors.is_empty() {
+            fill(&mut self.author, entry.author);
+        }
+        fill(&mut self.description, entry.description);
+    }
+}
+
+impl From<CatalogEntry> for CreateBookRequest {
+    fn from(value: CatalogEntry) -> Self {
+        let CatalogEntry {
+            isbn,
+            title,
+            author,
+            description,
+        } = value;
+
+        Self {
+            title,
+            author,
+            authors: vec![],
+            isbn,
+            description,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterBookQuery {
+    /// ISBN から書誌情報を引き、空欄の項目を埋めてから登録する
+    #[serde(default)]
+    pub autofill: bool,
+}
+
+/// ISBN-10 または ISBN-13 としてチェックディジットが正しいかを検証する。
+pub(crate) fn validate_isbn(value: &str, _context: &()) -> garde::Result {
+    match normalize_isbn(value) {
+        Some(_) => Ok(()),
+        None => Err(garde::Error::new("invalid ISBN-10 or ISBN-13")),
+    }
+}
+
+/// 著者は、表示用の文字列と人物の並びの少なくとも一方で指定する。
+fn require_author(
+    authors: &[BookAuthorRequest],
+) -> impl FnOnce(&String, &()) -> garde::Result + '_ {
+    move |author, _| {
+        if author.trim().is_empty() && authors.is_empty() {
+            return Err(garde::Error::new("either author or authors is required"));
+        }
+        Ok(())
+    }
+}
+
+/// 検証済みの ISBN をハイフンを含まない ISBN-13 に揃える。
+fn to_isbn13(isbn: String) -> String {
+    normalize_isbn(&isbn).unwrap_or(isbn)
+}
+
 impl From<CreateBookRequest> for CreateBook {
     fn from(value: CreateBookRequest) -> Self {
         let CreateBookRequest {
             title,
             author,
+            authors,
             isbn,
             description,
         } = value;
@@ -42,21 +126,27 @@ impl From<CreateBookRequest> for CreateBook {
         Self {
             title,
             author,
-            isbn,
+            authors: authors.into_iter().map(BookAuthorEntry::from).collect(),
+            isbn: to_isbn13(isbn),
             description,
         }
     }
 }
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 #[cfg_attr(debug_assertions, derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateBookRequest {
     #[garde(length(min = 1))]
     pub title: String,
-    #[garde(length(min = 1))]
+    /// 表示用の著者。`authors` を指定しない場合は、`,` や `、` で区切って著者を登録し直す
+    #[garde(custom(require_author(&self.authors)))]
+    #[serde(default)]
     pub author: String,
-    #[garde(length(min = 1))]
+    #[garde(dive)]
+    #[serde(default)]
+    pub authors: Vec<BookAuthorRequest>,
+    #[garde(custom(validate_isbn))]
     pub isbn: String,
     #[garde(skip)]
     pub description: String,
@@ -73,6 +163,7 @@ impl From<UpdateBookRequestWithIds> for UpdateBook {
             UpdateBookRequest {
                 title,
                 author,
+                authors,
                 isbn,
                 description,
             },
@@ -82,15 +173,18 @@ impl From<UpdateBookRequestWithIds> for UpdateBook {
             book_id,
             title,
             author,
-            isbn,
+            authors: authors.into_iter().map(BookAuthorEntry::from).collect(),
+            isbn: to_isbn13(isbn),
             description,
             requested_user: user_id,
+            any_owner: false,
         }
     }
 }
 
 #[derive(Debug, Deserialize, Validate)]
 #[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
 pub struct BookListQuery {
     #[garde(range(min = 0))]
     #[serde(default = "default_limit")]
@@ -98,6 +192,20 @@ pub struct BookListQuery {
     #[garde(range(min = 0))]
     #[serde(default)] // 0
     pub offset: i64,
+    #[garde(length(min = 1, max = 255))]
+    pub q: Option<String>,
+    #[garde(skip)]
+    pub owner_id: Option<UserId>,
+    #[garde(skip)]
+    pub status: Option<BookCheckoutStatus>,
+    #[garde(length(min = 1, max = 17))]
+    pub isbn_prefix: Option<String>,
+    #[garde(skip)]
+    pub tag_id: Option<TagId>,
+    #[garde(skip)]
+    pub collection_id: Option<CollectionId>,
+    #[garde(skip)]
+    pub author_id: Option<AuthorId>,
 }
 
 const DEFAULT_LIMIT: i64 = 20;
@@ -107,9 +215,47 @@ const fn default_limit() -> i64 {
 
 impl From<BookListQuery> for BookListOptions {
     fn from(value: BookListQuery) -> Self {
-        let BookListQuery { limit, offset } = value;
+        let BookListQuery {
+            limit,
+            offset,
+            q,
+            owner_id,
+            status,
+            isbn_prefix,
+            tag_id,
+            collection_id,
+            author_id,
+        } = value;
+
+        Self {
+            limit,
+            offset,
+            query: q,
+            owner_id,
+            checkout_status: status.map(CheckoutStatus::from),
+            isbn_prefix,
+            tag_id,
+            collection_id,
+            author_id,
+            after: None,
+        }
+    }
+}
 
-        Self { limit, offset }
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BookCheckoutStatus {
+    Available,
+    CheckedOut,
+}
+
+impl From<BookCheckoutStatus> for CheckoutStatus {
+    fn from(value: BookCheckoutStatus) -> Self {
+        match value {
+            BookCheckoutStatus::Available => Self::Available,
+            BookCheckoutStatus::CheckedOut => Self::CheckedOut,
+        }
     }
 }
 
@@ -120,36 +266,205 @@ pub struct BookResponse {
     pub id: BookId,
     pub title: String,
     pub author: String,
+    pub authors: Vec<BookAuthorResponse>,
     pub isbn: String,
     pub description: String,
     pub owner: BookOwner,
-    pub checkout: Option<BookCheckoutResponse>,
+    pub total_copies: usize,
+    pub available_copies: usize,
+    pub copies: Vec<BookCopyResponse>,
+    pub tags: Vec<TagResponse>,
+    /// 表紙画像が登録されていない場合は `null`
+    pub cover_url: Option<String>,
+    pub cover_thumbnail_url: Option<String>,
 }
 
 impl From<Book> for BookResponse {
     fn from(value: Book) -> Self {
+        let total_copies = value.total_copies();
+        let available_copies = value.available_copies();
         let Book {
             id,
             title,
             author,
+            authors,
             isbn,
             description,
             owner,
-            checkout,
+            copies,
+            tags,
+            cover,
+            ..
         } = value;
 
+        // 画像を差し替えた際にキャッシュが使われないよう、登録日時をクエリに含める
+        let cover_urls = cover.map(|cover| {
+            let version = cover.updated_at.timestamp_millis();
+            (
+                format!("/api/v1/books/{id}/cover?v={version}"),
+                format!("/api/v1/books/{id}/cover/thumbnail?v={version}"),
+            )
+        });
+        let (cover_url, cover_thumbnail_url) = cover_urls.unzip();
+
         Self {
             id,
             title,
             author,
+            authors: authors.into_iter().map(BookAuthorResponse::from).collect(),
             isbn,
             description,
             owner: owner.into(),
+            total_copies,
+            available_copies,
+            copies: copies.into_iter().map(BookCopyResponse::from).collect(),
+            tags: tags.into_iter().map(TagResponse::from).collect(),
+            cover_url,
+            cover_thumbnail_url,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BookCopyResponse {
+    pub id: BookCopyId,
+    pub barcode: String,
+    pub shelf_location: String,
+    pub condition: CopyConditionName,
+    pub checkout: Option<BookCheckoutResponse>,
+}
+
+impl From<BookCopy> for BookCopyResponse {
+    fn from(value: BookCopy) -> Self {
+        let BookCopy {
+            id,
+            barcode,
+            shelf_location,
+            condition,
+            checkout,
+        } = value;
+
+        Self {
+            id,
+            barcode,
+            shelf_location,
+            condition: condition.into(),
             checkout: checkout.map(BookCheckoutResponse::from),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CopyConditionName {
+    New,
+    Good,
+    Fair,
+    Poor,
+    Damaged,
+}
+
+impl From<CopyCondition> for CopyConditionName {
+    fn from(value: CopyCondition) -> Self {
+        match value {
+            CopyCondition::New => Self::New,
+            CopyCondition::Good => Self::Good,
+            CopyCondition::Fair => Self::Fair,
+            CopyCondition::Poor => Self::Poor,
+            CopyCondition::Damaged => Self::Damaged,
+        }
+    }
+}
+
+impl From<CopyConditionName> for CopyCondition {
+    fn from(value: CopyConditionName) -> Self {
+        match value {
+            CopyConditionName::New => Self::New,
+            CopyConditionName::Good => Self::Good,
+            CopyConditionName::Fair => Self::Fair,
+            CopyConditionName::Poor => Self::Poor,
+            CopyConditionName::Damaged => Self::Damaged,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BookCopyRequest {
+    #[garde(length(min = 1, max = 64))]
+    pub barcode: String,
+    #[garde(length(max = 255))]
+    #[serde(default)]
+    pub shelf_location: String,
+    #[garde(skip)]
+    pub condition: CopyConditionName,
+}
+
+#[derive(new)]
+pub struct CreateBookCopyRequestWithIds(BookId, UserId, BookCopyRequest);
+
+impl From<CreateBookCopyRequestWithIds> for CreateBookCopy {
+    fn from(value: CreateBookCopyRequestWithIds) -> Self {
+        let CreateBookCopyRequestWithIds(
+            book_id,
+            user_id,
+            BookCopyRequest {
+                barcode,
+                shelf_location,
+                condition,
+            },
+        ) = value;
+
+        Self {
+            book_id,
+            barcode,
+            shelf_location,
+            condition: condition.into(),
+            requested_user: user_id,
+            any_owner: false,
+        }
+    }
+}
+
+#[derive(new)]
+pub struct UpdateBookCopyRequestWithIds(BookId, BookCopyId, UserId, BookCopyRequest);
+
+impl From<UpdateBookCopyRequestWithIds> for UpdateBookCopy {
+    fn from(value: UpdateBookCopyRequestWithIds) -> Self {
+        let UpdateBookCopyRequestWithIds(
+            book_id,
+            copy_id,
+            user_id,
+            BookCopyRequest {
+                barcode,
+                shelf_location,
+                condition,
+            },
+        ) = value;
+
+        Self {
+            book_id,
+            copy_id,
+            barcode,
+            shelf_location,
+            condition: condition.into(),
+            requested_user: user_id,
+            any_owner: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BookCopyCreatedResponse {
+    pub id: BookCopyId,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(debug_assertions, derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
@@ -158,6 +473,8 @@ pub struct PaginatedBookResponse {
     pub limit: i64,
     pub offset: i64,
     pub items: Vec<BookResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facets: Option<BookFacetsResponse>,
 }
 
 impl From<PaginatedList<Book>> for PaginatedBookResponse {
@@ -174,6 +491,60 @@ impl From<PaginatedList<Book>> for PaginatedBookResponse {
             limit,
             offset,
             items: items.into_iter().map(BookResponse::from).collect(),
+            facets: None,
+        }
+    }
+}
+
+impl PaginatedBookResponse {
+    pub fn with_facets(self, facets: Option<BookFacets>) -> Self {
+        Self {
+            facets: facets.map(BookFacetsResponse::from),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BookFacetsResponse {
+    pub available: i64,
+    pub checked_out: i64,
+    pub owners: Vec<OwnerFacetResponse>,
+}
+
+impl From<BookFacets> for BookFacetsResponse {
+    fn from(value: BookFacets) -> Self {
+        let BookFacets {
+            available,
+            checked_out,
+            owners,
+        } = value;
+
+        Self {
+            available,
+            checked_out,
+            owners: owners.into_iter().map(OwnerFacetResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct OwnerFacetResponse {
+    pub owner: BookOwner,
+    pub count: i64,
+}
+
+impl From<OwnerFacet> for OwnerFacetResponse {
+    fn from(value: OwnerFacet) -> Self {
+        let OwnerFacet { owner, count } = value;
+
+        Self {
+            owner: owner.into(),
+            count,
         }
     }
 }
@@ -185,20 +556,26 @@ pub struct BookCheckoutResponse {
     pub id: CheckoutId,
     pub checked_out_by: CheckoutUser,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub overdue: bool,
 }
 
 impl From<Checkout> for BookCheckoutResponse {
     fn from(value: Checkout) -> Self {
+        let overdue = value.is_overdue(Utc::now());
         let Checkout {
             checkout_id,
             checked_out_by,
             checked_out_at,
+            due_at,
         } = value;
 
         Self {
             id: checkout_id,
             checked_out_by: checked_out_by.into(),
             checked_out_at,
+            due_at,
+            overdue,
         }
     }
 }
@@ -0,0 +1,349 @@
+use std::io::BufRead;
+
+use axum::http::{header, HeaderMap};
+use kernel::model::{
+    book::Book,
+    id::{BookId, UserId},
+};
+use serde::{Deserialize, Serialize};
+use shared::error::{AppError, AppResult};
+use strum::{EnumIter, IntoEnumIterator};
+
+use super::{
+    book::CreateBookRequest,
+    interchange::{
+        dublin_core::{self, DublinCoreReader, DublinCoreRecord},
+        marc::MarcRecord,
+        marc21::{self, Marc21Reader},
+        marcxml::{self, MarcXmlReader},
+        XML_DECLARATION,
+    },
+};
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+/// 1 行または 1 レコード分の読み込みの結果。エラーの場合はその理由を返す
+pub type ParsedBookRow = Result<CreateBookRequest, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, EnumIter)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BookFileFormat {
+    /// 1 行目をヘッダーとする CSV
+    Csv,
+    /// 1 行に 1 冊分の JSON を記述した JSON Lines
+    Jsonl,
+    /// ISO 2709 の形式で連結した MARC21 のレコード
+    Marc21,
+    /// MARCXML の `collection` 要素
+    Marcxml,
+    /// `records` 要素にまとめた Dublin Core (`oai_dc`) のレコード
+    DublinCore,
+}
+
+impl BookFileFormat {
+    pub fn media_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Jsonl => "application/x-ndjson",
+            Self::Marc21 => marc21::MEDIA_TYPE,
+            Self::Marcxml => marcxml::MEDIA_TYPE,
+            Self::DublinCore => dublin_core::MEDIA_TYPE,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            _ => self.media_type(),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Jsonl => "jsonl",
+            Self::Marc21 => "mrc",
+            Self::Marcxml | Self::DublinCore => "xml",
+        }
+    }
+
+    /// `Accept` ヘッダーから応答の形式を選ぶ。
+    /// JSON を求められた場合や、対応する形式が含まれていない場合は `None` を返す。
+    pub fn from_accept(headers: &HeaderMap) -> Option<Self> {
+        let mut ranges = headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|range| {
+                let mut params = range.split(';').map(str::trim);
+                let media_type = params.next().filter(|m| !m.is_empty())?;
+                let quality = params
+                    .find_map(|p| p.strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                Some((media_type, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .collect::<Vec<_>>();
+        // 安定ソートのため、同じ優先度の場合は記述された順となる
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges.into_iter().find_map(|(media_type, _)| {
+            if matches!(media_type, "application/json" | "application/*" | "*/*") {
+                return Some(None);
+            }
+            Self::iter()
+                .find(|f| f.media_type().eq_ignore_ascii_case(media_type))
+                .map(Some)
+        })?
+    }
+
+    /// 一覧を書き出す際に、先頭に 1 度だけ置く内容
+    pub fn encode_header(self) -> AppResult<Vec<u8>> {
+        match self {
+            Self::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                writer
+                    .write_record(BookExportRecord::CSV_HEADER)
+                    .map_err(encode_error)?;
+                writer.into_inner().map_err(encode_error)
+            }
+            Self::Jsonl | Self::Marc21 => Ok(Vec::new()),
+            Self::Marcxml => Ok(marcxml::collection_header().into_bytes()),
+            Self::DublinCore => Ok(dublin_core::collection_header().into_bytes()),
+        }
+    }
+
+    /// 一覧を書き出す際に、末尾に 1 度だけ置く内容
+    pub fn encode_footer(self) -> &'static [u8] {
+        match self {
+            Self::Csv | Self::Jsonl | Self::Marc21 => b"",
+            Self::Marcxml => marcxml::COLLECTION_FOOTER.as_bytes(),
+            Self::DublinCore => dublin_core::COLLECTION_FOOTER.as_bytes(),
+        }
+    }
+
+    /// 蔵書を書き出す。ヘッダーとフッターは含まないため、ページごとに分けて呼び出せる。
+    pub fn encode_books<'a>(self, books: impl IntoIterator<Item = &'a Book>) -> AppResult<Vec<u8>> {
+        let mut data = Vec::new();
+        match self {
+            Self::Csv => {
+                let mut writer = csv::Writer::from_writer(&mut data);
+                for book in books {
+                    writer
+                        .write_record(BookExportRecord::from(book).to_csv_record())
+                        .map_err(encode_error)?;
+                }
+                writer.flush().map_err(encode_error)?;
+            }
+            Self::Jsonl => {
+                for book in books {
+                    serde_json::to_writer(&mut data, &BookExportRecord::from(book))
+                        .map_err(encode_error)?;
+                    data.push(b'\n');
+                }
+            }
+            Self::Marc21 => {
+                for book in books {
+                    data.extend(marc21::encode(&MarcRecord::from(book))?);
+                }
+            }
+            Self::Marcxml => {
+                for book in books {
+                    data.extend(marcxml::encode(&MarcRecord::from(book)).into_bytes());
+                }
+            }
+            Self::DublinCore => {
+                for book in books {
+                    data.extend(dublin_core::encode(&DublinCoreRecord::from(book)).into_bytes());
+                }
+            }
+        }
+        Ok(data)
+    }
+
+    /// 1 冊分を単独の文書として書き出す。XML の形式ではコレクションで包まずにレコードの要素を返す。
+    pub fn encode_book(self, book: &Book) -> AppResult<Vec<u8>> {
+        let mut data = match self {
+            Self::Marcxml | Self::DublinCore => XML_DECLARATION.as_bytes().to_vec(),
+            _ => self.encode_header()?,
+        };
+        data.extend(self.encode_books([book])?);
+        Ok(data)
+    }
+
+    /// 少しずつ読み込みながら、1 行または 1 レコードずつ登録リクエストに変換する。
+    /// 読み込み自体に失敗した場合は以降を読めないため、そのエラーを最後に返して打ち切る。
+    pub fn read_books<R: BufRead + 'static>(
+        self,
+        reader: R,
+    ) -> Box<dyn Iterator<Item = ParsedBookRow>> {
+        match self {
+            Self::Csv => Box::new(
+                csv::ReaderBuilder::new()
+                    .trim(csv::Trim::All)
+                    .from_reader(reader)
+                    .into_deserialize::<CreateBookRequest>()
+                    .scan(false, |failed, row| {
+                        if *failed {
+                            return None;
+                        }
+                        *failed = matches!(&row, Err(e) if e.is_io_error());
+                        Some(row.map_err(|e| e.to_string()))
+                    }),
+            ),
+            Self::Jsonl => Box::new(
+                reader
+                    .lines()
+                    .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+                    .scan(false, |failed, line| {
+                        if *failed {
+                            return None;
+                        }
+                        *failed = line.is_err();
+                        Some(match line {
+                            Ok(line) => serde_json::from_str(&line).map_err(|e| e.to_string()),
+                            Err(e) => Err(e.to_string()),
+                        })
+                    }),
+            ),
+            Self::Marc21 => Box::new(Marc21Reader::new(reader).map(|r| r.map(Into::into))),
+            Self::Marcxml => Box::new(MarcXmlReader::new(reader).map(|r| r.map(Into::into))),
+            Self::DublinCore => Box::new(DublinCoreReader::new(reader).map(|r| r.map(Into::into))),
+        }
+    }
+}
+
+fn encode_error(e: impl std::fmt::Display) -> AppError {
+    AppError::ConversionEntityError(e.to_string())
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BookImportMode {
+    /// 検証のみを行い、蔵書は登録しない
+    #[default]
+    DryRun,
+    /// 検証を通過した行を登録する。エラーとなった行は登録せずに読み飛ばす
+    Commit,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookImportQuery {
+    pub format: BookFileFormat,
+    #[serde(default)]
+    pub mode: BookImportMode,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookExportQuery {
+    pub format: BookFileFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BookImportResponse {
+    pub mode: BookImportMode,
+    /// 読み込んだ行の数。CSV のヘッダー行と JSON Lines の空行は含まない
+    pub total: usize,
+    /// 登録した行の数。ドライランでは登録できる行の数となる
+    pub imported: usize,
+    pub errors: Vec<BookImportRowError>,
+}
+
+impl BookImportResponse {
+    pub fn new(mode: BookImportMode) -> Self {
+        Self {
+            mode,
+            total: 0,
+            imported: 0,
+            errors: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BookImportRowError {
+    /// 1 から始まる行番号。CSV のヘッダー行と JSON Lines の空行は数えない
+    pub row: usize,
+    pub isbn: Option<String>,
+    pub message: String,
+}
+
+/// エクスポートする 1 冊分の情報。貸出中の複本の借り手の名前も含める。
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookExportRecord {
+    pub id: BookId,
+    pub title: String,
+    pub author: String,
+    pub isbn: String,
+    pub description: String,
+    pub owner_id: UserId,
+    pub owner_name: String,
+    pub total_copies: usize,
+    pub available_copies: usize,
+    pub checked_out_by: Vec<String>,
+}
+
+impl BookExportRecord {
+    pub const CSV_HEADER: [&'static str; 10] = [
+        "id",
+        "title",
+        "author",
+        "isbn",
+        "description",
+        "owner_id",
+        "owner_name",
+        "total_copies",
+        "available_copies",
+        "checked_out_by",
+    ];
+
+    /// CSV では借り手の名前を `;` で区切って 1 つの列にまとめる。
+    pub fn to_csv_record(&self) -> [String; 10] {
+        [
+            self.id.to_string(),
+            self.title.clone(),
+            self.author.clone(),
+            self.isbn.clone(),
+            self.description.clone(),
+            self.owner_id.to_string(),
+            self.owner_name.clone(),
+            self.total_copies.to_string(),
+            self.available_copies.to_string(),
+            self.checked_out_by.join(";"),
+        ]
+    }
+}
+
+impl From<&Book> for BookExportRecord {
+    fn from(book: &Book) -> Self {
+        Self {
+            id: book.id,
+            title: book.title.clone(),
+            author: book.author.clone(),
+            isbn: book.isbn.clone(),
+            description: book.description.clone(),
+            owner_id: book.owner.id,
+            owner_name: book.owner.name.clone(),
+            total_copies: book.total_copies(),
+            available_copies: book.available_copies(),
+            checked_out_by: book
+                .copies
+                .iter()
+                .filter_map(|copy| copy.checkout.as_ref())
+                .map(|checkout| checkout.checked_out_by.name.clone())
+                .collect(),
+        }
+    }
+}
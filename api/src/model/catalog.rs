@@ -0,0 +1,10 @@
+use garde::Validate;
+use serde::Deserialize;
+
+use super::book::validate_isbn;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CatalogIsbnPath {
+    #[garde(custom(validate_isbn))]
+    pub isbn: String,
+}
@@ -1,13 +1,32 @@
 use chrono::{DateTime, Utc};
+use garde::Validate;
 use kernel::model::{
     checkout::{Checkout, CheckoutBook},
-    id::{BookId, CheckoutId, UserId},
+    id::{BookCopyId, BookId, CheckoutId, UserId},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[cfg(debug_assertions)]
 use utoipa::ToSchema;
 
+/// 貸出期間として指定できる日数の上限
+const MAX_LOAN_PERIOD_DAYS: i64 = 90;
+
+#[derive(Debug, Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CheckoutQuery {
+    /// 既定の貸出期間に代えて用いる日数。checkout:create:any の権限を持つユーザーのみ指定できる
+    #[garde(range(min = 1, max = MAX_LOAN_PERIOD_DAYS))]
+    pub loan_period_days: Option<i64>,
+    /// 他のユーザーに代わって貸し出す場合の借り手
+    #[garde(skip)]
+    pub user_id: Option<UserId>,
+    /// 借りる複本。指定しない場合は貸出中でない複本が選ばれる
+    #[garde(skip)]
+    pub copy_id: Option<BookCopyId>,
+}
+
 #[derive(Serialize)]
 #[cfg_attr(debug_assertions, derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
@@ -29,26 +48,41 @@ impl From<Vec<Checkout>> for CheckoutsResponse {
 pub struct CheckoutResponse {
     pub id: CheckoutId,
     pub checked_out_by: UserId,
+    pub processed_by: UserId,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i32,
+    pub overdue: bool,
     pub returned_at: Option<DateTime<Utc>>,
+    pub returned_by: Option<UserId>,
     pub book: CheckoutBookResponse,
 }
 
 impl From<Checkout> for CheckoutResponse {
     fn from(value: Checkout) -> Self {
+        let overdue = value.is_overdue(Utc::now());
         let Checkout {
             id,
             checked_out_at,
             checked_out_by,
+            processed_by,
+            due_at,
+            renewal_count,
             returned_at,
+            returned_by,
             book,
         } = value;
 
         Self {
             id,
             checked_out_by,
+            processed_by,
             checked_out_at,
+            due_at,
+            renewal_count,
+            overdue,
             returned_at,
+            returned_by,
             book: CheckoutBookResponse::from(book),
         }
     }
@@ -59,6 +93,7 @@ impl From<Checkout> for CheckoutResponse {
 #[serde(rename_all = "camelCase")]
 pub struct CheckoutBookResponse {
     pub id: BookId,
+    pub copy_id: Option<BookCopyId>,
     pub title: String,
     pub author: String,
     pub isbn: String,
@@ -68,6 +103,7 @@ impl From<CheckoutBook> for CheckoutBookResponse {
     fn from(value: CheckoutBook) -> Self {
         let CheckoutBook {
             book_id,
+            copy_id,
             title,
             author,
             isbn,
@@ -75,6 +111,7 @@ impl From<CheckoutBook> for CheckoutBookResponse {
 
         Self {
             id: book_id,
+            copy_id,
             title,
             author,
             isbn,
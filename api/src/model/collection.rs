@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    collection::{
+        event::{CreateCollection, UpdateCollection},
+        Collection, CollectionOwner,
+    },
+    id::{CollectionId, UserId},
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionRequest {
+    #[garde(length(min = 1, max = 255))]
+    pub name: String,
+    #[garde(skip)]
+    #[serde(default)]
+    pub description: String,
+    /// `true` の場合は、他のユーザーも参照・蔵書の出し入れができる
+    #[garde(skip)]
+    #[serde(default)]
+    pub shared: bool,
+}
+
+#[derive(new)]
+pub struct CreateCollectionRequestWithUserId(UserId, CollectionRequest);
+
+impl From<CreateCollectionRequestWithUserId> for CreateCollection {
+    fn from(value: CreateCollectionRequestWithUserId) -> Self {
+        let CreateCollectionRequestWithUserId(
+            user_id,
+            CollectionRequest {
+                name,
+                description,
+                shared,
+            },
+        ) = value;
+
+        Self {
+            name,
+            description,
+            shared,
+            requested_user: user_id,
+        }
+    }
+}
+
+#[derive(new)]
+pub struct UpdateCollectionRequestWithIds(CollectionId, UserId, CollectionRequest);
+
+impl From<UpdateCollectionRequestWithIds> for UpdateCollection {
+    fn from(value: UpdateCollectionRequestWithIds) -> Self {
+        let UpdateCollectionRequestWithIds(
+            collection_id,
+            user_id,
+            CollectionRequest {
+                name,
+                description,
+                shared,
+            },
+        ) = value;
+
+        Self {
+            collection_id,
+            name,
+            description,
+            shared,
+            requested_user: user_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionsResponse {
+    pub items: Vec<CollectionResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionResponse {
+    pub id: CollectionId,
+    pub name: String,
+    pub description: String,
+    pub owner: CollectionOwnerResponse,
+    pub shared: bool,
+    pub book_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Collection> for CollectionResponse {
+    fn from(value: Collection) -> Self {
+        let Collection {
+            id,
+            name,
+            description,
+            owner,
+            shared,
+            book_count,
+            created_at,
+            updated_at,
+        } = value;
+
+        Self {
+            id,
+            name,
+            description,
+            owner: owner.into(),
+            shared,
+            book_count,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionOwnerResponse {
+    pub id: UserId,
+    pub name: String,
+}
+
+impl From<CollectionOwner> for CollectionOwnerResponse {
+    fn from(value: CollectionOwner) -> Self {
+        let CollectionOwner { id, name } = value;
+
+        Self { id, name }
+    }
+}
@@ -0,0 +1,68 @@
+use std::io::Cursor;
+
+use image::{ImageError, ImageFormat, ImageReader, Limits};
+use shared::error::{AppError, AppResult};
+
+/// アップロードを受け付ける表紙画像の上限のサイズ（バイト）
+pub const MAX_COVER_SIZE: usize = 5 * 1024 * 1024;
+/// 受け付ける表紙画像の幅と高さの上限（ピクセル）
+pub const MAX_COVER_DIMENSION: u32 = 4096;
+/// 画像の展開に使うメモリの上限（バイト）。上限の大きさの画像を RGBA で展開できる量とする
+const MAX_DECODE_ALLOC: u64 = MAX_COVER_DIMENSION as u64 * MAX_COVER_DIMENSION as u64 * 4;
+/// 縮小した画像の幅と高さの上限（ピクセル）。縦横比は維持する
+const THUMBNAIL_SIZE: u32 = 200;
+
+/// 検証と縮小を済ませた表紙画像。
+#[derive(Debug)]
+pub struct CoverUpload {
+    pub content_type: String,
+    pub image: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+}
+
+impl CoverUpload {
+    /// リクエストで申告された Content-Type は信用せず、データの先頭から画像の形式を判別する。
+    /// 画像の展開と縮小は重い処理のため、非同期のランタイム上では `spawn_blocking` で呼び出す。
+    pub fn from_bytes(data: Vec<u8>) -> AppResult<Self> {
+        if data.len() > MAX_COVER_SIZE {
+            return Err(AppError::PayloadTooLargeError(MAX_COVER_SIZE));
+        }
+
+        let format = match image::guess_format(&data) {
+            Ok(format @ (ImageFormat::Png | ImageFormat::Jpeg)) => format,
+            _ => {
+                return Err(AppError::UnprocessableEntity(
+                    "Cover image must be PNG or JPEG".into(),
+                ))
+            }
+        };
+
+        // ファイルが小さくても大きな寸法を申告できるため、展開する前に寸法とメモリ量を制限する
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(MAX_COVER_DIMENSION);
+        limits.max_image_height = Some(MAX_COVER_DIMENSION);
+        limits.max_alloc = Some(MAX_DECODE_ALLOC);
+        let mut reader = ImageReader::with_format(Cursor::new(&data), format);
+        reader.limits(limits);
+
+        let thumbnail = reader
+            .decode()
+            .map_err(|e| match e {
+                ImageError::Limits(_) => AppError::UnprocessableEntity(format!(
+                    "Cover image must be at most {MAX_COVER_DIMENSION}x{MAX_COVER_DIMENSION} pixels"
+                )),
+                e => AppError::UnprocessableEntity(format!("Invalid cover image: {e}")),
+            })?
+            .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+        let mut encoded = Cursor::new(Vec::new());
+        thumbnail
+            .write_to(&mut encoded, format)
+            .map_err(|e| AppError::UnprocessableEntity(format!("Invalid cover image: {e}")))?;
+
+        Ok(Self {
+            content_type: format.to_mime_type().to_string(),
+            image: data,
+            thumbnail: encoded.into_inner(),
+        })
+    }
+}
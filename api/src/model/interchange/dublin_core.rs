@@ -0,0 +1,169 @@
+use std::{fmt::Write, io::BufRead};
+
+use kernel::model::book::{isbn::normalize_isbn, Book};
+use quick_xml::{escape::escape, events::Event, Reader};
+
+use super::{xml_error, XML_DECLARATION};
+use crate::model::book::CreateBookRequest;
+
+/// Dublin Core には登録されたメディアタイプがないため、慣例に従ってこの名前を使う
+pub const MEDIA_TYPE: &str = "application/dc+xml";
+pub const OAI_DC_NAMESPACE: &str = "http://www.openarchives.org/OAI/2.0/oai_dc/";
+pub const DC_NAMESPACE: &str = "http://purl.org/dc/elements/1.1/";
+
+const ISBN_URN_PREFIX: &str = "urn:isbn:";
+
+pub fn collection_header() -> String {
+    format!("{XML_DECLARATION}<records>\n")
+}
+
+pub const COLLECTION_FOOTER: &str = "</records>\n";
+
+/// OAI-PMH で使われる `oai_dc:dc` 要素の書誌情報。要素はいずれも繰り返すことができる。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DublinCoreRecord {
+    pub identifiers: Vec<String>,
+    pub titles: Vec<String>,
+    pub creators: Vec<String>,
+    pub descriptions: Vec<String>,
+}
+
+impl From<&Book> for DublinCoreRecord {
+    fn from(book: &Book) -> Self {
+        Self {
+            identifiers: vec![
+                format!("urn:uuid:{}", book.id),
+                format!("{ISBN_URN_PREFIX}{}", book.isbn),
+            ],
+            titles: vec![book.title.clone()],
+            creators: vec![book.author.clone()],
+            descriptions: Some(book.description.clone())
+                .filter(|d| !d.is_empty())
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+impl From<DublinCoreRecord> for CreateBookRequest {
+    fn from(record: DublinCoreRecord) -> Self {
+        // `urn:isbn:` の付いた識別子を優先し、なければ ISBN として読める識別子を使う
+        let isbn = record
+            .identifiers
+            .iter()
+            .find_map(|id| id.strip_prefix(ISBN_URN_PREFIX))
+            .or_else(|| {
+                record
+                    .identifiers
+                    .iter()
+                    .map(|id| id.trim_start_matches("ISBN").trim())
+                    .find(|id| normalize_isbn(id).is_some())
+            })
+            .unwrap_or_default()
+            .to_string();
+
+        Self {
+            title: record.titles.into_iter().next().unwrap_or_default(),
+            author: record.creators.join(", "),
+            authors: vec![],
+            isbn,
+            description: record.descriptions.join("\n"),
+        }
+    }
+}
+
+/// レコードを 1 つの `oai_dc:dc` 要素として書き出す。
+pub fn encode(record: &DublinCoreRecord) -> String {
+    let mut xml =
+        format!("<oai_dc:dc xmlns:oai_dc=\"{OAI_DC_NAMESPACE}\" xmlns:dc=\"{DC_NAMESPACE}\">");
+    let elements = [
+        ("title", &record.titles),
+        ("creator", &record.creators),
+        ("description", &record.descriptions),
+        ("identifier", &record.identifiers),
+    ];
+    for (name, values) in elements {
+        for value in values {
+            let _ = write!(xml, "<dc:{name}>{}</dc:{name}>", escape(value.as_str()));
+        }
+    }
+    xml.push_str("<dc:type>Text</dc:type></oai_dc:dc>\n");
+    xml
+}
+
+/// `dc` 要素を 1 件ずつ読み込む。`records` などの外側の要素の名前は問わない。
+/// XML として壊れている場合は以降を読めないため、エラーを返した後は読み込みを打ち切る。
+pub struct DublinCoreReader<R> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    finished: bool,
+}
+
+impl<R: BufRead> DublinCoreReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Reader::from_reader(reader),
+            buf: Vec::new(),
+            finished: false,
+        }
+    }
+
+    fn read_record(&mut self) -> Result<Option<DublinCoreRecord>, quick_xml::Error> {
+        let mut record: Option<DublinCoreRecord> = None;
+        let mut text = String::new();
+
+        loop {
+            self.buf.clear();
+            match self.reader.read_event_into(&mut self.buf)? {
+                Event::Start(e) => {
+                    text.clear();
+                    if e.local_name().as_ref() == b"dc" && record.is_none() {
+                        record = Some(DublinCoreRecord::default());
+                    }
+                }
+                Event::Text(e) => text.push_str(&e.unescape()?),
+                Event::CData(e) => text.push_str(&String::from_utf8_lossy(&e)),
+                Event::End(e) => {
+                    let Some(current) = record.as_mut() else {
+                        continue;
+                    };
+                    let values = match e.local_name().as_ref() {
+                        b"dc" => return Ok(record),
+                        b"identifier" => &mut current.identifiers,
+                        b"title" => &mut current.titles,
+                        b"creator" => &mut current.creators,
+                        b"description" => &mut current.descriptions,
+                        _ => continue,
+                    };
+                    let value = std::mem::take(&mut text);
+                    if !value.trim().is_empty() {
+                        values.push(value.trim().to_string());
+                    }
+                }
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for DublinCoreReader<R> {
+    type Item = Result<DublinCoreRecord, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.read_record() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(xml_error(e)))
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use kernel::model::book::Book;
+
+use crate::model::book::CreateBookRequest;
+
+/// ISO 2709 ではフィールドの長さを 4 桁で表すため、長い解題は複数の 520 に分ける
+const MAX_FIELD_CHUNK: usize = 9000;
+
+/// MARC21 の書誌レコード。ISO 2709 と MARCXML のどちらにも変換できる。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarcRecord {
+    pub leader: String,
+    pub control_fields: Vec<ControlField>,
+    pub data_fields: Vec<DataField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlField {
+    pub tag: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataField {
+    pub tag: String,
+    pub ind1: char,
+    pub ind2: char,
+    pub subfields: Vec<Subfield>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subfield {
+    pub code: char,
+    pub value: String,
+}
+
+impl DataField {
+    fn new(tag: &str, ind1: char, ind2: char, subfields: &[(char, &str)]) -> Self {
+        Self {
+            tag: tag.to_string(),
+            ind1,
+            ind2,
+            subfields: subfields
+                .iter()
+                .map(|(code, value)| Subfield {
+                    code: *code,
+                    value: value.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn subfield(&self, code: char) -> Option<&str> {
+        self.subfields
+            .iter()
+            .find(|s| s.code == code)
+            .map(|s| s.value.as_str())
+    }
+}
+
+impl MarcRecord {
+    /// 新規・単行書・UTF-8 のリーダー。長さと開始位置は ISO 2709 に書き出す際に埋める。
+    pub const DEFAULT_LEADER: &'static str = "00000nam a2200000   4500";
+
+    fn fields<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a DataField> {
+        self.data_fields.iter().filter(move |f| f.tag == tag)
+    }
+
+    fn first_subfield(&self, tags: &[&str], code: char) -> Option<&str> {
+        tags.iter().find_map(|tag| {
+            self.data_fields
+                .iter()
+                .filter(|f| f.tag == *tag)
+                .find_map(|f| f.subfield(code))
+        })
+    }
+}
+
+impl From<&Book> for MarcRecord {
+    fn from(book: &Book) -> Self {
+        let mut data_fields = vec![
+            DataField::new("020", ' ', ' ', &[('a', &book.isbn)]),
+            DataField::new("100", '1', ' ', &[('a', &book.author)]),
+            DataField::new("245", '1', '0', &[('a', &book.title)]),
+        ];
+        data_fields.extend(
+            split_at_char_boundary(&book.description, MAX_FIELD_CHUNK)
+                .map(|chunk| DataField::new("520", ' ', ' ', &[('a', chunk)])),
+        );
+
+        Self {
+            leader: Self::DEFAULT_LEADER.to_string(),
+            control_fields: vec![ControlField {
+                tag: "001".to_string(),
+                value: book.id.to_string(),
+            }],
+            data_fields,
+        }
+    }
+}
+
+impl From<MarcRecord> for CreateBookRequest {
+    /// 取り込みに必要な項目だけを取り出す。項目が欠けていても、ここでは空欄のままにして検証に任せる。
+    fn from(record: MarcRecord) -> Self {
+        let title = match record.fields("245").next() {
+            Some(field) => [field.subfield('a'), field.subfield('b')]
+                .into_iter()
+                .flatten()
+                .map(trim_isbd_punctuation)
+                .collect::<Vec<_>>()
+                .join(" : "),
+            None => String::new(),
+        };
+        let author = record
+            .first_subfield(&["100", "110", "700"], 'a')
+            .map(|v| v.trim().trim_end_matches(',').to_string())
+            .unwrap_or_default();
+        // 020$a には「9784065369579 (pbk.)」のように付記が続くことがある
+        let isbn = record
+            .first_subfield(&["020"], 'a')
+            .and_then(|v| v.split_whitespace().next())
+            .unwrap_or_default()
+            .to_string();
+        let description = record
+            .fields("520")
+            .filter_map(|f| f.subfield('a'))
+            .collect::<String>();
+
+        Self {
+            title,
+            author,
+            authors: vec![],
+            isbn,
+            description,
+        }
+    }
+}
+
+/// 目録規則で項目の末尾に付ける「 /」「 :」などの区切り記号を取り除く。
+fn trim_isbd_punctuation(value: &str) -> &str {
+    let value = value.trim();
+    [" /", " :", " ;", " ="]
+        .iter()
+        .find_map(|p| value.strip_suffix(p))
+        .unwrap_or(value)
+        .trim_end()
+}
+
+fn split_at_char_boundary(value: &str, max_len: usize) -> impl Iterator<Item = &str> {
+    let mut rest = value;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let mut end = rest.len().min(max_len);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+        Some(chunk)
+    })
+}
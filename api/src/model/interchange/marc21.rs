@@ -0,0 +1,216 @@
+use std::io::{ErrorKind, Read};
+
+use shared::error::{AppError, AppResult};
+
+use super::marc::{ControlField, DataField, MarcRecord, Subfield};
+
+pub const MEDIA_TYPE: &str = "application/marc";
+
+const SUBFIELD_DELIMITER: u8 = 0x1f;
+const FIELD_TERMINATOR: u8 = 0x1e;
+const RECORD_TERMINATOR: u8 = 0x1d;
+const LEADER_LEN: usize = 24;
+const DIRECTORY_ENTRY_LEN: usize = 12;
+const MAX_RECORD_LEN: usize = 99999;
+
+/// レコードを ISO 2709 の形式で書き出す。
+pub fn encode(record: &MarcRecord) -> AppResult<Vec<u8>> {
+    let mut directory = Vec::new();
+    let mut data = Vec::new();
+
+    let control_fields = record
+        .control_fields
+        .iter()
+        .map(|f| (&f.tag, f.value.as_bytes().to_vec()));
+    let data_fields = record.data_fields.iter().map(|f| {
+        let mut bytes = Vec::new();
+        let mut buf = [0; 4];
+        bytes.extend_from_slice(f.ind1.encode_utf8(&mut buf).as_bytes());
+        bytes.extend_from_slice(f.ind2.encode_utf8(&mut buf).as_bytes());
+        for subfield in &f.subfields {
+            bytes.push(SUBFIELD_DELIMITER);
+            bytes.extend_from_slice(subfield.code.encode_utf8(&mut buf).as_bytes());
+            bytes.extend_from_slice(subfield.value.as_bytes());
+        }
+        (&f.tag, bytes)
+    });
+
+    for (tag, mut field) in control_fields.chain(data_fields) {
+        field.push(FIELD_TERMINATOR);
+        if field.len() > 9999 {
+            return Err(AppError::ConversionEntityError(format!(
+                "MARC field {tag} is too long"
+            )));
+        }
+        directory
+            .extend_from_slice(format!("{tag:0>3}{:04}{:05}", field.len(), data.len()).as_bytes());
+        data.extend_from_slice(&field);
+    }
+    directory.push(FIELD_TERMINATOR);
+
+    let base_address = LEADER_LEN + directory.len();
+    let record_len = base_address + data.len() + 1;
+    if record_len > MAX_RECORD_LEN {
+        return Err(AppError::ConversionEntityError(
+            "MARC record is too long".into(),
+        ));
+    }
+
+    let leader = normalize_leader(&record.leader);
+    let mut bytes = Vec::with_capacity(record_len);
+    bytes.extend_from_slice(format!("{record_len:05}").as_bytes());
+    bytes.extend_from_slice(&leader.as_bytes()[5..12]);
+    bytes.extend_from_slice(format!("{base_address:05}").as_bytes());
+    bytes.extend_from_slice(&leader.as_bytes()[17..]);
+    bytes.extend_from_slice(&directory);
+    bytes.extend_from_slice(&data);
+    bytes.push(RECORD_TERMINATOR);
+
+    Ok(bytes)
+}
+
+/// 24 バイトの ASCII でないリーダーは既定のものに置き換える。
+fn normalize_leader(leader: &str) -> &str {
+    if leader.len() == LEADER_LEN && leader.is_ascii() {
+        leader
+    } else {
+        MarcRecord::DEFAULT_LEADER
+    }
+}
+
+/// ISO 2709 の形式の 1 レコード分を読み込む。
+pub fn decode(bytes: &[u8]) -> Result<MarcRecord, String> {
+    let invalid = |reason: &str| format!("invalid MARC21 record: {reason}");
+
+    if bytes.len() < LEADER_LEN + 1 || !bytes[..LEADER_LEN].is_ascii() {
+        return Err(invalid("leader is broken"));
+    }
+    let leader = String::from_utf8_lossy(&bytes[..LEADER_LEN]).into_owned();
+    let base_address: usize = leader[12..17]
+        .parse()
+        .map_err(|_| invalid("base address is not a number"))?;
+    let directory = bytes
+        .get(LEADER_LEN..base_address.saturating_sub(1))
+        .filter(|d| d.len() % DIRECTORY_ENTRY_LEN == 0)
+        .ok_or_else(|| invalid("directory is broken"))?;
+
+    let mut record = MarcRecord {
+        leader,
+        ..Default::default()
+    };
+    for entry in directory.chunks(DIRECTORY_ENTRY_LEN) {
+        // 位置で切り出すため、マルチバイト文字を含むエントリーは壊れているものとして扱う
+        let entry = std::str::from_utf8(entry)
+            .ok()
+            .filter(|e| e.is_ascii())
+            .ok_or_else(|| invalid("directory is broken"))?;
+        let tag = entry[..3].to_string();
+        let (len, start): (usize, usize) = entry[3..7]
+            .parse()
+            .ok()
+            .zip(entry[7..12].parse().ok())
+            .ok_or_else(|| invalid("directory is broken"))?;
+        let field = bytes
+            .get(base_address + start..base_address + start + len)
+            .ok_or_else(|| invalid("field is out of range"))?;
+        let field = field.strip_suffix(&[FIELD_TERMINATOR]).unwrap_or(field);
+        let field = String::from_utf8_lossy(field);
+
+        if tag.starts_with("00") {
+            record.control_fields.push(ControlField {
+                tag,
+                value: field.into_owned(),
+            });
+            continue;
+        }
+
+        let mut parts = field.split(SUBFIELD_DELIMITER as char);
+        let mut indicators = parts.next().unwrap_or_default().chars();
+        record.data_fields.push(DataField {
+            tag,
+            ind1: indicators.next().unwrap_or(' '),
+            ind2: indicators.next().unwrap_or(' '),
+            subfields: parts
+                .filter_map(|part| {
+                    let mut chars = part.chars();
+                    chars.next().map(|code| Subfield {
+                        code,
+                        value: chars.as_str().to_string(),
+                    })
+                })
+                .collect(),
+        });
+    }
+
+    Ok(record)
+}
+
+/// 連結された ISO 2709 のレコードを 1 件ずつ読み込む。
+/// レコード長が読めない場合は次のレコードの位置もわからないため、エラーを返した後は読み込みを打ち切る。
+pub struct Marc21Reader<R> {
+    reader: R,
+    finished: bool,
+}
+
+impl<R: Read> Marc21Reader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            finished: false,
+        }
+    }
+
+    fn read_record(&mut self) -> Result<Option<Vec<u8>>, String> {
+        // レコードの間に改行を挟むファイルもあるため、先頭の空白は読み飛ばす
+        let mut first = [0; 1];
+        loop {
+            match self.reader.read(&mut first) {
+                Ok(0) => return Ok(None),
+                Ok(_) if first[0].is_ascii_whitespace() => continue,
+                Ok(_) => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+
+        let mut len = [0; 5];
+        len[0] = first[0];
+        self.reader
+            .read_exact(&mut len[1..])
+            .map_err(|e| e.to_string())?;
+        let len: usize = std::str::from_utf8(&len)
+            .ok()
+            .and_then(|len| len.parse().ok())
+            .filter(|len| *len > LEADER_LEN)
+            .ok_or_else(|| "invalid MARC21 record: record length is broken".to_string())?;
+
+        let mut bytes = vec![0; len];
+        bytes[..5].copy_from_slice(&format!("{len:05}").into_bytes());
+        self.reader
+            .read_exact(&mut bytes[5..])
+            .map_err(|e| e.to_string())?;
+
+        Ok(Some(bytes))
+    }
+}
+
+impl<R: Read> Iterator for Marc21Reader<R> {
+    type Item = Result<MarcRecord, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.read_record() {
+            Ok(Some(bytes)) => Some(decode(&bytes)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
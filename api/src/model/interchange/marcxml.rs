@@ -0,0 +1,165 @@
+use std::{fmt::Write, io::BufRead};
+
+use quick_xml::{escape::escape, events::Event, Reader};
+
+use super::{
+    marc::{ControlField, DataField, MarcRecord, Subfield},
+    xml_attribute, xml_error, XML_DECLARATION,
+};
+
+pub const MEDIA_TYPE: &str = "application/marcxml+xml";
+pub const NAMESPACE: &str = "http://www.loc.gov/MARC21/slim";
+
+pub fn collection_header() -> String {
+    format!("{XML_DECLARATION}<collection xmlns=\"{NAMESPACE}\">\n")
+}
+
+pub const COLLECTION_FOOTER: &str = "</collection>\n";
+
+/// レコードを 1 つの `record` 要素として書き出す。コレクションに含めても単独でも使えるよう、名前空間を宣言する。
+pub fn encode(record: &MarcRecord) -> String {
+    let mut xml = format!("<record xmlns=\"{NAMESPACE}\">");
+    let _ = write!(xml, "<leader>{}</leader>", escape(record.leader.as_str()));
+    for field in &record.control_fields {
+        let _ = write!(
+            xml,
+            "<controlfield tag=\"{}\">{}</controlfield>",
+            escape(field.tag.as_str()),
+            escape(field.value.as_str())
+        );
+    }
+    for field in &record.data_fields {
+        let _ = write!(
+            xml,
+            "<datafield tag=\"{}\" ind1=\"{}\" ind2=\"{}\">",
+            escape(field.tag.as_str()),
+            escape(field.ind1.to_string()),
+            escape(field.ind2.to_string())
+        );
+        for subfield in &field.subfields {
+            let _ = write!(
+                xml,
+                "<subfield code=\"{}\">{}</subfield>",
+                escape(subfield.code.to_string()),
+                escape(subfield.value.as_str())
+            );
+        }
+        xml.push_str("</datafield>");
+    }
+    xml.push_str("</record>\n");
+    xml
+}
+
+/// `collection` 要素に含まれる、または単独の `record` 要素を 1 件ずつ読み込む。
+/// XML として壊れている場合は以降を読めないため、エラーを返した後は読み込みを打ち切る。
+pub struct MarcXmlReader<R> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    finished: bool,
+}
+
+impl<R: BufRead> MarcXmlReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Reader::from_reader(reader),
+            buf: Vec::new(),
+            finished: false,
+        }
+    }
+
+    fn read_record(&mut self) -> Result<Option<MarcRecord>, quick_xml::Error> {
+        let mut record: Option<MarcRecord> = None;
+        let mut text = String::new();
+
+        loop {
+            self.buf.clear();
+            match self.reader.read_event_into(&mut self.buf)? {
+                Event::Start(e) => {
+                    text.clear();
+                    match (e.local_name().as_ref(), record.as_mut()) {
+                        (b"record", None) => record = Some(MarcRecord::default()),
+                        (b"datafield", Some(record)) => {
+                            let indicator = |name| {
+                                xml_attribute(&e, name)
+                                    .map(|v| v.and_then(|v| v.chars().next()).unwrap_or(' '))
+                            };
+                            record.data_fields.push(DataField {
+                                tag: xml_attribute(&e, "tag")?.unwrap_or_default(),
+                                ind1: indicator("ind1")?,
+                                ind2: indicator("ind2")?,
+                                subfields: Vec::new(),
+                            });
+                        }
+                        (b"controlfield", Some(record)) => {
+                            record.control_fields.push(ControlField {
+                                tag: xml_attribute(&e, "tag")?.unwrap_or_default(),
+                                value: String::new(),
+                            });
+                        }
+                        (b"subfield", Some(record)) => {
+                            let code = xml_attribute(&e, "code")?
+                                .and_then(|v| v.chars().next())
+                                .unwrap_or(' ');
+                            if let Some(field) = record.data_fields.last_mut() {
+                                field.subfields.push(Subfield {
+                                    code,
+                                    value: String::new(),
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Event::Text(e) => text.push_str(&e.unescape()?),
+                Event::CData(e) => text.push_str(&String::from_utf8_lossy(&e)),
+                Event::End(e) => {
+                    let Some(current) = record.as_mut() else {
+                        continue;
+                    };
+                    match e.local_name().as_ref() {
+                        b"record" => return Ok(record),
+                        b"leader" => current.leader = std::mem::take(&mut text),
+                        b"controlfield" => {
+                            if let Some(field) = current.control_fields.last_mut() {
+                                field.value = std::mem::take(&mut text);
+                            }
+                        }
+                        b"subfield" => {
+                            if let Some(subfield) = current
+                                .data_fields
+                                .last_mut()
+                                .and_then(|f| f.subfields.last_mut())
+                            {
+                                subfield.value = std::mem::take(&mut text);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for MarcXmlReader<R> {
+    type Item = Result<MarcRecord, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.read_record() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(xml_error(e)))
+            }
+        }
+    }
+}
@@ -0,0 +1,22 @@
+//! 図書館の間で書誌情報を交換するための形式。
+//! 蔵書を MARC21 (ISO 2709)・MARCXML・Dublin Core に変換し、また読み込んで登録のリクエストに変換する。
+
+use quick_xml::events::BytesStart;
+
+pub mod dublin_core;
+pub mod marc;
+pub mod marc21;
+pub mod marcxml;
+
+pub(crate) const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+
+fn xml_attribute(element: &BytesStart, name: &str) -> Result<Option<String>, quick_xml::Error> {
+    match element.try_get_attribute(name)? {
+        Some(attr) => Ok(Some(attr.unescape_value()?.into_owned())),
+        None => Ok(None),
+    }
+}
+
+fn xml_error(error: quick_xml::Error) -> String {
+    format!("invalid XML: {error}")
+}
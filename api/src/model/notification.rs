@@ -0,0 +1,124 @@
+use derive_new::new;
+use kernel::model::{
+    id::UserId,
+    notification::{
+        event::UpdateNotificationPreferences, NotificationKind, NotificationPreference,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKindName {
+    CheckedOut,
+    Returned,
+    Overdue,
+    RoleChanged,
+    PasswordReset,
+}
+
+impl From<NotificationKind> for NotificationKindName {
+    fn from(value: NotificationKind) -> Self {
+        match value {
+            NotificationKind::CheckedOut => Self::CheckedOut,
+            NotificationKind::Returned => Self::Returned,
+            NotificationKind::Overdue => Self::Overdue,
+            NotificationKind::RoleChanged => Self::RoleChanged,
+            NotificationKind::PasswordReset => Self::PasswordReset,
+        }
+    }
+}
+
+impl From<NotificationKindName> for NotificationKind {
+    fn from(value: NotificationKindName) -> Self {
+        match value {
+            NotificationKindName::CheckedOut => Self::CheckedOut,
+            NotificationKindName::Returned => Self::Returned,
+            NotificationKindName::Overdue => Self::Overdue,
+            NotificationKindName::RoleChanged => Self::RoleChanged,
+            NotificationKindName::PasswordReset => Self::PasswordReset,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferencesResponse {
+    pub items: Vec<NotificationPreferenceResponse>,
+}
+
+impl From<Vec<NotificationPreference>> for NotificationPreferencesResponse {
+    fn from(value: Vec<NotificationPreference>) -> Self {
+        Self {
+            items: value
+                .into_iter()
+                .map(NotificationPreferenceResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferenceResponse {
+    pub kind: NotificationKindName,
+    pub enabled: bool,
+}
+
+impl From<NotificationPreference> for NotificationPreferenceResponse {
+    fn from(value: NotificationPreference) -> Self {
+        let NotificationPreference { kind, enabled } = value;
+
+        Self {
+            kind: kind.into(),
+            enabled,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotificationPreferencesRequest {
+    pub items: Vec<UpdateNotificationPreferenceItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotificationPreferenceItem {
+    pub kind: NotificationKindName,
+    pub enabled: bool,
+}
+
+#[derive(new)]
+pub struct UpdateNotificationPreferencesRequestWithUserId(
+    UserId,
+    UpdateNotificationPreferencesRequest,
+);
+
+impl From<UpdateNotificationPreferencesRequestWithUserId> for UpdateNotificationPreferences {
+    fn from(value: UpdateNotificationPreferencesRequestWithUserId) -> Self {
+        let UpdateNotificationPreferencesRequestWithUserId(
+            user_id,
+            UpdateNotificationPreferencesRequest { items },
+        ) = value;
+
+        UpdateNotificationPreferences {
+            user_id,
+            preferences: items
+                .into_iter()
+                .map(|item| NotificationPreference {
+                    kind: item.kind.into(),
+                    enabled: item.enabled,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    id::RoleId,
+    role::{
+        event::{CreateRole, UpdateRole},
+        Permission, Role,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+pub enum PermissionName {
+    #[serde(rename = "book:write:any")]
+    BookWriteAny,
+    #[serde(rename = "checkout:create:any")]
+    CheckoutCreateAny,
+    #[serde(rename = "checkout:return:any")]
+    CheckoutReturnAny,
+    #[serde(rename = "user:manage")]
+    UserManage,
+    #[serde(rename = "role:manage")]
+    RoleManage,
+    #[serde(rename = "audit:read")]
+    AuditRead,
+}
+
+impl From<Permission> for PermissionName {
+    fn from(value: Permission) -> Self {
+        match value {
+            Permission::BookWriteAny => Self::BookWriteAny,
+            Permission::CheckoutCreateAny => Self::CheckoutCreateAny,
+            Permission::CheckoutReturnAny => Self::CheckoutReturnAny,
+            Permission::UserManage => Self::UserManage,
+            Permission::RoleManage => Self::RoleManage,
+            Permission::AuditRead => Self::AuditRead,
+        }
+    }
+}
+
+impl From<PermissionName> for Permission {
+    fn from(value: PermissionName) -> Self {
+        match value {
+            PermissionName::BookWriteAny => Self::BookWriteAny,
+            PermissionName::CheckoutCreateAny => Self::CheckoutCreateAny,
+            PermissionName::CheckoutReturnAny => Self::CheckoutReturnAny,
+            PermissionName::UserManage => Self::UserManage,
+            PermissionName::RoleManage => Self::RoleManage,
+            PermissionName::AuditRead => Self::AuditRead,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RolesResponse {
+    pub items: Vec<RoleResponse>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RoleResponse {
+    pub id: RoleId,
+    pub name: String,
+    pub permissions: Vec<PermissionName>,
+    /// 組み込みのロールは変更・削除できない
+    pub builtin: bool,
+}
+
+impl From<Role> for RoleResponse {
+    fn from(value: Role) -> Self {
+        let builtin = value.is_builtin();
+        let Role {
+            id,
+            name,
+            permissions,
+        } = value;
+
+        Self {
+            id,
+            name,
+            permissions: permissions.into_iter().map(PermissionName::from).collect(),
+            builtin,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRoleRequest {
+    #[garde(length(min = 1, max = 255))]
+    pub name: String,
+    #[garde(skip)]
+    pub permissions: Vec<PermissionName>,
+}
+
+impl From<CreateRoleRequest> for CreateRole {
+    fn from(value: CreateRoleRequest) -> Self {
+        let CreateRoleRequest { name, permissions } = value;
+
+        Self {
+            name,
+            permissions: permissions.into_iter().map(Permission::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRoleRequest {
+    #[garde(length(min = 1, max = 255))]
+    pub name: String,
+    #[garde(skip)]
+    pub permissions: Vec<PermissionName>,
+}
+
+#[derive(new)]
+pub struct UpdateRoleRequestWithId(RoleId, UpdateRoleRequest);
+
+impl From<UpdateRoleRequestWithId> for UpdateRole {
+    fn from(value: UpdateRoleRequestWithId) -> Self {
+        let UpdateRoleRequestWithId(role_id, UpdateRoleRequest { name, permissions }) = value;
+
+        Self {
+            role_id,
+            name,
+            permissions: permissions.into_iter().map(Permission::from).collect(),
+        }
+    }
+}
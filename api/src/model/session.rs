@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use kernel::model::{auth::Session, id::SessionId};
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SessionsResponse {
+    pub items: Vec<SessionResponse>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SessionResponse {
+    pub id: SessionId,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl From<Session> for SessionResponse {
+    fn from(value: Session) -> Self {
+        let Session {
+            id,
+            user_agent,
+            created_at,
+            last_seen_at,
+            ..
+        } = value;
+
+        Self {
+            id,
+            user_agent,
+            created_at,
+            last_seen_at,
+        }
+    }
+}
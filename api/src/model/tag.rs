@@ -0,0 +1,73 @@
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    id::TagId,
+    tag::{
+        event::{CreateTag, UpdateTag},
+        Tag,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TagRequest {
+    #[garde(length(min = 1, max = 64), custom(validate_tag_name))]
+    pub name: String,
+}
+
+/// 前後の空白だけが異なるタグが作られないよう、空白で始まる・終わる名前は受け付けない。
+fn validate_tag_name(value: &str, _context: &()) -> garde::Result {
+    if value.trim() != value {
+        return Err(garde::Error::new(
+            "tag name must not start or end with whitespace",
+        ));
+    }
+    Ok(())
+}
+
+impl From<TagRequest> for CreateTag {
+    fn from(value: TagRequest) -> Self {
+        let TagRequest { name } = value;
+
+        Self { name }
+    }
+}
+
+#[derive(new)]
+pub struct UpdateTagRequestWithId(TagId, TagRequest);
+
+impl From<UpdateTagRequestWithId> for UpdateTag {
+    fn from(value: UpdateTagRequestWithId) -> Self {
+        let UpdateTagRequestWithId(tag_id, TagRequest { name }) = value;
+
+        Self { tag_id, name }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TagsResponse {
+    pub items: Vec<TagResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TagResponse {
+    pub id: TagId,
+    pub name: String,
+}
+
+impl From<Tag> for TagResponse {
+    fn from(value: Tag) -> Self {
+        let Tag { id, name } = value;
+
+        Self { id, name }
+    }
+}
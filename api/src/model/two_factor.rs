@@ -0,0 +1,75 @@
+use garde::Validate;
+use kernel::model::auth::two_factor::{
+    TwoFactorEnrollment, TwoFactorStatus, UpdateTwoFactorPolicy,
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorStatusResponse {
+    pub enabled: bool,
+    /// ロールで 2 段階認証が必須とされている場合に `true`
+    pub required: bool,
+}
+
+impl From<TwoFactorStatus> for TwoFactorStatusResponse {
+    fn from(value: TwoFactorStatus) -> Self {
+        let TwoFactorStatus { enabled, required } = value;
+        Self { enabled, required }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorEnrollmentResponse {
+    /// Base32 でエンコードした秘密鍵。QR コードを読み取れない場合に手入力する。
+    pub secret: String,
+    pub otpauth_uri: String,
+    /// 認証アプリを使えなくなった場合に一度だけ使えるコード。再表示はできない。
+    pub recovery_codes: Vec<String>,
+}
+
+impl From<TwoFactorEnrollment> for TwoFactorEnrollmentResponse {
+    fn from(value: TwoFactorEnrollment) -> Self {
+        let TwoFactorEnrollment {
+            secret,
+            otpauth_uri,
+            recovery_codes,
+        } = value;
+        Self {
+            secret,
+            otpauth_uri,
+            recovery_codes,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorCodeRequest {
+    /// 認証アプリに表示されたコード。無効化の際はリカバリーコードも使える。
+    #[garde(length(min = 1))]
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTwoFactorPolicyRequest {
+    /// ロール名
+    pub role: String,
+    pub required: bool,
+}
+
+impl From<UpdateTwoFactorPolicyRequest> for UpdateTwoFactorPolicy {
+    fn from(value: UpdateTwoFactorPolicyRequest) -> Self {
+        let UpdateTwoFactorPolicyRequest { role, required } = value;
+        Self::new(role, required)
+    }
+}
@@ -2,44 +2,16 @@ use derive_new::new;
 use garde::Validate;
 use kernel::model::{
     id::UserId,
-    role::Role,
     user::{
         event::{CreateUser, UpdateUserPassword, UpdateUserRole},
         User,
     },
 };
 use serde::{Deserialize, Serialize};
-use strum::VariantNames;
 
 #[cfg(debug_assertions)]
 use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, VariantNames)]
-#[cfg_attr(debug_assertions, derive(ToSchema))]
-#[strum(serialize_all = "kebab-case")]
-pub enum RoleName {
-    Admin,
-    User,
-}
-
-impl From<Role> for RoleName {
-    fn from(value: Role) -> Self {
-        match value {
-            Role::Admin => Self::Admin,
-            Role::User => Self::User,
-        }
-    }
-}
-
-impl From<RoleName> for Role {
-    fn from(value: RoleName) -> Self {
-        match value {
-            RoleName::Admin => Self::Admin,
-            RoleName::User => Self::User,
-        }
-    }
-}
-
 #[derive(Serialize, Deserialize)]
 #[cfg_attr(debug_assertions, derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
@@ -54,7 +26,8 @@ pub struct UserResponse {
     pub id: UserId,
     pub name: String,
     pub email: String,
-    pub role: RoleName,
+    /// ロール名
+    pub role: String,
 }
 
 impl From<User> for UserResponse {
@@ -70,7 +43,7 @@ impl From<User> for UserResponse {
             id,
             name,
             email,
-            role: RoleName::from(role),
+            role: role.name,
         }
     }
 }
@@ -134,11 +107,12 @@ impl From<CreateUserRequest> for CreateUser {
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[cfg_attr(debug_assertions, derive(ToSchema))]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUserRoleRequest {
-    role: RoleName,
+    /// 割り当てるロールの名前
+    role: String,
 }
 
 #[derive(new)]
@@ -150,7 +124,7 @@ impl From<UpdateUserRoleRequestWithUserId> for UpdateUserRole {
 
         Self {
             user_id,
-            role: Role::from(role),
+            role_name: role,
         }
     }
 }
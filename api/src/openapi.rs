@@ -24,31 +24,133 @@ Rust でしたいと思った際に、どのようなクレートや実装を利
         handler::book::register_book,
         handler::book::update_book,
         handler::book::delete_book,
+        handler::catalog::show_catalog_entry,
+        handler::book::register_book_copy,
+        handler::book::update_book_copy,
+        handler::book::delete_book_copy,
+        handler::book::upload_book_cover,
+        handler::book::show_book_cover,
+        handler::book::show_book_cover_thumbnail,
+        handler::book_transfer::import_books,
+        handler::book_transfer::export_books,
+        handler::tag::show_tags,
+        handler::tag::create_tag,
+        handler::tag::update_tag,
+        handler::tag::delete_tag,
+        handler::tag::add_book_tag,
+        handler::tag::remove_book_tag,
+        handler::collection::show_collections,
+        handler::collection::create_collection,
+        handler::collection::show_collection,
+        handler::collection::update_collection,
+        handler::collection::delete_collection,
+        handler::collection::add_collection_book,
+        handler::collection::remove_collection_book,
+        handler::author::show_author,
         handler::checkout::checkout_book,
         handler::checkout::return_book,
+        handler::checkout::renew_checkout,
+        handler::checkout::show_overdue_list,
         handler::checkout::checkout_history,
+        handler::reservation::place_reservation,
+        handler::reservation::show_reservations,
+        handler::reservation::cancel_reservation,
         handler::user::get_current_user,
+        handler::notification::show_notification_preferences,
+        handler::notification::update_notification_preferences,
+        handler::session::show_sessions,
+        handler::session::revoke_session,
+        handler::two_factor::show_two_factor_status,
+        handler::two_factor::enroll_two_factor,
+        handler::two_factor::confirm_two_factor,
+        handler::two_factor::disable_two_factor,
+        handler::api_key::show_api_keys,
+        handler::api_key::create_api_key,
+        handler::api_key::revoke_api_key,
+        handler::audit::show_audit_logs,
         handler::auth::login,
         handler::auth::logout,
+        handler::auth::refresh,
+        handler::auth::oidc_authorize,
+        handler::auth::oidc_callback,
+        handler::auth::request_password_reset,
+        handler::auth::confirm_password_reset,
+        handler::auth::enroll_two_factor_challenge,
+        handler::auth::verify_two_factor,
     ),
     components(schemas(
         model::book::CreateBookRequest,
         model::book::UpdateBookRequest,
         model::book::BookResponse,
         model::book::PaginatedBookResponse,
+        model::book::BookCheckoutStatus,
+        model::book::BookFacetsResponse,
+        model::book::OwnerFacetResponse,
         model::book::BookCheckoutResponse,
+        model::book::BookCopyResponse,
+        model::book::BookCopyRequest,
+        model::book::BookCopyCreatedResponse,
+        model::book::CopyConditionName,
+        model::book_transfer::BookFileFormat,
+        model::book_transfer::BookImportMode,
+        model::book_transfer::BookImportResponse,
+        model::book_transfer::BookImportRowError,
+        model::tag::TagRequest,
+        model::tag::TagsResponse,
+        model::tag::TagResponse,
+        model::collection::CollectionRequest,
+        model::collection::CollectionsResponse,
+        model::collection::CollectionResponse,
+        model::collection::CollectionOwnerResponse,
+        model::author::AuthorRoleName,
+        model::author::BookAuthorRequest,
+        model::author::BookAuthorResponse,
+        model::author::AuthorResponse,
         model::checkout::CheckoutsResponse,
         model::checkout::CheckoutResponse,
         model::checkout::CheckoutBookResponse,
+        model::reservation::ReservationsResponse,
+        model::reservation::ReservationResponse,
         model::user::BookOwner,
         model::user::CheckoutUser,
         model::user::UserResponse,
-        model::user::RoleName,
+        model::notification::NotificationKindName,
+        model::notification::NotificationPreferencesResponse,
+        model::notification::NotificationPreferenceResponse,
+        model::notification::UpdateNotificationPreferencesRequest,
+        model::notification::UpdateNotificationPreferenceItem,
+        model::session::SessionsResponse,
+        model::session::SessionResponse,
+        model::two_factor::TwoFactorStatusResponse,
+        model::two_factor::TwoFactorEnrollmentResponse,
+        model::two_factor::TwoFactorCodeRequest,
+        model::api_key::ApiKeyScopeName,
+        model::api_key::CreateApiKeyRequest,
+        model::api_key::ApiKeysResponse,
+        model::api_key::ApiKeyResponse,
+        model::api_key::IssuedApiKeyResponse,
+        model::audit::PaginatedAuditLogResponse,
+        model::audit::AuditLogResponse,
+        model::audit::AuditActionName,
         model::auth::LoginRequest,
+        model::auth::RefreshTokenRequest,
+        model::auth::PasswordResetRequest,
+        model::auth::PasswordResetConfirmRequest,
         model::auth::AccessTokenResponse,
+        model::auth::LoginResponse,
+        model::auth::TwoFactorChallengeResponse,
+        model::auth::TwoFactorEnrollChallengeRequest,
+        model::auth::VerifyTwoFactorRequest,
         kernel::model::id::BookId,
         kernel::model::id::UserId,
         kernel::model::id::CheckoutId,
+        kernel::model::id::ReservationId,
+        kernel::model::id::AuditLogId,
+        kernel::model::id::SessionId,
+        kernel::model::id::ApiKeyId,
+        kernel::model::id::TagId,
+        kernel::model::id::CollectionId,
+        kernel::model::id::AuthorId,
     ))
 )]
 pub struct ApiDoc;
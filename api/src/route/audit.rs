@@ -0,0 +1,8 @@
+use axum::{routing::get, Router};
+use registry::AppRegistry;
+
+use crate::handler::audit::show_audit_logs;
+
+pub fn build_audit_routes() -> Router<AppRegistry> {
+    Router::new().route("/audit-logs", get(show_audit_logs))
+}
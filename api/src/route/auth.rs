@@ -1,12 +1,25 @@
-use axum::{routing::post, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 use registry::AppRegistry;
 
-use crate::handler::auth::{login, logout};
+use crate::handler::auth::{
+    confirm_password_reset, enroll_two_factor_challenge, login, logout, oidc_authorize,
+    oidc_callback, refresh, request_password_reset, verify_two_factor,
+};
 
 pub fn routes() -> Router<AppRegistry> {
     let auth_router = Router::new()
         .route("/login", post(login))
-        .route("/logout", post(logout));
+        .route("/logout", post(logout))
+        .route("/refresh", post(refresh))
+        .route("/oidc/authorize", get(oidc_authorize))
+        .route("/oidc/callback", get(oidc_callback))
+        .route("/password-reset/request", post(request_password_reset))
+        .route("/password-reset/confirm", post(confirm_password_reset))
+        .route("/2fa/enroll", post(enroll_two_factor_challenge))
+        .route("/2fa/verify", post(verify_two_factor));
 
     Router::new().nest("/auth", auth_router)
 }
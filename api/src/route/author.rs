@@ -0,0 +1,10 @@
+use axum::{routing::get, Router};
+use registry::AppRegistry;
+
+use crate::handler::author::show_author;
+
+pub fn build_author_routes() -> Router<AppRegistry> {
+    let authors_routers = Router::new().route("/:author_id", get(show_author));
+
+    Router::new().nest("/authors", authors_routers)
+}
@@ -1,30 +1,69 @@
 use axum::{
-    routing::{get, post, put},
+    extract::DefaultBodyLimit,
+    routing::{delete, get, post, put},
     Router,
 };
 
 use registry::AppRegistry;
 
 use crate::handler::{
-    book::{delete_book, register_book, show_book, show_book_list, update_book},
-    checkout::{checkout_book, checkout_history, return_book, show_checked_out_list},
+    book::{
+        delete_book, delete_book_copy, register_book, register_book_copy, show_book,
+        show_book_cover, show_book_cover_thumbnail, show_book_list, update_book, update_book_copy,
+        upload_book_cover,
+    },
+    book_transfer::{export_books, import_books},
+    checkout::{
+        checkout_book, checkout_history, renew_checkout, return_book, show_checked_out_list,
+        show_overdue_list,
+    },
+    reservation::{cancel_reservation, place_reservation, show_reservations},
 };
+use crate::model::cover::MAX_COVER_SIZE;
 
 pub fn build_book_routes() -> Router<AppRegistry> {
     let books_routers = Router::new()
         .route("/", post(register_book))
         .route("/", get(show_book_list))
+        .route("/import", post(import_books))
+        .route("/export", get(export_books))
         .route(
             "/:book_id",
             get(show_book).put(update_book).delete(delete_book),
         )
+        .route(
+            "/:book_id/cover",
+            put(upload_book_cover)
+                .get(show_book_cover)
+                // multipart の境界などの分だけ、画像の上限より少し大きく取る
+                .layer(DefaultBodyLimit::max(MAX_COVER_SIZE + 64 * 1024)),
+        )
+        .route("/:book_id/cover/thumbnail", get(show_book_cover_thumbnail))
+        .route("/:book_id/copies", post(register_book_copy))
+        .route(
+            "/:book_id/copies/:copy_id",
+            put(update_book_copy).delete(delete_book_copy),
+        )
         .route("/checkouts", get(show_checked_out_list))
+        .route("/checkouts/overdue", get(show_overdue_list))
         .route("/:book_id/checkouts", post(checkout_book))
         .route(
             "/:book_id/checkouts/:checkout_id/returned",
             put(return_book),
         )
-        .route("/:book_id/checkout-history", get(checkout_history));
+        .route(
+            "/:book_id/checkouts/:checkout_id/renewal",
+            put(renew_checkout),
+        )
+        .route("/:book_id/checkout-history", get(checkout_history))
+        .route(
+            "/:book_id/reservations",
+            post(place_reservation).get(show_reservations),
+        )
+        .route(
+            "/:book_id/reservations/:reservation_id",
+            delete(cancel_reservation),
+        );
 
     Router::new().nest("/books", books_routers)
 }
@@ -0,0 +1,8 @@
+use axum::{routing::get, Router};
+use registry::AppRegistry;
+
+use crate::handler::catalog::show_catalog_entry;
+
+pub fn build_catalog_routes() -> Router<AppRegistry> {
+    Router::new().route("/catalog/isbn/:isbn", get(show_catalog_entry))
+}
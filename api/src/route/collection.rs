@@ -0,0 +1,27 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+use registry::AppRegistry;
+
+use crate::handler::collection::{
+    add_collection_book, create_collection, delete_collection, remove_collection_book,
+    show_collection, show_collections, update_collection,
+};
+
+pub fn build_collection_routes() -> Router<AppRegistry> {
+    let collections_routers = Router::new()
+        .route("/", get(show_collections).post(create_collection))
+        .route(
+            "/:collection_id",
+            get(show_collection)
+                .put(update_collection)
+                .delete(delete_collection),
+        )
+        .route(
+            "/:collection_id/books/:book_id",
+            put(add_collection_book).delete(remove_collection_book),
+        );
+
+    Router::new().nest("/collections", collections_routers)
+}
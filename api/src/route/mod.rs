@@ -1,5 +1,11 @@
+pub mod audit;
 pub mod auth;
+pub mod author;
 pub mod book;
+pub mod catalog;
+pub mod collection;
 pub mod health;
+pub mod role;
+pub mod tag;
 pub mod user;
 pub mod v1;
@@ -0,0 +1,13 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+use registry::AppRegistry;
+
+use crate::handler::role::{create_role, delete_role, list_roles, update_role};
+
+pub fn build_role_routes() -> Router<AppRegistry> {
+    Router::new()
+        .route("/roles", get(list_roles).post(create_role))
+        .route("/roles/:role_id", put(update_role).delete(delete_role))
+}
@@ -0,0 +1,21 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+use registry::AppRegistry;
+
+use crate::handler::tag::{
+    add_book_tag, create_tag, delete_tag, remove_book_tag, show_tags, update_tag,
+};
+
+pub fn build_tag_routes() -> Router<AppRegistry> {
+    let tags_routers = Router::new()
+        .route("/", get(show_tags).post(create_tag))
+        .route("/:tag_id", put(update_tag).delete(delete_tag))
+        .route(
+            "/:tag_id/books/:book_id",
+            put(add_book_tag).delete(remove_book_tag),
+        );
+
+    Router::new().nest("/tags", tags_routers)
+}
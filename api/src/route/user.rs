@@ -1,12 +1,21 @@
 use axum::{
-    routing::{delete, get, put},
+    routing::{delete, get, post, put},
     Router,
 };
 use registry::AppRegistry;
 
-use crate::handler::user::{
-    change_password, change_role, delete_user, get_checkouts, get_current_user, list_users,
-    register_user,
+use crate::handler::{
+    api_key::{create_api_key, revoke_api_key, show_api_keys},
+    notification::{show_notification_preferences, update_notification_preferences},
+    session::{revoke_session, revoke_user_sessions, show_sessions},
+    two_factor::{
+        confirm_two_factor, disable_two_factor, enroll_two_factor, show_two_factor_status,
+        update_two_factor_policy,
+    },
+    user::{
+        change_password, change_role, delete_user, get_checkouts, get_current_user, list_users,
+        register_user, unlock_user,
+    },
 };
 
 pub fn build_user_routes() -> Router<AppRegistry> {
@@ -14,7 +23,28 @@ pub fn build_user_routes() -> Router<AppRegistry> {
         .route("/users/me", get(get_current_user))
         .route("/users/me/password", put(change_password))
         .route("/users/me/checkouts", get(get_checkouts))
+        .route(
+            "/users/me/notification-preferences",
+            get(show_notification_preferences).put(update_notification_preferences),
+        )
+        .route("/users/me/sessions", get(show_sessions))
+        .route("/users/me/sessions/:session_id", delete(revoke_session))
+        .route(
+            "/users/me/2fa",
+            get(show_two_factor_status)
+                .post(enroll_two_factor)
+                .delete(disable_two_factor),
+        )
+        .route("/users/me/2fa/confirm", post(confirm_two_factor))
+        .route(
+            "/users/me/api-keys",
+            get(show_api_keys).post(create_api_key),
+        )
+        .route("/users/me/api-keys/:api_key_id", delete(revoke_api_key))
+        .route("/users/2fa-policy", put(update_two_factor_policy))
         .route("/users", get(list_users).post(register_user))
         .route("/users/:user_id", delete(delete_user))
         .route("/users/:user_id/role", put(change_role))
+        .route("/users/:user_id/sessions", delete(revoke_user_sessions))
+        .route("/users/:user_id/lockout", delete(unlock_user))
 }
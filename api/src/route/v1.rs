@@ -1,13 +1,24 @@
 use axum::Router;
 use registry::AppRegistry;
 
-use super::{book::build_book_routes, health::build_health_check_routes, user::build_user_routes};
+use super::{
+    audit::build_audit_routes, author::build_author_routes, book::build_book_routes,
+    catalog::build_catalog_routes, collection::build_collection_routes,
+    health::build_health_check_routes, role::build_role_routes, tag::build_tag_routes,
+    user::build_user_routes,
+};
 
 pub fn routes() -> Router<AppRegistry> {
     let router = Router::new()
         .merge(build_health_check_routes())
         .merge(build_user_routes())
-        .merge(build_book_routes());
+        .merge(build_book_routes())
+        .merge(build_catalog_routes())
+        .merge(build_tag_routes())
+        .merge(build_collection_routes())
+        .merge(build_author_routes())
+        .merge(build_audit_routes())
+        .merge(build_role_routes());
 
     Router::new().nest("/api/v1", router)
 }
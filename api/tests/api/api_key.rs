@@ -0,0 +1,318 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_auth, fixture_registry, make_router, user_role, v1, TestRequestExt},
+};
+use api::model::api_key::IssuedApiKeyResponse;
+use kernel::{
+    model::{
+        api_key::{ApiKey, ApiKeyPrincipal, ApiKeyScope, IssuedApiKey},
+        audit::AuditAction,
+        author::Author,
+        catalog::CatalogEntry,
+        id::{ApiKeyId, UserId},
+        list::PaginatedList,
+        user::User,
+    },
+    repository::{
+        api_key::MockApiKeyRepository, audit::MockAuditRepository, author::MockAuthorRepository,
+        book::MockBookRepository, catalog::MockCatalogLookup, collection::MockCollectionRepository,
+        tag::MockTagRepository,
+    },
+};
+
+const AUTHOR_PATH: &str = "/authors/0190d1d2-5c4e-7a3b-9f6e-2d8c4b1a0e97";
+
+fn create_request(scopes: serde_json::Value, authorization: &str) -> anyhow::Result<Request<Body>> {
+    Ok(Request::post(&v1("/users/me/api-keys"))
+        .header("Authorization", authorization)
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::json!({ "name": "backup script", "scopes": scopes }).to_string(),
+        ))?)
+}
+
+/// `ApiKey valid_key` で `scopes` を持つ API キーとして認証されるようにする
+fn with_api_key(registry: &mut registry::MockAppRegistryExt, scopes: Vec<ApiKeyScope>) {
+    registry.expect_api_key_repository().returning(move || {
+        let mut mock = MockApiKeyRepository::new();
+        let scopes = scopes.clone();
+        mock.expect_authenticate()
+            .withf(|secret| secret == "valid_key")
+            .returning(move |_| {
+                Ok(Some(ApiKeyPrincipal {
+                    api_key_id: ApiKeyId::new(),
+                    user: User {
+                        id: UserId::new(),
+                        email: "dummy@example.com".to_string(),
+                        name: "dummy".to_string(),
+                        role: user_role(),
+                    },
+                    scopes: scopes.clone(),
+                }))
+            });
+        mock.expect_authenticate().returning(|_| Ok(None));
+        Arc::new(mock)
+    });
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_api_key_201(mut fixture_auth: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture_auth.expect_api_key_repository().returning(|| {
+        let mut mock = MockApiKeyRepository::new();
+        mock.expect_create()
+            .withf(|e| e.name == "backup script" && e.scopes == vec![ApiKeyScope::ReadBooks])
+            .returning(|e| {
+                Ok(IssuedApiKey {
+                    api_key: ApiKey {
+                        id: ApiKeyId::new(),
+                        user_id: e.user_id,
+                        name: e.name,
+                        prefix: "rbm_abcdefgh".into(),
+                        scopes: e.scopes,
+                        expires_at: e.expires_at,
+                        last_used_at: None,
+                        created_at: Utc::now(),
+                    },
+                    secret: "rbm_abcdefgh_secret".into(),
+                })
+            });
+        Arc::new(mock)
+    });
+    fixture_auth.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(|e| e.action == AuditAction::ApiKeyCreated && e.after.is_some())
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = create_request(serde_json::json!(["read_books"]), "Bearer dummy_token")?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, IssuedApiKeyResponse);
+    assert_eq!(result.secret, "rbm_abcdefgh_secret");
+    assert_eq!(result.api_key.prefix, "rbm_abcdefgh");
+
+    Ok(())
+}
+
+#[rstest]
+#[case(serde_json::json!(["admin"]), StatusCode::FORBIDDEN)]
+#[case(serde_json::json!([]), StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn create_api_key_with_invalid_scopes(
+    mut fixture_auth: registry::MockAppRegistryExt,
+    #[case] scopes: serde_json::Value,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_api_key_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let resp = app
+        .oneshot(create_request(scopes, "Bearer dummy_token")?)
+        .await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn api_key_cannot_manage_api_keys(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    with_api_key(&mut fixture_registry, vec![ApiKeyScope::Admin]);
+    fixture_registry.expect_audit_repository().never();
+
+    let app: axum::Router = make_router(fixture_registry);
+    let resp = app
+        .oneshot(create_request(
+            serde_json::json!(["read_books"]),
+            "ApiKey valid_key",
+        )?)
+        .await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(vec![ApiKeyScope::ReadBooks], "valid_key", StatusCode::OK)]
+#[case(vec![ApiKeyScope::Admin], "valid_key", StatusCode::OK)]
+#[case(vec![ApiKeyScope::ManageCheckouts], "valid_key", StatusCode::FORBIDDEN)]
+#[case(vec![ApiKeyScope::ReadBooks], "revoked_key", StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn show_book_list_with_api_key(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] scopes: Vec<ApiKeyScope>,
+    #[case] api_key: &str,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    with_api_key(&mut fixture_registry, scopes);
+    fixture_registry.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_all().returning(|opt| {
+            Ok(PaginatedList {
+                total: 0,
+                limit: opt.limit,
+                offset: opt.offset,
+                items: vec![],
+            })
+        });
+        Arc::new(mock)
+    });
+    // API キーで認証する場合は、アクセストークンを検証しない
+    fixture_registry.expect_auth_repository().never();
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = Request::get(&v1("/books"))
+        .header("Authorization", format!("ApiKey {api_key}"))
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/tags", vec![ApiKeyScope::ReadBooks], StatusCode::OK)]
+#[case("/tags", vec![ApiKeyScope::ManageCheckouts], StatusCode::FORBIDDEN)]
+#[case("/collections", vec![ApiKeyScope::ReadBooks], StatusCode::OK)]
+#[case("/collections", vec![ApiKeyScope::ManageCheckouts], StatusCode::FORBIDDEN)]
+#[case(AUTHOR_PATH, vec![ApiKeyScope::ReadBooks], StatusCode::OK)]
+#[case(AUTHOR_PATH, vec![ApiKeyScope::ManageCheckouts], StatusCode::FORBIDDEN)]
+#[case("/catalog/isbn/9784065369579", vec![ApiKeyScope::ReadBooks], StatusCode::OK)]
+#[case("/catalog/isbn/9784065369579", vec![ApiKeyScope::ManageCheckouts], StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn show_book_resources_with_api_key(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] scopes: Vec<ApiKeyScope>,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    with_api_key(&mut fixture_registry, scopes);
+    fixture_registry.expect_tag_repository().returning(|| {
+        let mut mock = MockTagRepository::new();
+        mock.expect_find_all().returning(|| Ok(vec![]));
+        Arc::new(mock)
+    });
+    fixture_registry
+        .expect_collection_repository()
+        .returning(|| {
+            let mut mock = MockCollectionRepository::new();
+            mock.expect_find_all().returning(|_| Ok(vec![]));
+            Arc::new(mock)
+        });
+    fixture_registry.expect_author_repository().returning(|| {
+        let mut mock = MockAuthorRepository::new();
+        mock.expect_find_by_id().returning(|author_id| {
+            Ok(Some(Author {
+                id: author_id,
+                name: "Yuki Toyoda".into(),
+            }))
+        });
+        Arc::new(mock)
+    });
+    fixture_registry.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_all().returning(|opt| {
+            Ok(PaginatedList {
+                total: 0,
+                limit: opt.limit,
+                offset: opt.offset,
+                items: vec![],
+            })
+        });
+        Arc::new(mock)
+    });
+    fixture_registry.expect_catalog_lookup().returning(|| {
+        let mut mock = MockCatalogLookup::new();
+        mock.expect_find_by_isbn().returning(|isbn| {
+            Ok(Some(CatalogEntry {
+                isbn: isbn.into(),
+                title: "RustによるWebアプリケーション開発".into(),
+                author: "Yuki Toyoda".into(),
+                description: "".into(),
+            }))
+        });
+        Arc::new(mock)
+    });
+    fixture_registry.expect_auth_repository().never();
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = Request::get(&v1(path))
+        .header("Authorization", "ApiKey valid_key")
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn checkout_with_read_only_api_key_403(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    with_api_key(&mut fixture_registry, vec![ApiKeyScope::ReadBooks]);
+    fixture_registry.expect_checkout_repository().never();
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = Request::post(&v1(&format!(
+        "/books/{}/checkouts",
+        kernel::model::id::BookId::new()
+    )))
+    .header("Authorization", "ApiKey valid_key")
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn revoke_api_key_204(mut fixture_auth: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let api_key_id = ApiKeyId::new();
+
+    fixture_auth.expect_api_key_repository().returning(move || {
+        let mut mock = MockApiKeyRepository::new();
+        mock.expect_revoke()
+            .withf(move |e| e.api_key_id == api_key_id)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+    fixture_auth.expect_audit_repository().returning(move || {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(move |e| {
+                e.action == AuditAction::ApiKeyRevoked && e.target_id == api_key_id.raw()
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::delete(&v1(&format!("/users/me/api-keys/{api_key_id}")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, fixture_admin, make_router, v1, TestRequestExt},
+};
+use api::model::audit::{AuditActionName, PaginatedAuditLogResponse};
+use kernel::{
+    model::{
+        audit::{AuditAction, AuditLog},
+        book::Book,
+        id::{AuditLogId, BookId, UserId},
+        list::PaginatedList,
+        user::BookOwner,
+    },
+    repository::{audit::MockAuditRepository, book::MockBookRepository},
+};
+
+#[rstest]
+#[tokio::test]
+async fn show_audit_logs_with_filters_200(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let actor_id = UserId::new();
+
+    fixture_admin.expect_audit_repository().returning(move || {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_find_all()
+            .withf(move |opt| opt.actor_id == Some(actor_id) && opt.from.is_some())
+            .returning(move |opt| {
+                Ok(PaginatedList {
+                    total: 1,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: vec![AuditLog {
+                        id: AuditLogId::new(),
+                        actor_id,
+                        action: AuditAction::UserRoleChanged,
+                        target_id: UserId::new().raw(),
+                        before: Some(serde_json::json!({ "role": "user" })),
+                        after: Some(serde_json::json!({ "role": "admin" })),
+                        request_id: None,
+                        occurred_at: chrono::Utc::now(),
+                    }],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_admin);
+
+    let path = format!("/audit-logs?actorId={actor_id}&from=2024-10-01T00:00:00Z");
+    let req = Request::get(&v1(&path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, PaginatedAuditLogResponse);
+    assert_eq!(result.total, 1);
+    assert_eq!(result.items[0].action, AuditActionName::UserRoleChanged);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_audit_logs_by_non_admin_403(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture
+        .expect_audit_repository()
+        .returning(|| Arc::new(MockAuditRepository::new()));
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1("/audit-logs"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn delete_book_records_audit_log(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id().returning(|id| {
+            Ok(Some(Book {
+                id,
+                title: "Rust による Web アプリケーション開発".to_string(),
+                isbn: "".to_string(),
+                author: "Yuki Toyoda".to_string(),
+                authors: vec![],
+                description: "".to_string(),
+                owner: BookOwner {
+                    id: UserId::new(),
+                    name: "radish-miyazaki".to_string(),
+                },
+                copies: vec![],
+                tags: vec![],
+                cover: None,
+                created_at: chrono::Utc::now(),
+            }))
+        });
+        mock.expect_delete().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+    fixture.expect_audit_repository().returning(move || {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(move |e| {
+                e.action == AuditAction::BookDeleted
+                    && e.target_id == book_id.raw()
+                    && e.before.is_some()
+                    && e.request_id.as_deref() == Some("req-123")
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::delete(&v1(&format!("/books/{book_id}")))
+        .bearer()
+        .header("x-request-id", "req-123")
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
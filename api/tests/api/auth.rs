@@ -0,0 +1,635 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_admin, fixture_auth, fixture_registry, make_router, v1, TestRequestExt},
+};
+use api::model::{
+    auth::{AccessTokenResponse, TwoFactorChallengeResponse},
+    two_factor::TwoFactorEnrollmentResponse,
+};
+use kernel::{
+    model::{
+        audit::AuditAction,
+        auth::{
+            oidc::{OidcAuthorizationRequest, OidcLogin},
+            two_factor::{TwoFactorChallenge, TwoFactorEnrollment},
+            AccessToken, AuthToken, RefreshToken,
+        },
+        id::UserId,
+    },
+    repository::{
+        audit::MockAuditRepository, auth::MockAuthRepository,
+        login_attempt::MockLoginAttemptRepository, oidc::MockOidcRepository,
+        password_reset::MockPasswordResetRepository, two_factor::MockTwoFactorRepository,
+    },
+};
+use shared::error::AppError;
+
+fn login_request(email: &str, password: &str) -> anyhow::Result<Request<Body>> {
+    Ok(Request::post("/auth/login")
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::json!({ "email": email, "password": password }).to_string(),
+        ))?)
+}
+
+fn post_json(path: &str, body: serde_json::Value) -> anyhow::Result<Request<Body>> {
+    Ok(Request::post(path)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))?)
+}
+
+/// 2 段階認証を要求しないユーザーとしてログインさせる
+fn without_two_factor(registry: &mut registry::MockAppRegistryExt) {
+    registry.expect_two_factor_repository().returning(|| {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_create_challenge().returning(|_| Ok(None));
+        Arc::new(mock)
+    });
+}
+
+fn refresh_request(refresh_token: &str) -> anyhow::Result<Request<Body>> {
+    Ok(Request::post("/auth/refresh")
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::json!({ "refreshToken": refresh_token }).to_string(),
+        ))?)
+}
+
+#[rstest]
+#[tokio::test]
+async fn refresh_token_200(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+            mock.expect_refresh_token()
+                .withf(|token| token.0 == "old_refresh")
+                .returning(move |_| {
+                    Ok(AuthToken {
+                        user_id,
+                        access_token: AccessToken("new_access".to_string()),
+                        refresh_token: RefreshToken("new_refresh".to_string()),
+                        expires_in: 900,
+                    })
+                });
+            Arc::new(mock)
+        });
+    fixture_registry
+        .expect_audit_repository()
+        .returning(move || {
+            let mut mock = MockAuditRepository::new();
+            mock.expect_record()
+                .withf(move |e| e.action == AuditAction::TokenRefreshed && e.actor_id == user_id)
+                .times(1)
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let resp = app.oneshot(refresh_request("old_refresh")?).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, AccessTokenResponse);
+    assert_eq!(result.user_id, user_id);
+    assert_eq!(result.access_token, "new_access");
+    assert_eq!(result.refresh_token, "new_refresh");
+    assert_eq!(result.expires_in, 900);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn refresh_token_with_revoked_token_401(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_registry.expect_auth_repository().returning(|| {
+        let mut mock = MockAuthRepository::new();
+        mock.expect_refresh_token()
+            .returning(|_| Err(AppError::UnauthorizedError));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let resp = app.oneshot(refresh_request("reused_refresh")?).await?;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn oidc_authorize_redirects_to_provider(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_registry.expect_oidc_repository().returning(|| {
+        let mut mock = MockOidcRepository::new();
+        mock.expect_create_authorization_request().returning(|| {
+            Ok(OidcAuthorizationRequest {
+                authorization_url: "https://idp.example.com/authorize?state=abc".to_string(),
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = Request::get("/auth/oidc/authorize").body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+    assert_eq!(
+        resp.headers()["location"],
+        "https://idp.example.com/authorize?state=abc"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case(true, 2)]
+#[case(false, 1)]
+#[tokio::test]
+async fn oidc_callback_200(
+    mut fixture_auth: registry::MockAppRegistryExt,
+    #[case] provisioned: bool,
+    #[case] expected_audit_logs: usize,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+
+    fixture_auth.expect_oidc_repository().returning(move || {
+        let mut mock = MockOidcRepository::new();
+        mock.expect_callback()
+            .withf(|e| e.code == "code" && e.state == "state")
+            .returning(move |_| {
+                Ok(OidcLogin {
+                    user_id,
+                    provisioned,
+                })
+            });
+        Arc::new(mock)
+    });
+    without_two_factor(&mut fixture_auth);
+    fixture_auth
+        .expect_audit_repository()
+        .times(expected_audit_logs)
+        .returning(|| {
+            let mut mock = MockAuditRepository::new();
+            mock.expect_record().returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::get("/auth/oidc/callback?code=code&state=state").body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, AccessTokenResponse);
+    assert_eq!(result.user_id, user_id);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn oidc_callback_with_invalid_state_403(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_registry.expect_oidc_repository().returning(|| {
+        let mut mock = MockOidcRepository::new();
+        mock.expect_callback()
+            .returning(|_| Err(AppError::UnauthenticatedError));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = Request::get("/auth/oidc/callback?code=code&state=unknown").body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn login_200_resets_failures(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth
+        .expect_login_attempt_repository()
+        .returning(|| {
+            let mut mock = MockLoginAttemptRepository::new();
+            mock.expect_begin()
+                .withf(|a| a.email == "alice@example.com")
+                .returning(|_| Ok(()));
+            mock.expect_record_success().times(1).returning(|_| Ok(()));
+            mock.expect_record_failure().never();
+            Arc::new(mock)
+        });
+    without_two_factor(&mut fixture_auth);
+    fixture_auth.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let resp = app
+        .oneshot(login_request("alice@example.com", "password")?)
+        .await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn login_with_two_factor_returns_challenge(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth
+        .expect_login_attempt_repository()
+        .returning(|| {
+            let mut mock = MockLoginAttemptRepository::new();
+            mock.expect_begin().returning(|_| Ok(()));
+            mock.expect_record_success().returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+    fixture_auth.expect_two_factor_repository().returning(|| {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_create_challenge().returning(|_| {
+            Ok(Some(TwoFactorChallenge {
+                challenge_token: "challenge".into(),
+                enrollment_required: false,
+                expires_in: 300,
+            }))
+        });
+        Arc::new(mock)
+    });
+    // コードを確認するまではログインしたことにならない
+    fixture_auth.expect_audit_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let resp = app
+        .oneshot(login_request("alice@example.com", "password")?)
+        .await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, serde_json::Value);
+    assert!(result.get("accessToken").is_none());
+    let challenge: TwoFactorChallengeResponse = serde_json::from_value(result)?;
+    assert_eq!(challenge.challenge_token, "challenge");
+    assert!(!challenge.enrollment_required);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn enroll_two_factor_with_challenge_200(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+
+    fixture_registry
+        .expect_two_factor_repository()
+        .returning(move || {
+            let mut mock = MockTwoFactorRepository::new();
+            mock.expect_enroll_with_challenge()
+                .withf(|token| token == "challenge")
+                .returning(move |_| {
+                    Ok((
+                        user_id,
+                        TwoFactorEnrollment {
+                            secret: "JBSWY3DPEHPK3PXP".into(),
+                            otpauth_uri:
+                                "otpauth://totp/rusty-book-manager?secret=JBSWY3DPEHPK3PXP".into(),
+                            recovery_codes: vec!["recovery-code".into()],
+                        },
+                    ))
+                });
+            Arc::new(mock)
+        });
+    fixture_registry
+        .expect_audit_repository()
+        .returning(move || {
+            let mut mock = MockAuditRepository::new();
+            mock.expect_record()
+                .withf(move |e| e.action == AuditAction::TwoFactorEnrolled && e.actor_id == user_id)
+                .times(1)
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = post_json(
+        "/auth/2fa/enroll",
+        serde_json::json!({ "challengeToken": "challenge" }),
+    )?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, TwoFactorEnrollmentResponse);
+    assert_eq!(result.secret, "JBSWY3DPEHPK3PXP");
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn verify_two_factor_200(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+
+    fixture_auth
+        .expect_two_factor_repository()
+        .returning(move || {
+            let mut mock = MockTwoFactorRepository::new();
+            mock.expect_verify_challenge()
+                .withf(|e| e.challenge_token == "challenge" && e.code == "123456")
+                .returning(move |_| Ok(user_id));
+            Arc::new(mock)
+        });
+    fixture_auth.expect_audit_repository().returning(move || {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(move |e| e.action == AuditAction::LoggedIn && e.actor_id == user_id)
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = post_json(
+        "/auth/2fa/verify",
+        serde_json::json!({ "challengeToken": "challenge", "code": "123456" }),
+    )?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, AccessTokenResponse);
+    assert_eq!(result.user_id, user_id);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn verify_two_factor_with_expired_challenge_401(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_registry
+        .expect_two_factor_repository()
+        .returning(|| {
+            let mut mock = MockTwoFactorRepository::new();
+            mock.expect_verify_challenge()
+                .returning(|_| Err(AppError::UnauthorizedError));
+            Arc::new(mock)
+        });
+    fixture_registry.expect_auth_repository().never();
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = post_json(
+        "/auth/2fa/verify",
+        serde_json::json!({ "challengeToken": "expired", "code": "123456" }),
+    )?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn login_with_wrong_password_records_failure(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_registry.expect_auth_repository().returning(|| {
+        let mut mock = MockAuthRepository::new();
+        mock.expect_verify_user()
+            .returning(|_, _| Err(AppError::UnauthenticatedError));
+        Arc::new(mock)
+    });
+    fixture_registry
+        .expect_login_attempt_repository()
+        .returning(|| {
+            let mut mock = MockLoginAttemptRepository::new();
+            mock.expect_begin().returning(|_| Ok(()));
+            mock.expect_record_failure()
+                .withf(|a| a.email == "alice@example.com")
+                .times(1)
+                .returning(|_| Ok(()));
+            mock.expect_record_success().never();
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let resp = app
+        .oneshot(login_request("alice@example.com", "wrong")?)
+        .await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(AppError::AccountLockedError(600), StatusCode::LOCKED, "600")]
+#[case(AppError::LoginThrottledError(4), StatusCode::TOO_MANY_REQUESTS, "4")]
+#[tokio::test]
+async fn login_while_throttled_is_rejected_before_verification(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] error: AppError,
+    #[case] expected: StatusCode,
+    #[case] retry_after: &str,
+) -> anyhow::Result<()> {
+    let error = std::sync::Mutex::new(Some(error));
+    fixture_registry
+        .expect_login_attempt_repository()
+        .return_once(move || {
+            let mut mock = MockLoginAttemptRepository::new();
+            mock.expect_begin()
+                .return_once(move |_| Err(error.lock().unwrap().take().unwrap()));
+            Arc::new(mock)
+        });
+    fixture_registry.expect_auth_repository().never();
+
+    let app: axum::Router = make_router(fixture_registry);
+    let resp = app
+        .oneshot(login_request("alice@example.com", "password")?)
+        .await?;
+    assert_eq!(resp.status(), expected);
+    assert_eq!(resp.headers()["retry-after"], retry_after);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn unlock_user_204(mut fixture_admin: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+
+    fixture_admin
+        .expect_login_attempt_repository()
+        .returning(move || {
+            let mut mock = MockLoginAttemptRepository::new();
+            mock.expect_unlock()
+                .withf(move |id| *id == user_id)
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+    fixture_admin.expect_audit_repository().returning(move || {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(move |e| {
+                e.action == AuditAction::AccountUnlocked && e.target_id == user_id.raw()
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_admin);
+    let req = Request::delete(&v1(&format!("/users/{user_id}/lockout")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn unlock_user_by_non_admin_403(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_login_attempt_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::delete(&v1(&format!("/users/{}/lockout", UserId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("anne.sallow@example.com", StatusCode::ACCEPTED)]
+#[case("not-an-email", StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn request_password_reset(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] email: &'static str,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+
+    fixture_registry
+        .expect_password_reset_repository()
+        .returning(move || {
+            let mut mock = MockPasswordResetRepository::new();
+            mock.expect_request()
+                .withf(move |e| e.email == email)
+                .returning(move |_| Ok(Some(user_id)));
+            Arc::new(mock)
+        });
+    fixture_registry
+        .expect_audit_repository()
+        .returning(move || {
+            let mut mock = MockAuditRepository::new();
+            mock.expect_record()
+                .withf(move |e| {
+                    e.action == AuditAction::PasswordResetRequested && e.actor_id == user_id
+                })
+                .times(1)
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = post_json(
+        "/auth/password-reset/request",
+        serde_json::json!({ "email": email }),
+    )?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn confirm_password_reset_204(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+
+    fixture_registry
+        .expect_password_reset_repository()
+        .returning(move || {
+            let mut mock = MockPasswordResetRepository::new();
+            mock.expect_confirm()
+                .withf(|e| e.token == "reset_token" && e.new_password == "new_password")
+                .returning(move |_| Ok(user_id));
+            Arc::new(mock)
+        });
+    fixture_registry
+        .expect_audit_repository()
+        .returning(move || {
+            let mut mock = MockAuditRepository::new();
+            mock.expect_record()
+                .withf(move |e| e.action == AuditAction::PasswordReset && e.actor_id == user_id)
+                .times(1)
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = post_json(
+        "/auth/password-reset/confirm",
+        serde_json::json!({ "token": "reset_token", "newPassword": "new_password" }),
+    )?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn confirm_password_reset_with_invalid_token_401(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_registry
+        .expect_password_reset_repository()
+        .returning(|| {
+            let mut mock = MockPasswordResetRepository::new();
+            mock.expect_confirm()
+                .returning(|_| Err(AppError::UnauthorizedError));
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = post_json(
+        "/auth/password-reset/confirm",
+        serde_json::json!({ "token": "used_token", "newPassword": "new_password" }),
+    )?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
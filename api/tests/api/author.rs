@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, fixture_auth, make_router, v1, TestRequestExt},
+};
+use api::model::author::AuthorResponse;
+use kernel::{
+    model::{
+        author::{event::AuthorRef, Author, AuthorRole},
+        id::{AuthorId, BookId},
+        list::PaginatedList,
+    },
+    repository::{
+        audit::MockAuditRepository, author::MockAuthorRepository, book::MockBookRepository,
+    },
+};
+
+fn create_book_request(body: serde_json::Value) -> anyhow::Result<Request<Body>> {
+    Ok(Request::post(&v1("/books"))
+        .bearer()
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))?)
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_author_200(mut fixture_auth: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let author_id = AuthorId::new();
+    fixture_auth.expect_author_repository().returning(|| {
+        let mut mock = MockAuthorRepository::new();
+        mock.expect_find_by_id().returning(|id| {
+            Ok(Some(Author {
+                id,
+                name: "豊田優貴".into(),
+            }))
+        });
+        Arc::new(mock)
+    });
+    fixture_auth.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_all()
+            .withf(move |o| o.author_id == Some(author_id) && o.limit == 10)
+            .returning(|o| {
+                Ok(PaginatedList {
+                    total: 0,
+                    limit: o.limit,
+                    offset: o.offset,
+                    items: vec![],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::get(&v1(&format!("/authors/{author_id}?limit=10")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, AuthorResponse);
+    assert_eq!(result.id, author_id);
+    assert_eq!(result.name, "豊田優貴");
+    assert_eq!(result.books.limit, 10);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_author_404(mut fixture_auth: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture_auth.expect_author_repository().returning(|| {
+        let mut mock = MockAuthorRepository::new();
+        mock.expect_find_by_id().returning(|_| Ok(None));
+        Arc::new(mock)
+    });
+    fixture_auth.expect_book_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::get(&v1(&format!("/authors/{}", AuthorId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn register_book_with_authors_201(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let author_id = AuthorId::new();
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_create()
+            .withf(move |e, _| {
+                e.author.is_empty()
+                    && e.authors.len() == 2
+                    && e.authors[0].author == AuthorRef::Id(author_id)
+                    && e.authors[0].role == AuthorRole::Author
+                    && e.authors[1].author == AuthorRef::Name("尾崎亮太".into())
+                    && e.authors[1].role == AuthorRole::Translator
+            })
+            .returning(|_, _| Ok(BookId::new()));
+        Arc::new(mock)
+    });
+    fixture.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let req = create_book_request(serde_json::json!({
+        "title": "プログラミング言語Rust",
+        "authors": [
+            { "id": author_id },
+            { "name": "尾崎亮太", "role": "translator" },
+        ],
+        "isbn": "9784048930703",
+        "description": "",
+    }))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(serde_json::json!({ "author": "", "authors": [] }))]
+#[case(serde_json::json!({ "authors": [{ "role": "editor" }] }))]
+#[case(serde_json::json!({ "authors": [{ "name": "" }] }))]
+#[case(serde_json::json!({ "authors": [{ "name": "尾崎亮太", "role": "reviewer" }] }))]
+#[tokio::test]
+async fn register_book_with_invalid_authors_4xx(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] authors: serde_json::Value,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().never();
+
+    let mut body = serde_json::json!({
+        "title": "プログラミング言語Rust",
+        "isbn": "9784048930703",
+        "description": "",
+    });
+    body.as_object_mut()
+        .unwrap()
+        .extend(authors.as_object().unwrap().clone());
+
+    let app: axum::Router = make_router(fixture);
+    let resp = app.oneshot(create_book_request(body)?).await?;
+    assert!(resp.status().is_client_error());
+
+    Ok(())
+}
@@ -9,17 +9,17 @@ use tower::ServiceExt;
 
 use crate::{
     deserialize_json,
-    helper::{fixture, make_router, v1, TestRequestExt},
+    helper::{fixture, fixture_admin, fixture_auth, make_router, v1, TestRequestExt},
 };
-use api::model::book::PaginatedBookResponse;
+use api::model::book::{BookCopyCreatedResponse, BookResponse, PaginatedBookResponse};
 use kernel::{
     model::{
-        book::Book,
-        id::{BookId, UserId},
+        book::{Book, BookCopy, BookCover, BookFacets, Checkout, CopyCondition},
+        id::{BookCopyId, BookId, CheckoutId, UserId},
         list::PaginatedList,
-        user::BookOwner,
+        user::{BookOwner, CheckoutUser},
     },
-    repository::book::MockBookRepository,
+    repository::{audit::MockAuditRepository, book::MockBookRepository},
 };
 
 #[rstest]
@@ -44,12 +44,16 @@ async fn show_book_list_with_query_200(
                 title: "Rust による Web アプリケーション開発".to_string(),
                 isbn: "".to_string(),
                 author: "Yuki Toyoda".to_string(),
+                authors: vec![],
                 description: "Rust による Web アプリケーション開発".to_string(),
                 owner: BookOwner {
                     id: UserId::new(),
                     name: "radish-miyazaki".to_string(),
                 },
-                checkout: None,
+                copies: vec![],
+                tags: vec![],
+                cover: None,
+                created_at: chrono::Utc::now(),
             }];
 
             Ok(PaginatedList {
@@ -94,12 +98,16 @@ async fn show_book_list_with_query_400(
                 title: "Rust による Web アプリケーション開発".to_string(),
                 isbn: "".to_string(),
                 author: "Yuki Toyoda".to_string(),
+                authors: vec![],
                 description: "Rust による Web アプリケーション開発".to_string(),
                 owner: BookOwner {
                     id: UserId::new(),
                     name: "radish-miyazaki".to_string(),
                 },
-                checkout: None,
+                copies: vec![],
+                tags: vec![],
+                cover: None,
+                created_at: chrono::Utc::now(),
             }];
 
             Ok(PaginatedList {
@@ -121,3 +129,370 @@ async fn show_book_list_with_query_400(
 
     Ok(())
 }
+
+#[rstest]
+#[case("/books?q=rust")]
+#[case("/books?status=checked_out")]
+#[case("/books?isbnPrefix=978-4")]
+#[tokio::test]
+async fn show_book_list_with_search_query_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_facets().returning(|_| {
+            Ok(BookFacets {
+                available: 2,
+                checked_out: 1,
+                owners: vec![],
+            })
+        });
+        mock.expect_find_all().returning(|opt| {
+            Ok(PaginatedList {
+                total: 0,
+                limit: opt.limit,
+                offset: opt.offset,
+                items: vec![],
+            })
+        });
+
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, PaginatedBookResponse);
+    let facets = result
+        .facets
+        .expect("facets should be returned in search mode");
+    assert_eq!(facets.available, 2);
+    assert_eq!(facets.checked_out, 1);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/books?q=")]
+#[case("/books?status=unknown")]
+#[tokio::test]
+async fn show_book_list_with_search_query_400(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    fixture
+        .expect_book_repository()
+        .returning(|| Arc::new(MockBookRepository::new()));
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_book_with_copies_200(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id().returning(move |id| {
+            let copy = |barcode: &str, checkout: Option<Checkout>| BookCopy {
+                id: BookCopyId::new(),
+                barcode: barcode.to_string(),
+                shelf_location: "A-1".to_string(),
+                condition: CopyCondition::Good,
+                checkout,
+            };
+            Ok(Some(Book {
+                id,
+                title: "Rust による Web アプリケーション開発".to_string(),
+                isbn: "".to_string(),
+                author: "Yuki Toyoda".to_string(),
+                authors: vec![],
+                description: "".to_string(),
+                owner: BookOwner {
+                    id: UserId::new(),
+                    name: "radish-miyazaki".to_string(),
+                },
+                copies: vec![
+                    copy(
+                        "RBM-0001",
+                        Some(Checkout {
+                            checkout_id: CheckoutId::new(),
+                            checked_out_by: CheckoutUser {
+                                id: UserId::new(),
+                                name: "borrower".to_string(),
+                            },
+                            checked_out_at: chrono::Utc::now(),
+                            due_at: chrono::Utc::now() + chrono::Duration::days(14),
+                        }),
+                    ),
+                    copy("RBM-0002", None),
+                ],
+                tags: vec![],
+                cover: Some(BookCover {
+                    content_type: "image/png".to_string(),
+                    updated_at: chrono::Utc::now(),
+                }),
+                created_at: chrono::Utc::now(),
+            }))
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::get(&v1(&format!("/books/{book_id}")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, BookResponse);
+    assert_eq!(result.total_copies, 2);
+    assert_eq!(result.available_copies, 1);
+    assert_eq!(result.copies.len(), 2);
+    assert!(
+        result.cover_thumbnail_url.is_some_and(
+            |url| url.starts_with(&format!("/api/v1/books/{book_id}/cover/thumbnail?v="))
+        )
+    );
+
+    Ok(())
+}
+
+fn create_copy_request(book_id: BookId, barcode: &str) -> anyhow::Result<Request<Body>> {
+    Ok(Request::post(&v1(&format!("/books/{book_id}/copies")))
+        .bearer()
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::json!({ "barcode": barcode, "shelfLocation": "B-3", "condition": "new" })
+                .to_string(),
+        ))?)
+}
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+#[tokio::test]
+async fn register_book_copy_any_owner_follows_permission(
+    fixture_auth: registry::MockAppRegistryExt,
+    fixture_admin: registry::MockAppRegistryExt,
+    #[case] is_admin: bool,
+) -> anyhow::Result<()> {
+    let mut registry = if is_admin {
+        fixture_admin
+    } else {
+        fixture_auth
+    };
+    let copy_id = BookCopyId::new();
+
+    registry.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_create_copy()
+            .withf(move |e| {
+                e.any_owner == is_admin
+                    && e.barcode == "RBM-0100"
+                    && e.condition == CopyCondition::New
+            })
+            .returning(move |_| Ok(copy_id));
+        Arc::new(mock)
+    });
+    registry.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+    let resp = app
+        .oneshot(create_copy_request(BookId::new(), "RBM-0100")?)
+        .await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, BookCopyCreatedResponse);
+    assert_eq!(result.id, copy_id);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn register_book_copy_with_empty_barcode_400(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_book_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let resp = app.oneshot(create_copy_request(BookId::new(), "")?).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("4-06-536957-6", "9784065369579")]
+#[case("978-4-06-536957-9", "9784065369579")]
+#[tokio::test]
+async fn register_book_normalizes_isbn(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] isbn: &'static str,
+    #[case] expected: &'static str,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_create()
+            .withf(move |e, _| e.isbn == expected)
+            .returning(|_, _| Ok(BookId::new()));
+        Arc::new(mock)
+    });
+    fixture.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let resp = app.oneshot(create_book_request(isbn)?).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("")]
+#[case("978-4-06-536957-0")]
+#[case("4-06-536957-X")]
+#[tokio::test]
+async fn register_book_with_invalid_isbn_400(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] isbn: &str,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().never();
+
+    let app: axum::Router = make_router(fixture);
+    let resp = app.oneshot(create_book_request(isbn)?).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+fn create_book_request(isbn: &str) -> anyhow::Result<Request<Body>> {
+    Ok(Request::post(&v1("/books"))
+        .bearer()
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::json!({
+                "title": "RustによるWebアプリケーション開発",
+                "author": "豊田優貴他",
+                "isbn": isbn,
+                "description": "",
+            })
+            .to_string(),
+        ))?)
+}
+
+fn plain_book(id: BookId) -> Book {
+    Book {
+        id,
+        title: "RustによるWebアプリケーション開発".to_string(),
+        isbn: "9784065369579".to_string(),
+        author: "豊田優貴他".to_string(),
+        authors: vec![],
+        description: "設計からリリース・運用まで".to_string(),
+        owner: BookOwner {
+            id: UserId::new(),
+            name: "radish-miyazaki".to_string(),
+        },
+        copies: vec![],
+        tags: vec![],
+        cover: None,
+        created_at: chrono::Utc::now(),
+    }
+}
+
+#[rstest]
+#[case("application/marcxml+xml", "application/marcxml+xml", "<record xmlns=")]
+#[case(
+    "application/dc+xml;q=0.9, application/json;q=0.5",
+    "application/dc+xml",
+    "<oai_dc:dc"
+)]
+#[case("application/json, application/marc", "application/json", "{")]
+#[case("text/html, */*;q=0.8", "application/json", "{")]
+#[tokio::test]
+async fn show_book_negotiates_format(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] accept: &str,
+    #[case] expected_type: &str,
+    #[case] expected_body: &str,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_by_id()
+            .returning(|id| Ok(Some(plain_book(id))));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::get(&v1(&format!("/books/{}", BookId::new())))
+        .bearer()
+        .header("Accept", accept)
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["Content-Type"], expected_type);
+
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    let body = String::from_utf8(body.to_vec())?;
+    assert!(body.contains(expected_body));
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_book_list_as_marc21(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_facets().never();
+        mock.expect_find_all().returning(|opt| {
+            Ok(PaginatedList {
+                total: 2,
+                limit: opt.limit,
+                offset: opt.offset,
+                items: vec![plain_book(BookId::new()), plain_book(BookId::new())],
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::get(&v1("/books?q=Rust"))
+        .bearer()
+        .header("Accept", "application/marc")
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["Content-Type"], "application/marc");
+
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+    let records = api::model::book_transfer::BookFileFormat::Marc21
+        .read_books(std::io::Cursor::new(body.to_vec()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(anyhow::Error::msg)?;
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].isbn, "9784065369579");
+
+    Ok(())
+}
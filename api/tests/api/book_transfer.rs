@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, make_router, v1, TestRequestExt},
+};
+use api::model::book_transfer::{BookExportRecord, BookImportMode, BookImportResponse};
+use kernel::{
+    model::{
+        book::{Book, BookCopy, Checkout, CopyCondition},
+        id::{BookCopyId, BookId, CheckoutId, UserId},
+        list::PaginatedList,
+        user::{BookOwner, CheckoutUser},
+    },
+    repository::{audit::MockAuditRepository, book::MockBookRepository},
+};
+use shared::error::AppError;
+
+fn import_request(query: &str, body: &str) -> anyhow::Result<Request<Body>> {
+    Ok(Request::post(&v1(&format!("/books/import?{query}")))
+        .bearer()
+        .body(Body::from(body.to_string()))?)
+}
+
+/// 登録日時の新しい順に並ぶよう、`index` 秒ずつ古い登録日時を持たせる。
+fn exported_book(index: i64) -> Book {
+    Book {
+        id: BookId::new(),
+        title: format!("Book {index}"),
+        isbn: "9784065369579".to_string(),
+        author: "Yuki Toyoda".to_string(),
+        authors: vec![],
+        description: "".to_string(),
+        owner: BookOwner {
+            id: UserId::new(),
+            name: "radish-miyazaki".to_string(),
+        },
+        copies: vec![BookCopy {
+            id: BookCopyId::new(),
+            barcode: format!("RBM-{index:04}"),
+            shelf_location: "A-1".to_string(),
+            condition: CopyCondition::Good,
+            checkout: Some(Checkout {
+                checkout_id: CheckoutId::new(),
+                checked_out_by: CheckoutUser {
+                    id: UserId::new(),
+                    name: "borrower".to_string(),
+                },
+                checked_out_at: chrono::Utc::now(),
+                due_at: chrono::Utc::now() + chrono::Duration::days(14),
+            }),
+        }],
+        tags: vec![],
+        cover: None,
+        created_at: created_at_base() - chrono::Duration::seconds(index),
+    }
+}
+
+fn created_at_base() -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::from_timestamp(1_731_571_200, 0).unwrap()
+}
+
+/// `total` 冊の蔵書を、リクエストされたページごとに返す。
+fn with_books(registry: &mut registry::MockAppRegistryExt, total: i64) {
+    registry.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_all().returning(move |opt| {
+            // 書き出しは offset を使わず、直前のページの末尾の蔵書を起点に取得する
+            assert_eq!(opt.offset, 0);
+            let start = opt.after.map_or(0, |after| {
+                (created_at_base() - after.created_at).num_seconds() + 1
+            });
+            let items = (start..total.min(start + opt.limit))
+                .map(exported_book)
+                .collect();
+            Ok(PaginatedList {
+                total,
+                limit: opt.limit,
+                offset: opt.offset,
+                items,
+            })
+        });
+        Arc::new(mock)
+    });
+}
+
+#[rstest]
+#[tokio::test]
+async fn import_books_dry_run_reports_invalid_rows(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_exists_by_isbn()
+            .returning(|_, isbn| Ok(isbn == "9784798061702"));
+        mock.expect_create().never();
+        Arc::new(mock)
+    });
+
+    let csv = "\
+title,author,isbn,description
+Rust による Web アプリケーション開発,Yuki Toyoda,978-4-06-536957-9,設計からリリース・運用まで
+,Yuki Toyoda,9784798061702,
+Duplicated,Yuki Toyoda,4065369576,
+Invalid ISBN,Yuki Toyoda,9784065369570,
+Already Registered,Yuki Toyoda,978-4-7980-6170-2,
+";
+    let app: axum::Router = make_router(fixture);
+    let resp = app.oneshot(import_request("format=csv", csv)?).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, BookImportResponse);
+    assert_eq!(result.mode, BookImportMode::DryRun);
+    assert_eq!(result.total, 5);
+    assert_eq!(result.imported, 1);
+    assert_eq!(
+        result.errors.iter().map(|e| e.row).collect::<Vec<_>>(),
+        vec![2, 3, 4, 5]
+    );
+    assert_eq!(result.errors[1].isbn.as_deref(), Some("9784065369579"));
+    // 登録済みの蔵書と重複する行は、ドライランでもエラーとなる
+    assert_eq!(result.errors[3].isbn.as_deref(), Some("9784798061702"));
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn import_books_commit_creates_valid_rows(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_create().returning(|event, _| {
+            if event.isbn == "9784798061702" {
+                Err(AppError::ConflictError("duplicated".into()))
+            } else {
+                Ok(BookId::new())
+            }
+        });
+        Arc::new(mock)
+    });
+    fixture.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let jsonl = r#"{"title":"Rust による Web アプリケーション開発","author":"Yuki Toyoda","isbn":"978-4-06-536957-9","description":""}
+
+{"title":"Already registered","author":"Yuki Toyoda","isbn":"9784798061702","description":""}
+not a json
+"#;
+    let app: axum::Router = make_router(fixture);
+    let resp = app
+        .oneshot(import_request("format=jsonl&mode=commit", jsonl)?)
+        .await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, BookImportResponse);
+    assert_eq!(result.mode, BookImportMode::Commit);
+    assert_eq!(result.total, 3);
+    assert_eq!(result.imported, 1);
+    assert_eq!(
+        result.errors.iter().map(|e| e.row).collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn import_books_without_format_400(
+    fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let app: axum::Router = make_router(fixture);
+    let resp = app.oneshot(import_request("mode=commit", "")?).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn export_books_as_csv_pages_through_catalog(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    with_books(&mut fixture, 150);
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::get(&v1("/books/export?format=csv"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()[header::CONTENT_DISPOSITION],
+        "attachment; filename=\"books.csv\""
+    );
+
+    let body = to_bytes(resp.into_body(), usize::MAX).await?;
+    let mut reader = csv::Reader::from_reader(&body[..]);
+    assert_eq!(
+        reader.headers()?.iter().collect::<Vec<_>>(),
+        BookExportRecord::CSV_HEADER
+    );
+    let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(records.len(), 150);
+    assert_eq!(&records[149][1], "Book 149");
+    assert_eq!(&records[0][6], "radish-miyazaki");
+    assert_eq!(&records[0][9], "borrower");
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn export_books_as_jsonl(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    with_books(&mut fixture, 3);
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::get(&v1("/books/export?format=jsonl"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], "application/x-ndjson");
+
+    let body = to_bytes(resp.into_body(), usize::MAX).await?;
+    let records = std::str::from_utf8(&body)?
+        .lines()
+        .map(serde_json::from_str::<BookExportRecord>)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].total_copies, 1);
+    assert_eq!(records[0].available_copies, 0);
+    assert_eq!(records[0].checked_out_by, vec!["borrower".to_string()]);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn import_books_from_marcxml(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_create()
+            .withf(|event, _| {
+                event.isbn == "9784065369579" && event.title == "RustによるWebアプリケーション開発"
+            })
+            .returning(|_, _| Ok(BookId::new()));
+        Arc::new(mock)
+    });
+    fixture.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<collection xmlns="http://www.loc.gov/MARC21/slim">
+  <record>
+    <leader>00000nam a2200000   4500</leader>
+    <datafield tag="020" ind1=" " ind2=" "><subfield code="a">4-06-536957-6</subfield></datafield>
+    <datafield tag="100" ind1="1" ind2=" "><subfield code="a">豊田優貴</subfield></datafield>
+    <datafield tag="245" ind1="1" ind2="0"><subfield code="a">RustによるWebアプリケーション開発 /</subfield></datafield>
+  </record>
+  <record>
+    <leader>00000nam a2200000   4500</leader>
+    <datafield tag="245" ind1="0" ind2="0"><subfield code="a">No ISBN</subfield></datafield>
+  </record>
+</collection>"#;
+    let app: axum::Router = make_router(fixture);
+    let resp = app
+        .oneshot(import_request("format=marcxml&mode=commit", xml)?)
+        .await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, BookImportResponse);
+    assert_eq!(result.total, 2);
+    assert_eq!(result.imported, 1);
+    assert_eq!(result.errors[0].row, 2);
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture, make_router, v1, TestRequestExt},
+};
+use api::model::book::CreateBookRequest;
+use kernel::{
+    model::catalog::CatalogEntry,
+    repository::{
+        audit::MockAuditRepository, book::MockBookRepository, catalog::MockCatalogLookup,
+    },
+};
+
+fn catalog_entry() -> CatalogEntry {
+    CatalogEntry {
+        isbn: "9784065369579".into(),
+        title: "RustによるWebアプリケーション開発".into(),
+        author: "豊田優貴他".into(),
+        description: "設計からリリース・運用まで".into(),
+    }
+}
+
+fn with_catalog(registry: &mut registry::MockAppRegistryExt) {
+    registry.expect_catalog_lookup().returning(|| {
+        let mut mock = MockCatalogLookup::new();
+        mock.expect_find_by_isbn()
+            .returning(|isbn| Ok(Some(catalog_entry()).filter(|entry| entry.isbn == isbn)));
+        Arc::new(mock)
+    });
+}
+
+#[rstest]
+#[case("/catalog/isbn/978-4-06-536957-9")]
+#[case("/catalog/isbn/4065369576")]
+#[tokio::test]
+async fn show_catalog_entry_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+) -> anyhow::Result<()> {
+    with_catalog(&mut fixture);
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, CreateBookRequest);
+    assert_eq!(result.isbn, "9784065369579");
+    assert_eq!(result.title, "RustによるWebアプリケーション開発");
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/catalog/isbn/9784798061702", StatusCode::NOT_FOUND)]
+#[case("/catalog/isbn/9784065369570", StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn show_catalog_entry_error(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    with_catalog(&mut fixture);
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::get(&v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn register_book_with_autofill_keeps_given_fields(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    with_catalog(&mut fixture);
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_create()
+            .withf(|e, _| {
+                e.title == "RustによるWebアプリケーション開発"
+                    && e.author == "Yuki Toyoda"
+                    && e.description == "設計からリリース・運用まで"
+            })
+            .returning(|_, _| Ok(kernel::model::id::BookId::new()));
+        Arc::new(mock)
+    });
+    fixture.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::post(&v1("/books?autofill=true"))
+        .bearer()
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::json!({ "isbn": "978-4-06-536957-9", "author": "Yuki Toyoda" }).to_string(),
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn register_book_without_autofill_requires_title(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_catalog_lookup().never();
+    fixture.expect_book_repository().never();
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::post(&v1("/books"))
+        .bearer()
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::json!({ "isbn": "978-4-06-536957-9", "author": "Yuki Toyoda" }).to_string(),
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::helper::{fixture_admin, fixture_auth, make_router, v1, TestRequestExt};
+use kernel::{
+    model::id::{BookId, CheckoutId, UserId},
+    repository::{audit::MockAuditRepository, checkout::MockCheckoutRepository},
+};
+
+fn with_audit(registry: &mut registry::MockAppRegistryExt) {
+    registry.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+}
+
+#[rstest]
+#[tokio::test]
+async fn checkout_on_behalf_of_user_201(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let borrower_id = UserId::new();
+
+    fixture_admin
+        .expect_checkout_repository()
+        .returning(move || {
+            let mut mock = MockCheckoutRepository::new();
+            mock.expect_create()
+                .withf(move |e| e.checked_out_by == borrower_id && e.processed_by != borrower_id)
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+    with_audit(&mut fixture_admin);
+
+    let app: axum::Router = make_router(fixture_admin);
+    let req = Request::post(&v1(&format!(
+        "/books/{}/checkouts?userId={borrower_id}",
+        BookId::new()
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn checkout_on_behalf_of_user_without_permission_403(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_checkout_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::post(&v1(&format!(
+        "/books/{}/checkouts?userId={}",
+        BookId::new(),
+        UserId::new()
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn checkout_with_loan_period_without_permission_403(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_checkout_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::post(&v1(&format!(
+        "/books/{}/checkouts?loanPeriodDays=90",
+        BookId::new()
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn checkout_with_loan_period_201(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_admin
+        .expect_checkout_repository()
+        .returning(move || {
+            let mut mock = MockCheckoutRepository::new();
+            mock.expect_create()
+                .withf(|e| e.loan_period == Some(chrono::Duration::days(90)))
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+    with_audit(&mut fixture_admin);
+
+    let app: axum::Router = make_router(fixture_admin);
+    let req = Request::post(&v1(&format!(
+        "/books/{}/checkouts?loanPeriodDays=90",
+        BookId::new()
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(true)]
+#[case(false)]
+#[tokio::test]
+async fn return_book_any_borrower_follows_permission(
+    fixture_admin: registry::MockAppRegistryExt,
+    fixture_auth: registry::MockAppRegistryExt,
+    #[case] admin: bool,
+) -> anyhow::Result<()> {
+    let mut registry = if admin { fixture_admin } else { fixture_auth };
+    registry.expect_checkout_repository().returning(move || {
+        let mut mock = MockCheckoutRepository::new();
+        mock.expect_update_returned()
+            .withf(move |e| e.any_borrower == admin)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+    with_audit(&mut registry);
+
+    let app: axum::Router = make_router(registry);
+    let req = Request::put(&v1(&format!(
+        "/books/{}/checkouts/{}/returned",
+        BookId::new(),
+        CheckoutId::new()
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_auth, make_router, v1, TestRequestExt},
+};
+use api::model::collection::CollectionResponse;
+use kernel::{
+    model::{
+        audit::AuditAction,
+        collection::{Collection, CollectionOwner},
+        id::{BookId, CollectionId, TagId, UserId},
+        list::PaginatedList,
+    },
+    repository::{
+        audit::MockAuditRepository, book::MockBookRepository, collection::MockCollectionRepository,
+    },
+};
+
+fn collection(id: CollectionId, shared: bool) -> Collection {
+    Collection {
+        id,
+        name: "Reading list".into(),
+        description: "".into(),
+        owner: CollectionOwner {
+            id: UserId::new(),
+            name: "dummy".into(),
+        },
+        shared,
+        book_count: 0,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_collection_201(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_collection_repository().returning(|| {
+        let mut mock = MockCollectionRepository::new();
+        mock.expect_create()
+            .withf(|e| e.name == "Reading list" && e.shared)
+            .returning(|e| Ok(collection(CollectionId::new(), e.shared)));
+        Arc::new(mock)
+    });
+    fixture_auth.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(|e| e.action == AuditAction::CollectionCreated && e.after.is_some())
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::post(&v1("/collections"))
+        .bearer()
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::json!({ "name": "Reading list", "shared": true }).to_string(),
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, CollectionResponse);
+    assert_eq!(result.name, "Reading list");
+    assert!(result.shared);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_collection_not_visible_404(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_collection_repository().returning(|| {
+        let mut mock = MockCollectionRepository::new();
+        mock.expect_find_by_id().returning(|_, _| Ok(None));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::get(&v1(&format!("/collections/{}", CollectionId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_book_list_in_hidden_collection_404(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_collection_repository().returning(|| {
+        let mut mock = MockCollectionRepository::new();
+        mock.expect_find_by_id().returning(|_, _| Ok(None));
+        Arc::new(mock)
+    });
+    fixture_auth.expect_book_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::get(&v1(&format!("/books?collectionId={}", CollectionId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_book_list_filtered_by_tag_and_collection(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let tag_id = TagId::new();
+    let collection_id = CollectionId::new();
+    fixture_auth
+        .expect_collection_repository()
+        .returning(move || {
+            let mut mock = MockCollectionRepository::new();
+            mock.expect_find_by_id()
+                .withf(move |id, _| *id == collection_id)
+                .returning(|id, _| Ok(Some(collection(id, true))));
+            Arc::new(mock)
+        });
+    fixture_auth.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_facets()
+            .withf(move |o| o.tag_id == Some(tag_id) && o.collection_id == Some(collection_id))
+            .returning(|_| Ok(Default::default()));
+        mock.expect_find_all()
+            .withf(move |o| o.tag_id == Some(tag_id) && o.collection_id == Some(collection_id))
+            .returning(|o| {
+                Ok(PaginatedList {
+                    total: 0,
+                    limit: o.limit,
+                    offset: o.offset,
+                    items: vec![],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::get(&v1(&format!(
+        "/books?tagId={tag_id}&collectionId={collection_id}"
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn add_collection_book_204(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let collection_id = CollectionId::new();
+    let book_id = BookId::new();
+    fixture_auth
+        .expect_collection_repository()
+        .returning(move || {
+            let mut mock = MockCollectionRepository::new();
+            mock.expect_add_book()
+                .withf(move |e| e.collection_id == collection_id && e.book_id == book_id)
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+    fixture_auth.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(|e| e.action == AuditAction::CollectionBookAdded)
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::put(&v1(&format!(
+        "/collections/{collection_id}/books/{book_id}"
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use std::{io::Cursor, sync::Arc};
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::helper::{fixture, make_router, v1, TestRequestExt};
+use api::model::cover::{MAX_COVER_DIMENSION, MAX_COVER_SIZE};
+use image::{ImageFormat, RgbImage};
+use kernel::{
+    model::{
+        book::{CoverImage, CoverVariant},
+        id::BookId,
+    },
+    repository::{audit::MockAuditRepository, book::MockBookRepository},
+};
+
+const BOUNDARY: &str = "cover-boundary";
+
+fn png(width: u32, height: u32) -> anyhow::Result<Vec<u8>> {
+    let mut data = Cursor::new(Vec::new());
+    RgbImage::new(width, height).write_to(&mut data, ImageFormat::Png)?;
+    Ok(data.into_inner())
+}
+
+fn upload_request(
+    book_id: BookId,
+    content_type: &str,
+    data: &[u8],
+) -> anyhow::Result<Request<Body>> {
+    let mut body = format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"cover\"\r\nContent-Type: {content_type}\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+
+    Ok(Request::put(&v1(&format!("/books/{book_id}/cover")))
+        .bearer()
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={BOUNDARY}"),
+        )
+        .body(Body::from(body))?)
+}
+
+#[rstest]
+#[tokio::test]
+async fn upload_book_cover_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(|| {
+        let mut mock = MockBookRepository::new();
+        mock.expect_update_cover()
+            .withf(|e| {
+                let thumbnail = image::load_from_memory(&e.thumbnail).unwrap();
+                // 申告された Content-Type ではなく、データから判別した形式で保存する
+                e.content_type == "image/png"
+                    && !e.any_owner
+                    && thumbnail.width() == 200
+                    && thumbnail.height() == 100
+            })
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+    fixture.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let req = upload_request(BookId::new(), "application/octet-stream", &png(800, 400)?)?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(b"GIF89a not supported".to_vec(), StatusCode::UNPROCESSABLE_ENTITY)]
+#[case(b"\x89PNG\r\n\x1a\n broken".to_vec(), StatusCode::UNPROCESSABLE_ENTITY)]
+#[case(vec![0; MAX_COVER_SIZE + 1], StatusCode::PAYLOAD_TOO_LARGE)]
+// ファイルは小さくても、寸法が上限を超える画像は展開しない
+#[case(png(MAX_COVER_DIMENSION + 1, 1).unwrap(), StatusCode::UNPROCESSABLE_ENTITY)]
+#[tokio::test]
+async fn upload_book_cover_rejects_invalid_image(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] data: Vec<u8>,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().never();
+
+    let app: axum::Router = make_router(fixture);
+    let req = upload_request(BookId::new(), "image/png", &data)?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/cover", CoverVariant::Original)]
+#[case("/cover/thumbnail", CoverVariant::Thumbnail)]
+#[tokio::test]
+async fn show_book_cover_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] suffix: &str,
+    #[case] variant: CoverVariant,
+) -> anyhow::Result<()> {
+    fixture.expect_book_repository().returning(move || {
+        let mut mock = MockBookRepository::new();
+        mock.expect_find_cover()
+            .withf(move |_, v| *v == variant)
+            .returning(|_, _| {
+                Ok(Some(CoverImage {
+                    content_type: "image/jpeg".into(),
+                    data: b"jpeg".to_vec(),
+                }))
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+    let req = Request::get(&v1(&format!("/books/{}{suffix}", BookId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], "image/jpeg");
+
+    Ok(())
+}
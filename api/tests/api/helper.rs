@@ -3,11 +3,17 @@ use std::sync::Arc;
 use api::route::{auth, v1};
 use axum::{http::request::Builder, Router};
 use kernel::{
-    model::{auth::AccessToken, id::UserId, role::Role, user::User},
+    model::{
+        auth::{AccessToken, AuthToken, RefreshToken},
+        id::{RoleId, UserId},
+        role::{Permission, Role, ADMIN_ROLE_NAME, DEFAULT_ROLE_NAME},
+        user::User,
+    },
     repository::{auth::MockAuthRepository, user::MockUserRepository},
 };
 use registry::MockAppRegistryExt;
 use rstest::fixture;
+use strum::IntoEnumIterator;
 
 #[fixture]
 pub fn fixture_registry() -> MockAppRegistryExt {
@@ -15,21 +21,68 @@ pub fn fixture_registry() -> MockAppRegistryExt {
 }
 
 #[fixture]
-pub fn fixture_auth(mut fixture_registry: MockAppRegistryExt) -> MockAppRegistryExt {
-    fixture_registry.expect_auth_repository().returning(|| {
-        let mut mock_auth_repository = MockAuthRepository::new();
-
-        mock_auth_repository
-            .expect_fetch_user_id_from_token()
-            .returning(|_| Ok(Some(UserId::new())));
-        mock_auth_repository
-            .expect_verify_user()
-            .returning(|_, _| Ok(UserId::new()));
-        mock_auth_repository
-            .expect_create_token()
-            .returning(|_| Ok(AccessToken("dummy".to_string())));
-        Arc::new(mock_auth_repository)
-    });
+pub fn fixture_auth(fixture_registry: MockAppRegistryExt) -> MockAppRegistryExt {
+    with_authorized_role(fixture_registry, user_role)
+}
+
+#[fixture]
+pub fn fixture_admin(fixture_registry: MockAppRegistryExt) -> MockAppRegistryExt {
+    with_authorized_role(fixture_registry, admin_role)
+}
+
+/// すべての権限を持つ管理者のロール
+pub fn admin_role() -> Role {
+    Role {
+        id: RoleId::new(),
+        name: ADMIN_ROLE_NAME.into(),
+        permissions: Permission::iter().collect(),
+    }
+}
+
+/// 権限を持たない一般ユーザーのロール
+pub fn user_role() -> Role {
+    Role {
+        id: RoleId::new(),
+        name: DEFAULT_ROLE_NAME.into(),
+        permissions: vec![],
+    }
+}
+
+/// トークンを検証すると `role()` のロールを持つユーザーとして認可されるようにする
+pub fn with_authorized_role(
+    mut fixture_registry: MockAppRegistryExt,
+    role: fn() -> Role,
+) -> MockAppRegistryExt {
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock_auth_repository = MockAuthRepository::new();
+
+            mock_auth_repository
+                .expect_fetch_user_from_token()
+                .returning(move |_| {
+                    Ok(Some(User {
+                        id: UserId::new(),
+                        email: "dummy@example.com".to_string(),
+                        name: "dummy".to_string(),
+                        role: role(),
+                    }))
+                });
+            mock_auth_repository
+                .expect_verify_user()
+                .returning(|_, _| Ok(UserId::new()));
+            mock_auth_repository
+                .expect_create_token()
+                .returning(|event| {
+                    Ok(AuthToken {
+                        user_id: event.user_id,
+                        access_token: AccessToken("dummy".to_string()),
+                        refresh_token: RefreshToken("dummy_refresh".to_string()),
+                        expires_in: 900,
+                    })
+                });
+            Arc::new(mock_auth_repository)
+        });
 
     fixture_registry
 }
@@ -45,7 +98,7 @@ pub fn fixture(mut fixture_auth: MockAppRegistryExt) -> MockAppRegistryExt {
                     id,
                     email: "dummy@example.com".to_string(),
                     name: "dummy".to_string(),
-                    role: Role::User,
+                    role: user_role(),
                 }))
             });
         Arc::new(mock_user_repository)
@@ -0,0 +1,224 @@
+use std::io::Cursor;
+
+use api::model::{
+    book::CreateBookRequest,
+    book_transfer::BookFileFormat,
+    interchange::{
+        dublin_core::{self, DublinCoreRecord},
+        marc::MarcRecord,
+        marc21, marcxml,
+    },
+};
+use kernel::model::{
+    book::Book,
+    id::{BookId, UserId},
+    user::BookOwner,
+};
+use rstest::rstest;
+
+fn book(title: &str, description: &str) -> Book {
+    Book {
+        id: BookId::new(),
+        title: title.to_string(),
+        isbn: "9784065369579".to_string(),
+        author: "豊田優貴, 松本健太郎, 吉川哲史".to_string(),
+        authors: vec![],
+        description: description.to_string(),
+        owner: BookOwner {
+            id: UserId::new(),
+            name: "radish-miyazaki".to_string(),
+        },
+        copies: vec![],
+        tags: vec![],
+        cover: None,
+        created_at: chrono::Utc::now(),
+    }
+}
+
+fn assert_same_book(req: &CreateBookRequest, book: &Book) {
+    assert_eq!(req.title, book.title);
+    assert_eq!(req.author, book.author);
+    assert_eq!(req.isbn, book.isbn);
+    assert_eq!(req.description, book.description);
+}
+
+fn read_all(format: BookFileFormat, data: Vec<u8>) -> Vec<Result<CreateBookRequest, String>> {
+    format.read_books(Cursor::new(data)).collect()
+}
+
+#[test]
+fn marc21_round_trip() -> anyhow::Result<()> {
+    let book = book(
+        "RustによるWebアプリケーション開発",
+        "設計からリリース・運用まで",
+    );
+    let record = MarcRecord::from(&book);
+
+    let bytes = marc21::encode(&record)?;
+    assert_eq!(
+        bytes.len(),
+        std::str::from_utf8(&bytes[..5])?.parse::<usize>()?
+    );
+    assert_eq!(bytes.last(), Some(&0x1d));
+
+    let decoded = marc21::decode(&bytes).map_err(anyhow::Error::msg)?;
+    assert_eq!(decoded.control_fields, record.control_fields);
+    assert_eq!(decoded.data_fields, record.data_fields);
+    assert_same_book(&decoded.into(), &book);
+
+    Ok(())
+}
+
+#[test]
+fn marc21_splits_long_description_and_joins_it_back() -> anyhow::Result<()> {
+    let book = book("Long", &"長い解題。".repeat(2000));
+
+    let bytes = marc21::encode(&MarcRecord::from(&book))?;
+    let decoded = marc21::decode(&bytes).map_err(anyhow::Error::msg)?;
+    assert!(
+        decoded
+            .data_fields
+            .iter()
+            .filter(|f| f.tag == "520")
+            .count()
+            > 1
+    );
+    assert_same_book(&decoded.into(), &book);
+
+    Ok(())
+}
+
+#[test]
+fn marcxml_round_trip() -> anyhow::Result<()> {
+    let book = book("<Rust> & \"Web\"", "");
+    let record = MarcRecord::from(&book);
+
+    let xml = marcxml::encode(&record);
+    assert!(xml.contains("&lt;Rust&gt; &amp;"));
+
+    let mut records = marcxml::MarcXmlReader::new(Cursor::new(xml));
+    let decoded = records.next().transpose().map_err(anyhow::Error::msg)?;
+    assert_eq!(decoded.as_ref(), Some(&record));
+    assert!(records.next().is_none());
+    assert_same_book(&decoded.unwrap().into(), &book);
+
+    Ok(())
+}
+
+#[test]
+fn dublin_core_round_trip() -> anyhow::Result<()> {
+    let book = book(
+        "RustによるWebアプリケーション開発",
+        "設計からリリース・運用まで",
+    );
+    let record = DublinCoreRecord::from(&book);
+
+    let xml = dublin_core::encode(&record);
+    assert!(xml.contains("<dc:identifier>urn:isbn:9784065369579</dc:identifier>"));
+
+    let mut records = dublin_core::DublinCoreReader::new(Cursor::new(xml));
+    let decoded = records.next().transpose().map_err(anyhow::Error::msg)?;
+    assert_eq!(decoded.as_ref(), Some(&record));
+    assert_same_book(&decoded.unwrap().into(), &book);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(BookFileFormat::Marc21)]
+#[case(BookFileFormat::Marcxml)]
+#[case(BookFileFormat::DublinCore)]
+fn collection_round_trip(#[case] format: BookFileFormat) -> anyhow::Result<()> {
+    let books = [
+        book(
+            "RustによるWebアプリケーション開発",
+            "設計からリリース・運用まで",
+        ),
+        book("実践Rustプログラミング入門", ""),
+    ];
+
+    let mut data = format.encode_header()?;
+    data.extend(format.encode_books(&books)?);
+    data.extend(format.encode_footer());
+
+    let rows = read_all(format, data);
+    assert_eq!(rows.len(), 2);
+    for (row, book) in rows.iter().zip(&books) {
+        let req = row.as_ref().map_err(|e| anyhow::anyhow!("{e}"))?;
+        assert_same_book(req, book);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn marcxml_reads_records_catalogued_elsewhere() -> anyhow::Result<()> {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<marc:collection xmlns:marc="http://www.loc.gov/MARC21/slim">
+  <marc:record>
+    <marc:leader>00000nam a2200000 i 4500</marc:leader>
+    <marc:controlfield tag="001">JP23795131</marc:controlfield>
+    <marc:datafield tag="020" ind1=" " ind2=" ">
+      <marc:subfield code="a">4065369576 (pbk.)</marc:subfield>
+    </marc:datafield>
+    <marc:datafield tag="245" ind1="0" ind2="0">
+      <marc:subfield code="a">RustによるWebアプリケーション開発 :</marc:subfield>
+      <marc:subfield code="b">設計からリリース・運用まで /</marc:subfield>
+      <marc:subfield code="c">豊田優貴 [ほか] 著</marc:subfield>
+    </marc:datafield>
+    <marc:datafield tag="700" ind1="1" ind2=" ">
+      <marc:subfield code="a">豊田, 優貴,</marc:subfield>
+    </marc:datafield>
+  </marc:record>
+</marc:collection>"#;
+
+    let rows = read_all(BookFileFormat::Marcxml, xml.as_bytes().to_vec());
+    let req = rows[0].as_ref().map_err(|e| anyhow::anyhow!("{e}"))?;
+    assert_eq!(
+        req.title,
+        "RustによるWebアプリケーション開発 : 設計からリリース・運用まで"
+    );
+    assert_eq!(req.author, "豊田, 優貴");
+    assert_eq!(req.isbn, "4065369576");
+
+    Ok(())
+}
+
+#[test]
+fn marc21_stops_reading_at_broken_record_length() -> anyhow::Result<()> {
+    let mut data = BookFileFormat::Marc21.encode_books(&[book("A", "")])?;
+    data.extend(b"xxxxx-broken");
+
+    let rows = read_all(BookFileFormat::Marc21, data);
+    assert_eq!(rows.len(), 2);
+    assert!(rows[0].is_ok());
+    assert!(rows[1].is_err());
+
+    Ok(())
+}
+
+#[test]
+fn marc21_rejects_non_ascii_directory() -> anyhow::Result<()> {
+    let mut data = marc21::encode(&MarcRecord::from(&book("A", "")))?;
+    // 12 バイトだが、文字の境界が位置とずれるエントリー
+    data[24..36].copy_from_slice("aあbbbbbbbb".as_bytes());
+
+    let err = marc21::decode(&data).unwrap_err();
+    assert!(err.contains("directory is broken"));
+
+    let rows = read_all(BookFileFormat::Marc21, data);
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].is_err());
+
+    Ok(())
+}
+
+#[test]
+fn marcxml_stops_reading_at_broken_xml() {
+    let xml = "<collection><record><leader>x</leader></record><record></collection>";
+
+    let rows = read_all(BookFileFormat::Marcxml, xml.as_bytes().to_vec());
+    assert_eq!(rows.len(), 2);
+    assert!(rows[0].is_ok());
+    assert!(rows[1].is_err());
+}
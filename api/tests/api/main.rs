@@ -1,2 +1,16 @@
+mod api_key;
+mod audit;
+mod auth;
+mod author;
 mod book;
+mod book_transfer;
+mod catalog;
+mod checkout;
+mod collection;
+mod cover;
 mod helper;
+mod interchange;
+mod role;
+mod session;
+mod tag;
+mod two_factor;
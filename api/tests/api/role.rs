@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{
+        fixture_admin, fixture_auth, fixture_registry, make_router, v1, with_authorized_role,
+        TestRequestExt,
+    },
+};
+use api::model::role::RoleResponse;
+use kernel::{
+    model::{
+        audit::AuditAction,
+        id::{RoleId, UserId},
+        list::PaginatedList,
+        role::{Permission, Role},
+    },
+    repository::{audit::MockAuditRepository, role::MockRoleRepository},
+};
+use shared::error::AppError;
+
+fn create_request() -> anyhow::Result<Request<Body>> {
+    Ok(Request::post(&v1("/roles"))
+        .bearer()
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::json!({ "name": "Librarian", "permissions": ["checkout:return:any"] })
+                .to_string(),
+        ))?)
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_role_201(mut fixture_admin: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture_admin.expect_role_repository().returning(|| {
+        let mut mock = MockRoleRepository::new();
+        mock.expect_create()
+            .withf(|e| {
+                e.name == "Librarian" && e.permissions == vec![Permission::CheckoutReturnAny]
+            })
+            .returning(|e| {
+                Ok(Role {
+                    id: RoleId::new(),
+                    name: e.name,
+                    permissions: e.permissions,
+                })
+            });
+        Arc::new(mock)
+    });
+    fixture_admin.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(|e| e.action == AuditAction::RoleCreated && e.after.is_some())
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_admin);
+    let resp = app.oneshot(create_request()?).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, RoleResponse);
+    assert_eq!(result.name, "Librarian");
+    assert!(!result.builtin);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_role_without_permission_403(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_role_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let resp = app.oneshot(create_request()?).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_builtin_role_422(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_admin.expect_role_repository().returning(|| {
+        let mut mock = MockRoleRepository::new();
+        mock.expect_find_by_id().returning(|_| Ok(None));
+        mock.expect_update().returning(|_| {
+            Err(AppError::UnprocessableEntity(
+                "Built-in roles cannot be modified".into(),
+            ))
+        });
+        Arc::new(mock)
+    });
+    fixture_admin.expect_audit_repository().never();
+
+    let app: axum::Router = make_router(fixture_admin);
+    let req = Request::put(&v1(&format!("/roles/{}", RoleId::new())))
+        .bearer()
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::json!({ "name": "Admin", "permissions": [] }).to_string(),
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    Ok(())
+}
+
+fn auditor_role() -> Role {
+    Role {
+        id: RoleId::new(),
+        name: "Auditor".into(),
+        permissions: vec![Permission::AuditRead],
+    }
+}
+
+fn operator_role() -> Role {
+    Role {
+        id: RoleId::new(),
+        name: "Operator".into(),
+        permissions: vec![Permission::UserManage],
+    }
+}
+
+#[rstest]
+#[case(auditor_role, StatusCode::OK)]
+#[case(operator_role, StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn show_audit_logs_with_custom_role(
+    fixture_registry: registry::MockAppRegistryExt,
+    #[case] role: fn() -> Role,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = with_authorized_role(fixture_registry, role);
+    registry.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_find_all().returning(|opt| {
+            Ok(PaginatedList {
+                total: 0,
+                limit: opt.limit,
+                offset: opt.offset,
+                items: vec![],
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+    let req = Request::get(&v1(&format!("/audit-logs?actorId={}", UserId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
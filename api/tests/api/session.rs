@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{admin_role, fixture_registry, make_router, user_role, v1, TestRequestExt},
+};
+use api::model::session::SessionsResponse;
+use kernel::{
+    model::{
+        audit::AuditAction,
+        auth::Session,
+        id::{SessionId, UserId},
+        user::User,
+    },
+    repository::{audit::MockAuditRepository, auth::MockAuthRepository},
+};
+use shared::error::AppError;
+
+/// ログイン中のユーザーを `user_id` に固定し、セッション操作の期待値を `setup` で追加する
+fn with_auth(
+    registry: &mut registry::MockAppRegistryExt,
+    user_id: UserId,
+    admin: bool,
+    setup: fn(&mut MockAuthRepository),
+) {
+    registry.expect_auth_repository().returning(move || {
+        let mut mock = MockAuthRepository::new();
+        mock.expect_fetch_user_from_token().returning(move |_| {
+            Ok(Some(User {
+                id: user_id,
+                email: "dummy@example.com".to_string(),
+                name: "dummy".to_string(),
+                role: if admin { admin_role() } else { user_role() },
+            }))
+        });
+        setup(&mut mock);
+        Arc::new(mock)
+    });
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_sessions_200(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    with_auth(&mut fixture_registry, UserId::new(), false, |mock| {
+        mock.expect_find_sessions().returning(|user_id| {
+            let now = Utc::now();
+            Ok(vec![Session {
+                id: SessionId::new(),
+                user_id,
+                user_agent: Some("curl/8.0".to_string()),
+                created_at: now,
+                last_seen_at: now,
+            }])
+        });
+    });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = Request::get(&v1("/users/me/sessions"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, SessionsResponse);
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].user_agent.as_deref(), Some("curl/8.0"));
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn revoke_session_204(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let session_id = SessionId::new();
+
+    fixture_registry
+        .expect_auth_repository()
+        .returning(move || {
+            let mut mock = MockAuthRepository::new();
+            mock.expect_fetch_user_from_token().returning(move |_| {
+                Ok(Some(User {
+                    id: user_id,
+                    email: "dummy@example.com".to_string(),
+                    name: "dummy".to_string(),
+                    role: user_role(),
+                }))
+            });
+            mock.expect_revoke_session()
+                .withf(move |e| e.session_id == session_id && e.user_id == user_id)
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+    fixture_registry
+        .expect_audit_repository()
+        .returning(move || {
+            let mut mock = MockAuditRepository::new();
+            mock.expect_record()
+                .withf(move |e| {
+                    e.action == AuditAction::SessionRevoked && e.target_id == session_id.raw()
+                })
+                .times(1)
+                .returning(|_| Ok(()));
+            Arc::new(mock)
+        });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = Request::delete(&v1(&format!("/users/me/sessions/{session_id}")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn revoke_session_of_other_user_404(
+    mut fixture_registry: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    with_auth(&mut fixture_registry, UserId::new(), false, |mock| {
+        mock.expect_revoke_session().returning(|_| {
+            Err(AppError::EntityNotFound(
+                "Specified session not found".to_string(),
+            ))
+        });
+    });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = Request::delete(&v1(&format!("/users/me/sessions/{}", SessionId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(true, StatusCode::NO_CONTENT)]
+#[case(false, StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn revoke_user_sessions_requires_admin(
+    mut fixture_registry: registry::MockAppRegistryExt,
+    #[case] admin: bool,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    with_auth(&mut fixture_registry, UserId::new(), admin, |mock| {
+        mock.expect_revoke_all_sessions().returning(|_| Ok(()));
+    });
+    fixture_registry.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_registry);
+    let req = Request::delete(&v1(&format!("/users/{}/sessions", UserId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_admin, fixture_auth, fixture_registry, make_router, v1, TestRequestExt},
+};
+use api::model::tag::{TagResponse, TagsResponse};
+use kernel::{
+    model::{
+        audit::AuditAction,
+        id::{BookId, TagId},
+        tag::Tag,
+    },
+    repository::{audit::MockAuditRepository, tag::MockTagRepository},
+};
+
+fn tag_request(method: &str, path: &str, name: &str) -> anyhow::Result<Request<Body>> {
+    Ok(Request::builder()
+        .method(method)
+        .uri(v1(path))
+        .bearer()
+        .header("Content-Type", "application/json")
+        .body(Body::from(serde_json::json!({ "name": name }).to_string()))?)
+}
+
+fn expect_audit(registry: &mut registry::MockAppRegistryExt, action: AuditAction) {
+    registry.expect_audit_repository().returning(move || {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(move |e| e.action == action)
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_tags_200(mut fixture_auth: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture_auth.expect_tag_repository().returning(|| {
+        let mut mock = MockTagRepository::new();
+        mock.expect_find_all().returning(|| {
+            Ok(vec![Tag {
+                id: TagId::new(),
+                name: "Rust".into(),
+            }])
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::get(&v1("/tags")).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, TagsResponse);
+    assert_eq!(result.items.len(), 1);
+    assert_eq!(result.items[0].name, "Rust");
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_tag_201(mut fixture_auth: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    fixture_auth.expect_tag_repository().returning(|| {
+        let mut mock = MockTagRepository::new();
+        mock.expect_create()
+            .withf(|e| e.name == "Rust")
+            .returning(|e| {
+                Ok(Tag {
+                    id: TagId::new(),
+                    name: e.name,
+                })
+            });
+        Arc::new(mock)
+    });
+    expect_audit(&mut fixture_auth, AuditAction::TagCreated);
+
+    let app: axum::Router = make_router(fixture_auth);
+    let resp = app.oneshot(tag_request("POST", "/tags", "Rust")?).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, TagResponse);
+    assert_eq!(result.name, "Rust");
+
+    Ok(())
+}
+
+#[rstest]
+#[case("")]
+#[case(" Rust")]
+#[tokio::test]
+async fn create_tag_with_invalid_name_400(
+    mut fixture_auth: registry::MockAppRegistryExt,
+    #[case] name: &str,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_tag_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let resp = app.oneshot(tag_request("POST", "/tags", name)?).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_tag_without_permission_403(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_tag_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let path = format!("/tags/{}", TagId::new());
+    let resp = app.oneshot(tag_request("PUT", &path, "Rust")?).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn delete_tag_204(mut fixture_admin: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let tag_id = TagId::new();
+    fixture_admin.expect_tag_repository().returning(move || {
+        let mut mock = MockTagRepository::new();
+        mock.expect_find_by_id().returning(move |id| {
+            Ok(Some(Tag {
+                id,
+                name: "Rust".into(),
+            }))
+        });
+        mock.expect_delete()
+            .withf(move |e| e.tag_id == tag_id)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+    expect_audit(&mut fixture_admin, AuditAction::TagDeleted);
+
+    let app: axum::Router = make_router(fixture_admin);
+    let req = Request::delete(&v1(&format!("/tags/{tag_id}")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(fixture_auth(fixture_registry()), false)]
+#[case(fixture_admin(fixture_registry()), true)]
+#[tokio::test]
+async fn add_book_tag_204(
+    #[case] mut registry: registry::MockAppRegistryExt,
+    #[case] any_owner: bool,
+) -> anyhow::Result<()> {
+    let tag_id = TagId::new();
+    let book_id = BookId::new();
+    registry.expect_tag_repository().returning(move || {
+        let mut mock = MockTagRepository::new();
+        mock.expect_add_to_book()
+            .withf(move |e| e.tag_id == tag_id && e.book_id == book_id && e.any_owner == any_owner)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+    expect_audit(&mut registry, AuditAction::BookTagAdded);
+
+    let app: axum::Router = make_router(registry);
+    let req = Request::put(&v1(&format!("/tags/{tag_id}/books/{book_id}")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
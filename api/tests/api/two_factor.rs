@@ -0,0 +1,213 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use rstest::rstest;
+use tower::ServiceExt;
+
+use crate::{
+    deserialize_json,
+    helper::{fixture_admin, fixture_auth, make_router, v1, TestRequestExt},
+};
+use api::model::two_factor::{TwoFactorEnrollmentResponse, TwoFactorStatusResponse};
+use kernel::{
+    model::{
+        audit::AuditAction,
+        auth::two_factor::{TwoFactorEnrollment, TwoFactorStatus},
+    },
+    repository::{audit::MockAuditRepository, two_factor::MockTwoFactorRepository},
+};
+use shared::error::AppError;
+
+fn json_request(
+    method: &str,
+    path: &str,
+    body: serde_json::Value,
+) -> anyhow::Result<Request<Body>> {
+    Ok(Request::builder()
+        .method(method)
+        .uri(v1(path))
+        .bearer()
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))?)
+}
+
+#[rstest]
+#[tokio::test]
+async fn show_two_factor_status_200(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_two_factor_repository().returning(|| {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_find_status().returning(|_| {
+            Ok(TwoFactorStatus {
+                enabled: false,
+                required: true,
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::get(&v1("/users/me/2fa"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, TwoFactorStatusResponse);
+    assert!(!result.enabled);
+    assert!(result.required);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn enroll_two_factor_200(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_two_factor_repository().returning(|| {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_enroll().returning(|_| {
+            Ok(TwoFactorEnrollment {
+                secret: "JBSWY3DPEHPK3PXP".into(),
+                otpauth_uri: "otpauth://totp/rusty-book-manager?secret=JBSWY3DPEHPK3PXP".into(),
+                recovery_codes: vec!["recovery-code".into()],
+            })
+        });
+        Arc::new(mock)
+    });
+    fixture_auth.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(|e| e.action == AuditAction::TwoFactorEnrolled)
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = Request::post(&v1("/users/me/2fa"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, TwoFactorEnrollmentResponse);
+    assert_eq!(result.secret, "JBSWY3DPEHPK3PXP");
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn confirm_two_factor_204(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_two_factor_repository().returning(|| {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_confirm()
+            .withf(|e| e.code == "123456")
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+    fixture_auth.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(|e| e.action == AuditAction::TwoFactorEnabled)
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = json_request(
+        "POST",
+        "/users/me/2fa/confirm",
+        serde_json::json!({ "code": "123456" }),
+    )?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn disable_required_two_factor_403(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_two_factor_repository().returning(|| {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_disable()
+            .returning(|_| Err(AppError::ForbiddenOperationError));
+        Arc::new(mock)
+    });
+    fixture_auth.expect_audit_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = json_request(
+        "DELETE",
+        "/users/me/2fa",
+        serde_json::json!({ "code": "123456" }),
+    )?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_two_factor_policy_200(
+    mut fixture_admin: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_admin.expect_two_factor_repository().returning(|| {
+        let mut mock = MockTwoFactorRepository::new();
+        mock.expect_update_policy()
+            .withf(|e| e.role_name == "Admin" && e.required)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+    fixture_admin.expect_audit_repository().returning(|| {
+        let mut mock = MockAuditRepository::new();
+        mock.expect_record()
+            .withf(|e| e.action == AuditAction::TwoFactorPolicyChanged)
+            .times(1)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture_admin);
+    let req = json_request(
+        "PUT",
+        "/users/2fa-policy",
+        serde_json::json!({ "role": "Admin", "required": true }),
+    )?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_two_factor_policy_by_non_admin_403(
+    mut fixture_auth: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture_auth.expect_two_factor_repository().never();
+
+    let app: axum::Router = make_router(fixture_auth);
+    let req = json_request(
+        "PUT",
+        "/users/2fa-policy",
+        serde_json::json!({ "role": "User", "required": true }),
+    )?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
@@ -3,6 +3,20 @@ INSERT INTO
 VALUES ('Admin'),
     ('User') ON CONFLICT DO NOTHING;
 
+INSERT INTO role_permissions (role_id, permission)
+SELECT role_id, permission
+FROM roles
+CROSS JOIN UNNEST(ARRAY[
+    'book:write:any',
+    'checkout:create:any',
+    'checkout:return:any',
+    'user:manage',
+    'role:manage',
+    'audit:read'
+]) AS permission
+WHERE name = 'Admin'
+ON CONFLICT DO NOTHING;
+
 INSERT INTO
     users (
         name,
@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+
+use super::ApiKeyScope;
+use crate::model::id::{ApiKeyId, UserId};
+
+#[derive(new)]
+pub struct CreateApiKey {
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    /// `None` の場合は失効させるまで使える
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(new)]
+pub struct RevokeApiKey {
+    pub user_id: UserId,
+    pub api_key_id: ApiKeyId,
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use strum::{AsRefStr, EnumIter, EnumString};
+
+use super::{
+    id::{ApiKeyId, UserId},
+    user::User,
+};
+
+pub mod event;
+
+/// API キーで許可する操作の範囲
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, AsRefStr, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// 蔵書の参照
+    ReadBooks,
+    /// 貸出・返却・予約の操作
+    ManageCheckouts,
+    /// 上記を含む、発行したユーザーが行えるすべての操作
+    Admin,
+}
+
+impl ApiKeyScope {
+    pub fn grants(&self, required: ApiKeyScope) -> bool {
+        *self == Self::Admin || *self == required
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    pub id: ApiKeyId,
+    pub user_id: UserId,
+    pub name: String,
+    /// 一覧でキーを見分けるための、シークレットの先頭部分
+    pub prefix: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 発行直後の API キー。シークレットはハッシュ値のみを保存するため、これ以降は取得できない。
+#[derive(Debug)]
+pub struct IssuedApiKey {
+    pub api_key: ApiKey,
+    pub secret: String,
+}
+
+/// API キーで認証されたユーザー
+#[derive(Debug)]
+pub struct ApiKeyPrincipal {
+    pub api_key_id: ApiKeyId,
+    pub user: User,
+    pub scopes: Vec<ApiKeyScope>,
+}
+
+impl ApiKeyPrincipal {
+    pub fn has_scope(&self, required: ApiKeyScope) -> bool {
+        self.scopes.iter().any(|scope| scope.grants(required))
+    }
+}
@@ -0,0 +1,15 @@
+use derive_new::new;
+
+use crate::model::id::UserId;
+
+use super::AuditAction;
+
+#[derive(Debug, new)]
+pub struct CreateAuditLog {
+    pub actor_id: UserId,
+    pub action: AuditAction,
+    pub target_id: uuid::Uuid,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use strum::{AsRefStr, EnumString};
+
+use super::id::{AuditLogId, UserId};
+
+pub mod event;
+
+/// 監査ログに記録する変更操作の種別。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum AuditAction {
+    BookCreated,
+    BookUpdated,
+    BookDeleted,
+    BookCopyCreated,
+    BookCopyUpdated,
+    BookCopyDeleted,
+    BookCoverUpdated,
+    BookCheckedOut,
+    BookReturned,
+    CheckoutRenewed,
+    ReservationPlaced,
+    ReservationCancelled,
+    UserCreated,
+    UserDeleted,
+    UserRoleChanged,
+    UserPasswordChanged,
+    NotificationPreferencesUpdated,
+    LoggedIn,
+    LoggedOut,
+    TokenRefreshed,
+    SessionRevoked,
+    AllSessionsRevoked,
+    AccountUnlocked,
+    PasswordResetRequested,
+    PasswordReset,
+    TwoFactorEnrolled,
+    TwoFactorEnabled,
+    TwoFactorDisabled,
+    TwoFactorPolicyChanged,
+    ApiKeyCreated,
+    ApiKeyRevoked,
+    RoleCreated,
+    RoleUpdated,
+    RoleDeleted,
+    TagCreated,
+    TagUpdated,
+    TagDeleted,
+    BookTagAdded,
+    BookTagRemoved,
+    CollectionCreated,
+    CollectionUpdated,
+    CollectionDeleted,
+    CollectionBookAdded,
+    CollectionBookRemoved,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    pub id: AuditLogId,
+    pub actor_id: UserId,
+    pub action: AuditAction,
+    /// 操作の対象となったエンティティの ID
+    pub target_id: uuid::Uuid,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+pub struct AuditLogListOptions {
+    pub actor_id: Option<UserId>,
+    pub target_id: Option<uuid::Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: i64,
+    pub offset: i64,
+}
@@ -1,18 +1,29 @@
-use uuid::Uuid;
+use derive_new::new;
 
-use crate::model::id::UserId;
+use crate::model::id::{SessionId, UserId};
 
+#[derive(new)]
 pub struct CreateToken {
     pub user_id: UserId,
-    pub access_token: String,
+    /// ログイン時のクライアントの User-Agent
+    pub user_agent: Option<String>,
 }
 
-impl CreateToken {
-    pub fn new(user_id: UserId) -> Self {
-        let access_token = Uuid::new_v4().simple().to_string();
-        Self {
-            user_id,
-            access_token,
-        }
-    }
+#[derive(new)]
+pub struct RevokeSession {
+    pub session_id: SessionId,
+    pub user_id: UserId,
+}
+
+/// パスワードの再設定を依頼する。登録されていないメールアドレスの場合も同じように扱う。
+#[derive(new)]
+pub struct RequestPasswordReset {
+    pub email: String,
+}
+
+#[derive(new)]
+pub struct ConfirmPasswordReset {
+    /// 再設定の依頼時に利用者へ送信したトークン
+    pub token: String,
+    pub new_password: String,
 }
@@ -1,3 +1,51 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use derive_new::new;
+
+use crate::model::{
+    id::{SessionId, UserId},
+    user::User,
+};
+
 pub mod event;
+pub mod oidc;
+pub mod two_factor;
 
 pub struct AccessToken(pub String);
+pub struct RefreshToken(pub String);
+
+/// ログインやリフレッシュ時に発行される、アクセストークンとリフレッシュトークンの組
+pub struct AuthToken {
+    pub user_id: UserId,
+    pub access_token: AccessToken,
+    pub refresh_token: RefreshToken,
+    /// アクセストークンの有効期間（秒）
+    pub expires_in: u64,
+}
+
+/// ログイン 1 回ごとに作られるセッション。リフレッシュトークンのローテーションをまたいで維持される。
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub id: SessionId,
+    pub user_id: UserId,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// 検証済みのアクセストークンから得られる情報
+pub struct AccessTokenClaims {
+    pub user_id: UserId,
+    pub session_id: Option<SessionId>,
+    /// トークン自体にユーザー情報が含まれる場合は、データベースを参照せずに認可できる
+    pub user: Option<User>,
+}
+
+/// ログインの試行。失敗回数はメールアドレスと接続元の IP アドレスのそれぞれで数える。
+#[derive(Debug, Clone, new)]
+pub struct LoginAttempt {
+    pub email: String,
+    /// 接続元を特定できない場合は、メールアドレスでのみ制限する
+    pub client_ip: Option<IpAddr>,
+}
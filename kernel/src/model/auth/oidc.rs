@@ -0,0 +1,21 @@
+use derive_new::new;
+
+use crate::model::id::UserId;
+
+/// IdP の認可エンドポイントへ利用者を誘導するための URL
+pub struct OidcAuthorizationRequest {
+    pub authorization_url: String,
+}
+
+/// IdP からリダイレクトで戻ってきた認可コードと state
+#[derive(new)]
+pub struct OidcCallback {
+    pub code: String,
+    pub state: String,
+}
+
+pub struct OidcLogin {
+    pub user_id: UserId,
+    /// このログインでユーザーが自動登録された場合に `true`
+    pub provisioned: bool,
+}
@@ -0,0 +1,53 @@
+use derive_new::new;
+
+use crate::model::id::UserId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    /// ユーザーのロールで 2 段階認証が必須とされているか
+    pub required: bool,
+}
+
+/// 認証アプリへの登録に必要な情報。秘密鍵とリカバリーコードはこのときにしか返さない。
+pub struct TwoFactorEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+    pub recovery_codes: Vec<String>,
+}
+
+/// パスワードの確認後、2 段階目のコードの入力を求めるためのチャレンジ
+pub struct TwoFactorChallenge {
+    pub challenge_token: String,
+    /// ロールで必須とされているが未登録の場合に `true`。コードの確認前に登録を済ませる必要がある。
+    pub enrollment_required: bool,
+    /// チャレンジの有効期間（秒）
+    pub expires_in: u64,
+}
+
+/// 登録した認証アプリのコードを確認し、2 段階認証を有効にする
+#[derive(new)]
+pub struct ConfirmTwoFactor {
+    pub user_id: UserId,
+    pub code: String,
+}
+
+/// 認証アプリのコードまたはリカバリーコードを確認し、2 段階認証を無効にする
+#[derive(new)]
+pub struct DisableTwoFactor {
+    pub user_id: UserId,
+    pub code: String,
+}
+
+/// チャレンジに対して認証アプリのコードまたはリカバリーコードを確認する
+#[derive(new)]
+pub struct VerifyTwoFactorChallenge {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+#[derive(new)]
+pub struct UpdateTwoFactorPolicy {
+    pub role_name: String,
+    pub required: bool,
+}
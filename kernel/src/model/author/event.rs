@@ -0,0 +1,28 @@
+use derive_new::new;
+
+use super::{split_author_names, AuthorRole};
+use crate::model::id::AuthorId;
+
+/// 登録済みの人物を ID で指すか、名前で指す。名前の場合は未登録であれば新たに登録する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthorRef {
+    Id(AuthorId),
+    Name(String),
+}
+
+/// 蔵書の登録・更新時に指定する、蔵書に関わった人物とその役割。
+#[derive(Debug, Clone, PartialEq, Eq, new)]
+pub struct BookAuthorEntry {
+    pub author: AuthorRef,
+    pub role: AuthorRole,
+}
+
+impl BookAuthorEntry {
+    /// 1 つの文字列で書かれた著者を、著者の役割を持つ人物の並びに分ける。
+    pub fn from_display_name(value: &str) -> Vec<Self> {
+        split_author_names(value)
+            .into_iter()
+            .map(|name| Self::new(AuthorRef::Name(name), AuthorRole::Author))
+            .collect()
+    }
+}
@@ -0,0 +1,41 @@
+use strum::{AsRefStr, EnumString};
+
+use super::id::AuthorId;
+
+pub mod event;
+
+/// 著者や編者などの人物。表記の揺れを避けるため、大文字・小文字の違いだけの名前は同じ人物として扱う。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    pub id: AuthorId,
+    pub name: String,
+}
+
+/// 蔵書に関わった人物と、その役割。蔵書ごとに登録した順序で並ぶ。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookAuthor {
+    pub id: AuthorId,
+    pub name: String,
+    pub role: AuthorRole,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum AuthorRole {
+    #[default]
+    Author,
+    Editor,
+    Translator,
+    Illustrator,
+}
+
+/// 「豊田優貴, 松本健太郎」のように 1 つの文字列で書かれた著者を、人物ごとに分ける。
+/// 区切りとみなす文字はマイグレーションで既存の蔵書を分けた際のものと揃えている。
+pub fn split_author_names(value: &str) -> Vec<String> {
+    value
+        .split([',', ';', '、', '，', '；'])
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -1,8 +1,17 @@
-use crate::model::id::{BookId, UserId};
+use derive_new::new;
+
+use crate::model::{
+    author::event::BookAuthorEntry,
+    book::CopyCondition,
+    id::{BookCopyId, BookId, UserId},
+};
 
 pub struct CreateBook {
     pub title: String,
+    /// 表示用の著者。空の場合は `authors` の名前をつなげたものを使う
     pub author: String,
+    /// 空の場合は `author` を区切って著者を登録する
+    pub authors: Vec<BookAuthorEntry>,
     pub isbn: String,
     pub description: String,
 }
@@ -11,14 +20,67 @@ pub struct CreateBook {
 pub struct UpdateBook {
     pub book_id: BookId,
     pub title: String,
+    /// 表示用の著者。空の場合は `authors` の名前をつなげたものを使う
     pub author: String,
+    /// 空の場合は `author` を区切って著者を登録し直す
+    pub authors: Vec<BookAuthorEntry>,
     pub isbn: String,
     pub description: String,
     pub requested_user: UserId,
+    /// `true` の場合は、他のユーザーが所有する蔵書も更新できる
+    pub any_owner: bool,
 }
 
 #[derive(Debug)]
 pub struct DeleteBook {
     pub book_id: BookId,
     pub requested_user: UserId,
+    /// `true` の場合は、他のユーザーが所有する蔵書も削除できる
+    pub any_owner: bool,
+}
+
+#[derive(Debug, new)]
+pub struct CreateBookCopy {
+    pub book_id: BookId,
+    pub barcode: String,
+    pub shelf_location: String,
+    pub condition: CopyCondition,
+    pub requested_user: UserId,
+    /// `true` の場合は、他のユーザーが所有する蔵書にも複本を追加できる
+    pub any_owner: bool,
+}
+
+#[derive(Debug, new)]
+pub struct UpdateBookCopy {
+    pub book_id: BookId,
+    pub copy_id: BookCopyId,
+    pub barcode: String,
+    pub shelf_location: String,
+    pub condition: CopyCondition,
+    pub requested_user: UserId,
+    /// `true` の場合は、他のユーザーが所有する蔵書の複本も更新できる
+    pub any_owner: bool,
+}
+
+/// 貸出中の複本は削除できない
+#[derive(Debug, new)]
+pub struct DeleteBookCopy {
+    pub book_id: BookId,
+    pub copy_id: BookCopyId,
+    pub requested_user: UserId,
+    /// `true` の場合は、他のユーザーが所有する蔵書の複本も削除できる
+    pub any_owner: bool,
+}
+
+/// 表紙画像を登録する。既に登録されている場合は置き換える。
+/// 縮小した画像は元の画像と同じ形式で保存する
+#[derive(Debug, new)]
+pub struct UpdateBookCover {
+    pub book_id: BookId,
+    pub content_type: String,
+    pub image: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+    pub requested_user: UserId,
+    /// `true` の場合は、他のユーザーが所有する蔵書の表紙画像も登録できる
+    pub any_owner: bool,
 }
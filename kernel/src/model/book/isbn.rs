@@ -0,0 +1,121 @@
+/// ISBN-10 または ISBN-13 の文字列を検証し、ハイフンを含まない ISBN-13 に正規化する。
+///
+/// 区切り文字としてハイフンと空白を許容する。チェックディジットが一致しない場合は `None` を返す。
+pub fn normalize_isbn(value: &str) -> Option<String> {
+    let compact: String = value
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    match compact.len() {
+        10 if is_valid_isbn10(&compact) => Some(isbn10_to_isbn13(&compact)),
+        13 if is_valid_isbn13(&compact) => Some(compact),
+        _ => None,
+    }
+}
+
+fn is_valid_isbn10(isbn: &str) -> bool {
+    let mut sum = 0;
+    for (i, c) in isbn.chars().enumerate() {
+        let digit = match c {
+            '0'..='9' => c as u32 - '0' as u32,
+            // チェックディジットに限り、10 を X で表す
+            'X' if i == 9 => 10,
+            _ => return false,
+        };
+        sum += digit * (10 - i as u32);
+    }
+    sum % 11 == 0
+}
+
+fn is_valid_isbn13(isbn: &str) -> bool {
+    if !isbn.chars().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    let (body, check) = isbn.split_at(12);
+    isbn13_check_digit(body) == check.chars().next()
+}
+
+fn isbn10_to_isbn13(isbn: &str) -> String {
+    let body = format!("978{}", &isbn[..9]);
+    let check = isbn13_check_digit(&body).unwrap_or('0');
+    format!("{body}{check}")
+}
+
+/// ISBN-13 の先頭 12 桁からチェックディジットを計算する。
+fn isbn13_check_digit(body: &str) -> Option<char> {
+    let mut sum = 0;
+    for (i, c) in body.chars().enumerate() {
+        let digit = c.to_digit(10)?;
+        sum += if i % 2 == 0 { digit } else { digit * 3 };
+    }
+    char::from_digit((10 - sum % 10) % 10, 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_isbn() {
+        assert_eq!(
+            normalize_isbn("978-4-06-536957-9").as_deref(),
+            Some("9784065369579")
+        );
+        assert_eq!(
+            normalize_isbn("4-06-536957-6").as_deref(),
+            Some("9784065369579")
+        );
+        // 区切り文字として空白も許容する
+        assert_eq!(
+            normalize_isbn(" 978 4 06 536957 9 ").as_deref(),
+            Some("9784065369579")
+        );
+        // チェックディジットが X の ISBN-10 は小文字でもよい
+        assert_eq!(
+            normalize_isbn("0-8044-2957-x").as_deref(),
+            Some("9780804429573")
+        );
+
+        // チェックディジットの誤り
+        assert_eq!(normalize_isbn("978-4-06-536957-0"), None);
+        assert_eq!(normalize_isbn("4-06-536957-X"), None);
+        // 桁数の誤り
+        assert_eq!(normalize_isbn(""), None);
+        assert_eq!(normalize_isbn("406536957"), None);
+        assert_eq!(normalize_isbn("97840653695790"), None);
+        // X は ISBN-10 のチェックディジットにしか使えない
+        assert_eq!(normalize_isbn("4X65369576"), None);
+        assert_eq!(normalize_isbn("978406536957X"), None);
+        // ASCII 以外の数字は受け付けない
+        assert_eq!(normalize_isbn("９７８４０６５３６９５７９"), None);
+        assert_eq!(normalize_isbn("978406536957٩"), None);
+        assert_eq!(normalize_isbn("４０６５３６９５７６"), None);
+    }
+
+    #[test]
+    fn test_isbn10_check_digit() {
+        assert!(is_valid_isbn10("4065369576"));
+        assert!(is_valid_isbn10("080442957X"));
+        assert!(!is_valid_isbn10("4065369575"));
+        assert!(!is_valid_isbn10("40653695X6"));
+    }
+
+    #[test]
+    fn test_isbn13_check_digit() {
+        assert_eq!(isbn13_check_digit("978406536957"), Some('9'));
+        // チェックディジットが 0 になる場合
+        assert_eq!(isbn13_check_digit("978406536950"), Some('0'));
+        assert_eq!(isbn13_check_digit("97840653695X"), None);
+        assert!(is_valid_isbn13("9784065369579"));
+        assert!(!is_valid_isbn13("9784065369578"));
+        assert!(!is_valid_isbn13("978406536957X"));
+    }
+
+    #[test]
+    fn test_isbn10_to_isbn13() {
+        assert_eq!(isbn10_to_isbn13("4065369576"), "9784065369579");
+        assert_eq!(isbn10_to_isbn13("080442957X"), "9780804429573");
+    }
+}
@@ -1,27 +1,155 @@
 use chrono::{DateTime, Utc};
+use strum::{AsRefStr, EnumString};
 
 use super::{
-    id::{BookId, CheckoutId},
+    author::BookAuthor,
+    id::{AuthorId, BookCopyId, BookId, CheckoutId, CollectionId, TagId, UserId},
+    tag::Tag,
     user::{BookOwner, CheckoutUser},
 };
 
 pub mod event;
+pub mod isbn;
 
 #[derive(Debug)]
 pub struct Book {
     pub id: BookId,
     pub title: String,
+    /// 表示や検索に使う、1 つの文字列にまとめた著者
     pub author: String,
+    pub authors: Vec<BookAuthor>,
     pub isbn: String,
     pub description: String,
     pub owner: BookOwner,
-    pub checkout: Option<Checkout>,
+    pub copies: Vec<BookCopy>,
+    /// 表紙画像が登録されていない場合は `None`
+    pub cover: Option<BookCover>,
+    pub tags: Vec<Tag>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Book {
+    pub fn total_copies(&self) -> usize {
+        self.copies.len()
+    }
+
+    /// 貸出中でない複本の数
+    pub fn available_copies(&self) -> usize {
+        self.copies
+            .iter()
+            .filter(|copy| copy.checkout.is_none())
+            .count()
+    }
 }
 
+/// 蔵書の書誌情報に対して、実際に貸し出す 1 冊ごとの複本。
 #[derive(Debug)]
+pub struct BookCopy {
+    pub id: BookCopyId,
+    pub barcode: String,
+    pub shelf_location: String,
+    pub condition: CopyCondition,
+    pub checkout: Option<Checkout>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum CopyCondition {
+    New,
+    Good,
+    Fair,
+    Poor,
+    Damaged,
+}
+
+#[derive(Debug, Default)]
 pub struct BookListOptions {
     pub limit: i64,
     pub offset: i64,
+    /// タイトル・著者・説明文を対象としたフリーテキスト検索の文字列
+    pub query: Option<String>,
+    pub owner_id: Option<UserId>,
+    pub checkout_status: Option<CheckoutStatus>,
+    pub isbn_prefix: Option<String>,
+    pub tag_id: Option<TagId>,
+    /// 参照できるコレクションかどうかは、呼び出し側で確かめておく
+    pub collection_id: Option<CollectionId>,
+    pub author_id: Option<AuthorId>,
+    /// 指定した蔵書より後に並ぶ蔵書だけを取得する。一覧を辿る間に蔵書が追加・削除されても、
+    /// `offset` と違って取得漏れや重複が起きない。検索語を指定した場合は関連度順に並ぶため使えない
+    pub after: Option<BookCursor>,
+}
+
+impl BookListOptions {
+    /// 絞り込み条件が 1 つでも指定されている場合は検索モードとして扱う。
+    pub fn is_search(&self) -> bool {
+        self.query.is_some()
+            || self.owner_id.is_some()
+            || self.checkout_status.is_some()
+            || self.isbn_prefix.is_some()
+            || self.tag_id.is_some()
+            || self.collection_id.is_some()
+            || self.author_id.is_some()
+    }
+}
+
+/// 登録日時の新しい順に並べた蔵書の一覧での位置。直前に取得した蔵書の登録日時と ID で表す。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookCursor {
+    pub created_at: DateTime<Utc>,
+    pub book_id: BookId,
+}
+
+impl From<&Book> for BookCursor {
+    fn from(value: &Book) -> Self {
+        Self {
+            created_at: value.created_at,
+            book_id: value.id,
+        }
+    }
+}
+
+/// 貸出状況による絞り込み。貸出中でない複本が 1 冊でもあれば貸出可能とみなす。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum CheckoutStatus {
+    Available,
+    CheckedOut,
+}
+
+/// 登録済みの表紙画像の情報。画像そのものは `BlobStore` に保存する。
+#[derive(Debug, Clone)]
+pub struct BookCover {
+    pub content_type: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 表紙画像の種類。一覧表示などには縮小した画像を使う。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverVariant {
+    Original,
+    Thumbnail,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverImage {
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// 検索条件に一致した蔵書の内訳。
+/// 各ファセットは自身の絞り込み条件を除いた条件で集計する。
+#[derive(Debug, Default)]
+pub struct BookFacets {
+    pub available: i64,
+    pub checked_out: i64,
+    pub owners: Vec<OwnerFacet>,
+}
+
+#[derive(Debug)]
+pub struct OwnerFacet {
+    pub owner: BookOwner,
+    pub count: i64,
 }
 
 #[derive(Debug)]
@@ -29,4 +157,11 @@ pub struct Checkout {
     pub checkout_id: CheckoutId,
     pub checked_out_by: CheckoutUser,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+}
+
+impl Checkout {
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        now > self.due_at
+    }
 }
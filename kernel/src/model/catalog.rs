@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// 外部の書誌データベースから取得した、蔵書の登録に使える書誌情報。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    /// ハイフンを含まない ISBN-13
+    pub isbn: String,
+    pub title: String,
+    pub author: String,
+    pub description: String,
+}
@@ -1,19 +1,37 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use derive_new::new;
 
-use crate::model::id::{BookId, CheckoutId, UserId};
+use crate::model::id::{BookCopyId, BookId, CheckoutId, UserId};
 
 #[derive(new)]
 pub struct CreateCheckout {
     pub book_id: BookId,
+    /// 借りる複本。指定しない場合は貸出中でない複本から選ぶ
+    pub copy_id: Option<BookCopyId>,
+    /// 本を借りる利用者
     pub checked_out_by: UserId,
+    /// 貸出の手続きをした利用者。本人が手続きした場合は `checked_out_by` と同じになる
+    pub processed_by: UserId,
     pub checked_out_at: DateTime<Utc>,
+    /// 貸出期間。指定しない場合は設定値の貸出期間が使われる
+    pub loan_period: Option<Duration>,
 }
 
 #[derive(new)]
 pub struct UpdateReturned {
     pub checkout_id: CheckoutId,
     pub book_id: BookId,
+    /// 返却の手続きをした利用者
     pub returned_by: UserId,
     pub returned_at: DateTime<Utc>,
+    /// 借りている本人以外の貸出も返却できるか
+    pub any_borrower: bool,
+}
+
+#[derive(new)]
+pub struct UpdateRenewed {
+    pub checkout_id: CheckoutId,
+    pub book_id: BookId,
+    pub renewed_by: UserId,
+    pub renewed_at: DateTime<Utc>,
 }
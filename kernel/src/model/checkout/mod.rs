@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 
-use crate::model::id::{BookId, CheckoutId, UserId};
+use crate::model::id::{BookCopyId, BookId, CheckoutId, UserId};
 
 pub mod event;
 
@@ -8,14 +8,29 @@ pub mod event;
 pub struct Checkout {
     pub id: CheckoutId,
     pub checked_out_by: UserId,
+    /// 貸出の手続きをした利用者
+    pub processed_by: UserId,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i32,
     pub returned_at: Option<DateTime<Utc>>,
+    /// 返却の手続きをした利用者
+    pub returned_by: Option<UserId>,
     pub book: CheckoutBook,
 }
 
+impl Checkout {
+    /// 未返却であれば指定日時の時点で、返却済みであれば返却日時の時点で返却期限を過ぎているかを判定する。
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.returned_at.unwrap_or(now) > self.due_at
+    }
+}
+
 #[derive(Debug)]
 pub struct CheckoutBook {
     pub book_id: BookId,
+    /// 貸し出した複本。複本を導入する前に返却された貸出では記録されていない
+    pub copy_id: Option<BookCopyId>,
     pub title: String,
     pub author: String,
     pub isbn: String,
@@ -0,0 +1,42 @@
+use derive_new::new;
+
+use crate::model::id::{BookId, CollectionId, UserId};
+
+#[derive(new)]
+pub struct CreateCollection {
+    pub name: String,
+    pub description: String,
+    pub shared: bool,
+    pub requested_user: UserId,
+}
+
+/// 名前・説明・共有の設定は所有者のみが変更できる
+#[derive(new)]
+pub struct UpdateCollection {
+    pub collection_id: CollectionId,
+    pub name: String,
+    pub description: String,
+    pub shared: bool,
+    pub requested_user: UserId,
+}
+
+#[derive(new)]
+pub struct DeleteCollection {
+    pub collection_id: CollectionId,
+    pub requested_user: UserId,
+}
+
+/// 蔵書をコレクションに加える。既に含まれている場合は何もしない。
+#[derive(new)]
+pub struct AddCollectionBook {
+    pub collection_id: CollectionId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+}
+
+#[derive(new)]
+pub struct RemoveCollectionBook {
+    pub collection_id: CollectionId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+}
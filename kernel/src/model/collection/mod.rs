@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+
+use super::id::{CollectionId, UserId};
+
+pub mod event;
+
+/// 蔵書をまとめる名前付きの棚。所有者のみが参照できるが、共有すると全ユーザーが参照できる。
+#[derive(Debug)]
+pub struct Collection {
+    pub id: CollectionId,
+    pub name: String,
+    pub description: String,
+    pub owner: CollectionOwner,
+    /// `true` の場合は、他のユーザーも参照・蔵書の出し入れができる
+    pub shared: bool,
+    pub book_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct CollectionOwner {
+    pub id: UserId,
+    pub name: String,
+}
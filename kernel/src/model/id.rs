@@ -65,5 +65,14 @@ macro_rules! defined_id {
 }
 
 defined_id!(BookId);
+defined_id!(BookCopyId);
 defined_id!(UserId);
 defined_id!(CheckoutId);
+defined_id!(ReservationId);
+defined_id!(AuditLogId);
+defined_id!(SessionId);
+defined_id!(ApiKeyId);
+defined_id!(RoleId);
+defined_id!(TagId);
+defined_id!(CollectionId);
+defined_id!(AuthorId);
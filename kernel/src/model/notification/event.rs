@@ -0,0 +1,19 @@
+use derive_new::new;
+
+use crate::model::id::UserId;
+
+use super::{NotificationKind, NotificationPreference};
+
+#[derive(Debug, new)]
+pub struct CreateNotification {
+    pub user_id: UserId,
+    pub kind: NotificationKind,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug)]
+pub struct UpdateNotificationPreferences {
+    pub user_id: UserId,
+    pub preferences: Vec<NotificationPreference>,
+}
@@ -0,0 +1,39 @@
+use strum::{AsRefStr, EnumIter, EnumString};
+
+use super::id::UserId;
+
+pub mod event;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, AsRefStr, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum NotificationKind {
+    CheckedOut,
+    Returned,
+    Overdue,
+    RoleChanged,
+    PasswordReset,
+}
+
+impl NotificationKind {
+    /// 利用者が受け取りを停止できる種別か。手続きに欠かせない通知は停止できない。
+    pub fn is_optional(&self) -> bool {
+        !matches!(self, Self::PasswordReset)
+    }
+}
+
+/// 送信先の情報を解決済みの通知。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub user_id: UserId,
+    pub email: String,
+    pub user_name: String,
+    pub kind: NotificationKind,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotificationPreference {
+    pub kind: NotificationKind,
+    pub enabled: bool,
+}
@@ -0,0 +1,164 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::id::{BookCopyId, BookId, CheckoutId, UserId};
+
+/// 書籍・貸出・ユーザーに対する変更を表すドメインイベント。
+/// 変更と同じトランザクションでアウトボックスに記録され、後から外部へ配信される。
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged, rename_all_fields = "camelCase")]
+pub enum DomainEvent {
+    BookCreated {
+        book_id: BookId,
+        owned_by: UserId,
+        title: String,
+        author: String,
+        isbn: String,
+    },
+    BookUpdated {
+        book_id: BookId,
+        updated_by: UserId,
+        title: String,
+        author: String,
+        isbn: String,
+    },
+    BookDeleted {
+        book_id: BookId,
+        deleted_by: UserId,
+    },
+    BookCopyAdded {
+        book_id: BookId,
+        copy_id: BookCopyId,
+        added_by: UserId,
+        barcode: String,
+        shelf_location: String,
+        condition: String,
+    },
+    BookCopyUpdated {
+        book_id: BookId,
+        copy_id: BookCopyId,
+        updated_by: UserId,
+        barcode: String,
+        shelf_location: String,
+        condition: String,
+    },
+    BookCopyRemoved {
+        book_id: BookId,
+        copy_id: BookCopyId,
+        removed_by: UserId,
+    },
+    BookCoverUpdated {
+        book_id: BookId,
+        updated_by: UserId,
+        content_type: String,
+    },
+    BookCheckedOut {
+        checkout_id: CheckoutId,
+        book_id: BookId,
+        copy_id: BookCopyId,
+        checked_out_by: UserId,
+        processed_by: UserId,
+        checked_out_at: DateTime<Utc>,
+        due_at: DateTime<Utc>,
+    },
+    BookReturned {
+        checkout_id: CheckoutId,
+        book_id: BookId,
+        checked_out_by: UserId,
+        returned_by: UserId,
+        returned_at: DateTime<Utc>,
+    },
+    CheckoutRenewed {
+        checkout_id: CheckoutId,
+        book_id: BookId,
+        renewed_by: UserId,
+        renewed_at: DateTime<Utc>,
+        due_at: DateTime<Utc>,
+    },
+    UserCreated {
+        user_id: UserId,
+        name: String,
+        email: String,
+        role: String,
+    },
+    UserPasswordChanged {
+        user_id: UserId,
+    },
+    UserRoleChanged {
+        user_id: UserId,
+        role: String,
+    },
+    UserDeleted {
+        user_id: UserId,
+    },
+}
+
+impl DomainEvent {
+    pub fn aggregate_type(&self) -> &'static str {
+        match self {
+            Self::BookCreated { .. }
+            | Self::BookUpdated { .. }
+            | Self::BookDeleted { .. }
+            | Self::BookCopyAdded { .. }
+            | Self::BookCopyUpdated { .. }
+            | Self::BookCopyRemoved { .. }
+            | Self::BookCoverUpdated { .. } => "book",
+            Self::BookCheckedOut { .. }
+            | Self::BookReturned { .. }
+            | Self::CheckoutRenewed { .. } => "checkout",
+            Self::UserCreated { .. }
+            | Self::UserPasswordChanged { .. }
+            | Self::UserRoleChanged { .. }
+            | Self::UserDeleted { .. } => "user",
+        }
+    }
+
+    pub fn aggregate_id(&self) -> uuid::Uuid {
+        match self {
+            Self::BookCreated { book_id, .. }
+            | Self::BookUpdated { book_id, .. }
+            | Self::BookDeleted { book_id, .. }
+            | Self::BookCopyAdded { book_id, .. }
+            | Self::BookCopyUpdated { book_id, .. }
+            | Self::BookCopyRemoved { book_id, .. }
+            | Self::BookCoverUpdated { book_id, .. } => book_id.raw(),
+            Self::BookCheckedOut { checkout_id, .. }
+            | Self::BookReturned { checkout_id, .. }
+            | Self::CheckoutRenewed { checkout_id, .. } => checkout_id.raw(),
+            Self::UserCreated { user_id, .. }
+            | Self::UserPasswordChanged { user_id }
+            | Self::UserRoleChanged { user_id, .. }
+            | Self::UserDeleted { user_id } => user_id.raw(),
+        }
+    }
+
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            Self::BookCreated { .. } => "book.created",
+            Self::BookUpdated { .. } => "book.updated",
+            Self::BookDeleted { .. } => "book.deleted",
+            Self::BookCopyAdded { .. } => "book.copy_added",
+            Self::BookCopyUpdated { .. } => "book.copy_updated",
+            Self::BookCopyRemoved { .. } => "book.copy_removed",
+            Self::BookCoverUpdated { .. } => "book.cover_updated",
+            Self::BookCheckedOut { .. } => "checkout.created",
+            Self::BookReturned { .. } => "checkout.returned",
+            Self::CheckoutRenewed { .. } => "checkout.renewed",
+            Self::UserCreated { .. } => "user.created",
+            Self::UserPasswordChanged { .. } => "user.password_changed",
+            Self::UserRoleChanged { .. } => "user.role_changed",
+            Self::UserDeleted { .. } => "user.deleted",
+        }
+    }
+}
+
+/// アウトボックスに記録され、配信を待っているイベント。`payload` は JSON 文字列。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxEvent {
+    pub id: i64,
+    pub aggregate_type: String,
+    pub aggregate_id: uuid::Uuid,
+    pub event_type: String,
+    pub payload: String,
+    pub occurred_at: DateTime<Utc>,
+}
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::model::id::{BookId, ReservationId, UserId};
+
+pub mod event;
+
+/// 返却された書籍を予約者のために取り置いておく期間
+pub const PICKUP_PERIOD_DAYS: i64 = 7;
+
+pub fn pickup_deadline_from(held_at: DateTime<Utc>) -> DateTime<Utc> {
+    held_at + Duration::days(PICKUP_PERIOD_DAYS)
+}
+
+#[derive(Debug)]
+pub struct Reservation {
+    pub id: ReservationId,
+    pub book_id: BookId,
+    pub reserved_by: UserId,
+    pub reserved_at: DateTime<Utc>,
+    /// 予約待ちの列における順番（1 始まり）
+    pub position: i64,
+    /// 取り置き中の場合のみ、受け取り期限が入る
+    pub pickup_deadline: Option<DateTime<Utc>>,
+}
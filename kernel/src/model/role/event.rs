@@ -0,0 +1,22 @@
+use derive_new::new;
+
+use super::Permission;
+use crate::model::id::RoleId;
+
+#[derive(new)]
+pub struct CreateRole {
+    pub name: String,
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(new)]
+pub struct UpdateRole {
+    pub role_id: RoleId,
+    pub name: String,
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(new)]
+pub struct DeleteRole {
+    pub role_id: RoleId,
+}
@@ -0,0 +1,53 @@
+use strum::{AsRefStr, EnumIter, EnumString};
+
+use super::id::RoleId;
+
+pub mod event;
+
+/// 管理者のロール。すべての権限を持ち、変更や削除はできない。
+pub const ADMIN_ROLE_NAME: &str = "Admin";
+/// 新規登録したユーザーに割り当てるロール。権限を持たず、変更や削除はできない。
+pub const DEFAULT_ROLE_NAME: &str = "User";
+
+/// ロールに付与する権限。自身の蔵書や貸出の操作など、全ユーザーに許された操作には権限を要さない。
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, EnumString, AsRefStr, EnumIter,
+)]
+pub enum Permission {
+    /// 他のユーザーが所有する蔵書の更新・削除
+    #[strum(serialize = "book:write:any")]
+    BookWriteAny,
+    /// 他のユーザーに代わっての貸出
+    #[strum(serialize = "checkout:create:any")]
+    CheckoutCreateAny,
+    /// 他のユーザーの貸出の返却
+    #[strum(serialize = "checkout:return:any")]
+    CheckoutReturnAny,
+    /// ユーザーの登録・削除・ロールの変更・ロックの解除など
+    #[strum(serialize = "user:manage")]
+    UserManage,
+    /// ロールの作成・変更・削除と、ロールごとの 2 段階認証の設定
+    #[strum(serialize = "role:manage")]
+    RoleManage,
+    /// 監査ログの参照
+    #[strum(serialize = "audit:read")]
+    AuditRead,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Role {
+    pub id: RoleId,
+    pub name: String,
+    pub permissions: Vec<Permission>,
+}
+
+impl Role {
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+
+    /// 組み込みのロールは、権限を変えると管理者がいなくなるなどの恐れがあるため変更できない
+    pub fn is_builtin(&self) -> bool {
+        self.name == ADMIN_ROLE_NAME || self.name == DEFAULT_ROLE_NAME
+    }
+}
@@ -0,0 +1,38 @@
+use derive_new::new;
+
+use crate::model::id::{BookId, TagId, UserId};
+
+#[derive(new)]
+pub struct CreateTag {
+    pub name: String,
+}
+
+#[derive(new)]
+pub struct UpdateTag {
+    pub tag_id: TagId,
+    pub name: String,
+}
+
+#[derive(new)]
+pub struct DeleteTag {
+    pub tag_id: TagId,
+}
+
+/// 蔵書にタグを付ける。既に付いている場合は何もしない。
+#[derive(Debug, new)]
+pub struct AddBookTag {
+    pub tag_id: TagId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+    /// `true` の場合は、他のユーザーが所有する蔵書にもタグを付けられる
+    pub any_owner: bool,
+}
+
+#[derive(Debug, new)]
+pub struct RemoveBookTag {
+    pub tag_id: TagId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+    /// `true` の場合は、他のユーザーが所有する蔵書からもタグを外せる
+    pub any_owner: bool,
+}
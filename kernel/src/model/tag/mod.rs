@@ -0,0 +1,10 @@
+use super::id::TagId;
+
+pub mod event;
+
+/// 蔵書を分類するためのタグ。ジャンルなどの語彙として全ユーザーで共有する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub id: TagId,
+    pub name: String,
+}
@@ -1,4 +1,4 @@
-use crate::model::{id::UserId, role::Role};
+use crate::model::id::UserId;
 
 #[derive(Debug)]
 pub struct CreateUser {
@@ -10,7 +10,7 @@ pub struct CreateUser {
 #[derive(Debug)]
 pub struct UpdateUserRole {
     pub user_id: UserId,
-    pub role: Role,
+    pub role_name: String,
 }
 
 #[derive(Debug)]
@@ -20,6 +20,14 @@ pub struct UpdateUserPassword {
     pub new_password: String,
 }
 
+/// 現在のパスワードを確認せずにパスワードを設定する。
+/// パスワードの再設定のように、本人確認を別の手段で済ませた場合に用いる。
+#[derive(Debug)]
+pub struct ResetUserPassword {
+    pub user_id: UserId,
+    pub new_password: String,
+}
+
 #[derive(Debug)]
 pub struct DeleteUser {
     pub user_id: UserId,
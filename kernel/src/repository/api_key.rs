@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    api_key::{
+        event::{CreateApiKey, RevokeApiKey},
+        ApiKey, ApiKeyPrincipal, IssuedApiKey,
+    },
+    id::UserId,
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    async fn create(&self, event: CreateApiKey) -> AppResult<IssuedApiKey>;
+    async fn find_all(&self, user_id: UserId) -> AppResult<Vec<ApiKey>>;
+    async fn revoke(&self, event: RevokeApiKey) -> AppResult<()>;
+    /// シークレットに対応する有効な API キーを探し、最終利用日時を更新する
+    async fn authenticate(&self, secret: &str) -> AppResult<Option<ApiKeyPrincipal>>;
+}
@@ -0,0 +1,15 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    audit::{event::CreateAuditLog, AuditLog, AuditLogListOptions},
+    list::PaginatedList,
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    async fn record(&self, event: CreateAuditLog) -> AppResult<()>;
+    /// 条件に一致する監査ログを新しい順に取得する。
+    async fn find_all(&self, options: AuditLogListOptions) -> AppResult<PaginatedList<AuditLog>>;
+}
@@ -2,18 +2,40 @@ use async_trait::async_trait;
 use shared::error::AppResult;
 
 use crate::model::{
-    auth::{event::CreateToken, AccessToken},
-    id::UserId,
+    auth::{
+        event::{CreateToken, RevokeSession},
+        AccessToken, AccessTokenClaims, AuthToken, RefreshToken, Session,
+    },
+    id::{SessionId, UserId},
+    user::User,
 };
 
+/// アクセストークンの発行・検証・失効を担う。
+/// Redis に保存する不透明なトークンと、署名付きの JWT を設定で切り替える。
+#[mockall::automock]
+#[async_trait]
+pub trait AccessTokenStore: Send + Sync {
+    async fn issue(&self, user: &User, session_id: SessionId) -> AppResult<AccessToken>;
+    /// 有効なトークンであればその内容を返す。期限切れや失効済みのトークンは `None` となる。
+    async fn verify(&self, access_token: &AccessToken) -> AppResult<Option<AccessTokenClaims>>;
+    async fn revoke(&self, access_token: &AccessToken) -> AppResult<()>;
+}
+
 #[mockall::automock]
 #[async_trait]
 pub trait AuthRepository: Send + Sync {
-    async fn fetch_user_id_from_token(
-        &self,
-        access_token: &AccessToken,
-    ) -> AppResult<Option<UserId>>;
+    async fn fetch_user_from_token(&self, access_token: &AccessToken) -> AppResult<Option<User>>;
     async fn verify_user(&self, email: &str, password: &str) -> AppResult<UserId>;
-    async fn create_token(&self, event: CreateToken) -> AppResult<AccessToken>;
+    /// 新しいセッションを作成し、アクセストークンとリフレッシュトークンを発行する
+    async fn create_token(&self, event: CreateToken) -> AppResult<AuthToken>;
+    /// リフレッシュトークンをローテーションし、新しいトークンの組を発行する。
+    /// 使用済みのリフレッシュトークンが再利用された場合は、トークンファミリー全体を失効させる。
+    async fn refresh_token(&self, refresh_token: RefreshToken) -> AppResult<AuthToken>;
+    /// アクセストークンと、それが属するセッションを失効させる
     async fn delete_token(&self, access_token: AccessToken) -> AppResult<()>;
+    /// ユーザーの有効なセッションを、最終アクセス日時の新しい順に取得する
+    async fn find_sessions(&self, user_id: UserId) -> AppResult<Vec<Session>>;
+    async fn revoke_session(&self, event: RevokeSession) -> AppResult<()>;
+    /// ユーザーのすべてのセッションを失効させる
+    async fn revoke_all_sessions(&self, user_id: UserId) -> AppResult<()>;
 }
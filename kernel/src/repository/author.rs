@@ -0,0 +1,10 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{author::Author, id::AuthorId};
+
+#[mockall::automock]
+#[async_trait]
+pub trait AuthorRepository: Send + Sync {
+    async fn find_by_id(&self, author_id: AuthorId) -> AppResult<Option<Author>>;
+}
@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+/// 画像などのバイナリデータを、キーを指定して保存する先。保存先は設定によって切り替える。
+#[mockall::automock]
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// 同じキーのデータが既にある場合は上書きする。
+    async fn put(&self, key: &str, content_type: &str, data: Vec<u8>) -> AppResult<()>;
+    async fn get(&self, key: &str) -> AppResult<Option<Vec<u8>>>;
+    /// 存在しないキーを指定してもエラーにはしない。
+    async fn delete(&self, key: &str) -> AppResult<()>;
+}
@@ -3,19 +3,35 @@ use shared::error::AppResult;
 
 use crate::model::{
     book::{
-        event::{CreateBook, DeleteBook, UpdateBook},
-        Book, BookListOptions,
+        event::{
+            CreateBook, CreateBookCopy, DeleteBook, DeleteBookCopy, UpdateBook, UpdateBookCopy,
+            UpdateBookCover,
+        },
+        Book, BookFacets, BookListOptions, CoverImage, CoverVariant,
     },
-    id::{BookId, UserId},
+    id::{BookCopyId, BookId, UserId},
     list::PaginatedList,
 };
 
 #[mockall::automock]
 #[async_trait]
 pub trait BookRepository: Send + Sync {
-    async fn create(&self, event: CreateBook, user_id: UserId) -> AppResult<()>;
+    async fn create(&self, event: CreateBook, user_id: UserId) -> AppResult<BookId>;
     async fn find_all(&self, options: BookListOptions) -> AppResult<PaginatedList<Book>>;
+    async fn find_facets(&self, options: &BookListOptions) -> AppResult<BookFacets>;
     async fn find_by_id(&self, book_id: BookId) -> AppResult<Option<Book>>;
+    /// 所有者が同じ ISBN の蔵書を登録済みかを確認する。`isbn` は正規化済みのものを渡す。
+    async fn exists_by_isbn(&self, user_id: UserId, isbn: &str) -> AppResult<bool>;
     async fn update(&self, event: UpdateBook) -> AppResult<()>;
+    /// 蔵書を削除する。表紙画像などの保存済みのファイルもあわせて削除する。
     async fn delete(&self, event: DeleteBook) -> AppResult<()>;
+    async fn create_copy(&self, event: CreateBookCopy) -> AppResult<BookCopyId>;
+    async fn update_copy(&self, event: UpdateBookCopy) -> AppResult<()>;
+    async fn delete_copy(&self, event: DeleteBookCopy) -> AppResult<()>;
+    async fn update_cover(&self, event: UpdateBookCover) -> AppResult<()>;
+    async fn find_cover(
+        &self,
+        book_id: BookId,
+        variant: CoverVariant,
+    ) -> AppResult<Option<CoverImage>>;
 }
@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::catalog::CatalogEntry;
+
+/// ISBN から書誌情報を引く手段。問い合わせ先は設定によって切り替える。
+#[mockall::automock]
+#[async_trait]
+pub trait CatalogLookup: Send + Sync {
+    /// ハイフンを含まない ISBN-13 で書誌情報を検索する。見つからない場合は `None` を返す。
+    async fn find_by_isbn(&self, isbn: &str) -> AppResult<Option<CatalogEntry>>;
+}
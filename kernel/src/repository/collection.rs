@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    collection::{
+        event::{
+            AddCollectionBook, CreateCollection, DeleteCollection, RemoveCollectionBook,
+            UpdateCollection,
+        },
+        Collection,
+    },
+    id::{CollectionId, UserId},
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait CollectionRepository: Send + Sync {
+    async fn create(&self, event: CreateCollection) -> AppResult<Collection>;
+    /// 指定したユーザーが所有するコレクションと、共有されたコレクションを返す
+    async fn find_all(&self, user_id: UserId) -> AppResult<Vec<Collection>>;
+    /// 指定したユーザーが参照できないコレクションは `None` を返す
+    async fn find_by_id(
+        &self,
+        collection_id: CollectionId,
+        user_id: UserId,
+    ) -> AppResult<Option<Collection>>;
+    async fn update(&self, event: UpdateCollection) -> AppResult<()>;
+    async fn delete(&self, event: DeleteCollection) -> AppResult<()>;
+    async fn add_book(&self, event: AddCollectionBook) -> AppResult<()>;
+    async fn remove_book(&self, event: RemoveCollectionBook) -> AppResult<()>;
+}
@@ -0,0 +1,21 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{auth::LoginAttempt, id::UserId};
+
+/// ログインの失敗を記録し、総当たりによるパスワードの推測を防ぐ。
+#[mockall::automock]
+#[async_trait]
+pub trait LoginAttemptRepository: Send + Sync {
+    /// ログインを試行してよいかを確認し、認証に先立って試行を数える。
+    /// アカウントがロックされている場合は `AccountLockedError`、
+    /// 失敗後の待機時間中や、並行した試行が上限を超える場合は `LoginThrottledError` を返す。
+    async fn begin(&self, attempt: &LoginAttempt) -> AppResult<()>;
+    /// 認証の失敗を記録し、次に試行できるまでの待機時間を失敗回数に応じて延ばす。
+    /// 失敗回数が上限に達したアカウントは一定時間ロックする。
+    async fn record_failure(&self, attempt: &LoginAttempt) -> AppResult<()>;
+    /// 認証の成功を記録し、メールアドレスと IP アドレスの失敗回数と待機時間を解除する。
+    async fn record_success(&self, attempt: &LoginAttempt) -> AppResult<()>;
+    /// 管理者がアカウントのロックを解除する。失敗回数もあわせてリセットする。
+    async fn unlock(&self, user_id: UserId) -> AppResult<()>;
+}
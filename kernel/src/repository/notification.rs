@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    id::UserId,
+    notification::{
+        event::{CreateNotification, UpdateNotificationPreferences},
+        Notification, NotificationPreference,
+    },
+};
+
+/// メールや Webhook など、通知を実際に届ける手段。
+#[mockall::automock]
+#[async_trait]
+pub trait NotificationSender: Send + Sync {
+    async fn send(&self, notification: &Notification) -> AppResult<()>;
+}
+
+#[mockall::automock]
+#[async_trait]
+pub trait NotificationRepository: Send + Sync {
+    /// 利用者の通知設定を踏まえて通知を送信する。受け取りを停止している種別の通知は送信しない。
+    async fn notify(&self, event: CreateNotification) -> AppResult<()>;
+    async fn find_preferences(&self, user_id: UserId) -> AppResult<Vec<NotificationPreference>>;
+    async fn update_preferences(&self, event: UpdateNotificationPreferences) -> AppResult<()>;
+}
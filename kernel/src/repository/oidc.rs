@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::auth::oidc::{OidcAuthorizationRequest, OidcCallback, OidcLogin};
+
+#[mockall::automock]
+#[async_trait]
+pub trait OidcRepository: Send + Sync {
+    /// PKCE の code_verifier と nonce を保存し、認可エンドポイントの URL を組み立てる
+    async fn create_authorization_request(&self) -> AppResult<OidcAuthorizationRequest>;
+    /// 認可コードをトークンと交換し、ID トークンの利用者を `users` に紐付ける。
+    /// 紐付くユーザーが見つからず、自動登録も無効な場合は認証エラーとなる。
+    async fn callback(&self, event: OidcCallback) -> AppResult<OidcLogin>;
+}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use shared::error::AppResult;
+
+use crate::model::outbox::OutboxEvent;
+
+/// アウトボックスのイベントを外部のストリームへ配信する手段。
+#[mockall::automock]
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    async fn publish(&self, event: &OutboxEvent) -> AppResult<()>;
+}
+
+#[mockall::automock]
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    /// 未配信のイベントを記録順に最大 `limit` 件配信し、配信できた件数を返す。
+    /// 配信に失敗した場合はそれ以降のイベントを配信せず、次回の呼び出しで再送する。
+    async fn publish_pending(&self, limit: i64) -> AppResult<usize>;
+    /// 配信日時が `before` より前の配信済みイベントを削除し、削除した件数を返す。
+    /// 未配信のイベントは削除しない。
+    async fn delete_published_before(&self, before: DateTime<Utc>) -> AppResult<u64>;
+}
@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    auth::event::{ConfirmPasswordReset, RequestPasswordReset},
+    id::UserId,
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait PasswordResetRepository: Send + Sync {
+    /// 再設定用のトークンを発行し、利用者へ送信する。
+    /// 登録の有無を推測されないよう、該当するユーザーがいない場合もエラーにせず `None` を返す。
+    async fn request(&self, event: RequestPasswordReset) -> AppResult<Option<UserId>>;
+    /// トークンを消費してパスワードを再設定し、対象のユーザーの ID を返す。
+    /// トークンは一度しか使えず、無効または期限切れの場合は `UnauthorizedError` を返す。
+    async fn confirm(&self, event: ConfirmPasswordReset) -> AppResult<UserId>;
+}
@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    id::BookId,
+    reservation::{
+        event::{CreateReservation, DeleteReservation},
+        Reservation,
+    },
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait ReservationRepository: Send + Sync {
+    async fn create(&self, event: CreateReservation) -> AppResult<()>;
+    async fn find_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Reservation>>;
+    async fn delete(&self, event: DeleteReservation) -> AppResult<()>;
+}
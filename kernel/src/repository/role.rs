@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    id::RoleId,
+    role::{
+        event::{CreateRole, DeleteRole, UpdateRole},
+        Role,
+    },
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait RoleRepository: Send + Sync {
+    async fn find_all(&self) -> AppResult<Vec<Role>>;
+    async fn find_by_id(&self, role_id: RoleId) -> AppResult<Option<Role>>;
+    async fn create(&self, event: CreateRole) -> AppResult<Role>;
+    /// 組み込みのロールは変更できない
+    async fn update(&self, event: UpdateRole) -> AppResult<()>;
+    /// 組み込みのロールや、ユーザーに割り当てられているロールは削除できない
+    async fn delete(&self, event: DeleteRole) -> AppResult<()>;
+}
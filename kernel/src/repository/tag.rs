@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    id::TagId,
+    tag::{
+        event::{AddBookTag, CreateTag, DeleteTag, RemoveBookTag, UpdateTag},
+        Tag,
+    },
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait TagRepository: Send + Sync {
+    async fn create(&self, event: CreateTag) -> AppResult<Tag>;
+    async fn find_all(&self) -> AppResult<Vec<Tag>>;
+    async fn find_by_id(&self, tag_id: TagId) -> AppResult<Option<Tag>>;
+    async fn update(&self, event: UpdateTag) -> AppResult<()>;
+    async fn delete(&self, event: DeleteTag) -> AppResult<()>;
+    async fn add_to_book(&self, event: AddBookTag) -> AppResult<()>;
+    async fn remove_from_book(&self, event: RemoveBookTag) -> AppResult<()>;
+}
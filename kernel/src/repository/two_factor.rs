@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+use crate::model::{
+    auth::two_factor::{
+        ConfirmTwoFactor, DisableTwoFactor, TwoFactorChallenge, TwoFactorEnrollment,
+        TwoFactorStatus, UpdateTwoFactorPolicy, VerifyTwoFactorChallenge,
+    },
+    id::UserId,
+};
+
+#[mockall::automock]
+#[async_trait]
+pub trait TwoFactorRepository: Send + Sync {
+    async fn find_status(&self, user_id: UserId) -> AppResult<TwoFactorStatus>;
+    /// 秘密鍵とリカバリーコードを発行する。有効にするには `confirm` でコードを確認する必要がある。
+    async fn enroll(&self, user_id: UserId) -> AppResult<TwoFactorEnrollment>;
+    async fn confirm(&self, event: ConfirmTwoFactor) -> AppResult<()>;
+    /// ロールで必須とされている場合は無効にできない
+    async fn disable(&self, event: DisableTwoFactor) -> AppResult<()>;
+    /// 2 段階認証が有効、またはロールで必須とされている場合にチャレンジを発行する。
+    /// いずれでもない場合は `None` を返し、パスワードのみでログインできる。
+    async fn create_challenge(&self, user_id: UserId) -> AppResult<Option<TwoFactorChallenge>>;
+    /// 登録が必要なチャレンジに対して、秘密鍵とリカバリーコードを発行する。
+    /// 登録したユーザーの ID もあわせて返す。
+    async fn enroll_with_challenge(
+        &self,
+        challenge_token: &str,
+    ) -> AppResult<(UserId, TwoFactorEnrollment)>;
+    /// コードを確認してチャレンジを消費し、ログインするユーザーの ID を返す。
+    /// 登録中の場合は、このコードの確認をもって 2 段階認証を有効にする。
+    async fn verify_challenge(&self, event: VerifyTwoFactorChallenge) -> AppResult<UserId>;
+    async fn update_policy(&self, event: UpdateTwoFactorPolicy) -> AppResult<()>;
+}
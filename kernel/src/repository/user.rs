@@ -4,7 +4,7 @@ use shared::error::AppResult;
 use crate::model::{
     id::UserId,
     user::{
-        event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+        event::{CreateUser, DeleteUser, ResetUserPassword, UpdateUserPassword, UpdateUserRole},
         User,
     },
 };
@@ -16,6 +16,7 @@ pub trait UserRepository: Send + Sync {
     async fn find_all(&self) -> AppResult<Vec<User>>;
     async fn create(&self, event: CreateUser) -> AppResult<User>;
     async fn update_password(&self, event: UpdateUserPassword) -> AppResult<()>;
+    async fn reset_password(&self, event: ResetUserPassword) -> AppResult<()>;
     async fn update_role(&self, event: UpdateUserRole) -> AppResult<()>;
     async fn delete(&self, event: DeleteUser) -> AppResult<()>;
 }
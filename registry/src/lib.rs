@@ -1,18 +1,35 @@
 use std::sync::Arc;
 
 use adapter::{
+    blob::build_blob_store,
+    catalog::build_catalog_lookup,
     database::ConnectionPool,
+    event::redis::RedisStreamEventPublisher,
+    notification::build_notification_sender,
+    oidc::OidcClient,
     redis::RedisClient,
     repository::{
-        auth::AuthRepositoryImpl, book::BookRepositoryImpl, checkout::CheckoutRepositoryImpl,
-        health::HealthCheckRepositoryImpl, user::UserRepositoryImpl,
+        api_key::ApiKeyRepositoryImpl, audit::AuditRepositoryImpl, auth::AuthRepositoryImpl,
+        author::AuthorRepositoryImpl, book::BookRepositoryImpl, checkout::CheckoutRepositoryImpl,
+        collection::CollectionRepositoryImpl, health::HealthCheckRepositoryImpl,
+        login_attempt::LoginAttemptRepositoryImpl, notification::NotificationRepositoryImpl,
+        oidc::OidcRepositoryImpl, outbox::OutboxRepositoryImpl,
+        password_reset::PasswordResetRepositoryImpl, reservation::ReservationRepositoryImpl,
+        role::RoleRepositoryImpl, tag::TagRepositoryImpl, two_factor::TwoFactorRepositoryImpl,
+        user::UserRepositoryImpl,
     },
+    token::build_access_token_store,
 };
 use kernel::repository::{
-    auth::AuthRepository, book::BookRepository, checkout::CheckoutRepository,
-    health::HealthCheckRepository, user::UserRepository,
+    api_key::ApiKeyRepository, audit::AuditRepository, auth::AuthRepository,
+    author::AuthorRepository, book::BookRepository, catalog::CatalogLookup,
+    checkout::CheckoutRepository, collection::CollectionRepository, health::HealthCheckRepository,
+    login_attempt::LoginAttemptRepository, notification::NotificationRepository,
+    oidc::OidcRepository, outbox::OutboxRepository, password_reset::PasswordResetRepository,
+    reservation::ReservationRepository, role::RoleRepository, tag::TagRepository,
+    two_factor::TwoFactorRepository, user::UserRepository,
 };
-use shared::config::AppConfig;
+use shared::{config::AppConfig, error::AppResult};
 
 #[derive(Clone)]
 pub struct AppRegistryImpl {
@@ -21,6 +38,20 @@ pub struct AppRegistryImpl {
     auth_repository: Arc<dyn AuthRepository>,
     user_repository: Arc<dyn UserRepository>,
     checkout_repository: Arc<dyn CheckoutRepository>,
+    reservation_repository: Arc<dyn ReservationRepository>,
+    notification_repository: Arc<dyn NotificationRepository>,
+    outbox_repository: Arc<dyn OutboxRepository>,
+    audit_repository: Arc<dyn AuditRepository>,
+    oidc_repository: Arc<dyn OidcRepository>,
+    login_attempt_repository: Arc<dyn LoginAttemptRepository>,
+    password_reset_repository: Arc<dyn PasswordResetRepository>,
+    two_factor_repository: Arc<dyn TwoFactorRepository>,
+    api_key_repository: Arc<dyn ApiKeyRepository>,
+    role_repository: Arc<dyn RoleRepository>,
+    tag_repository: Arc<dyn TagRepository>,
+    collection_repository: Arc<dyn CollectionRepository>,
+    author_repository: Arc<dyn AuthorRepository>,
+    catalog_lookup: Arc<dyn CatalogLookup>,
 }
 
 impl AppRegistryImpl {
@@ -28,24 +59,94 @@ impl AppRegistryImpl {
         pool: ConnectionPool,
         redis_client: Arc<RedisClient>,
         app_config: AppConfig,
-    ) -> Self {
+    ) -> AppResult<Self> {
+        let notification_repository = Arc::new(NotificationRepositoryImpl::new(
+            pool.clone(),
+            build_notification_sender(&app_config.notification)?,
+        ));
         let health_check_repository = Arc::new(HealthCheckRepositoryImpl::new(pool.clone()));
-        let book_repository = Arc::new(BookRepositoryImpl::new(pool.clone()));
+        let blob_store = build_blob_store(&app_config.blob_store)?;
+        let book_repository = Arc::new(BookRepositoryImpl::new(pool.clone(), blob_store.clone()));
         let auth_repository = Arc::new(AuthRepositoryImpl::new(
             pool.clone(),
             redis_client.clone(),
+            build_access_token_store(&app_config.auth, redis_client.clone())?,
             app_config.auth.ttl,
+            app_config.auth.refresh_ttl,
+        ));
+        let user_repository = Arc::new(UserRepositoryImpl::new(
+            pool.clone(),
+            notification_repository.clone(),
+            auth_repository.clone(),
+            blob_store,
+        ));
+        let checkout_repository = Arc::new(CheckoutRepositoryImpl::new(
+            pool.clone(),
+            app_config.checkout.loan_period_days,
+            app_config.checkout.max_renewals,
+            notification_repository.clone(),
+        ));
+        let reservation_repository = Arc::new(ReservationRepositoryImpl::new(pool.clone()));
+        let outbox_repository = Arc::new(OutboxRepositoryImpl::new(
+            pool.clone(),
+            Arc::new(RedisStreamEventPublisher::new(redis_client.clone())),
+        ));
+        let audit_repository = Arc::new(AuditRepositoryImpl::new(pool.clone()));
+        let oidc_repository = Arc::new(OidcRepositoryImpl::new(
+            pool.clone(),
+            app_config
+                .auth
+                .oidc
+                .as_ref()
+                .map(|config| OidcClient::new(config).map(Arc::new))
+                .transpose()?,
+        ));
+        let login_attempt_repository = Arc::new(LoginAttemptRepositoryImpl::new(
+            pool.clone(),
+            redis_client.clone(),
+            app_config.auth.login_throttle.clone(),
+        ));
+        let password_reset_repository = Arc::new(PasswordResetRepositoryImpl::new(
+            pool.clone(),
+            user_repository.clone(),
+            notification_repository.clone(),
+            app_config.auth.password_reset_ttl,
+        ));
+        let two_factor_repository = Arc::new(TwoFactorRepositoryImpl::new(
+            pool.clone(),
+            app_config.auth.two_factor.clone(),
+        ));
+        let api_key_repository = Arc::new(ApiKeyRepositoryImpl::new(pool.clone()));
+        let role_repository = Arc::new(RoleRepositoryImpl::new(
+            pool.clone(),
+            auth_repository.clone(),
         ));
-        let user_repository = Arc::new(UserRepositoryImpl::new(pool.clone()));
-        let checkout_repository = Arc::new(CheckoutRepositoryImpl::new(pool.clone()));
+        let tag_repository = Arc::new(TagRepositoryImpl::new(pool.clone()));
+        let collection_repository = Arc::new(CollectionRepositoryImpl::new(pool.clone()));
+        let author_repository = Arc::new(AuthorRepositoryImpl::new(pool.clone()));
+        let catalog_lookup = build_catalog_lookup(&app_config.catalog, redis_client.clone())?;
 
-        Self {
+        Ok(Self {
             health_check_repository,
             book_repository,
             auth_repository,
             user_repository,
             checkout_repository,
-        }
+            reservation_repository,
+            notification_repository,
+            outbox_repository,
+            audit_repository,
+            oidc_repository,
+            login_attempt_repository,
+            password_reset_repository,
+            two_factor_repository,
+            api_key_repository,
+            role_repository,
+            tag_repository,
+            collection_repository,
+            author_repository,
+            catalog_lookup,
+        })
     }
 }
 
@@ -56,6 +157,20 @@ pub trait AppRegistryExt {
     fn auth_repository(&self) -> Arc<dyn AuthRepository>;
     fn user_repository(&self) -> Arc<dyn UserRepository>;
     fn checkout_repository(&self) -> Arc<dyn CheckoutRepository>;
+    fn reservation_repository(&self) -> Arc<dyn ReservationRepository>;
+    fn notification_repository(&self) -> Arc<dyn NotificationRepository>;
+    fn outbox_repository(&self) -> Arc<dyn OutboxRepository>;
+    fn audit_repository(&self) -> Arc<dyn AuditRepository>;
+    fn oidc_repository(&self) -> Arc<dyn OidcRepository>;
+    fn login_attempt_repository(&self) -> Arc<dyn LoginAttemptRepository>;
+    fn password_reset_repository(&self) -> Arc<dyn PasswordResetRepository>;
+    fn two_factor_repository(&self) -> Arc<dyn TwoFactorRepository>;
+    fn api_key_repository(&self) -> Arc<dyn ApiKeyRepository>;
+    fn role_repository(&self) -> Arc<dyn RoleRepository>;
+    fn tag_repository(&self) -> Arc<dyn TagRepository>;
+    fn collection_repository(&self) -> Arc<dyn CollectionRepository>;
+    fn author_repository(&self) -> Arc<dyn AuthorRepository>;
+    fn catalog_lookup(&self) -> Arc<dyn CatalogLookup>;
 }
 
 impl AppRegistryExt for AppRegistryImpl {
@@ -78,6 +193,62 @@ impl AppRegistryExt for AppRegistryImpl {
     fn checkout_repository(&self) -> Arc<dyn CheckoutRepository> {
         self.checkout_repository.clone()
     }
+
+    fn reservation_repository(&self) -> Arc<dyn ReservationRepository> {
+        self.reservation_repository.clone()
+    }
+
+    fn notification_repository(&self) -> Arc<dyn NotificationRepository> {
+        self.notification_repository.clone()
+    }
+
+    fn outbox_repository(&self) -> Arc<dyn OutboxRepository> {
+        self.outbox_repository.clone()
+    }
+
+    fn audit_repository(&self) -> Arc<dyn AuditRepository> {
+        self.audit_repository.clone()
+    }
+
+    fn oidc_repository(&self) -> Arc<dyn OidcRepository> {
+        self.oidc_repository.clone()
+    }
+
+    fn login_attempt_repository(&self) -> Arc<dyn LoginAttemptRepository> {
+        self.login_attempt_repository.clone()
+    }
+
+    fn password_reset_repository(&self) -> Arc<dyn PasswordResetRepository> {
+        self.password_reset_repository.clone()
+    }
+
+    fn two_factor_repository(&self) -> Arc<dyn TwoFactorRepository> {
+        self.two_factor_repository.clone()
+    }
+
+    fn api_key_repository(&self) -> Arc<dyn ApiKeyRepository> {
+        self.api_key_repository.clone()
+    }
+
+    fn role_repository(&self) -> Arc<dyn RoleRepository> {
+        self.role_repository.clone()
+    }
+
+    fn tag_repository(&self) -> Arc<dyn TagRepository> {
+        self.tag_repository.clone()
+    }
+
+    fn collection_repository(&self) -> Arc<dyn CollectionRepository> {
+        self.collection_repository.clone()
+    }
+
+    fn author_repository(&self) -> Arc<dyn AuthorRepository> {
+        self.author_repository.clone()
+    }
+
+    fn catalog_lookup(&self) -> Arc<dyn CatalogLookup> {
+        self.catalog_lookup.clone()
+    }
 }
 
 pub type AppRegistry = Arc<dyn AppRegistryExt + Sync + Send + 'static>;
@@ -1,9 +1,25 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+
+/// 環境変数を読み取って解析する。設定されていない場合は `default` を用いる。
+fn var_or<T>(key: &str, default: T) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match std::env::var(key) {
+        Ok(value) => Ok(value.parse()?),
+        Err(_) => Ok(default),
+    }
+}
 
 pub struct AppConfig {
     pub database: DatabaseConfig,
     pub redis: RedisConfig,
     pub auth: AuthConfig,
+    pub checkout: CheckoutConfig,
+    pub notification: NotificationConfig,
+    pub catalog: CatalogConfig,
+    pub blob_store: BlobStoreConfig,
 }
 
 impl AppConfig {
@@ -23,12 +39,75 @@ impl AppConfig {
 
         let auth = AuthConfig {
             ttl: std::env::var("AUTH_TOKEN_TTL")?.parse()?,
+            refresh_ttl: std::env::var("AUTH_REFRESH_TOKEN_TTL")?.parse()?,
+            backend: match std::env::var("AUTH_BACKEND")?.as_str() {
+                "redis" => AuthBackendConfig::Redis,
+                "jwt" => AuthBackendConfig::Jwt(JwtConfig::from_env()?),
+                other => bail!("Unknown auth backend: {}", other),
+            },
+            oidc: OidcConfig::from_env()?,
+            login_throttle: LoginThrottleConfig::from_env()?,
+            password_reset_ttl: var_or("AUTH_PASSWORD_RESET_TTL", 3600)?,
+            two_factor: TwoFactorConfig {
+                issuer: std::env::var("AUTH_TOTP_ISSUER")
+                    .unwrap_or_else(|_| "rusty-book-manager".to_string()),
+                challenge_ttl: var_or("AUTH_TWO_FACTOR_CHALLENGE_TTL", 300)?,
+            },
+        };
+
+        let checkout = CheckoutConfig {
+            loan_period_days: std::env::var("CHECKOUT_LOAN_PERIOD_DAYS")?.parse()?,
+            max_renewals: std::env::var("CHECKOUT_MAX_RENEWALS")?.parse()?,
+        };
+
+        let notification = match std::env::var("NOTIFICATION_SENDER")?.as_str() {
+            "log" => NotificationConfig::Log,
+            "smtp" => NotificationConfig::Smtp(SmtpConfig {
+                host: std::env::var("SMTP_HOST")?,
+                port: std::env::var("SMTP_PORT")?.parse()?,
+                username: std::env::var("SMTP_USERNAME").ok(),
+                password: std::env::var("SMTP_PASSWORD").ok(),
+                starttls: var_or("SMTP_STARTTLS", false)?,
+                from: std::env::var("SMTP_FROM")?,
+                timeout: var_or("SMTP_TIMEOUT", 5)?,
+            }),
+            "webhook" => NotificationConfig::Webhook(WebhookConfig {
+                url: std::env::var("NOTIFICATION_WEBHOOK_URL")?,
+                timeout: var_or("NOTIFICATION_WEBHOOK_TIMEOUT", 5)?,
+                connect_timeout: var_or("NOTIFICATION_WEBHOOK_CONNECT_TIMEOUT", 2)?,
+            }),
+            other => bail!("Unknown notification sender: {}", other),
+        };
+
+        let catalog = CatalogConfig::from_env()?;
+
+        let blob_store = match std::env::var("BLOB_STORE")
+            .unwrap_or_else(|_| "local".to_string())
+            .as_str()
+        {
+            "local" => BlobStoreConfig::Local(LocalBlobStoreConfig {
+                root: std::env::var("BLOB_STORE_PATH").unwrap_or_else(|_| "storage".to_string()),
+            }),
+            "s3" => BlobStoreConfig::S3(S3Config {
+                endpoint: std::env::var("S3_ENDPOINT")?,
+                region: std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                bucket: std::env::var("S3_BUCKET")?,
+                access_key_id: std::env::var("S3_ACCESS_KEY_ID")?,
+                secret_access_key: std::env::var("S3_SECRET_ACCESS_KEY")?,
+                timeout: var_or("S3_TIMEOUT", 30)?,
+                connect_timeout: var_or("S3_CONNECT_TIMEOUT", 2)?,
+            }),
+            other => bail!("Unknown blob store: {}", other),
         };
 
         Ok(Self {
             database,
             redis,
             auth,
+            checkout,
+            notification,
+            catalog,
+            blob_store,
         })
     }
 }
@@ -48,4 +127,237 @@ pub struct RedisConfig {
 
 pub struct AuthConfig {
     pub ttl: u64,
+    pub refresh_ttl: u64,
+    pub backend: AuthBackendConfig,
+    /// 未設定の場合は OIDC によるログインを受け付けない
+    pub oidc: Option<OidcConfig>,
+    pub login_throttle: LoginThrottleConfig,
+    /// パスワード再設定用トークンの有効期間（秒）
+    pub password_reset_ttl: u64,
+    pub two_factor: TwoFactorConfig,
+}
+
+pub enum AuthBackendConfig {
+    /// 不透明なアクセストークンを Redis に保存する
+    Redis,
+    /// 署名付きの JWT をアクセストークンとして発行する
+    Jwt(JwtConfig),
+}
+
+pub struct JwtConfig {
+    /// 検証に用いる鍵の一覧。ローテーション中は旧い鍵も残しておく。
+    pub keys: Vec<JwtSigningKey>,
+    /// 新しく発行するトークンの署名に用いる鍵の `kid`
+    pub current_kid: String,
+    /// Redis 上の失効リストを手元に同期する間隔（秒）
+    pub denylist_sync_interval: u64,
+}
+
+pub struct JwtSigningKey {
+    pub kid: String,
+    pub secret: String,
+}
+
+impl JwtConfig {
+    fn from_env() -> Result<Self> {
+        // `kid1:secret1,kid2:secret2` の形式で指定する
+        let keys = std::env::var("AUTH_JWT_KEYS")?
+            .split(',')
+            .map(|pair| match pair.split_once(':') {
+                Some((kid, secret)) if !kid.is_empty() && !secret.is_empty() => Ok(JwtSigningKey {
+                    kid: kid.to_string(),
+                    secret: secret.to_string(),
+                }),
+                _ => bail!("Invalid AUTH_JWT_KEYS entry: {}", pair),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let current_kid = std::env::var("AUTH_JWT_CURRENT_KID")?;
+        if !keys.iter().any(|k| k.kid == current_kid) {
+            bail!(
+                "AUTH_JWT_CURRENT_KID does not match any key: {}",
+                current_kid
+            );
+        }
+        let denylist_sync_interval = var_or("AUTH_JWT_DENYLIST_SYNC_INTERVAL", 5)?;
+
+        Ok(Self {
+            keys,
+            current_kid,
+            denylist_sync_interval,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct OidcConfig {
+    /// ID トークンの `iss` と照合するほか、`/.well-known/openid-configuration` の取得にも用いる
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    /// 未登録の利用者がログインした場合に、一般ユーザーとして自動で登録する
+    pub auto_provision: bool,
+    /// IdP への 1 回のリクエストで、応答を読み終えるまで待つ時間の上限（秒）
+    pub timeout: u64,
+    /// IdP への接続が確立するまで待つ時間の上限（秒）
+    pub connect_timeout: u64,
+}
+
+impl OidcConfig {
+    fn from_env() -> Result<Option<Self>> {
+        let Ok(issuer_url) = std::env::var("OIDC_ISSUER_URL") else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            issuer_url,
+            client_id: std::env::var("OIDC_CLIENT_ID")?,
+            client_secret: std::env::var("OIDC_CLIENT_SECRET")?,
+            redirect_uri: std::env::var("OIDC_REDIRECT_URI")?,
+            auto_provision: var_or("OIDC_AUTO_PROVISION", false)?,
+            timeout: var_or("OIDC_TIMEOUT", 5)?,
+            connect_timeout: var_or("OIDC_CONNECT_TIMEOUT", 2)?,
+        }))
+    }
+}
+
+/// ログインの総当たりを防ぐための設定。時間はいずれも秒で指定する。
+#[derive(Clone)]
+pub struct LoginThrottleConfig {
+    /// アカウントをロックするまでに許容する、連続したログインの失敗回数
+    pub max_failures: u64,
+    pub lockout_duration: u64,
+    /// 失敗のたびに倍にしていく待機時間の初期値と上限
+    pub backoff_base: u64,
+    pub backoff_max: u64,
+    /// 最後の失敗からこの時間が経過すると、失敗回数をリセットする
+    pub failure_window: u64,
+}
+
+impl LoginThrottleConfig {
+    fn from_env() -> Result<Self> {
+        Ok(Self {
+            max_failures: var_or("AUTH_LOCKOUT_MAX_FAILURES", 5)?,
+            lockout_duration: var_or("AUTH_LOCKOUT_DURATION", 900)?,
+            backoff_base: var_or("AUTH_LOGIN_BACKOFF_BASE", 1)?,
+            backoff_max: var_or("AUTH_LOGIN_BACKOFF_MAX", 60)?,
+            failure_window: var_or("AUTH_LOGIN_FAILURE_WINDOW", 900)?,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct TwoFactorConfig {
+    /// 認証アプリに表示される発行者名
+    pub issuer: String,
+    /// パスワードの確認後、2 段階目のコードを入力するまでの猶予（秒）
+    pub challenge_ttl: u64,
+}
+
+pub struct CheckoutConfig {
+    pub loan_period_days: i64,
+    pub max_renewals: i32,
+}
+
+pub enum NotificationConfig {
+    /// 通知内容をログに出力するだけで、外部には送信しない
+    Log,
+    Smtp(SmtpConfig),
+    Webhook(WebhookConfig),
+}
+
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub starttls: bool,
+    pub from: String,
+    /// 接続してから 1 通の送信を終えるまで待つ時間の上限（秒）
+    pub timeout: u64,
+}
+
+pub struct WebhookConfig {
+    pub url: String,
+    /// 1 回の送信で、応答を読み終えるまで待つ時間の上限（秒）
+    pub timeout: u64,
+    /// 接続が確立するまで待つ時間の上限（秒）
+    pub connect_timeout: u64,
+}
+
+pub struct CatalogConfig {
+    pub provider: CatalogProviderConfig,
+    /// ISBN ごとの問い合わせ結果をキャッシュしておく期間（秒）
+    pub cache_ttl: u64,
+}
+
+impl CatalogConfig {
+    fn from_env() -> Result<Self> {
+        let provider = match std::env::var("CATALOG_PROVIDER")
+            .unwrap_or_else(|_| "openlibrary".to_string())
+            .as_str()
+        {
+            "openlibrary" => CatalogProviderConfig::OpenLibrary(OpenLibraryConfig {
+                base_url: std::env::var("CATALOG_OPENLIBRARY_URL")
+                    .unwrap_or_else(|_| "https://openlibrary.org".to_string()),
+                timeout: var_or("CATALOG_OPENLIBRARY_TIMEOUT", 5)?,
+                connect_timeout: var_or("CATALOG_OPENLIBRARY_CONNECT_TIMEOUT", 2)?,
+            }),
+            "fixture" => CatalogProviderConfig::Fixture(CatalogFixtureConfig {
+                path: std::env::var("CATALOG_FIXTURE_PATH")?,
+            }),
+            other => bail!("Unknown catalog provider: {}", other),
+        };
+        let cache_ttl = var_or("CATALOG_CACHE_TTL", 86400)?;
+
+        Ok(Self {
+            provider,
+            cache_ttl,
+        })
+    }
+}
+
+pub enum CatalogProviderConfig {
+    /// OpenLibrary 互換の Books API に問い合わせる
+    OpenLibrary(OpenLibraryConfig),
+    /// JSON ファイルに用意した書誌情報から検索する。外部に接続できない環境で使う
+    Fixture(CatalogFixtureConfig),
+}
+
+pub struct OpenLibraryConfig {
+    pub base_url: String,
+    /// 1 回の問い合わせで、応答を読み終えるまで待つ時間の上限（秒）
+    pub timeout: u64,
+    /// 接続が確立するまで待つ時間の上限（秒）
+    pub connect_timeout: u64,
+}
+
+pub struct CatalogFixtureConfig {
+    pub path: String,
+}
+
+pub enum BlobStoreConfig {
+    /// ローカルのファイルシステムに保存する
+    Local(LocalBlobStoreConfig),
+    /// S3 互換のオブジェクトストレージに保存する
+    S3(S3Config),
+}
+
+pub struct LocalBlobStoreConfig {
+    /// 保存先のディレクトリ。存在しない場合は作成する
+    pub root: String,
+}
+
+pub struct S3Config {
+    /// `https://s3.ap-northeast-1.amazonaws.com` や `http://localhost:9000` のように指定する。
+    /// バケットはパス形式で指定するため、MinIO などの互換サービスにもそのまま接続できる
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// 1 回のリクエストで、応答を読み終えるまで待つ時間の上限（秒）。表紙画像の転送も含む
+    pub timeout: u64,
+    /// 接続が確立するまで待つ時間の上限（秒）
+    pub connect_timeout: u64,
 }
@@ -1,4 +1,7 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -7,6 +10,9 @@ pub enum AppError {
     UnprocessableEntity(String),
     #[error("{0}")]
     EntityNotFound(String),
+    /// 一意であるべきリソースが既に登録されている
+    #[error("{0}")]
+    ConflictError(String),
     #[error("{0}")]
     ValidationError(#[from] garde::Report),
     #[error("Does not transaction.")]
@@ -31,13 +37,36 @@ pub enum AppError {
     ForbiddenOperationError,
     #[error("{0}")]
     ConversionEntityError(String),
+    #[error("Failed to send notification: {0}")]
+    NotificationError(String),
+    #[error("Identity provider error: {0}")]
+    IdentityProviderError(String),
+    #[error("Catalog lookup error: {0}")]
+    CatalogLookupError(String),
+    #[error("Blob store error: {0}")]
+    BlobStoreError(String),
+    /// アップロードされたファイルが上限のサイズ（バイト）を超えている
+    #[error("Payload exceeds the limit of {0} bytes.")]
+    PayloadTooLargeError(usize),
+    /// ログインの失敗が続いたため、指定の秒数が経過するまでログインを受け付けない
+    #[error("Too many login attempts. Retry after {0} seconds.")]
+    LoginThrottledError(u64),
+    /// ログインの失敗回数が上限に達したため、アカウントが一時的にロックされている
+    #[error("Account is temporarily locked. Retry after {0} seconds.")]
+    AccountLockedError(u64),
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
+        let retry_after = match self {
+            AppError::LoginThrottledError(secs) | AppError::AccountLockedError(secs) => Some(secs),
+            _ => None,
+        };
+
         let status_code = match self {
             AppError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
             AppError::EntityNotFound(_) => StatusCode::NOT_FOUND,
+            AppError::ConflictError(_) => StatusCode::CONFLICT,
             AppError::ValidationError(_) | AppError::ConvertToUuidError(_) => {
                 StatusCode::BAD_REQUEST
             }
@@ -45,12 +74,25 @@ impl IntoResponse for AppError {
                 StatusCode::FORBIDDEN
             }
             AppError::UnauthorizedError => StatusCode::UNAUTHORIZED,
+            AppError::LoginThrottledError(_) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::AccountLockedError(_) => StatusCode::LOCKED,
+            AppError::PayloadTooLargeError(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::IdentityProviderError(e) => {
+                tracing::error!(error.message = %e, "Identity provider returned an error");
+                StatusCode::BAD_GATEWAY
+            }
+            AppError::CatalogLookupError(e) => {
+                tracing::error!(error.message = %e, "Catalog provider returned an error");
+                StatusCode::BAD_GATEWAY
+            }
             e @ (AppError::TransactionError(_)
             | AppError::SpecificOperationError(_)
             | AppError::NoRowsAffectedError(_)
             | AppError::KeyValueStoreError(_)
             | AppError::BcyptError(_)
-            | AppError::ConversionEntityError(_)) => {
+            | AppError::ConversionEntityError(_)
+            | AppError::NotificationError(_)
+            | AppError::BlobStoreError(_)) => {
                 tracing::error!(
                     error.cause_chain = ?e,
                     error.message = %e,
@@ -60,7 +102,10 @@ impl IntoResponse for AppError {
             }
         };
 
-        status_code.into_response()
+        match retry_after {
+            Some(secs) => (status_code, [(header::RETRY_AFTER, secs.to_string())]).into_response(),
+            None => status_code.into_response(),
+        }
     }
 }
 
@@ -8,9 +8,10 @@ use axum::{http::Method, Router};
 use opentelemetry::{global, trace::TracerProvider, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::Resource;
-use tokio::net::TcpListener;
+use tokio::{net::TcpListener, sync::watch};
 use tower_http::{
     cors::{self, CorsLayer},
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
     trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer},
     LatencyUnit,
 };
@@ -19,11 +20,19 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilte
 
 use adapter::{database::connect_database_with, redis::RedisClient};
 use api::route::{auth, v1};
-use registry::AppRegistryImpl;
+use registry::{AppRegistry, AppRegistryImpl};
 use shared::{
     config::AppConfig,
     env::{which, Environment},
 };
+use worker::{
+    clock::SystemClock,
+    job::{
+        outbox::{OutboxRelayJob, OutboxRetentionJob},
+        overdue::OverdueReminderJob,
+    },
+    scheduler::JobScheduler,
+};
 
 #[cfg(debug_assertions)]
 use api::openapi::ApiDoc;
@@ -93,7 +102,15 @@ async fn bootstrap() -> Result<()> {
     let pool = connect_database_with(&app_config.database);
     let kv = Arc::new(RedisClient::new(&app_config.redis)?);
 
-    let registry = Arc::new(AppRegistryImpl::new(pool, kv, app_config));
+    let registry: AppRegistry = Arc::new(AppRegistryImpl::new(pool.clone(), kv, app_config)?);
+
+    // 定期実行するジョブを起動し、サーバーの停止時にあわせて停止させる
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let scheduler = JobScheduler::new(pool, registry.clone(), Arc::new(SystemClock))
+        .register(OverdueReminderJob::default())
+        .register(OutboxRelayJob::default())
+        .register(OutboxRetentionJob::default())
+        .start(shutdown_rx);
 
     let router = Router::new().merge(v1::routes()).merge(auth::routes());
 
@@ -113,6 +130,9 @@ async fn bootstrap() -> Result<()> {
                         .latency_unit(LatencyUnit::Millis),
                 ),
         )
+        // 監査ログなどでリクエストを追跡できるよう、リクエスト ID を付与してレスポンスにも返す
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         .with_state(registry);
 
     let addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 8080);
@@ -120,18 +140,29 @@ async fn bootstrap() -> Result<()> {
 
     tracing::info!("Listening on {}", addr);
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .context("Un expected error happened in server")
-        // 起動失敗時のエラーログを tracing::error! マクロで出力
-        .inspect_err(|e| {
-            tracing::error!(
-                error.cause_chain = ?e, // ? は Debug トレイトを実装している場合に使える
-                error.message = %e,     // % は Display トレイトを実装している場合に使える
-                "Un expected error"
-            )
-        })
+    // ログイン試行の制限に用いるため、接続元のアドレスをリクエストから参照できるようにする
+    let result = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        shutdown_signal().await;
+        let _ = shutdown_tx.send(true);
+    })
+    .await
+    .context("Un expected error happened in server")
+    // 起動失敗時のエラーログを tracing::error! マクロで出力
+    .inspect_err(|e| {
+        tracing::error!(
+            error.cause_chain = ?e, // ? は Debug トレイトを実装している場合に使える
+            error.message = %e,     // % は Display トレイトを実装している場合に使える
+            "Un expected error"
+        )
+    });
+
+    scheduler.await?;
+
+    result
 }
 
 async fn shutdown_signal() {
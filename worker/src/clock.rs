@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+
+/// ジョブが参照する現在日時の取得元。
+/// テストでは任意の日時を返す実装に差し替えることで、日時に依存する処理を検証できる。
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
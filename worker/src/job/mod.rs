@@ -0,0 +1,17 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+pub mod outbox;
+pub mod overdue;
+
+#[async_trait]
+pub trait Job: Send + Sync {
+    /// ジョブの名前。複数のレプリカ間で排他制御を行う際のキーとしても使われる
+    fn name(&self) -> &'static str;
+    fn interval(&self) -> Duration;
+    async fn run(&self, registry: &AppRegistry, now: DateTime<Utc>) -> AppResult<()>;
+}
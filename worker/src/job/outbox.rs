@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+
+use super::Job;
+
+/// アウトボックスに記録されたドメインイベントを、記録順に外部のストリームへ中継するジョブ。
+pub struct OutboxRelayJob {
+    interval: Duration,
+    batch_size: i64,
+}
+
+impl OutboxRelayJob {
+    pub fn new(interval: Duration, batch_size: i64) -> Self {
+        Self {
+            interval,
+            batch_size,
+        }
+    }
+}
+
+impl Default for OutboxRelayJob {
+    fn default() -> Self {
+        // 下流のサービスが変更をほぼ即時に追従できるよう、短い間隔で中継する
+        Self::new(Duration::from_secs(5), 100)
+    }
+}
+
+#[async_trait]
+impl Job for OutboxRelayJob {
+    fn name(&self) -> &'static str {
+        "outbox-relay"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn run(&self, registry: &AppRegistry, _now: DateTime<Utc>) -> AppResult<()> {
+        let outbox = registry.outbox_repository();
+
+        // 未配信のイベントがなくなるまで、バッチ単位で中継する
+        let mut total = 0;
+        loop {
+            let published = outbox.publish_pending(self.batch_size).await?;
+            total += published;
+            if (published as i64) < self.batch_size {
+                break;
+            }
+        }
+
+        if total > 0 {
+            tracing::info!(count = total, "Outbox events relayed");
+        }
+
+        Ok(())
+    }
+}
+
+/// 配信済みのまま保持期間を過ぎたドメインイベントを、アウトボックスから削除するジョブ。
+pub struct OutboxRetentionJob {
+    interval: Duration,
+    retention: Duration,
+}
+
+impl OutboxRetentionJob {
+    pub fn new(interval: Duration, retention: Duration) -> Self {
+        Self {
+            interval,
+            retention,
+        }
+    }
+}
+
+impl Default for OutboxRetentionJob {
+    fn default() -> Self {
+        // 配信の調査に使えるよう、配信済みのイベントも 1 週間は残しておく
+        Self::new(
+            Duration::from_secs(60 * 60),
+            Duration::from_secs(60 * 60 * 24 * 7),
+        )
+    }
+}
+
+#[async_trait]
+impl Job for OutboxRetentionJob {
+    fn name(&self) -> &'static str {
+        "outbox-retention"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn run(&self, registry: &AppRegistry, now: DateTime<Utc>) -> AppResult<()> {
+        let retention = chrono::Duration::from_std(self.retention)
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+
+        let deleted = registry
+            .outbox_repository()
+            .delete_published_before(now - retention)
+            .await?;
+
+        if deleted > 0 {
+            tracing::info!(count = deleted, "Published outbox events deleted");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use kernel::repository::outbox::MockOutboxRepository;
+    use mockall::predicate::eq;
+    use registry::MockAppRegistryExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_outbox_relay_drains_pending_events() -> anyhow::Result<()> {
+        // 1 回目はバッチが埋まり、2 回目で未配信のイベントがなくなる
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let mut registry = MockAppRegistryExt::new();
+        registry.expect_outbox_repository().returning(move || {
+            let calls = calls.clone();
+            let mut mock = MockOutboxRepository::new();
+            mock.expect_publish_pending()
+                .with(eq(2))
+                .times(2)
+                .returning(move |_| match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => Ok(2),
+                    _ => Ok(1),
+                });
+            Arc::new(mock)
+        });
+        let registry: AppRegistry = Arc::new(registry);
+
+        OutboxRelayJob::new(Duration::from_secs(1), 2)
+            .run(&registry, Utc::now())
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_outbox_retention_deletes_events_published_before_retention() -> anyhow::Result<()>
+    {
+        let now = Utc::now();
+
+        let mut registry = MockAppRegistryExt::new();
+        registry.expect_outbox_repository().returning(move || {
+            let mut mock = MockOutboxRepository::new();
+            mock.expect_delete_published_before()
+                .with(eq(now - chrono::Duration::days(7)))
+                .times(1)
+                .returning(|_| Ok(3));
+            Arc::new(mock)
+        });
+        let registry: AppRegistry = Arc::new(registry);
+
+        OutboxRetentionJob::default().run(&registry, now).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use kernel::model::notification::{event::CreateNotification, NotificationKind};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+use super::Job;
+
+/// 返却期限を過ぎた貸出を集計し、借りている利用者へ督促を通知するジョブ。
+pub struct OverdueReminderJob {
+    interval: Duration,
+}
+
+impl OverdueReminderJob {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval }
+    }
+}
+
+impl Default for OverdueReminderJob {
+    fn default() -> Self {
+        // 督促は 1 日 1 回で十分なため
+        Self::new(Duration::from_secs(60 * 60 * 24))
+    }
+}
+
+#[async_trait]
+impl Job for OverdueReminderJob {
+    fn name(&self) -> &'static str {
+        "overdue-reminder"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn run(&self, registry: &AppRegistry, now: DateTime<Utc>) -> AppResult<()> {
+        let checkouts = registry.checkout_repository().find_overdue_all(now).await?;
+        let notification = registry.notification_repository();
+
+        for checkout in &checkouts {
+            tracing::info!(
+                checkout_id = %checkout.id,
+                book_id = %checkout.book.book_id,
+                user_id = %checkout.checked_out_by,
+                due_at = %checkout.due_at,
+                "Overdue checkout found"
+            );
+
+            let event = CreateNotification::new(
+                checkout.checked_out_by,
+                NotificationKind::Overdue,
+                format!("Overdue: {}", checkout.book.title),
+                format!(
+                    "\"{}\" was due on {}. Please return it as soon as possible.",
+                    checkout.book.title,
+                    checkout.due_at.format("%Y-%m-%d")
+                ),
+            );
+            // 1 件の通知に失敗しても、残りの利用者への督促は続ける
+            if let Err(e) = notification.notify(event).await {
+                tracing::warn!(
+                    error.message = %e,
+                    checkout_id = %checkout.id,
+                    "Failed to send overdue notification"
+                );
+            }
+        }
+
+        tracing::info!(count = checkouts.len(), "Overdue reminder job finished");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::TimeZone;
+    use kernel::{
+        model::{
+            checkout::{Checkout, CheckoutBook},
+            id::{BookId, CheckoutId, UserId},
+        },
+        repository::{checkout::MockCheckoutRepository, notification::MockNotificationRepository},
+    };
+    use mockall::predicate::eq;
+    use registry::MockAppRegistryExt;
+
+    use super::*;
+    use crate::clock::Clock;
+
+    struct FixedClock(DateTime<Utc>);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overdue_reminder_uses_injected_clock() -> anyhow::Result<()> {
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 10, 1, 9, 0, 0).unwrap());
+        let now = clock.now();
+        let user_id = UserId::new();
+
+        let mut registry = MockAppRegistryExt::new();
+        registry.expect_checkout_repository().returning(move || {
+            let mut mock = MockCheckoutRepository::new();
+            mock.expect_find_overdue_all()
+                .with(eq(now))
+                .times(1)
+                .returning(move |now| {
+                    Ok(vec![Checkout {
+                        id: CheckoutId::new(),
+                        checked_out_by: user_id,
+                        processed_by: user_id,
+                        checked_out_at: now - chrono::Duration::days(20),
+                        due_at: now - chrono::Duration::days(6),
+                        renewal_count: 0,
+                        returned_at: None,
+                        returned_by: None,
+                        book: CheckoutBook {
+                            book_id: BookId::new(),
+                            copy_id: None,
+                            title: "RustによるWebアプリケーション開発".into(),
+                            author: "豊田優貴".into(),
+                            isbn: "978-4-06-536957-9".into(),
+                        },
+                    }])
+                });
+            Arc::new(mock)
+        });
+        registry
+            .expect_notification_repository()
+            .returning(move || {
+                let mut mock = MockNotificationRepository::new();
+                mock.expect_notify()
+                    .withf(move |e| e.user_id == user_id && e.kind == NotificationKind::Overdue)
+                    .times(1)
+                    .returning(|_| Ok(()));
+                Arc::new(mock)
+            });
+        let registry: AppRegistry = Arc::new(registry);
+
+        OverdueReminderJob::default()
+            .run(&registry, clock.now())
+            .await?;
+
+        Ok(())
+    }
+}
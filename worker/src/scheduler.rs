@@ -0,0 +1,223 @@
+use std::{sync::Arc, time::Duration};
+
+use adapter::database::{
+    find_job_last_run, record_job_run, try_advisory_xact_lock, ConnectionPool,
+};
+use registry::AppRegistry;
+use shared::error::{AppError, AppResult};
+use tokio::{sync::watch, task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{clock::Clock, job::Job};
+
+/// ティックの時刻や前回の実行にかかった時間のばらつきを吸収するため、
+/// 前回の実行から間隔がわずかに経過していない場合も実行する。その許容幅の上限。
+const MAX_JITTER_TOLERANCE: Duration = Duration::from_secs(30);
+
+/// ジョブの間隔に対して、実行を許容する早まりの幅。間隔の 1/10 と上限の小さいほうとする。
+fn jitter_tolerance(interval: Duration) -> Duration {
+    (interval / 10).min(MAX_JITTER_TOLERANCE)
+}
+
+pub struct JobScheduler {
+    db: ConnectionPool,
+    registry: AppRegistry,
+    clock: Arc<dyn Clock>,
+    jobs: Vec<Arc<dyn Job>>,
+}
+
+impl JobScheduler {
+    pub fn new(db: ConnectionPool, registry: AppRegistry, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            db,
+            registry,
+            clock,
+            jobs: Vec::new(),
+        }
+    }
+
+    pub fn register(mut self, job: impl Job + 'static) -> Self {
+        self.jobs.push(Arc::new(job));
+        self
+    }
+
+    /// 登録されたジョブをそれぞれの間隔で定期実行する。
+    /// `shutdown` に値が送られると、実行中のジョブの完了を待ってから終了する。
+    pub fn start(self, shutdown: watch::Receiver<bool>) -> JoinHandle<()> {
+        let scheduler = Arc::new(self);
+        let handles = scheduler
+            .jobs
+            .iter()
+            .cloned()
+            .map(|job| {
+                let scheduler = scheduler.clone();
+                let shutdown = shutdown.clone();
+                tokio::spawn(async move { scheduler.run_periodically(job, shutdown).await })
+            })
+            .collect::<Vec<_>>();
+
+        tokio::spawn(async move {
+            for handle in handles {
+                if let Err(e) = handle.await {
+                    tracing::error!(error.message = %e, "Job task panicked");
+                }
+            }
+            tracing::info!("Job scheduler stopped");
+        })
+    }
+
+    async fn run_periodically(&self, job: Arc<dyn Job>, mut shutdown: watch::Receiver<bool>) {
+        let mut ticker = tokio::time::interval(job.interval());
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if let Err(e) = self.run_once(job.as_ref()).await {
+                        tracing::error!(
+                            job = job.name(),
+                            error.cause_chain = ?e,
+                            error.message = %e,
+                            "Job failed"
+                        );
+                    }
+                }
+                // 送信側が破棄された場合も終了する
+                _ = shutdown.changed() => break,
+            }
+        }
+    }
+
+    /// アドバイザリロックを取得でき、かつ前回の実行からジョブの間隔が経過している場合のみジョブを実行する。
+    /// ロックはトランザクションの終了とともに解放されるため、同じジョブが複数のレプリカで同時に走ることはない。
+    /// 最終実行日時はデータベースに記録するため、再起動やレプリカの追加で間隔を待たずに再実行されることもない。
+    /// ティックは間隔ちょうどで訪れるため、わずかに早まったティックも `jitter_tolerance` の範囲で実行の対象とする。
+    pub async fn run_once(&self, job: &dyn Job) -> AppResult<bool> {
+        let mut tx = self.db.begin().await?;
+
+        if !try_advisory_xact_lock(&mut tx, job.name()).await? {
+            tracing::debug!(job = job.name(), "Job is running on another instance");
+            return Ok(false);
+        }
+
+        let now = self.clock.now();
+        if let Some(last_run_at) = find_job_last_run(&mut tx, job.name()).await? {
+            let interval = job.interval();
+            let interval = chrono::Duration::from_std(interval - jitter_tolerance(interval))
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?;
+            if now - last_run_at < interval {
+                tracing::debug!(job = job.name(), %last_run_at, "Job has run recently");
+                // ロックを確実に解放するため、トランザクションの破棄に任せず明示的に終了する
+                tx.rollback().await.map_err(AppError::TransactionError)?;
+                return Ok(false);
+            }
+        }
+
+        job.run(&self.registry, now).await?;
+
+        record_job_run(&mut tx, job.name(), now).await?;
+        tx.commit().await.map_err(AppError::TransactionError)?;
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, TimeZone, Utc};
+    use registry::MockAppRegistryExt;
+
+    use super::*;
+
+    struct FixedClock(DateTime<Utc>);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0
+        }
+    }
+
+    #[derive(Default)]
+    struct CountingJob(AtomicUsize);
+
+    #[async_trait]
+    impl Job for CountingJob {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        fn interval(&self) -> Duration {
+            Duration::from_secs(60 * 60)
+        }
+
+        async fn run(&self, _registry: &AppRegistry, _now: DateTime<Utc>) -> AppResult<()> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[sqlx::test(migrations = "../adapter/migrations")]
+    async fn test_run_once_waits_for_interval(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let started_at = Utc.with_ymd_and_hms(2024, 11, 14, 9, 0, 0).unwrap();
+        let scheduler = |now| {
+            JobScheduler::new(
+                ConnectionPool::new(pool.clone()),
+                Arc::new(MockAppRegistryExt::new()),
+                Arc::new(FixedClock(now)),
+            )
+        };
+        let job = CountingJob::default();
+
+        assert!(scheduler(started_at).run_once(&job).await?);
+        // 再起動や別のレプリカからの実行でも、間隔が経過するまでは何もしない
+        assert!(!scheduler(started_at).run_once(&job).await?);
+        assert!(
+            !scheduler(started_at + chrono::Duration::minutes(59))
+                .run_once(&job)
+                .await?
+        );
+        assert_eq!(job.0.load(Ordering::SeqCst), 1);
+
+        assert!(
+            scheduler(started_at + chrono::Duration::hours(1))
+                .run_once(&job)
+                .await?
+        );
+        assert_eq!(job.0.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+
+    #[sqlx::test(migrations = "../adapter/migrations")]
+    async fn test_run_once_tolerates_early_tick(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let started_at = Utc.with_ymd_and_hms(2024, 11, 14, 9, 0, 0).unwrap();
+        let scheduler = |now| {
+            JobScheduler::new(
+                ConnectionPool::new(pool.clone()),
+                Arc::new(MockAppRegistryExt::new()),
+                Arc::new(FixedClock(now)),
+            )
+        };
+        let job = CountingJob::default();
+
+        // 前回の実行の記録が遅れ、次のティックが間隔の経過よりわずかに早く訪れた場合も実行する
+        assert!(
+            scheduler(started_at + chrono::Duration::milliseconds(250))
+                .run_once(&job)
+                .await?
+        );
+        assert!(
+            scheduler(started_at + chrono::Duration::hours(1))
+                .run_once(&job)
+                .await?
+        );
+        assert_eq!(job.0.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+}